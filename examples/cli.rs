@@ -27,7 +27,7 @@ mod poseidon;
 #[allow(dead_code)]
 mod merkle;
 
-use merkle::{MerkleTreeUpdateCircuit, Tree};
+use merkle::{empty_tree, MerkleTreeUpdateCircuit};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -213,7 +213,7 @@ where
     const NAME: &'static str = "MerkleTree";
 
     fn get_default_input() -> F {
-        *Tree::default().get_root()
+        *empty_tree().get_root()
     }
 
     #[instrument("update_leaves", skip_all)]
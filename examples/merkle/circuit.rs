@@ -2,21 +2,33 @@ use std::{collections::VecDeque, iter};
 
 use rand::Rng;
 use sirius::{
+    gadgets::merkle::{MerkleTreeChip, Proof, Tree},
     halo2_proofs::{circuit::*, plonk::*},
     halo2curves::ff::{FromUniformBytes, PrimeFieldBits},
     ivc::{StepCircuit, SynthesisError},
-    main_gate::{MainGate, RegionCtx},
+    main_gate::{MainGate, MainGateConfig, RegionCtx},
+    poseidon::{PoseidonRO, Spec},
 };
 use tracing::info;
 
-const INDEX_LIMIT: u32 = 1 << 31;
+pub const T: usize = 16;
+pub const RATE: usize = T - 1;
+
+pub type RandomOracle = PoseidonRO<T, RATE>;
+
+/// Depth of the tree, i.e. there are `2^DEPTH` leaves
+pub const DEPTH: u8 = 31;
 const ARITY: usize = 1;
 
-use super::merkle_tree_gadget::{
-    chip::MerkleTreeUpdateChip,
-    off_circuit::{NodeUpdate, Proof, Tree},
-    *,
-};
+const R_F: usize = 10;
+const R_P: usize = 10;
+
+pub fn empty_tree<F>() -> Tree<F, RandomOracle>
+where
+    F: PrimeFieldBits + serde::Serialize + FromUniformBytes<64>,
+{
+    Tree::new(DEPTH, Spec::new(R_F, R_P))
+}
 
 type ProofBatch<F> = Box<[Proof<F>]>;
 
@@ -24,7 +36,7 @@ pub struct MerkleTreeUpdateCircuit<F>
 where
     F: PrimeFieldBits + serde::Serialize + FromUniformBytes<64>,
 {
-    tree: Tree<F>,
+    tree: Tree<F, RandomOracle>,
     proofs_batches: VecDeque<ProofBatch<F>>,
     batch_size: usize,
 }
@@ -35,7 +47,7 @@ where
 {
     fn default() -> Self {
         Self {
-            tree: Default::default(),
+            tree: empty_tree(),
             proofs_batches: VecDeque::new(),
             batch_size: 2,
         }
@@ -78,16 +90,13 @@ where
         // 'allow' is necessary, because otherwise the closure captures rnd and we have to copy it
         #[allow(clippy::needless_borrows_for_generic_args)]
         self.update_leaves(iter::repeat_with(move || {
-            (rng.gen::<u32>() % INDEX_LIMIT, F::random(&mut rng))
+            (rng.gen_range(0..1u64 << DEPTH), F::random(&mut rng))
         }));
     }
 
-    fn update_leaves(&mut self, update: impl Iterator<Item = (u32, F)>) -> (F, F) {
+    fn update_leaves(&mut self, update: impl Iterator<Item = (u64, F)>) -> (F, F) {
         assert!(update.size_hint().0 >= self.batch_size);
-        let proofs = update
-            .map(|(index, data)| self.tree.update_leaf(index, data))
-            .take(self.batch_size)
-            .collect::<Box<[_]>>();
+        let proofs = self.tree.update_leaves(update.take(self.batch_size));
 
         let old = proofs.first().unwrap().root().old;
         let new = proofs.last().unwrap().root().new;
@@ -102,7 +111,7 @@ impl<F> StepCircuit<1, F> for MerkleTreeUpdateCircuit<F>
 where
     F: PrimeFieldBits + serde::Serialize + FromUniformBytes<64>,
 {
-    type Config = MainGateConfig;
+    type Config = MainGateConfig<T>;
     fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
         MainGate::configure(cs)
     }
@@ -127,17 +136,17 @@ where
                 |region| {
                     let mut region = RegionCtx::new(region, 0);
 
-                    let mut prev = z_i[0].clone();
-                    for proof in self.front_proof_batch().iter() {
-                        let NodeUpdate { old, new, .. } = MerkleTreeUpdateChip::new(proof.clone())
-                            .prove_next_update(&mut region, config.clone())?;
+                    let updates = MerkleTreeChip::<F, RandomOracle, T>::new(
+                        config.clone(),
+                        Spec::new(R_F, R_P),
+                    )
+                    .verify_batch_update(&mut region, self.front_proof_batch())?;
 
-                        region.constrain_equal(prev.cell(), old.cell())?;
-                        prev = new;
-                    }
-                    info!("offset = {}", region.offset);
+                    let (first, last) = (updates.first().unwrap(), updates.last().unwrap());
+                    region.constrain_equal(z_i[0].cell(), first.old_root.cell())?;
+                    info!("offset = {}", region.offset());
 
-                    Ok([prev])
+                    Ok([last.new_root.clone()])
                 },
             )
             .map_err(SynthesisError::Halo2)
@@ -171,18 +180,8 @@ where
 
                 region.next();
 
-                let mut prev = Option::<AssignedCell<F, F>>::None;
-                for proof in self.front_proof_batch().iter() {
-                    let NodeUpdate { old, new, .. } = MerkleTreeUpdateChip::new(proof.clone())
-                        .prove_next_update(&mut region, config.clone())?;
-
-                    if let Some(prev) = prev {
-                        region.constrain_equal(prev.cell(), old.cell())?;
-                    }
-                    prev = Some(new);
-                }
-
-                Ok([prev])
+                MerkleTreeChip::<F, RandomOracle, T>::new(config.clone(), Spec::new(R_F, R_P))
+                    .verify_batch_update(&mut region, self.front_proof_batch())
             },
         )?;
 
@@ -14,7 +14,6 @@ use tracing::info;
 use tracing_subscriber::{filter::LevelFilter, fmt::format::FmtSpan, EnvFilter};
 
 pub mod circuit;
-mod merkle_tree_gadget;
 
 mod ipa;
 mod kzg;
@@ -28,13 +27,11 @@ mod sirius_mod {
         ff::Field,
         group::{prime::PrimeCurve, Group},
         ivc::{step_circuit::trivial, CircuitPublicParamsInput, PublicParams, IVC},
+        poseidon::ROPair,
     };
     use tracing::info_span;
 
-    use crate::{
-        circuit::MerkleTreeUpdateCircuit,
-        merkle_tree_gadget::{off_circuit::Tree, *},
-    };
+    use crate::circuit::{empty_tree, MerkleTreeUpdateCircuit, RandomOracle, T};
 
     const ARITY: usize = 1;
 
@@ -55,7 +52,6 @@ mod sirius_mod {
     type C2Affine = <C2 as PrimeCurve>::Affine;
     type C2Scalar = <C2 as Group>::Scalar;
 
-    type RandomOracle = sirius::poseidon::PoseidonRO<T, RATE>;
     type RandomOracleConstant<F> = <RandomOracle as ROPair<F>>::Args;
 
     fn get_or_create_commitment_key<C: CurveAffine>(
@@ -121,7 +117,7 @@ mod sirius_mod {
         let mut ivc = IVC::new(
             &pp,
             &sc1,
-            [*Tree::default().get_root()],
+            [*empty_tree().get_root()],
             &sc2,
             [C2Scalar::ZERO],
             false,
@@ -1,6 +1,3 @@
 pub mod circuit;
-pub mod merkle_tree_gadget;
 
-pub use circuit::MerkleTreeUpdateCircuit;
-#[allow(unused_imports)]
-pub use merkle_tree_gadget::off_circuit::Tree;
+pub use circuit::{empty_tree, MerkleTreeUpdateCircuit};
@@ -24,6 +24,12 @@ use crate::{group::Curve, util::parallelize};
 pub enum Error {
     #[error("Can't commit too long input: input len: {input_len}, but limit is {limit}")]
     TooLongInput { input_len: usize, limit: usize },
+    #[error("Can't combine {vectors} vectors with {commitments} commitments & {coefficients} coefficients")]
+    LengthMismatch {
+        vectors: usize,
+        commitments: usize,
+        coefficients: usize,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -91,6 +97,41 @@ impl<C: CurveAffine> CommitmentKey<C> {
 }
 
 impl<C: CurveAffine> CommitmentKey<C> {
+    /// Checks that each of `commitments` is the commitment of the corresponding vector from
+    /// `vectors`, all at once
+    ///
+    /// The random linear combination of `vectors` with `coefficients` is committed with a single
+    /// MSM & compared with the same combination of `commitments`. A mismatch of any pair is
+    /// detected with overwhelming probability, if `coefficients` are random.
+    pub fn is_rlc_of_commitments(
+        &self,
+        vectors: &[&[C::Scalar]],
+        commitments: &[C],
+        coefficients: &[C::Scalar],
+    ) -> Result<bool, Error> {
+        if vectors.len() != commitments.len() || vectors.len() != coefficients.len() {
+            return Err(Error::LengthMismatch {
+                vectors: vectors.len(),
+                commitments: commitments.len(),
+                coefficients: coefficients.len(),
+            });
+        }
+
+        let len = vectors.iter().map(|v| v.len()).max().unwrap_or_default();
+        let combined = (0..len)
+            .into_par_iter()
+            .map(|index| {
+                vectors
+                    .iter()
+                    .zip(coefficients.iter())
+                    .filter_map(|(v, coeff)| Some(*v.get(index)? * coeff))
+                    .sum()
+            })
+            .collect::<Vec<C::Scalar>>();
+
+        Ok(self.commit(&combined)? == best_multiexp(coefficients, commitments).to_affine())
+    }
+
     /// Saves `Self` as memory cast to a file.
     /// Fast, but takes up a lot of memory.
     ///
@@ -180,7 +221,8 @@ pub fn setup_smallest_key<C: CurveAffine>(
         ((n * 2usize.pow(K)) as f64).log2().ceil() as usize
     }
 
-    let num_lookup = cs.lookups().len();
+    // each shuffle argument uses the same columns as lookup one
+    let num_lookup = cs.lookups().len() + cs.shuffles().len();
     let p1 = smallest_power(cs.num_advice_columns() + 5 * num_lookup, k_table_size);
     let p2 = smallest_power(cs.num_selectors + cs.num_fixed_columns(), k_table_size);
     CommitmentKey::<C>::setup(p1.max(p2), tag)
@@ -212,3 +254,39 @@ mod file_tests {
         assert_eq!(key, loaded);
     }
 }
+
+#[cfg(test)]
+mod rlc_tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::halo2curves::bn256::{Fr, G1Affine};
+
+    #[traced_test]
+    #[test]
+    fn rlc_of_commitments() {
+        let key = CommitmentKey::<G1Affine>::setup(3, b"");
+
+        let v1 = (1..=8).map(Fr::from).collect::<Vec<_>>();
+        let v2 = (10..=13).map(Fr::from).collect::<Vec<_>>();
+        let commitments = [key.commit(&v1).unwrap(), key.commit(&v2).unwrap()];
+        let coefficients = [Fr::from(7), Fr::from(11)];
+
+        assert_eq!(
+            key.is_rlc_of_commitments(&[&v1, &v2], &commitments, &coefficients),
+            Ok(true)
+        );
+        assert_eq!(
+            key.is_rlc_of_commitments(&[&v2, &v1], &commitments, &coefficients),
+            Ok(false)
+        );
+        assert_eq!(
+            key.is_rlc_of_commitments(&[&v1, &v2], &commitments, &coefficients[..1]),
+            Err(Error::LengthMismatch {
+                vectors: 2,
+                commitments: 2,
+                coefficients: 1,
+            })
+        );
+    }
+}
@@ -1,6 +1,14 @@
+use std::{
+    collections::BTreeMap,
+    iter,
+    sync::{Arc, RwLock},
+};
+
+use rayon::prelude::*;
+
 pub use crate::halo2curves::{CurveAffine, CurveExt};
 use crate::{
-    ff::{Field, PrimeField},
+    ff::{BatchInvert, Field, PrimeField},
     group::{ff::WithSmallOrderMulGroup, GroupOpsOwned, ScalarMulOwned},
     polynomial::univariate::UnivariatePoly,
     util,
@@ -8,7 +16,8 @@ use crate::{
 
 /// Given FFT domain size k, return the omega in case of fft
 /// or return the omega_inv in case if ifft
-/// TODO #274: can consider hardcode if this fn is called multiple times
+///
+/// If it's called multiple times for the same `k`, use [`EvaluationDomain`] instead
 pub(crate) fn get_omega_or_inv<F: PrimeField>(k: u32, is_inverse: bool) -> F {
     assert!(k <= F::S, "k={} should no larger than F::S={}", k, F::S);
     let mut omega_or_inv = if is_inverse {
@@ -59,10 +68,29 @@ fn bitreverse(input: usize, limit: usize) -> usize {
 ///
 /// This will use multithreading if beneficial.
 pub(crate) fn best_fft<Scalar: Field, G: FftGroup<Scalar>>(a: &mut [G], omega: Scalar, log_n: u32) {
+    let twiddles = compute_twiddles(omega, a.len() / 2);
+
+    best_fft_with_twiddles(a, &twiddles, log_n)
+}
+
+/// `{1, omega, omega^2, ..., omega^(count - 1)}`
+fn compute_twiddles<Scalar: Field>(omega: Scalar, count: usize) -> Vec<Scalar> {
+    iter::successors(Some(Scalar::ONE), |w| Some(*w * omega))
+        .take(count)
+        .collect()
+}
+
+/// [`best_fft`] with precomputed `n / 2` twiddle factors, see [`EvaluationDomain`]
+pub(crate) fn best_fft_with_twiddles<Scalar: Field, G: FftGroup<Scalar>>(
+    a: &mut [G],
+    twiddles: &[Scalar],
+    log_n: u32,
+) {
     let threads = rayon::current_num_threads();
     let log_threads = threads.ilog2();
     let n = a.len();
     assert_eq!(n, 1 << log_n);
+    assert_eq!(twiddles.len(), n / 2);
 
     for k in 0..n {
         let rk = bitreverse(k, log_n as usize);
@@ -71,15 +99,6 @@ pub(crate) fn best_fft<Scalar: Field, G: FftGroup<Scalar>>(a: &mut [G], omega: S
         }
     }
 
-    // precompute twiddle factors
-    let twiddles: Vec<_> = (0..(n / 2))
-        .scan(Scalar::ONE, |w, _| {
-            let tw = *w;
-            *w *= &omega;
-            Some(tw)
-        })
-        .collect();
-
     if log_n <= log_threads {
         let mut chunk = 2_usize;
         let mut twiddle_chunk = n / 2;
@@ -110,7 +129,7 @@ pub(crate) fn best_fft<Scalar: Field, G: FftGroup<Scalar>>(a: &mut [G], omega: S
             twiddle_chunk /= 2;
         }
     } else {
-        recursive_butterfly_arithmetic(a, n, 1, &twiddles)
+        recursive_butterfly_arithmetic(a, n, 1, twiddles)
     }
 }
 
@@ -227,6 +246,162 @@ fn distribute_powers_zeta<F: PrimeField>(
     });
 }
 
+/// Cyclic subgroup `{1, omega, ..., omega^(n - 1)}` of size `n = 2^k` with precomputed twiddle
+/// factors & their inverses
+///
+/// Unlike [`fft`] & [`ifft`], which compute the root of unity & twiddles on every call, the
+/// domain is computed once per `k` & can be shared, see [`DomainCache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluationDomain<F: PrimeField> {
+    k: u32,
+    /// `{1, omega, ..., omega^(n - 1)}`, the first half of it is twiddles of [`fft`]
+    elements: Box<[F]>,
+    /// `{1, omega^-1, ..., omega^-(n/2 - 1)}`, twiddles of [`ifft`]
+    inv_twiddles: Box<[F]>,
+    /// `1 / n`
+    n_inv: F,
+}
+
+impl<F: PrimeField> EvaluationDomain<F> {
+    pub fn new(k: u32) -> Self {
+        let n = 1usize << k;
+        let elements = compute_twiddles(get_omega_or_inv::<F>(k, false), n).into_boxed_slice();
+
+        // omega^-i == omega^(n - i)
+        let inv_twiddles = iter::once(F::ONE)
+            .chain(elements.iter().skip(1).rev().copied())
+            .take(n / 2)
+            .collect();
+
+        Self {
+            k,
+            elements,
+            inv_twiddles,
+            n_inv: get_ifft_divisor(k),
+        }
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    pub fn size(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn omega(&self) -> F {
+        self.elements.get(1).copied().unwrap_or(F::ONE)
+    }
+
+    pub fn elements(&self) -> &[F] {
+        &self.elements
+    }
+
+    /// Same as [`fft`], `a.len()` must be equal to the domain size
+    pub fn fft(&self, a: &mut [F]) {
+        assert_eq!(a.len(), self.size());
+        best_fft_with_twiddles(a, &self.elements[..self.size() / 2], self.k);
+    }
+
+    /// Same as [`ifft`], `a.len()` must be equal to the domain size
+    pub fn ifft(&self, a: &mut [F]) {
+        assert_eq!(a.len(), self.size());
+        best_fft_with_twiddles(a, &self.inv_twiddles, self.k);
+
+        let n_inv = self.n_inv;
+        util::parallelize(a, |(a, _)| {
+            for a in a {
+                *a *= &n_inv;
+            }
+        });
+    }
+
+    /// [`EvaluationDomain::fft`] of many polynomials in parallel
+    pub fn fft_batch(&self, polys: &mut [impl AsMut<[F]> + Send]) {
+        polys
+            .par_iter_mut()
+            .for_each(|poly| self.fft(poly.as_mut()));
+    }
+
+    /// [`EvaluationDomain::ifft`] of many polynomials in parallel
+    pub fn ifft_batch(&self, polys: &mut [impl AsMut<[F]> + Send]) {
+        polys
+            .par_iter_mut()
+            .for_each(|poly| self.ifft(poly.as_mut()));
+    }
+
+    /// `Z(X) = X^n - 1`
+    pub fn eval_vanishing(&self, X: F) -> F {
+        X.pow_vartime([self.size() as u64]) - F::ONE
+    }
+
+    /// `{L_0(X), ..., L_(n-1)(X)}`, where `L_i(X) = omega^i / n * (X^n - 1) / (X - omega^i)`
+    ///
+    /// Unlike [`crate::polynomial::lagrange::iter_eval_lagrange_poly_for_cyclic_group`], uses one
+    /// batch inversion for all the basis.
+    pub fn eval_lagrange_basis(&self, X: F) -> Box<[F]> {
+        if let Some(index) = self.elements.iter().position(|element| *element == X) {
+            let mut basis = vec![F::ZERO; self.size()].into_boxed_slice();
+            basis[index] = F::ONE;
+            return basis;
+        }
+
+        let mut denominators = self
+            .elements
+            .iter()
+            .map(|element| X - element)
+            .collect::<Box<[_]>>();
+        denominators.iter_mut().batch_invert();
+
+        let numerator = self.eval_vanishing(X) * self.n_inv;
+        self.elements
+            .iter()
+            .zip(denominators.iter())
+            .map(|(element, denominator_inv)| *element * numerator * denominator_inv)
+            .collect()
+    }
+}
+
+impl<F: WithSmallOrderMulGroup<3>> EvaluationDomain<F> {
+    /// Elements of the coset `zeta * {1, omega, ..., omega^(n - 1)}`
+    pub fn iter_coset_elements(&self) -> impl '_ + Iterator<Item = F> {
+        self.elements.iter().map(|element| F::ZETA * element)
+    }
+
+    /// Same as [`coset_fft`], `a.len()` must be equal to the domain size
+    pub fn coset_fft(&self, a: &mut [F]) {
+        distribute_powers_zeta(a, F::ZETA, F::ZETA.square(), true);
+        self.fft(a);
+    }
+
+    /// Same as [`coset_ifft`], `a.len()` must be equal to the domain size
+    pub fn coset_ifft(&self, a: &mut [F]) {
+        self.ifft(a);
+        distribute_powers_zeta(a, F::ZETA, F::ZETA.square(), false);
+    }
+}
+
+/// [`EvaluationDomain`]s by `k`, each one is computed on first use
+#[derive(Debug, Default)]
+pub struct DomainCache<F: PrimeField> {
+    domains: RwLock<BTreeMap<u32, Arc<EvaluationDomain<F>>>>,
+}
+
+impl<F: PrimeField> DomainCache<F> {
+    pub fn get(&self, k: u32) -> Arc<EvaluationDomain<F>> {
+        if let Some(domain) = self.domains.read().unwrap().get(&k) {
+            return domain.clone();
+        }
+
+        self.domains
+            .write()
+            .unwrap()
+            .entry(k)
+            .or_insert_with(|| Arc::new(EvaluationDomain::new(k)))
+            .clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{array, iter};
@@ -295,6 +470,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn evaluation_domain_fft() {
+        let cache = DomainCache::<Fr>::default();
+
+        for k in [0, 1, 4, 8] {
+            let domain = cache.get(k);
+            assert_eq!(domain.omega(), get_omega_or_inv::<Fr>(k, false));
+
+            let original = generate_random_input::<Fr>(k);
+
+            let mut expected = original.clone();
+            fft(&mut expected);
+            let mut actual = original.clone();
+            domain.fft(&mut actual);
+            assert_eq!(actual, expected);
+
+            domain.ifft(&mut actual);
+            assert_eq!(actual, original);
+
+            let mut expected = original.clone();
+            coset_fft(&mut expected);
+            let mut actual = original.clone();
+            domain.coset_fft(&mut actual);
+            assert_eq!(actual, expected);
+
+            domain.coset_ifft(&mut actual);
+            assert_eq!(actual, original);
+        }
+
+        assert!(Arc::ptr_eq(&cache.get(4), &cache.get(4)));
+    }
+
+    #[test]
+    fn evaluation_domain_batch() {
+        let domain = EvaluationDomain::<Fr>::new(6);
+
+        let original = iter::repeat_with(|| generate_random_input::<Fr>(6))
+            .take(5)
+            .collect::<Vec<_>>();
+
+        let mut actual = original.clone();
+        domain.fft_batch(&mut actual);
+        actual
+            .iter()
+            .zip_eq(original.iter())
+            .for_each(|(actual, original)| {
+                let mut expected = original.clone();
+                fft(&mut expected);
+                assert_eq!(*actual, expected);
+            });
+
+        domain.ifft_batch(&mut actual);
+        assert_eq!(actual, original);
+    }
+
+    #[test]
+    fn evaluation_domain_lagrange_basis() {
+        let domain = EvaluationDomain::<Fr>::new(3);
+
+        for X in [Fr::random(OsRng), domain.elements()[5]] {
+            let expected = crate::polynomial::lagrange::iter_eval_lagrange_poly_for_cyclic_group(
+                X,
+                domain.k(),
+            )
+            .collect::<Box<[_]>>();
+            assert_eq!(domain.eval_lagrange_basis(X), expected);
+        }
+
+        let X = Fr::random(OsRng);
+        assert_eq!(domain.eval_vanishing(X), X.pow([8]) - Fr::ONE);
+    }
+
     #[test]
     fn test_bitreverse_basic() {
         assert_eq!(bitreverse(0b0001, 4), 0b1000);
@@ -0,0 +1,218 @@
+use std::iter;
+
+use bellpepper_core::{
+    ConstraintSystem, Index, LinearCombination as BellpepperLinearCombination, SynthesisError,
+    Variable,
+};
+use halo2_proofs::{circuit::Value, plonk::Error as Halo2Error};
+
+use crate::{
+    ff::PrimeField,
+    frontend::r1cs::R1CSChip,
+    main_gate::{AssignedValue, RegionCtx},
+};
+
+/// Sparse linear combination `sum coeff * variable`
+pub type LinearCombination<F> = Vec<(Index, F)>;
+
+/// `<a, w> * <b, w> = <c, w>` with the full annotation path
+#[derive(Clone, Debug)]
+pub struct Constraint<F: PrimeField> {
+    pub annotation: String,
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+/// [`ConstraintSystem`] which collects variables & constraints to be laid out later
+///
+/// Values are unknown if the circuit returns [`SynthesisError::AssignmentMissing`] for them.
+#[derive(Clone, Debug)]
+pub struct R1CSCollector<F: PrimeField> {
+    /// Including the constant `1` at index 0
+    inputs: Vec<Value<F>>,
+    aux: Vec<Value<F>>,
+    constraints: Vec<Constraint<F>>,
+    namespace: Vec<String>,
+}
+
+impl<F: PrimeField> Default for R1CSCollector<F> {
+    fn default() -> Self {
+        Self {
+            inputs: vec![Value::known(F::ONE)],
+            aux: vec![],
+            constraints: vec![],
+            namespace: vec![],
+        }
+    }
+}
+
+impl<F: PrimeField> R1CSCollector<F> {
+    pub fn inputs_count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn aux_count(&self) -> usize {
+        self.aux.len()
+    }
+
+    pub fn constraints(&self) -> &[Constraint<F>] {
+        &self.constraints
+    }
+
+    pub fn value(&self, variable: &Variable) -> Value<F> {
+        match variable.get_unchecked() {
+            Index::Input(index) => self.inputs[index],
+            Index::Aux(index) => self.aux[index],
+        }
+    }
+
+    fn eval(&self, lc: &LinearCombination<F>) -> Value<F> {
+        lc.iter()
+            .fold(Value::known(F::ZERO), |acc, (index, coeff)| {
+                acc + self.value(&Variable::new_unchecked(*index)) * Value::known(*coeff)
+            })
+    }
+
+    /// Returns the annotation of the first constraint unsatisfied by known values
+    pub fn which_is_unsatisfied(&self) -> Option<&str> {
+        self.constraints
+            .iter()
+            .find(|Constraint { a, b, c, .. }| {
+                matches!(
+                    (self.eval(a) * self.eval(b) - self.eval(c)).unwrap(),
+                    Some(diff) if diff != F::ZERO
+                )
+            })
+            .map(|constraint| constraint.annotation.as_str())
+    }
+
+    /// Assigns all variables & lays out all constraints
+    pub fn assign<const T: usize>(
+        &self,
+        chip: &R1CSChip<F, T>,
+        ctx: &mut RegionCtx<'_, F>,
+    ) -> Result<AssignedWires<F>, Halo2Error> {
+        let one = chip.assign_constant(ctx, F::ONE)?;
+        let inputs = iter::once(one)
+            .chain(chip.assign_variables(ctx, self.inputs.iter().skip(1).copied())?)
+            .collect();
+        let aux = chip.assign_variables(ctx, self.aux.iter().copied())?;
+
+        let wires = AssignedWires { inputs, aux };
+        for Constraint { a, b, c, .. } in self.constraints.iter() {
+            chip.enforce(ctx, &wires.lc(a), &wires.lc(b), &wires.lc(c))?;
+        }
+
+        Ok(wires)
+    }
+
+    fn alloc_value(
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Value<F>, SynthesisError> {
+        match f() {
+            Ok(value) => Ok(Value::known(value)),
+            Err(SynthesisError::AssignmentMissing) => Ok(Value::unknown()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn annotate(&self, annotation: String) -> String {
+        self.namespace
+            .iter()
+            .cloned()
+            .chain(iter::once(annotation))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl<F: PrimeField> ConstraintSystem<F> for R1CSCollector<F> {
+    type Root = Self;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn alloc<FN, A, AR>(&mut self, _annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.aux.push(Self::alloc_value(f)?);
+        Ok(Variable::new_unchecked(Index::Aux(self.aux.len() - 1)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, _annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inputs.push(Self::alloc_value(f)?);
+        Ok(Variable::new_unchecked(Index::Input(self.inputs.len() - 1)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(BellpepperLinearCombination<F>) -> BellpepperLinearCombination<F>,
+        LB: FnOnce(BellpepperLinearCombination<F>) -> BellpepperLinearCombination<F>,
+        LC: FnOnce(BellpepperLinearCombination<F>) -> BellpepperLinearCombination<F>,
+    {
+        let collect = |lc: BellpepperLinearCombination<F>| {
+            lc.iter()
+                .map(|(variable, coeff)| (variable.get_unchecked(), *coeff))
+                .collect()
+        };
+
+        let constraint = Constraint {
+            annotation: self.annotate(annotation().into()),
+            a: collect(a(BellpepperLinearCombination::zero())),
+            b: collect(b(BellpepperLinearCombination::zero())),
+            c: collect(c(BellpepperLinearCombination::zero())),
+        };
+        self.constraints.push(constraint);
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespace.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        self.namespace.pop();
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// Cells of all variables collected by [`R1CSCollector`]
+#[derive(Clone, Debug)]
+pub struct AssignedWires<F: PrimeField> {
+    /// Including the constant `1` at index 0
+    inputs: Vec<AssignedValue<F>>,
+    aux: Vec<AssignedValue<F>>,
+}
+
+impl<F: PrimeField> AssignedWires<F> {
+    pub fn get(&self, variable: &Variable) -> &AssignedValue<F> {
+        match variable.get_unchecked() {
+            Index::Input(index) => &self.inputs[index],
+            Index::Aux(index) => &self.aux[index],
+        }
+    }
+
+    fn lc(&self, lc: &LinearCombination<F>) -> Vec<(AssignedValue<F>, F)> {
+        lc.iter()
+            .map(|(index, coeff)| (self.get(&Variable::new_unchecked(*index)).clone(), *coeff))
+            .collect()
+    }
+}
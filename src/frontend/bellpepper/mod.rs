@@ -0,0 +1,49 @@
+//! # Bellpepper frontend
+//!
+//! Runs circuits written against [`bellpepper_core::ConstraintSystem`] (as in Nova) as
+//! [`crate::ivc::StepCircuit`]s.
+//!
+//! A Nova step circuit is ported by implementing [`StepCircuit`] of this module, which has the
+//! same signature as `nova_snark::traits::circuit::StepCircuit`, and wrapping it into
+//! [`BellpepperStepCircuit`]:
+//!
+//! ```ignore
+//! impl<F: PrimeField> bellpepper::StepCircuit<F> for CubicCircuit {
+//!     fn arity(&self) -> usize { 1 }
+//!
+//!     fn synthesize<CS: ConstraintSystem<F>>(
+//!         &self,
+//!         cs: &mut CS,
+//!         z: &[AllocatedNum<F>],
+//!     ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+//!         // the body of the Nova circuit as is
+//!     }
+//! }
+//!
+//! let step = BellpepperStepCircuit::<F, _, 1, T>::new(CubicCircuit)?;
+//! ```
+//!
+//! Synthesis is done in two passes: the circuit is first synthesized into [`R1CSCollector`], then
+//! the collected R1CS is laid out by [`crate::frontend::r1cs::R1CSChip`]. `z_i` are allocated as
+//! private variables, as the augmented circuit of Nova does. Public inputs allocated by the
+//! circuit are laid out as private variables too, since the step exposes only `z_out`.
+
+pub mod constraint_system;
+pub mod step_circuit;
+
+pub use bellpepper_core;
+pub use constraint_system::{AssignedWires, R1CSCollector};
+pub use step_circuit::{BellpepperStepCircuit, StepCircuit};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Synthesis(#[from] bellpepper_core::SynthesisError),
+    #[error("arity of the circuit is {actual}, while {expected} is expected")]
+    ArityMismatch { expected: usize, actual: usize },
+    #[error("constraint `{annotation}` isn't satisfied")]
+    UnsatisfiedConstraint { annotation: String },
+}
+
+#[cfg(test)]
+mod tests;
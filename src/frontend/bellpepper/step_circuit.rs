@@ -0,0 +1,177 @@
+use std::{array, marker::PhantomData};
+
+use bellpepper_core::{
+    num::AllocatedNum, ConstraintSystem, SynthesisError as BellpepperSynthesisError, Variable,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem as Halo2ConstraintSystem, Error as Halo2Error},
+};
+use tracing::*;
+
+use super::{Error, R1CSCollector};
+use crate::{
+    ff::PrimeField,
+    frontend::r1cs::R1CSChip,
+    ivc::{self, SynthesisError},
+    main_gate::{MainGate, MainGateConfig, RegionCtx},
+};
+
+/// Step circuit over bellpepper [`ConstraintSystem`], the same as Nova's one
+pub trait StepCircuit<F: PrimeField> {
+    /// Number of elements in `z_i` & `z_out`
+    fn arity(&self) -> usize;
+
+    /// Synthesizes the step & returns `z_out`
+    fn synthesize<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<Vec<AllocatedNum<F>>, BellpepperSynthesisError>;
+}
+
+/// Bellpepper [`StepCircuit`] as [`ivc::StepCircuit`], requires `T >= 2`
+#[derive(Clone, Debug)]
+pub struct BellpepperStepCircuit<F, C, const ARITY: usize, const T: usize>
+where
+    F: PrimeField,
+    C: StepCircuit<F>,
+{
+    circuit: C,
+    _p: PhantomData<F>,
+}
+
+impl<F, C, const ARITY: usize, const T: usize> BellpepperStepCircuit<F, C, ARITY, T>
+where
+    F: PrimeField,
+    C: StepCircuit<F>,
+{
+    pub fn new(circuit: C) -> Result<Self, Error> {
+        if circuit.arity() != ARITY {
+            return Err(Error::ArityMismatch {
+                expected: ARITY,
+                actual: circuit.arity(),
+            });
+        }
+
+        Ok(Self {
+            circuit,
+            _p: PhantomData,
+        })
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.circuit
+    }
+
+    /// Synthesizes the circuit into [`R1CSCollector`], returns variables of `z_i` & `z_out`
+    fn collect(
+        &self,
+        z_i: [Value<F>; ARITY],
+    ) -> Result<(R1CSCollector<F>, [Variable; ARITY], [Variable; ARITY]), Error> {
+        let mut cs = R1CSCollector::new();
+
+        let z_i = z_i
+            .iter()
+            .enumerate()
+            .map(|(index, z)| {
+                AllocatedNum::alloc(cs.namespace(|| format!("z_i[{index}]")), || {
+                    z.unwrap()
+                        .ok_or(BellpepperSynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let z_out = self.circuit.synthesize(&mut cs, &z_i)?;
+        if z_out.len() != ARITY {
+            return Err(Error::ArityMismatch {
+                expected: ARITY,
+                actual: z_out.len(),
+            });
+        }
+
+        Ok((
+            cs,
+            array::from_fn(|index| z_i[index].get_variable()),
+            array::from_fn(|index| z_out[index].get_variable()),
+        ))
+    }
+}
+
+impl<F, C, const ARITY: usize, const T: usize> ivc::StepCircuit<ARITY, F>
+    for BellpepperStepCircuit<F, C, ARITY, T>
+where
+    F: PrimeField,
+    C: StepCircuit<F>,
+{
+    type Config = MainGateConfig<T>;
+
+    fn configure(cs: &mut Halo2ConstraintSystem<F>) -> Self::Config {
+        MainGate::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; ARITY],
+    ) -> Result<[AssignedCell<F, F>; ARITY], SynthesisError> {
+        let (cs, z_in, z_out) = self
+            .collect(z_i.each_ref().map(|z| z.value().copied()))
+            .map_err(|err| {
+                error!("while synthesize bellpepper circuit: {err}");
+                SynthesisError::Halo2(Halo2Error::Synthesis)
+            })?;
+
+        let chip = R1CSChip::<F, T>::new(config);
+
+        let z_out = layouter.assign_region(
+            || "bellpepper",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let wires = cs.assign(&chip, &mut ctx)?;
+
+                for (variable, z) in z_in.iter().zip(z_i.iter()) {
+                    ctx.constrain_equal(wires.get(variable).cell(), z.cell())?;
+                }
+
+                Ok(z_out.map(|variable| wires.get(&variable).clone()))
+            },
+        )?;
+
+        Ok(z_out)
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; ARITY],
+        _k_table_size: u32,
+    ) -> Result<[F; ARITY], SynthesisError> {
+        let to_synthesis_error = |err: Error| {
+            error!("{err}");
+            SynthesisError::Halo2(Halo2Error::Synthesis)
+        };
+
+        let (cs, _z_in, z_out) = self
+            .collect(z_i.map(Value::known))
+            .map_err(to_synthesis_error)?;
+
+        if let Some(annotation) = cs.which_is_unsatisfied() {
+            return Err(to_synthesis_error(Error::UnsatisfiedConstraint {
+                annotation: annotation.to_owned(),
+            }));
+        }
+
+        z_out
+            .iter()
+            .map(|variable| cs.value(variable).unwrap())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|z_out| z_out.try_into().ok())
+            .ok_or_else(|| {
+                to_synthesis_error(Error::Synthesis(
+                    BellpepperSynthesisError::AssignmentMissing,
+                ))
+            })
+    }
+}
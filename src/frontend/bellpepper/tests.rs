@@ -0,0 +1,174 @@
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{ff::PrimeField, halo2curves::bn256::Fr, ivc, util::mock_prover::MockProver};
+
+const T: usize = 2;
+const K: u32 = 8;
+
+/// `x -> x^3 + x + 5`, the example of Nova, `shift` breaks the witness of `y`
+#[derive(Clone, Debug, Default)]
+struct CubicCircuit {
+    shift: u64,
+}
+
+impl<F: PrimeField> StepCircuit<F> for CubicCircuit {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+        let x = &z[0];
+        let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+        let x_cu = x_sq.mul(cs.namespace(|| "x_cu"), x)?;
+        let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+            Ok(x_cu.get_value().ok_or(SynthesisError::AssignmentMissing)?
+                + x.get_value().ok_or(SynthesisError::AssignmentMissing)?
+                + F::from(5)
+                + F::from(self.shift))
+        })?;
+
+        cs.enforce(
+            || "y = x^3 + x + 5",
+            |lc| lc + x_cu.get_variable() + x.get_variable() + (F::from(5), CS::one()),
+            |lc| lc + CS::one(),
+            |lc| lc + y.get_variable(),
+        );
+
+        Ok(vec![y])
+    }
+}
+
+/// `(a, b) -> (b, a + b)` with `a + b` allocated as a public input
+#[derive(Clone, Debug)]
+struct FibonacciCircuit;
+
+impl<F: PrimeField> StepCircuit<F> for FibonacciCircuit {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+        let (a, b) = (&z[0], &z[1]);
+
+        let sum = cs.alloc_input(
+            || "sum",
+            || {
+                Ok(a.get_value().ok_or(SynthesisError::AssignmentMissing)?
+                    + b.get_value().ok_or(SynthesisError::AssignmentMissing)?)
+            },
+        )?;
+        cs.enforce(
+            || "sum = a + b",
+            |lc| lc + a.get_variable() + b.get_variable(),
+            |lc| lc + CS::one(),
+            |lc| lc + sum,
+        );
+
+        let c = AllocatedNum::alloc(cs.namespace(|| "c"), || {
+            Ok(a.get_value().ok_or(SynthesisError::AssignmentMissing)?
+                + b.get_value().ok_or(SynthesisError::AssignmentMissing)?)
+        })?;
+        cs.enforce(
+            || "c = sum",
+            |lc| lc + sum,
+            |lc| lc + CS::one(),
+            |lc| lc + c.get_variable(),
+        );
+
+        Ok(vec![b.clone(), c])
+    }
+}
+
+#[traced_test]
+#[test]
+fn cubic_step_circuit() {
+    let step = BellpepperStepCircuit::<Fr, _, 1, T>::new(CubicCircuit::default()).unwrap();
+
+    let mut z_i = [Fr::from(2)];
+    for expected in [15, 3395] {
+        let z_out = ivc::StepCircuit::process_step(&step, &z_i, K).unwrap();
+        assert_eq!(z_out, [Fr::from(expected)]);
+
+        MockProver::run(K, &step, vec![], z_i)
+            .unwrap()
+            .verify(z_out)
+            .unwrap();
+
+        z_i = z_out;
+    }
+}
+
+#[traced_test]
+#[test]
+fn fibonacci_step_circuit() {
+    let step = BellpepperStepCircuit::<Fr, _, 2, T>::new(FibonacciCircuit).unwrap();
+
+    let mut z_i = [Fr::from(0), Fr::from(1)];
+    for _ in 0..3 {
+        let z_out = ivc::StepCircuit::process_step(&step, &z_i, K).unwrap();
+
+        MockProver::run(K, &step, vec![], z_i)
+            .unwrap()
+            .verify(z_out)
+            .unwrap();
+
+        z_i = z_out;
+    }
+
+    assert_eq!(z_i, [Fr::from(2), Fr::from(3)]);
+}
+
+#[traced_test]
+#[test]
+fn collector() {
+    let mut cs = R1CSCollector::<Fr>::new();
+    let x = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(Fr::from(3))).unwrap();
+    let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+        Err(SynthesisError::AssignmentMissing)
+    })
+    .unwrap();
+
+    StepCircuit::<Fr>::synthesize(
+        &CubicCircuit::default(),
+        &mut cs.namespace(|| "cubic"),
+        &[x],
+    )
+    .unwrap();
+
+    assert_eq!(cs.inputs_count(), 1);
+    assert_eq!(cs.aux_count(), 5);
+    assert_eq!(cs.constraints().len(), 3);
+    assert_eq!(cs.constraints()[2].annotation, "cubic/y = x^3 + x + 5");
+    assert!(cs.value(&y.get_variable()).unwrap().is_none());
+    assert_eq!(cs.which_is_unsatisfied(), None);
+}
+
+#[traced_test]
+#[test]
+fn wrong_witness() {
+    assert!(matches!(
+        BellpepperStepCircuit::<Fr, _, 2, T>::new(CubicCircuit::default()),
+        Err(Error::ArityMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+
+    let step = BellpepperStepCircuit::<Fr, _, 1, T>::new(CubicCircuit { shift: 1 }).unwrap();
+    assert!(ivc::StepCircuit::process_step(&step, &[Fr::from(2)], K).is_err());
+
+    assert!(MockProver::run(K, &step, vec![], [Fr::from(2)])
+        .unwrap()
+        .verify([Fr::from(16)])
+        .is_err());
+}
@@ -0,0 +1,173 @@
+//! # Circom frontend
+//!
+//! Runs circuits compiled by [circom](https://docs.circom.io) as [`crate::ivc::StepCircuit`]s,
+//! from the binary constraint system (`.r1cs`) & per-step witnesses (`.wtns`) produced by the
+//! circom witness generator. Only local files are read.
+//!
+//! Wires of the circom circuit are ordered as `[1, public outputs, public inputs, private inputs,
+//! internal]`. By default, as in Nova-Scotia, the first `ARITY` public inputs are `z_i` & the
+//! first `ARITY` public outputs are `z_out`, i.e. the main component should be declared as
+//!
+//! ```circom
+//! template Step() {
+//!     signal input step_in[ARITY];
+//!     signal output step_out[ARITY];
+//!     // ...
+//! }
+//! component main { public [step_in] } = Step();
+//! ```
+//!
+//! Any other wires can be designated by [`CircomStepCircuit::with_io`].
+//!
+//! - [`R1CS`] - parser of `.r1cs`, see the
+//!   [format](https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md)
+//! - [`read_witness`] - parser of `.wtns`
+//! - [`CircomStepCircuit`] - the constraint system laid out by [`crate::frontend::r1cs::R1CSChip`]
+
+use std::io;
+
+pub mod r1cs;
+pub mod step_circuit;
+pub mod witness;
+
+pub use r1cs::{Constraint, LinearCombination, R1CS};
+pub use step_circuit::CircomStepCircuit;
+pub use witness::read_witness;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("wrong magic of `{expected}` file")]
+    WrongMagic { expected: &'static str },
+    #[error("unsupported version {version} of `{kind}` file")]
+    UnsupportedVersion { kind: &'static str, version: u32 },
+    #[error("section {section_type} is missing")]
+    MissingSection { section_type: u32 },
+    #[error("section {section_type} is malformed")]
+    MalformedSection { section_type: u32 },
+    #[error("field elements of the file take {actual} bytes, while the field needs {expected}")]
+    FieldSizeMismatch { expected: usize, actual: usize },
+    #[error("the prime of the file doesn't match the field")]
+    FieldMismatch,
+    #[error("value is not less than the field modulus")]
+    ValueOutOfField,
+    #[error("wire {wire} is out of {wires_count} wires")]
+    WireOutOfRange { wire: usize, wires_count: usize },
+    #[error("witness has {actual} values, while r1cs has {expected} wires")]
+    WitnessSizeMismatch { expected: usize, actual: usize },
+    #[error("constraint #{index} isn't satisfied by the witness")]
+    UnsatisfiedConstraint { index: usize },
+    #[error("arity {arity} needs as many public signals, but there are {inputs} inputs & {outputs} outputs")]
+    NotEnoughPublicSignals {
+        arity: usize,
+        inputs: usize,
+        outputs: usize,
+    },
+    #[error("input #{index} of the witness doesn't match `z_i`")]
+    InputMismatch { index: usize },
+}
+
+/// Sections of the iden3 binary formats shared by `.r1cs` & `.wtns`
+mod binfile {
+    use std::{collections::HashMap, io::Read};
+
+    use num_bigint::BigUint;
+
+    use super::Error;
+    use crate::{ff::PrimeField, util};
+
+    pub struct Sections {
+        sections: HashMap<u32, Vec<u8>>,
+    }
+
+    impl Sections {
+        /// Reads the whole file, sections may follow in any order
+        pub fn read(
+            reader: &mut impl Read,
+            magic: &'static str,
+            supported_versions: &[u32],
+        ) -> Result<Self, Error> {
+            let mut file_magic = [0u8; 4];
+            reader.read_exact(&mut file_magic)?;
+            if file_magic != magic.as_bytes() {
+                return Err(Error::WrongMagic { expected: magic });
+            }
+
+            let version = read_u32(reader)?;
+            if !supported_versions.contains(&version) {
+                return Err(Error::UnsupportedVersion {
+                    kind: magic,
+                    version,
+                });
+            }
+
+            let sections_count = read_u32(reader)?;
+            let mut sections = HashMap::new();
+            for _ in 0..sections_count {
+                let section_type = read_u32(reader)?;
+                let size = read_u64(reader)?;
+
+                let mut content = vec![];
+                reader.by_ref().take(size).read_to_end(&mut content)?;
+                if content.len() as u64 != size {
+                    return Err(Error::MalformedSection { section_type });
+                }
+
+                sections.insert(section_type, content);
+            }
+
+            Ok(Self { sections })
+        }
+
+        pub fn get(&self, section_type: u32) -> Result<&[u8], Error> {
+            self.sections
+                .get(&section_type)
+                .map(Vec::as_slice)
+                .ok_or(Error::MissingSection { section_type })
+        }
+    }
+
+    pub fn read_u32(reader: &mut impl Read) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn read_u64(reader: &mut impl Read) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Reads the field element of [`PrimeField::Repr`] size in little-endian bytes
+    pub fn read_fe<F: PrimeField>(reader: &mut impl Read) -> Result<F, Error> {
+        let mut bytes = F::Repr::default();
+        reader.read_exact(bytes.as_mut())?;
+        util::fe_from_big(BigUint::from_bytes_le(bytes.as_ref())).ok_or(Error::ValueOutOfField)
+    }
+
+    /// Reads the size of field elements & checks it and the prime against `F`
+    pub fn read_field<F: PrimeField>(reader: &mut impl Read) -> Result<usize, Error> {
+        let field_size = read_u32(reader)? as usize;
+
+        let expected = F::Repr::default().as_ref().len();
+        if field_size != expected {
+            return Err(Error::FieldSizeMismatch {
+                expected,
+                actual: field_size,
+            });
+        }
+
+        let mut prime = F::Repr::default();
+        reader.read_exact(prime.as_mut())?;
+        if BigUint::from_bytes_le(prime.as_ref()) != util::modulus::<F>() {
+            return Err(Error::FieldMismatch);
+        }
+
+        Ok(field_size)
+    }
+}
+
+#[cfg(test)]
+mod tests;
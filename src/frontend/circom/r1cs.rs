@@ -0,0 +1,136 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use super::{
+    binfile::{self, Sections},
+    Error,
+};
+use crate::ff::PrimeField;
+
+const MAGIC: &str = "r1cs";
+const VERSION: u32 = 1;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+
+/// Sparse linear combination `sum coeff * w[wire]`
+pub type LinearCombination<F> = Vec<(usize, F)>;
+
+/// `<a, w> * <b, w> = <c, w>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint<F: PrimeField> {
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+/// Constraint system of a circom circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1CS<F: PrimeField> {
+    /// Including the constant wire `w[0] = 1`
+    pub wires_count: usize,
+    pub public_outputs_count: usize,
+    pub public_inputs_count: usize,
+    pub private_inputs_count: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+impl<F: PrimeField> R1CS<F> {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_reader(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Parses the `.r1cs` binary, the prime of which must be the modulus of `F`
+    ///
+    /// Sections of custom gates & wire labels are ignored.
+    pub fn from_reader(reader: &mut impl std::io::Read) -> Result<Self, Error> {
+        let sections = Sections::read(reader, MAGIC, &[VERSION])?;
+
+        let mut header = sections.get(HEADER_SECTION)?;
+        binfile::read_field::<F>(&mut header)?;
+        let wires_count = binfile::read_u32(&mut header)? as usize;
+        let public_outputs_count = binfile::read_u32(&mut header)? as usize;
+        let public_inputs_count = binfile::read_u32(&mut header)? as usize;
+        let private_inputs_count = binfile::read_u32(&mut header)? as usize;
+        let _labels_count = binfile::read_u64(&mut header)?;
+        let constraints_count = binfile::read_u32(&mut header)? as usize;
+
+        if 1 + public_outputs_count + public_inputs_count + private_inputs_count > wires_count {
+            return Err(Error::MalformedSection {
+                section_type: HEADER_SECTION,
+            });
+        }
+
+        let mut content = sections.get(CONSTRAINTS_SECTION)?;
+        let mut read_lc = || -> Result<LinearCombination<F>, Error> {
+            let terms_count = binfile::read_u32(&mut content)?;
+            (0..terms_count)
+                .map(|_| {
+                    let wire = binfile::read_u32(&mut content)? as usize;
+                    if wire >= wires_count {
+                        return Err(Error::WireOutOfRange { wire, wires_count });
+                    }
+                    Ok((wire, binfile::read_fe(&mut content)?))
+                })
+                .collect()
+        };
+
+        let constraints = (0..constraints_count)
+            .map(|_| {
+                Ok(Constraint {
+                    a: read_lc()?,
+                    b: read_lc()?,
+                    c: read_lc()?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        if !content.is_empty() {
+            return Err(Error::MalformedSection {
+                section_type: CONSTRAINTS_SECTION,
+            });
+        }
+
+        Ok(Self {
+            wires_count,
+            public_outputs_count,
+            public_inputs_count,
+            private_inputs_count,
+            constraints,
+        })
+    }
+
+    /// Index of the first public output wire
+    pub fn public_outputs_offset(&self) -> usize {
+        1
+    }
+
+    /// Index of the first public input wire
+    pub fn public_inputs_offset(&self) -> usize {
+        self.public_outputs_offset() + self.public_outputs_count
+    }
+
+    /// Checks the size of `witness` & each constraint against it
+    pub fn check_witness(&self, witness: &[F]) -> Result<(), Error> {
+        if witness.len() != self.wires_count {
+            return Err(Error::WitnessSizeMismatch {
+                expected: self.wires_count,
+                actual: witness.len(),
+            });
+        }
+
+        let eval = |lc: &LinearCombination<F>| {
+            lc.iter()
+                .map(|(wire, coeff)| witness[*wire] * coeff)
+                .sum::<F>()
+        };
+
+        match self
+            .constraints
+            .iter()
+            .position(|Constraint { a, b, c }| eval(a) * eval(b) != eval(c))
+        {
+            Some(index) => Err(Error::UnsatisfiedConstraint { index }),
+            None => Ok(()),
+        }
+    }
+}
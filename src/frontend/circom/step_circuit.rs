@@ -0,0 +1,161 @@
+use std::{array, iter, path::Path, sync::Arc};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error as Halo2Error},
+};
+use tracing::*;
+
+use super::{read_witness, Error, LinearCombination, R1CS};
+use crate::{
+    ff::PrimeField,
+    frontend::r1cs::R1CSChip,
+    ivc::{StepCircuit, SynthesisError},
+    main_gate::{AssignedValue, MainGate, MainGateConfig, RegionCtx},
+};
+
+/// One step of a circom circuit: the shared [`R1CS`] with the witness of this step
+///
+/// `z_i` is copy-constrained to `inputs` wires & `z_out` is taken from `outputs` wires, see
+/// [`crate::frontend::circom`] for defaults. Requires `T >= 2`.
+#[derive(Clone, Debug)]
+pub struct CircomStepCircuit<F: PrimeField, const ARITY: usize, const T: usize> {
+    r1cs: Arc<R1CS<F>>,
+    witness: Vec<F>,
+    inputs: [usize; ARITY],
+    outputs: [usize; ARITY],
+}
+
+impl<F: PrimeField, const ARITY: usize, const T: usize> CircomStepCircuit<F, ARITY, T> {
+    /// The step with the first `ARITY` public inputs & outputs as `z_i` & `z_out`
+    ///
+    /// The witness is checked against the constraint system.
+    pub fn new(r1cs: Arc<R1CS<F>>, witness: Vec<F>) -> Result<Self, Error> {
+        if r1cs.public_inputs_count < ARITY || r1cs.public_outputs_count < ARITY {
+            return Err(Error::NotEnoughPublicSignals {
+                arity: ARITY,
+                inputs: r1cs.public_inputs_count,
+                outputs: r1cs.public_outputs_count,
+            });
+        }
+
+        r1cs.check_witness(&witness)?;
+
+        let inputs = array::from_fn(|index| r1cs.public_inputs_offset() + index);
+        let outputs = array::from_fn(|index| r1cs.public_outputs_offset() + index);
+
+        Ok(Self {
+            r1cs,
+            witness,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// [`CircomStepCircuit::new`] with the witness read from the `.wtns` file
+    pub fn from_witness_file(r1cs: Arc<R1CS<F>>, path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::new(r1cs, read_witness(path)?)
+    }
+
+    /// Designates any wires as `z_i` & `z_out`
+    pub fn with_io(
+        mut self,
+        inputs: [usize; ARITY],
+        outputs: [usize; ARITY],
+    ) -> Result<Self, Error> {
+        let wires_count = self.r1cs.wires_count;
+        if let Some(wire) = inputs
+            .iter()
+            .chain(outputs.iter())
+            .find(|wire| **wire >= wires_count)
+        {
+            return Err(Error::WireOutOfRange {
+                wire: *wire,
+                wires_count,
+            });
+        }
+
+        self.inputs = inputs;
+        self.outputs = outputs;
+
+        Ok(self)
+    }
+
+    fn assign_lc(
+        wires: &[AssignedValue<F>],
+        lc: &LinearCombination<F>,
+    ) -> Vec<(AssignedValue<F>, F)> {
+        lc.iter()
+            .map(|(wire, coeff)| (wires[*wire].clone(), *coeff))
+            .collect()
+    }
+}
+
+impl<F: PrimeField, const ARITY: usize, const T: usize> StepCircuit<ARITY, F>
+    for CircomStepCircuit<F, ARITY, T>
+{
+    type Config = MainGateConfig<T>;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        MainGate::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; ARITY],
+    ) -> Result<[AssignedCell<F, F>; ARITY], SynthesisError> {
+        let chip = R1CSChip::<F, T>::new(config);
+
+        let z_out = layouter.assign_region(
+            || "circom",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let one = chip.assign_constant(&mut ctx, F::ONE)?;
+                let wires = iter::once(one)
+                    .chain(chip.assign_variables(
+                        &mut ctx,
+                        self.witness.iter().skip(1).copied().map(Value::known),
+                    )?)
+                    .collect::<Vec<_>>();
+
+                for (input, z) in self.inputs.iter().zip(z_i.iter()) {
+                    ctx.constrain_equal(wires[*input].cell(), z.cell())?;
+                }
+
+                for constraint in self.r1cs.constraints.iter() {
+                    chip.enforce(
+                        &mut ctx,
+                        &Self::assign_lc(&wires, &constraint.a),
+                        &Self::assign_lc(&wires, &constraint.b),
+                        &Self::assign_lc(&wires, &constraint.c),
+                    )?;
+                }
+
+                Ok(self.outputs.map(|output| wires[output].clone()))
+            },
+        )?;
+
+        Ok(z_out)
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; ARITY],
+        _k_table_size: u32,
+    ) -> Result<[F; ARITY], SynthesisError> {
+        if let Some(index) = self
+            .inputs
+            .iter()
+            .zip(z_i.iter())
+            .position(|(input, z)| self.witness[*input] != *z)
+        {
+            error!("{}", Error::InputMismatch { index });
+            return Err(SynthesisError::Halo2(Halo2Error::Synthesis));
+        }
+
+        Ok(self.outputs.map(|output| self.witness[output]))
+    }
+}
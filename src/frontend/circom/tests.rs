@@ -0,0 +1,235 @@
+use std::{fs, sync::Arc};
+
+use num_bigint::BigUint;
+use tempfile::tempdir;
+use tracing_test::traced_test;
+
+use super::{witness::read_witness_from, *};
+use crate::{
+    ff::{Field, PrimeField},
+    halo2curves::{bn256::Fr, pasta::Fp},
+    ivc::StepCircuit,
+    util::{self, mock_prover::MockProver},
+};
+
+const T: usize = 2;
+const K: u32 = 8;
+
+/// Wires: `[1, step_out, step_in, x, square]`
+///
+/// - `step_in * step_in = square`
+/// - `1 * (square + x + 2 * step_in + 3) = step_out`
+fn r1cs() -> R1CS<Fr> {
+    let one = Fr::ONE;
+    R1CS {
+        wires_count: 5,
+        public_outputs_count: 1,
+        public_inputs_count: 1,
+        private_inputs_count: 1,
+        constraints: vec![
+            Constraint {
+                a: vec![(2, one)],
+                b: vec![(2, one)],
+                c: vec![(4, one)],
+            },
+            Constraint {
+                a: vec![(0, one)],
+                b: vec![(4, one), (3, one), (2, Fr::from(2)), (0, Fr::from(3))],
+                c: vec![(1, one)],
+            },
+        ],
+    }
+}
+
+fn witness(step_in: u64, x: u64) -> Vec<Fr> {
+    let square = step_in * step_in;
+    [1, square + x + 2 * step_in + 3, step_in, x, square]
+        .map(Fr::from)
+        .to_vec()
+}
+
+fn put_fe<F: PrimeField>(bytes: &mut Vec<u8>, value: &F) {
+    let mut repr = util::fe_to_big(value).to_bytes_le();
+    repr.resize(32, 0);
+    bytes.extend(repr);
+}
+
+fn put_prime(bytes: &mut Vec<u8>, prime: &BigUint) {
+    let mut repr = prime.to_bytes_le();
+    repr.resize(32, 0);
+    bytes.extend(32u32.to_le_bytes());
+    bytes.extend(repr);
+}
+
+fn put_section(bytes: &mut Vec<u8>, section_type: u32, content: &[u8]) {
+    bytes.extend(section_type.to_le_bytes());
+    bytes.extend((content.len() as u64).to_le_bytes());
+    bytes.extend(content);
+}
+
+fn encode_r1cs(r1cs: &R1CS<Fr>, prime: &BigUint) -> Vec<u8> {
+    let mut header = vec![];
+    put_prime(&mut header, prime);
+    for count in [
+        r1cs.wires_count,
+        r1cs.public_outputs_count,
+        r1cs.public_inputs_count,
+        r1cs.private_inputs_count,
+    ] {
+        header.extend((count as u32).to_le_bytes());
+    }
+    header.extend((r1cs.wires_count as u64).to_le_bytes());
+    header.extend((r1cs.constraints.len() as u32).to_le_bytes());
+
+    let mut constraints = vec![];
+    for Constraint { a, b, c } in &r1cs.constraints {
+        for lc in [a, b, c] {
+            constraints.extend((lc.len() as u32).to_le_bytes());
+            for (wire, coeff) in lc {
+                constraints.extend((*wire as u32).to_le_bytes());
+                put_fe(&mut constraints, coeff);
+            }
+        }
+    }
+
+    let mut bytes = b"r1cs".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+    // sections may follow in any order
+    put_section(&mut bytes, 2, &constraints);
+    put_section(&mut bytes, 1, &header);
+    bytes
+}
+
+fn encode_witness(witness: &[Fr]) -> Vec<u8> {
+    let mut header = vec![];
+    put_prime(&mut header, &util::modulus::<Fr>());
+    header.extend((witness.len() as u32).to_le_bytes());
+
+    let mut values = vec![];
+    witness.iter().for_each(|value| put_fe(&mut values, value));
+
+    let mut bytes = b"wtns".to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+    put_section(&mut bytes, 1, &header);
+    put_section(&mut bytes, 2, &values);
+    bytes
+}
+
+#[test]
+fn parse_files() {
+    let dir = tempdir().unwrap();
+    let r1cs_path = dir.path().join("step.r1cs");
+    let witness_path = dir.path().join("step.wtns");
+
+    fs::write(&r1cs_path, encode_r1cs(&r1cs(), &util::modulus::<Fr>())).unwrap();
+    fs::write(&witness_path, encode_witness(&witness(2, 5))).unwrap();
+
+    let parsed = R1CS::<Fr>::from_file(&r1cs_path).unwrap();
+    assert_eq!(parsed, r1cs());
+    assert_eq!(read_witness::<Fr>(&witness_path).unwrap(), witness(2, 5));
+
+    let step =
+        CircomStepCircuit::<Fr, 1, T>::from_witness_file(Arc::new(parsed), &witness_path).unwrap();
+    assert_eq!(
+        step.process_step(&[Fr::from(2)], K).unwrap(),
+        [Fr::from(16)]
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let bytes = encode_r1cs(&r1cs(), &util::modulus::<Fp>());
+    assert!(matches!(
+        R1CS::<Fr>::from_reader(&mut bytes.as_slice()),
+        Err(Error::FieldMismatch)
+    ));
+
+    let bytes = encode_r1cs(&r1cs(), &util::modulus::<Fr>());
+    assert!(matches!(
+        R1CS::<Fr>::from_reader(&mut &bytes[..bytes.len() - 1]),
+        Err(Error::MalformedSection { .. })
+    ));
+
+    assert!(matches!(
+        read_witness_from::<Fr>(&mut encode_r1cs(&r1cs(), &util::modulus::<Fr>()).as_slice()),
+        Err(Error::WrongMagic { .. })
+    ));
+
+    let mut invalid = r1cs();
+    invalid.constraints[0].c = vec![(5, Fr::ONE)];
+    assert!(matches!(
+        R1CS::<Fr>::from_reader(&mut encode_r1cs(&invalid, &util::modulus::<Fr>()).as_slice()),
+        Err(Error::WireOutOfRange { wire: 5, .. })
+    ));
+
+    let mut bytes = b"wtns".to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(1u32.to_le_bytes());
+    put_section(&mut bytes, 1, &u32::MAX.to_le_bytes());
+    assert!(matches!(
+        read_witness_from::<Fr>(&mut bytes.as_slice()),
+        Err(Error::FieldSizeMismatch {
+            expected: 32,
+            actual,
+        }) if actual == u32::MAX as usize
+    ));
+}
+
+#[test]
+fn wrong_witness() {
+    let mut invalid = witness(2, 5);
+    invalid[1] += Fr::ONE;
+
+    assert!(matches!(
+        CircomStepCircuit::<Fr, 1, T>::new(Arc::new(r1cs()), invalid),
+        Err(Error::UnsatisfiedConstraint { index: 1 })
+    ));
+
+    let step = CircomStepCircuit::<Fr, 1, T>::new(Arc::new(r1cs()), witness(2, 5)).unwrap();
+    assert!(step.process_step(&[Fr::from(3)], K).is_err());
+    assert!(matches!(
+        step.with_io([2], [5]),
+        Err(Error::WireOutOfRange { wire: 5, .. })
+    ));
+}
+
+#[traced_test]
+#[test]
+fn circom_step_circuit() {
+    let r1cs = Arc::new(r1cs());
+
+    let mut z_i = [Fr::from(2)];
+    for (step_in, x) in [(2, 5), (16, 1)] {
+        let step = CircomStepCircuit::<Fr, 1, T>::new(r1cs.clone(), witness(step_in, x)).unwrap();
+        let z_out = step.process_step(&z_i, K).unwrap();
+
+        MockProver::run(K, &step, vec![], z_i)
+            .unwrap()
+            .verify(z_out)
+            .unwrap();
+
+        z_i = z_out;
+    }
+
+    assert_eq!(z_i, [Fr::from(292)]);
+}
+
+#[traced_test]
+#[test]
+fn circom_step_circuit_with_io() {
+    // `x` is the input & `square` is the output of the step
+    let step = CircomStepCircuit::<Fr, 1, T>::new(Arc::new(r1cs()), witness(3, 7))
+        .unwrap()
+        .with_io([3], [4])
+        .unwrap();
+
+    let z_out = step.process_step(&[Fr::from(7)], K).unwrap();
+    assert_eq!(z_out, [Fr::from(9)]);
+
+    MockProver::run(K, &step, vec![], [Fr::from(7)])
+        .unwrap()
+        .verify(z_out)
+        .unwrap();
+}
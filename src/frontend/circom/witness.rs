@@ -0,0 +1,38 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use super::{
+    binfile::{self, Sections},
+    Error,
+};
+use crate::ff::PrimeField;
+
+const MAGIC: &str = "wtns";
+const VERSIONS: [u32; 2] = [1, 2];
+
+const HEADER_SECTION: u32 = 1;
+const VALUES_SECTION: u32 = 2;
+
+/// Parses the `.wtns` file, the prime of which must be the modulus of `F`
+pub fn read_witness<F: PrimeField>(path: impl AsRef<Path>) -> Result<Vec<F>, Error> {
+    read_witness_from(&mut BufReader::new(File::open(path)?))
+}
+
+/// Parses the `.wtns` binary, see [`read_witness`]
+pub fn read_witness_from<F: PrimeField>(reader: &mut impl std::io::Read) -> Result<Vec<F>, Error> {
+    let sections = Sections::read(reader, MAGIC, &VERSIONS)?;
+
+    let mut header = sections.get(HEADER_SECTION)?;
+    let field_size = binfile::read_field::<F>(&mut header)?;
+    let values_count = binfile::read_u32(&mut header)? as usize;
+
+    let mut content = sections.get(VALUES_SECTION)?;
+    if content.len() != values_count * field_size {
+        return Err(Error::MalformedSection {
+            section_type: VALUES_SECTION,
+        });
+    }
+
+    (0..values_count)
+        .map(|_| binfile::read_fe(&mut content))
+        .collect()
+}
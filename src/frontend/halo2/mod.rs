@@ -0,0 +1,286 @@
+//! # Halo2 frontend
+//!
+//! Runs any [`halo2_proofs::plonk::Circuit`] as [`crate::ivc::StepCircuit`].
+//!
+//! Public IO of a halo2 circuit is exposed through [`Layouter::constrain_instance`], so the step
+//! input & output are declared as instance cells ([`InstancePosition`]) of the circuit: the
+//! advice cells constrained to them become `z_i` & `z_out`. These calls are intercepted & turned
+//! into copy constraints with `z_i` & `z_out`, while the other instance cells stay public inputs
+//! of the step, see [`crate::ivc::StepCircuit::instances`].
+//!
+//! Designated instance cells must be used only through [`Layouter::constrain_instance`], not
+//! queried by gates, since their values are zeroed.
+
+use std::array;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Cell, Layouter, Region, Table, Value},
+    plonk::{Advice, Challenge, Circuit, Column, ConstraintSystem, Error as Halo2Error, Instance},
+};
+use tracing::*;
+
+use crate::{
+    ff::PrimeField,
+    ivc::{StepCircuit, SynthesisError},
+    main_gate::RegionCtx,
+};
+
+/// Cell of the instance column of the wrapped circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InstancePosition {
+    /// Index among instance columns created by the wrapped circuit
+    pub column: usize,
+    pub row: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("instance position {position:?} is out of instances of the circuit")]
+    PositionOutOfRange { position: InstancePosition },
+    #[error("no cell is constrained to the instance position {position:?}")]
+    MissingCopy { position: InstancePosition },
+    #[error("input #{index} of instances doesn't match `z_i`")]
+    InputMismatch { index: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct Halo2StepConfig<Config: Clone> {
+    circuit: Config,
+    z_out: Column<Advice>,
+    instance_offset: usize,
+}
+
+/// The halo2 circuit with its instances & designated step input & output
+#[derive(Clone, Debug)]
+pub struct Halo2StepCircuit<F: PrimeField, C: Circuit<F>, const ARITY: usize> {
+    circuit: C,
+    instances: Vec<Vec<F>>,
+    inputs: [InstancePosition; ARITY],
+    outputs: [InstancePosition; ARITY],
+}
+
+impl<F: PrimeField, C: Circuit<F>, const ARITY: usize> Halo2StepCircuit<F, C, ARITY> {
+    pub fn new(
+        circuit: C,
+        instances: Vec<Vec<F>>,
+        inputs: [InstancePosition; ARITY],
+        outputs: [InstancePosition; ARITY],
+    ) -> Result<Self, Error> {
+        if let Some(position) = inputs.iter().chain(outputs.iter()).find(|position| {
+            instances
+                .get(position.column)
+                .map_or(true, |column| column.len() <= position.row)
+        }) {
+            return Err(Error::PositionOutOfRange {
+                position: *position,
+            });
+        }
+
+        Ok(Self {
+            circuit,
+            instances,
+            inputs,
+            outputs,
+        })
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.circuit
+    }
+
+    fn instance(&self, position: &InstancePosition) -> F {
+        self.instances[position.column][position.row]
+    }
+}
+
+impl<F: PrimeField, C: Circuit<F>, const ARITY: usize> StepCircuit<ARITY, F>
+    for Halo2StepCircuit<F, C, ARITY>
+{
+    type Config = Halo2StepConfig<C::Config>;
+
+    /// Instances of the wrapped circuit, where designated positions are zeroed
+    fn instances(&self) -> Vec<Vec<F>> {
+        let mut instances = self.instances.clone();
+        for position in self.inputs.iter().chain(self.outputs.iter()) {
+            instances[position.column][position.row] = F::ZERO;
+        }
+        instances
+    }
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance_offset = cs.num_instance_columns();
+        let circuit = C::configure(cs);
+
+        let z_out = cs.advice_column();
+        cs.enable_equality(z_out);
+
+        Halo2StepConfig {
+            circuit,
+            z_out,
+            instance_offset,
+        }
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; ARITY],
+    ) -> Result<[AssignedCell<F, F>; ARITY], SynthesisError> {
+        let positions = self
+            .inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let mut copies = vec![vec![]; positions.len()];
+
+        self.circuit.synthesize(
+            config.circuit,
+            IOLayouter {
+                inner: &mut *layouter,
+                instance_offset: config.instance_offset,
+                positions: &positions,
+                copies: &mut copies,
+            },
+        )?;
+
+        if let Some(slot) = copies.iter().position(Vec::is_empty) {
+            error!(
+                "{}",
+                Error::MissingCopy {
+                    position: positions[slot]
+                }
+            );
+            return Err(SynthesisError::Halo2(Halo2Error::Synthesis));
+        }
+
+        let (input_copies, output_copies) = copies.split_at(ARITY);
+
+        let z_out = layouter.assign_region(
+            || "halo2 step io",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                for (cells, z) in input_copies.iter().zip(z_i.iter()) {
+                    for cell in cells {
+                        ctx.constrain_equal(*cell, z.cell())?;
+                    }
+                }
+
+                let z_out = output_copies
+                    .iter()
+                    .zip(self.outputs.iter())
+                    .map(|(cells, position)| {
+                        let z = ctx.assign_advice(
+                            || "z_out",
+                            config.z_out,
+                            Value::known(self.instance(position)),
+                        )?;
+                        for cell in cells {
+                            ctx.constrain_equal(*cell, z.cell())?;
+                        }
+                        ctx.next();
+
+                        Ok(z)
+                    })
+                    .collect::<Result<Vec<_>, Halo2Error>>()?;
+
+                Ok(z_out.try_into().unwrap())
+            },
+        )?;
+
+        Ok(z_out)
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; ARITY],
+        _k_table_size: u32,
+    ) -> Result<[F; ARITY], SynthesisError> {
+        if let Some(index) = self
+            .inputs
+            .iter()
+            .zip(z_i.iter())
+            .position(|(position, z)| self.instance(position) != *z)
+        {
+            error!("{}", Error::InputMismatch { index });
+            return Err(SynthesisError::Halo2(Halo2Error::Synthesis));
+        }
+
+        Ok(array::from_fn(|index| self.instance(&self.outputs[index])))
+    }
+}
+
+/// Passes everything to the inner layouter, except for copies to designated instance cells,
+/// which are collected
+struct IOLayouter<'l, F: PrimeField, L: Layouter<F>> {
+    inner: &'l mut L,
+    instance_offset: usize,
+    positions: &'l [InstancePosition],
+    copies: &'l mut [Vec<Cell>],
+}
+
+impl<'l, F: PrimeField, L: Layouter<F>> Layouter<F> for IOLayouter<'l, F, L> {
+    type Root = Self;
+
+    fn assign_region<A, AR, N, NR>(&mut self, name: N, assignment: A) -> Result<AR, Halo2Error>
+    where
+        A: FnMut(Region<'_, F>) -> Result<AR, Halo2Error>,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        self.inner.assign_region(name, assignment)
+    }
+
+    fn assign_table<A, N, NR>(&mut self, name: N, assignment: A) -> Result<(), Halo2Error>
+    where
+        A: FnMut(Table<'_, F>) -> Result<(), Halo2Error>,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        self.inner.assign_table(name, assignment)
+    }
+
+    fn constrain_instance(
+        &mut self,
+        cell: Cell,
+        column: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Halo2Error> {
+        let slot = self.positions.iter().position(|position| {
+            self.instance_offset + position.column == column.index() && position.row == row
+        });
+
+        match slot {
+            Some(slot) => {
+                self.copies[slot].push(cell);
+                Ok(())
+            }
+            None => self.inner.constrain_instance(cell, column, row),
+        }
+    }
+
+    fn get_challenge(&self, challenge: Challenge) -> Value<F> {
+        self.inner.get_challenge(challenge)
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.inner.push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self, gadget_name: Option<String>) {
+        self.inner.pop_namespace(gadget_name)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,181 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver as Halo2MockProver,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error as Halo2Error, Instance, Selector},
+    poly::Rotation,
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{ff::Field, halo2curves::bn256::Fr, util::mock_prover::MockProver};
+
+const K: u32 = 6;
+
+#[derive(Clone, Debug)]
+struct SquareConfig {
+    x: Column<Advice>,
+    c: Column<Advice>,
+    out: Column<Advice>,
+    s: Selector,
+    instance: Column<Instance>,
+}
+
+/// Plain halo2 circuit: `out = x * x + c`, where instances are `[x, out, c]`
+#[derive(Clone, Debug)]
+struct SquareCircuit {
+    x: Value<Fr>,
+}
+
+impl SquareCircuit {
+    fn new(x: u64) -> Self {
+        Self {
+            x: Value::known(Fr::from(x)),
+        }
+    }
+}
+
+impl Circuit<Fr> for SquareCircuit {
+    type Config = SquareConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let x = meta.advice_column();
+        let c = meta.advice_column();
+        let out = meta.advice_column();
+        let instance = meta.instance_column();
+        for column in [x, c, out] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        let s = meta.selector();
+        meta.create_gate("out = x * x + c", |meta| {
+            let s = meta.query_selector(s);
+            let x = meta.query_advice(x, Rotation::cur());
+            let c = meta.query_advice(c, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (x.clone() * x + c - out)]
+        });
+
+        SquareConfig {
+            x,
+            c,
+            out,
+            s,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Halo2Error> {
+        let (x, out) = layouter.assign_region(
+            || "square",
+            |mut region| {
+                config.s.enable(&mut region, 0)?;
+
+                let x = region.assign_advice(|| "x", config.x, 0, || self.x)?;
+                let c =
+                    region.assign_advice_from_instance(|| "c", config.instance, 2, config.c, 0)?;
+                let out = region.assign_advice(
+                    || "out",
+                    config.out,
+                    0,
+                    || self.x * self.x + c.value().copied(),
+                )?;
+
+                Ok((x, out))
+            },
+        )?;
+
+        layouter
+            .namespace(|| "io")
+            .constrain_instance(x.cell(), config.instance, 0)?;
+        layouter.constrain_instance(out.cell(), config.instance, 1)
+    }
+}
+
+const INPUTS: [InstancePosition; 1] = [InstancePosition { column: 0, row: 0 }];
+const OUTPUTS: [InstancePosition; 1] = [InstancePosition { column: 0, row: 1 }];
+
+fn instances(x: u64, c: u64) -> Vec<Vec<Fr>> {
+    vec![[x, x * x + c, c].map(Fr::from).to_vec()]
+}
+
+#[traced_test]
+#[test]
+fn halo2_step_circuit() {
+    let mut z_i = [Fr::from(3)];
+    for (x, expected) in [(3, 14), (14, 201)] {
+        Halo2MockProver::run(K, &SquareCircuit::new(x), instances(x, 5))
+            .unwrap()
+            .verify()
+            .unwrap();
+
+        let step =
+            Halo2StepCircuit::new(SquareCircuit::new(x), instances(x, 5), INPUTS, OUTPUTS).unwrap();
+        assert_eq!(
+            step.instances(),
+            vec![vec![Fr::ZERO, Fr::ZERO, Fr::from(5)]]
+        );
+
+        let z_out = step.process_step(&z_i, K).unwrap();
+        assert_eq!(z_out, [Fr::from(expected)]);
+
+        MockProver::run(K, &step, step.instances(), z_i)
+            .unwrap()
+            .verify(z_out)
+            .unwrap();
+
+        z_i = z_out;
+    }
+}
+
+#[traced_test]
+#[test]
+fn halo2_step_circuit_errors() {
+    assert!(matches!(
+        Halo2StepCircuit::new(
+            SquareCircuit::new(3),
+            instances(3, 5),
+            [InstancePosition { column: 0, row: 3 }],
+            OUTPUTS
+        ),
+        Err(Error::PositionOutOfRange { .. })
+    ));
+
+    let step =
+        Halo2StepCircuit::new(SquareCircuit::new(3), instances(3, 5), INPUTS, OUTPUTS).unwrap();
+    assert!(step.process_step(&[Fr::from(4)], K).is_err());
+
+    // `c` is copied by the region, not by `constrain_instance`
+    let step = Halo2StepCircuit::new(
+        SquareCircuit::new(3),
+        instances(3, 5),
+        INPUTS,
+        [InstancePosition { column: 0, row: 2 }],
+    )
+    .unwrap();
+    assert!(MockProver::run(K, &step, step.instances(), [Fr::from(3)]).is_err());
+}
+
+#[traced_test]
+#[test]
+fn halo2_step_circuit_wrong_output() {
+    let mut instances = instances(3, 5);
+    instances[0][1] = Fr::from(15);
+
+    let step = Halo2StepCircuit::new(SquareCircuit::new(3), instances, INPUTS, OUTPUTS).unwrap();
+    let z_out = step.process_step(&[Fr::from(3)], K).unwrap();
+
+    assert!(MockProver::run(K, &step, step.instances(), [Fr::from(3)])
+        .unwrap()
+        .verify(z_out)
+        .is_err());
+}
@@ -0,0 +1,11 @@
+//! Adapters of circuits written for other proving systems into [`crate::ivc::StepCircuit`]
+//!
+//! - [`r1cs`] - R1CS constraints laid out on [`crate::main_gate::MainGate`]
+//! - [`circom`] - `.r1cs` & `.wtns` files produced by circom
+//! - [`bellpepper`] - circuits over bellpepper `ConstraintSystem`, as in Nova
+//! - [`halo2`] - any halo2 `Circuit` with designated instance cells as the step IO
+
+pub mod bellpepper;
+pub mod circom;
+pub mod halo2;
+pub mod r1cs;
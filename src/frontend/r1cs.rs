@@ -0,0 +1,141 @@
+//! R1CS constraints laid out on [`MainGate`]
+//!
+//! Each constraint `<A, w> * <B, w> = <C, w>` takes one row for the product & one row per
+//! `T` terms of each linear combination, except for combinations of a single variable, which are
+//! used as is.
+
+use halo2_proofs::{
+    circuit::{Chip, Value},
+    plonk::Error,
+};
+
+use crate::{
+    ff::PrimeField,
+    main_gate::{AssignedValue, MainGate, MainGateConfig, RegionCtx, WrapValue},
+};
+
+/// On-circuit linear combination `sum coeff * cell`
+pub type AssignedLinearCombination<F> = [(AssignedValue<F>, F)];
+
+/// Lays out R1CS over assigned variables, requires `T >= 2`
+#[derive(Debug)]
+pub struct R1CSChip<F: PrimeField, const T: usize> {
+    main_gate: MainGate<F, T>,
+}
+
+impl<F: PrimeField, const T: usize> R1CSChip<F, T> {
+    pub fn new(config: MainGateConfig<T>) -> Self {
+        Self {
+            main_gate: MainGate::new(config),
+        }
+    }
+
+    pub fn main_gate(&self) -> &MainGate<F, T> {
+        &self.main_gate
+    }
+
+    /// Assigns `value` constrained to be equal to the constant
+    pub fn assign_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: F,
+    ) -> Result<AssignedValue<F>, Error> {
+        self.main_gate.apply(
+            ctx,
+            (None, None, None),
+            Some(value),
+            (-F::ONE, value.into()),
+        )
+    }
+
+    /// Assigns unconstrained variables, `T` per row
+    pub fn assign_variables(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        values: impl IntoIterator<Item = Value<F>>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let state = self.main_gate.config().state;
+
+        let mut assigned = vec![];
+        let mut values = values.into_iter().peekable();
+        while values.peek().is_some() {
+            for (column, value) in state.iter().zip(values.by_ref()) {
+                assigned.push(ctx.assign_advice(|| "variable", *column, value)?);
+            }
+            ctx.next();
+        }
+
+        Ok(assigned)
+    }
+
+    /// Returns the cell with the value of `lc`
+    pub fn assign_lc(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lc: &AssignedLinearCombination<F>,
+    ) -> Result<AssignedValue<F>, Error> {
+        match lc {
+            [] => self.assign_constant(ctx, F::ZERO),
+            [(cell, coeff)] if *coeff == F::ONE => Ok(cell.clone()),
+            lc => {
+                let mut chunks = lc.chunks(T);
+                let first_chunk = chunks.next().expect("lc isn't empty");
+
+                let eval = |acc: Value<F>, chunk: &AssignedLinearCombination<F>| {
+                    chunk.iter().fold(acc, |acc, (cell, coeff)| {
+                        acc + cell.value().copied() * Value::known(*coeff)
+                    })
+                };
+                let unzip = |chunk: &AssignedLinearCombination<F>| {
+                    chunk
+                        .iter()
+                        .map(|(cell, coeff)| (*coeff, WrapValue::from(cell)))
+                        .unzip::<_, _, Vec<_>, Vec<_>>()
+                };
+
+                let (coeffs, cells) = unzip(first_chunk);
+                let mut acc = self.main_gate.apply(
+                    ctx,
+                    (Some(coeffs), None, Some(cells)),
+                    None,
+                    (-F::ONE, eval(Value::known(F::ZERO), first_chunk).into()),
+                )?;
+
+                for chunk in chunks {
+                    let (coeffs, cells) = unzip(chunk);
+                    let value = eval(acc.value().copied(), chunk);
+                    acc = self.main_gate.apply_with_input(
+                        ctx,
+                        (Some(coeffs), None, Some(cells)),
+                        (Some(F::ONE), Some(acc.into())),
+                        (-F::ONE, value.into()),
+                    )?;
+                }
+
+                Ok(acc)
+            }
+        }
+    }
+
+    /// Constrains `a * b = c`
+    pub fn enforce(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedLinearCombination<F>,
+        b: &AssignedLinearCombination<F>,
+        c: &AssignedLinearCombination<F>,
+    ) -> Result<(), Error> {
+        let a = self.assign_lc(ctx, a)?;
+        let b = self.assign_lc(ctx, b)?;
+        let c = self.assign_lc(ctx, c)?;
+
+        self.main_gate.apply(
+            ctx,
+            (None, Some(vec![F::ONE]), Some(vec![a.into(), b.into()])),
+            None,
+            (-F::ONE, c.into()),
+        )?;
+
+        Ok(())
+    }
+}
@@ -8,10 +8,95 @@ use halo2_proofs::{
 use tracing::*;
 
 use crate::{
-    ff::PrimeFieldBits,
-    main_gate::{AssignedValue, MainGate, MainGateConfig, RegionCtx},
+    ff::{Field, PrimeField, PrimeFieldBits, WithSmallOrderMulGroup},
+    group::{prime::PrimeCurveAffine, Curve},
+    halo2curves::arithmetic::CurveEndo,
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx},
 };
 
+/// Number of scalar bits processed by one step of the windowed methods of [`EccChip`]
+pub const DEFAULT_WINDOW_SIZE: usize = 4;
+
+/// The table of a window has `2^window_size` points, so the size is limited to keep it sane
+pub const MAX_WINDOW_SIZE: usize = 6;
+
+/// Bits count of each half of the scalar in [`AssignedGlvScalar`]
+pub const GLV_HALF_BITS: usize = 128;
+
+/// Seed of the x-coordinate of [`aux_point`]
+const AUX_SEED: u128 = 0xa409_3822_299f_31d0_082e_fa98_ec4e_6c89;
+
+/// Coordinates in the representation of [`AssignedPoint`], where `(0, 0)` is the infinity
+fn point_coordinates<C: CurveAffine>(point: &C) -> (C::Base, C::Base) {
+    Option::from(point.coordinates().map(|c| (*c.x(), *c.y())))
+        .unwrap_or((C::Base::ZERO, C::Base::ZERO))
+}
+
+/// Point found by try-and-increment from [`AUX_SEED`] shifted by `index`, so nobody knows its
+/// discrete logarithm, nor the relation between points with different `index`
+///
+/// The windowed methods of [`EccChip`] start the accumulator from these points, so the incomplete
+/// additions never meet the point at infinity
+fn aux_point<C: CurveAffine>(index: u64) -> C {
+    let mut x = C::Base::from_u128(AUX_SEED) + C::Base::from_u128((index as u128) << 64);
+    loop {
+        let y_square = x.square() * x + C::a() * x + C::b();
+        let point = Option::from(y_square.sqrt()).and_then(|y| Option::from(C::from_xy(x, y)));
+        if let Some(point) = point {
+            return point;
+        }
+        x += C::Base::ONE;
+    }
+}
+
+/// `(lambda, beta)`, such that `lambda * (x, y) = (beta * x, y)` for any point of `C`
+pub fn endomorphism<C>() -> (C::Scalar, C::Base)
+where
+    C: CurveAffine,
+    C::Base: WithSmallOrderMulGroup<3>,
+    C::Scalar: WithSmallOrderMulGroup<3>,
+{
+    let lambda = C::Scalar::ZETA;
+
+    let generator = C::generator();
+    let (x, y) = point_coordinates(&generator);
+    let expected = point_coordinates(&(generator * lambda).to_affine());
+
+    let beta = [C::Base::ZETA, C::Base::ZETA.square()]
+        .into_iter()
+        .find(|beta| (*beta * x, y) == expected)
+        .expect("both `ZETA` are cube roots of unity, so one of them matches `lambda`");
+
+    (lambda, beta)
+}
+
+/// Splits `k` into `(k1, k1_neg, k2, k2_neg)`, such that `k = ±k1 ± lambda * k2`, where `lambda`
+/// is from [`endomorphism`] & the sign is minus if the flag is set
+pub fn glv_decompose<C>(k: &C::Scalar) -> (u128, bool, u128, bool)
+where
+    C: CurveAffine,
+    C::CurveExt: CurveEndo,
+    C::Scalar: WithSmallOrderMulGroup<3>,
+{
+    let (k1, _, k2, _) = C::CurveExt::decompose_scalar(k);
+
+    let signed = |value: u128, is_neg: bool| {
+        let value = C::Scalar::from_u128(value);
+        if is_neg {
+            -value
+        } else {
+            value
+        }
+    };
+
+    // the sign convention of `decompose_scalar` differs between curves, so check it here
+    [(false, false), (false, true), (true, false), (true, true)]
+        .into_iter()
+        .find(|(k1_neg, k2_neg)| signed(k1, *k1_neg) + C::Scalar::ZETA * signed(k2, *k2_neg) == *k)
+        .map(|(k1_neg, k2_neg)| (k1, k1_neg, k2, k2_neg))
+        .expect("`decompose_scalar` splits by `ZETA` of the scalar field")
+}
+
 // assume point is not infinity
 #[derive(Clone, Debug)]
 pub struct AssignedPoint<C: CurveAffine> {
@@ -37,6 +122,17 @@ impl<C: CurveAffine> AssignedPoint<C> {
     }
 }
 
+/// Scalar `k = ±k1 ± lambda * k2` given by its GLV decomposition, see [`glv_decompose`]
+#[derive(Clone, Debug)]
+pub struct AssignedGlvScalar<F: PrimeField> {
+    /// Little-endian bits of `k1`
+    pub k1: Vec<AssignedBit<F>>,
+    pub k1_neg: AssignedBit<F>,
+    /// Little-endian bits of `k2`
+    pub k2: Vec<AssignedBit<F>>,
+    pub k2_neg: AssignedBit<F>,
+}
+
 pub struct EccChip<C: CurveAffine<Base = F>, F: PrimeFieldBits, const T: usize> {
     main_gate: MainGate<C::Base, T>,
 }
@@ -314,11 +410,40 @@ impl<C: CurveAffine<Base = F>, F: PrimeFieldBits, const T: usize> EccChip<C, F,
         let yd = self.main_gate.sub(ctx, &p.y, &q.y)?;
         let xd = self.main_gate.sub(ctx, &p.x, &q.x)?;
         let lambda = self.main_gate.divide(ctx, &yd, &xd)?;
-        let lambda2 = self.main_gate.square(ctx, &lambda)?;
+        self._add_by_slope(ctx, &lambda, p, &q.x)
+    }
+
+    // same as `_add_unsafe`, but the circuit is unsatisfiable if `p.x == q.x`, synthesis fails if
+    // it's known
+    fn _add_incomplete(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        p: &AssignedPoint<C>,
+        q: &AssignedPoint<C>,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let yd = self.main_gate.sub(ctx, &p.y, &q.y)?;
+        let xd = self.main_gate.sub(ctx, &p.x, &q.x)?;
+        let (is_zero, xd_inv) = self.main_gate.invert_with_flag(ctx, xd)?;
+        Self::check_not_degenerate(&is_zero, "incomplete addition of points with equal `x`")?;
+        self.main_gate
+            .assert_equal_const(ctx, is_zero, C::Base::ZERO)?;
+        let lambda = self.main_gate.mul(ctx, &yd, &xd_inv)?;
+        self._add_by_slope(ctx, &lambda, p, &q.x)
+    }
+
+    // point (xr, lambda * (p.x - xr) - p.y), where xr = lambda^2 - p.x - other_x
+    fn _add_by_slope(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        lambda: &AssignedValue<C::Base>,
+        p: &AssignedPoint<C>,
+        other_x: &AssignedValue<C::Base>,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let lambda2 = self.main_gate.square(ctx, lambda)?;
         let tmp1 = self.main_gate.sub(ctx, &lambda2, &p.x)?;
-        let xr = self.main_gate.sub(ctx, &tmp1, &q.x)?;
+        let xr = self.main_gate.sub(ctx, &tmp1, other_x)?;
         let tmp2 = self.main_gate.sub(ctx, &p.x, &xr)?;
-        let tmp3 = self.main_gate.mul(ctx, &lambda, &tmp2)?;
+        let tmp3 = self.main_gate.mul(ctx, lambda, &tmp2)?;
         let yr = self.main_gate.sub(ctx, &tmp3, &p.y)?;
         Ok(AssignedPoint { x: xr, y: yr })
     }
@@ -351,14 +476,35 @@ impl<C: CurveAffine<Base = F>, F: PrimeFieldBits, const T: usize> EccChip<C, F,
         let lnum = self.main_gate.mul_by_const(ctx, &xp2, C::Base::from(3))?;
         let lden = self.main_gate.add(ctx, &p.y, &p.y)?;
         let lambda = self.main_gate.divide(ctx, &lnum, &lden)?;
-        let lambda2 = self.main_gate.square(ctx, &lambda)?;
+        self._add_by_slope(ctx, &lambda, p, &p.x)
+    }
 
-        let tmp1 = self.main_gate.sub(ctx, &lambda2, &p.x)?;
-        let xr = self.main_gate.sub(ctx, &tmp1, &p.x)?;
-        let tmp2 = self.main_gate.sub(ctx, &p.x, &xr)?;
-        let tmp3 = self.main_gate.mul(ctx, &lambda, &tmp2)?;
-        let yr = self.main_gate.sub(ctx, &tmp3, &p.y)?;
-        Ok(AssignedPoint { x: xr, y: yr })
+    // same as `_double_unsafe`, but the circuit is unsatisfiable if `p.y == 0`, synthesis fails if
+    // it's known
+    fn _double_incomplete(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        p: &AssignedPoint<C>,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let xp2 = self.main_gate.square(ctx, &p.x)?;
+        let lnum = self.main_gate.mul_by_const(ctx, &xp2, C::Base::from(3))?;
+        let lden = self.main_gate.add(ctx, &p.y, &p.y)?;
+        let (is_zero, lden_inv) = self.main_gate.invert_with_flag(ctx, lden)?;
+        Self::check_not_degenerate(&is_zero, "incomplete doubling of point with zero `y`")?;
+        self.main_gate
+            .assert_equal_const(ctx, is_zero, C::Base::ZERO)?;
+        let lambda = self.main_gate.mul(ctx, &lnum, &lden_inv)?;
+        self._add_by_slope(ctx, &lambda, p, &p.x)
+    }
+
+    // fails synthesis on the known flag of `invert_with_flag`, the constraint on the flag would
+    // make the circuit unsatisfiable anyway
+    fn check_not_degenerate(is_zero: &AssignedValue<C::Base>, case: &str) -> Result<(), Error> {
+        if is_zero.value().copied().unwrap() == Some(C::Base::ONE) {
+            error!("{case}");
+            return Err(Error::Synthesis);
+        }
+        Ok(())
     }
 
     pub fn conditional_select(
@@ -377,6 +523,335 @@ impl<C: CurveAffine<Base = F>, F: PrimeFieldBits, const T: usize> EccChip<C, F,
                 .conditional_select(ctx, &lhs.y, &rhs.y, condition)?,
         })
     }
+
+    /// `-p` if `condition` is `1` & `p` if it's `0`
+    pub fn conditional_negate(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        p: &AssignedPoint<C>,
+        condition: &AssignedValue<C::Base>,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let neg = self.negate(ctx, p)?;
+        self.conditional_select(ctx, &neg, p, condition)
+    }
+
+    /// Assigns the point bound to the constant, the infinity is bound to `(0, 0)`
+    pub fn assign_constant_point(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        point: &C,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let (x, y) = point_coordinates(point);
+        Ok(AssignedPoint {
+            x: self.assign_constant(ctx, x)?,
+            y: self.assign_constant(ctx, y)?,
+        })
+    }
+
+    // out = c, by one row
+    fn assign_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        c: C::Base,
+    ) -> Result<AssignedValue<C::Base>, Error> {
+        self.main_gate
+            .apply(ctx, (None, None, None), Some(c), (-C::Base::ONE, c.into()))
+    }
+
+    // cond * lhs + (1 - cond) * rhs for constants, by one row
+    fn select_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        lhs: C::Base,
+        rhs: C::Base,
+        cond: &AssignedValue<C::Base>,
+    ) -> Result<AssignedValue<C::Base>, Error> {
+        let val = cond.value().map(|cond| *cond * (lhs - rhs) + rhs);
+        self.main_gate.apply(
+            ctx,
+            (Some(vec![lhs - rhs]), None, Some(vec![cond.into()])),
+            Some(rhs),
+            (-C::Base::ONE, val.into()),
+        )
+    }
+
+    /// Selects `table[index]`, where `index` is given by little-endian `bits`
+    fn select_from_table(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        table: &[AssignedPoint<C>],
+        bits: &[AssignedValue<C::Base>],
+    ) -> Result<AssignedPoint<C>, Error> {
+        let mut layer = table[..1 << bits.len()].to_vec();
+        for bit in bits {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.conditional_select(ctx, &pair[1], &pair[0], bit))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        Ok(layer.pop().expect("table size is a power of two"))
+    }
+
+    /// Same as [`Self::select_from_table`], but the first layer of the selection picks between
+    /// constants, so the table is never assigned
+    fn select_from_constant_table(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        table: &[C],
+        bits: &[AssignedValue<C::Base>],
+    ) -> Result<AssignedPoint<C>, Error> {
+        let Some((first_bit, rest_bits)) = bits.split_first() else {
+            return self.assign_constant_point(ctx, &table[0]);
+        };
+
+        let layer = table[..1 << bits.len()]
+            .chunks(2)
+            .map(|pair| -> Result<_, Error> {
+                let (lhs_x, lhs_y) = point_coordinates(&pair[1]);
+                let (rhs_x, rhs_y) = point_coordinates(&pair[0]);
+                Ok(AssignedPoint {
+                    x: self.select_constant(ctx, lhs_x, rhs_x, first_bit)?,
+                    y: self.select_constant(ctx, lhs_y, rhs_y, first_bit)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.select_from_table(ctx, &layer, rest_bits)
+    }
+
+    fn check_window_size(window_size: usize) -> Result<(), Error> {
+        if window_size == 0 || window_size > MAX_WINDOW_SIZE {
+            error!("window size must be in 1..={MAX_WINDOW_SIZE}, but it's {window_size}");
+            return Err(Error::Synthesis);
+        }
+        Ok(())
+    }
+
+    /// `scalar * p` by the windowed method, see [`Self::msm`]
+    ///
+    /// Unlike [`Self::scalar_mul`], which spends a doubling & an addition on each bit, it spends
+    /// an addition on each window of `window_size` bits & `2^window_size - 1` additions on the
+    /// table of the point
+    pub fn scalar_mul_windowed(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        p: &AssignedPoint<C>,
+        scalar_bits: &[AssignedValue<C::Base>],
+        window_size: usize,
+    ) -> Result<AssignedPoint<C>, Error> {
+        self.msm(ctx, &[(p, scalar_bits)], window_size)
+    }
+
+    /// `sum(scalar_i * p_i)` by the windowed method with shared doublings
+    ///
+    /// For each term the table `[aux_i, aux_i + p_i, ..., aux_i + (2^w - 1) * p_i]` is built,
+    /// where `aux_i` is [`aux_point`] with index `i`. Then for each window of `window_size`
+    /// bits, starting from the most significant one, the accumulator is doubled `w` times & the
+    /// table entries of all terms are added to it. Finally, the sum of all auxiliary points,
+    /// multiplied by their shifts, is subtracted by the complete addition, so the result can be
+    /// the point at infinity.
+    ///
+    /// Points at infinity are replaced by the generator & their table entries by `aux_i`.
+    ///
+    /// All other additions are incomplete: synthesis fails with [`Error::Synthesis`] if an
+    /// intermediate sum meets equal `x`, while the circuit is unsatisfiable for such a witness. Since the auxiliary points are independent, it requires a known relation
+    /// between them & the points `p_i`, so zero windows (e.g. when scalars are shorter than
+    /// their bit representation) are safe.
+    pub fn msm(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        terms: &[(&AssignedPoint<C>, &[AssignedValue<C::Base>])],
+        window_size: usize,
+    ) -> Result<AssignedPoint<C>, Error> {
+        Self::check_window_size(window_size)?;
+
+        let windows_count = terms
+            .iter()
+            .map(|(_, bits)| bits.len().div_ceil(window_size))
+            .max()
+            .unwrap_or_default();
+        if windows_count == 0 {
+            error!("msm of zero terms or empty scalars");
+            return Err(Error::Synthesis);
+        }
+
+        let auxes = (0..terms.len() as u64)
+            .map(aux_point::<C>)
+            .collect::<Vec<_>>();
+        let generator = self.assign_constant_point(ctx, &C::generator())?;
+
+        let mut tables = Vec::with_capacity(terms.len());
+        let mut is_infinity = Vec::with_capacity(terms.len());
+        for ((p, _), aux) in terms.iter().zip(&auxes) {
+            let is_p_iden = self.main_gate.is_infinity_point(ctx, &p.x, &p.y)?;
+            let p = self.conditional_select(ctx, &generator, p, &is_p_iden)?;
+
+            let mut table = vec![self.assign_constant_point(ctx, aux)?];
+            for _ in 1..(1 << window_size) {
+                let last = table.last().expect("table is not empty");
+                table.push(self._add_incomplete(ctx, last, &p)?);
+            }
+
+            tables.push(table);
+            is_infinity.push(is_p_iden);
+        }
+
+        debug!("msm of {} terms by {windows_count} windows", terms.len());
+
+        let mut acc = Option::<AssignedPoint<C>>::None;
+        for window_index in (0..windows_count).rev() {
+            if let Some(mut p) = acc.take() {
+                for _ in 0..window_size {
+                    p = self._double_incomplete(ctx, &p)?;
+                }
+                acc = Some(p);
+            }
+
+            for ((table, is_p_iden), (_, bits)) in tables.iter().zip(&is_infinity).zip(terms) {
+                let start = cmp::min(window_index * window_size, bits.len());
+                let end = cmp::min(start + window_size, bits.len());
+
+                let entry = if start == end {
+                    table[0].clone()
+                } else {
+                    let entry = self.select_from_table(ctx, table, &bits[start..end])?;
+                    self.conditional_select(ctx, &table[0], &entry, is_p_iden)?
+                };
+
+                acc = Some(match acc {
+                    Some(acc) => self._add_incomplete(ctx, &acc, &entry)?,
+                    None => entry,
+                });
+            }
+        }
+
+        // sum(aux_i) * sum(2^(w * j))
+        let shifts = (0..windows_count).fold(C::Scalar::ZERO, |sum, window_index| {
+            sum + C::Scalar::from(2).pow_vartime([(window_size * window_index) as u64])
+        });
+        let aux_sum = auxes.iter().map(|aux| aux.to_curve()).sum::<C::CurveExt>();
+        let correction = -(aux_sum * shifts).to_affine();
+        let correction = self.assign_constant_point(ctx, &correction)?;
+
+        self.add(ctx, &acc.expect("windows count is not zero"), &correction)
+    }
+
+    /// `scalar * base` for the `base` known at synthesis time, e.g. a generator
+    ///
+    /// The window `j` selects `aux_j + k * 2^(w * j) * base` from a constant table, where
+    /// `aux_j = 2^j * aux` & `aux` is [`aux_point`], so there are no doublings & no tables to
+    /// assign. The selected points are summed by incomplete additions & `(2^windows - 1) * aux`
+    /// is subtracted by the complete addition, so the result can be the point at infinity.
+    pub fn fixed_base_scalar_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, C::Base>,
+        base: &C,
+        scalar_bits: &[AssignedValue<C::Base>],
+        window_size: usize,
+    ) -> Result<AssignedPoint<C>, Error> {
+        Self::check_window_size(window_size)?;
+
+        if scalar_bits.is_empty() {
+            error!("fixed-base scalar mul of empty scalar");
+            return Err(Error::Synthesis);
+        }
+
+        let aux = aux_point::<C>(0).to_curve();
+
+        let mut window_aux = aux;
+        let mut window_base = base.to_curve();
+        let mut acc = Option::<AssignedPoint<C>>::None;
+        for bits in scalar_bits.chunks(window_size) {
+            let table = (0..1u64 << window_size)
+                .map(|k| (window_aux + window_base * C::Scalar::from(k)).to_affine())
+                .collect::<Vec<_>>();
+            let entry = self.select_from_constant_table(ctx, &table, bits)?;
+
+            acc = Some(match acc {
+                Some(acc) => self._add_incomplete(ctx, &acc, &entry)?,
+                None => entry,
+            });
+
+            window_aux = window_aux + window_aux;
+            window_base = window_base * C::Scalar::from(1 << window_size);
+        }
+
+        // `window_aux` is `2^windows * aux` here
+        let correction = (aux - window_aux).to_affine();
+        let correction = self.assign_constant_point(ctx, &correction)?;
+
+        self.add(ctx, &acc.expect("scalar is not empty"), &correction)
+    }
+}
+
+impl<C, F, const T: usize> EccChip<C, F, T>
+where
+    C: CurveAffine<Base = F>,
+    C::CurveExt: CurveEndo,
+    C::Scalar: WithSmallOrderMulGroup<3>,
+    F: PrimeFieldBits + WithSmallOrderMulGroup<3>,
+{
+    /// Assigns bits of [`glv_decompose`] of the `scalar`
+    ///
+    /// The decomposition isn't constrained to be equal to the `scalar`, so the caller must take
+    /// the scalar in this form, e.g. as a witness, or bind it on its own
+    pub fn assign_glv_scalar(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        scalar: Value<C::Scalar>,
+    ) -> Result<AssignedGlvScalar<F>, Error> {
+        let (k1, k1_neg, k2, k2_neg) = scalar
+            .unwrap()
+            .map(|scalar| glv_decompose::<C>(&scalar))
+            .unwrap_or_default();
+
+        let to_bits = |value: u128| {
+            (0..GLV_HALF_BITS)
+                .map(|index| (value >> index) & 1 == 1)
+                .collect::<Vec<_>>()
+        };
+
+        Ok(AssignedGlvScalar {
+            k1: self.main_gate.assign_bits(ctx, &to_bits(k1))?,
+            k1_neg: self
+                .main_gate
+                .assign_bit(ctx, Value::known(F::from(k1_neg as u64)))?,
+            k2: self.main_gate.assign_bits(ctx, &to_bits(k2))?,
+            k2_neg: self
+                .main_gate
+                .assign_bit(ctx, Value::known(F::from(k2_neg as u64)))?,
+        })
+    }
+
+    /// `k * p` as `±k1 * p ± k2 * phi(p)`, where `phi(x, y) = (beta * x, y)` is the endomorphism
+    /// of [`endomorphism`]
+    ///
+    /// It's a [`Self::msm`] of two terms with scalars of [`GLV_HALF_BITS`], so it takes half of
+    /// the doublings of [`Self::scalar_mul_windowed`]
+    pub fn scalar_mul_glv(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedPoint<C>,
+        scalar: &AssignedGlvScalar<F>,
+        window_size: usize,
+    ) -> Result<AssignedPoint<C>, Error> {
+        let (_, beta) = endomorphism::<C>();
+
+        let endo_p = AssignedPoint {
+            x: self.main_gate.mul_by_const(ctx, &p.x, beta)?,
+            y: p.y.clone(),
+        };
+
+        let p = self.conditional_negate(ctx, p, &scalar.k1_neg)?;
+        let endo_p = self.conditional_negate(ctx, &endo_p, &scalar.k2_neg)?;
+
+        self.msm(
+            ctx,
+            &[(&p, scalar.k1.as_slice()), (&endo_p, scalar.k2.as_slice())],
+            window_size,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -387,7 +862,7 @@ mod tests {
         circuit::{Layouter, SimpleFloorPlanner},
         plonk::{Circuit, Column, ConstraintSystem, Instance},
     };
-    use rand_core::OsRng;
+    use rand_core::{OsRng, RngCore};
     use tracing_test::traced_test;
 
     use super::*;
@@ -396,7 +871,7 @@ mod tests {
         ff::Field,
         halo2curves::pasta::{pallas, EqAffine, Fp, Fq},
         run_mock_prover_test,
-        util::fe_to_fe_safe,
+        util::{fe_to_bits_le, fe_to_fe_safe},
     };
 
     #[derive(Clone, Debug)]
@@ -641,4 +1116,217 @@ mod tests {
         let public_inputs = vec![vec![r.x, r.y]];
         run_mock_prover_test!(K, circuit, public_inputs);
     }
+
+    #[derive(Clone, Copy, Debug)]
+    enum MulCase {
+        Windowed,
+        Msm,
+        FixedBase,
+        Glv,
+    }
+
+    /// Checks the windowed methods of [`EccChip`] against off-circuit multiplication
+    struct MulTestCircuit {
+        points: Vec<pallas::Affine>,
+        scalars: Vec<Fq>,
+        case: MulCase,
+    }
+
+    impl MulTestCircuit {
+        fn expected(&self) -> Vec<Fp> {
+            let result = self.points.iter().zip(self.scalars.iter()).fold(
+                pallas::Affine::identity().to_curve(),
+                |acc, (point, scalar)| acc + *point * *scalar,
+            );
+            let (x, y) = point_coordinates(&result.to_affine());
+            vec![x, y]
+        }
+    }
+
+    impl Circuit<Fp> for MulTestCircuit {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            todo!()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let config = MainGate::configure(meta);
+            Self::Config { config, instance }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let ecc_chip = EccChip::<pallas::Affine, Fp, T>::new(config.config.clone());
+            let main_gate = MainGate::<Fp, T>::new(config.config);
+
+            let output = layouter.assign_region(
+                || "mul test circuit",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    let points = self
+                        .points
+                        .iter()
+                        .map(|point| ecc_chip.assign_from_curve(ctx, || "point", point))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let bits = self
+                        .scalars
+                        .iter()
+                        .map(|scalar| {
+                            let mut bits = fe_to_bits_le(scalar);
+                            bits.resize(Fq::NUM_BITS as usize, false);
+                            main_gate.assign_bits(ctx, &bits)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    match self.case {
+                        MulCase::Windowed => {
+                            ecc_chip.scalar_mul_windowed(ctx, &points[0], &bits[0], 3)
+                        }
+                        MulCase::Msm => {
+                            let terms = points
+                                .iter()
+                                .zip(bits.iter())
+                                .map(|(point, bits)| (point, bits.as_slice()))
+                                .collect::<Vec<_>>();
+                            ecc_chip.msm(ctx, &terms, DEFAULT_WINDOW_SIZE)
+                        }
+                        MulCase::FixedBase => ecc_chip.fixed_base_scalar_mul(
+                            ctx,
+                            &self.points[0],
+                            &bits[0],
+                            DEFAULT_WINDOW_SIZE,
+                        ),
+                        MulCase::Glv => {
+                            let scalar =
+                                ecc_chip.assign_glv_scalar(ctx, Value::known(self.scalars[0]))?;
+                            ecc_chip.scalar_mul_glv(ctx, &points[0], &scalar, DEFAULT_WINDOW_SIZE)
+                        }
+                    }
+                },
+            )?;
+            layouter.constrain_instance(output.x.cell(), config.instance, 0)?;
+            layouter.constrain_instance(output.y.cell(), config.instance, 1)?;
+            Ok(())
+        }
+    }
+
+    fn random_point() -> pallas::Affine {
+        (pallas::Affine::generator() * Fq::random(&mut OsRng)).to_affine()
+    }
+
+    fn run_mul_test(points: Vec<pallas::Affine>, scalars: Vec<Fq>, case: MulCase) {
+        let K: u32 = 15;
+        let circuit = MulTestCircuit {
+            points,
+            scalars,
+            case,
+        };
+        let public_inputs = vec![circuit.expected()];
+        run_mock_prover_test!(K, circuit, public_inputs);
+    }
+
+    #[traced_test]
+    #[test]
+    fn windowed_scalar_mul() {
+        run_mul_test(
+            vec![random_point()],
+            vec![Fq::random(&mut OsRng)],
+            MulCase::Windowed,
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn windowed_scalar_mul_by_zero() {
+        run_mul_test(vec![random_point()], vec![Fq::ZERO], MulCase::Windowed);
+    }
+
+    #[traced_test]
+    #[test]
+    fn msm_with_infinity() {
+        run_mul_test(
+            vec![random_point(), pallas::Affine::identity(), random_point()],
+            vec![
+                Fq::random(&mut OsRng),
+                Fq::random(&mut OsRng),
+                Fq::random(&mut OsRng),
+            ],
+            MulCase::Msm,
+        );
+    }
+
+    /// Scalars are much shorter than their bit representation, so all top windows are zero &
+    /// select the auxiliary points only, as for the powers of a challenge in the IVC fold circuit
+    #[traced_test]
+    #[test]
+    fn msm_with_zero_top_windows() {
+        run_mul_test(
+            (0..5).map(|_| random_point()).collect(),
+            (0..5).map(|_| Fq::from(OsRng.next_u64())).collect(),
+            MulCase::Msm,
+        );
+    }
+
+    /// The first table entry `aux_0 + p` meets equal `x`, so synthesis fails instead of
+    /// assigning an unsatisfiable circuit
+    #[traced_test]
+    #[test]
+    fn msm_of_auxiliary_point() {
+        let circuit = MulTestCircuit {
+            points: vec![aux_point(0)],
+            scalars: vec![Fq::random(&mut OsRng)],
+            case: MulCase::Msm,
+        };
+        let public_inputs = vec![circuit.expected()];
+
+        assert!(matches!(
+            halo2_proofs::dev::MockProver::run(15, &circuit, public_inputs),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[traced_test]
+    #[test]
+    fn fixed_base_scalar_mul() {
+        run_mul_test(
+            vec![pallas::Affine::generator()],
+            vec![Fq::random(&mut OsRng)],
+            MulCase::FixedBase,
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn glv_scalar_mul() {
+        run_mul_test(
+            vec![random_point()],
+            vec![Fq::random(&mut OsRng)],
+            MulCase::Glv,
+        );
+    }
+
+    #[test]
+    fn glv_decompose_recomposes() {
+        let (lambda, _) = endomorphism::<pallas::Affine>();
+        let scalar = Fq::random(&mut OsRng);
+        let (k1, k1_neg, k2, k2_neg) = glv_decompose::<pallas::Affine>(&scalar);
+
+        let signed = |value: u128, is_neg: bool| {
+            let value = Fq::from_u128(value);
+            if is_neg {
+                -value
+            } else {
+                value
+            }
+        };
+        assert_eq!(signed(k1, k1_neg) + lambda * signed(k2, k2_neg), scalar);
+    }
 }
@@ -0,0 +1,226 @@
+//! Lookup-based bitwise operations
+//!
+//! Both operands & the result are decomposed into nibbles by running sums in the same manner as
+//! [`crate::gadgets::range_check`], each triplet of nibbles is looked up in the table of
+//! `(tag, lhs, rhs, lhs op rhs)` for all nibbles `lhs` & `rhs`:
+//!
+//! | row   | z_lhs     | z_rhs     | z_out     | tag | q_lookup | q_running |
+//! |-------|-----------|-----------|-----------|-----|----------|-----------|
+//! | 0     | a_0       | b_0       | c_0       | op  | 1        | 16        |
+//! | ...   | ...       | ...       | ...       | ... | ...      | ...       |
+//! | n - 1 | a_{n - 1} | b_{n - 1} | c_{n - 1} | op  | 1        | 0         |
+//!
+//! The table has a row of zeros, so rows out of the operation look up zeros.
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, TableColumn},
+    poly::Rotation,
+};
+use tracing::*;
+
+use super::fe_to_u64;
+use crate::{
+    ff::PrimeField,
+    main_gate::{AssignedValue, RegionCtx},
+};
+
+/// Bits count of the words looked up in the table
+pub const NIBBLE_BITS: usize = 4;
+
+const NIBBLE_MASK: u64 = (1 << NIBBLE_BITS) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    Xor,
+    And,
+}
+
+impl BitwiseOp {
+    const ALL: [Self; 2] = [Self::Xor, Self::And];
+
+    /// Zero tag is reserved for the zero row of the table
+    fn tag(&self) -> u64 {
+        match self {
+            Self::Xor => 1,
+            Self::And => 2,
+        }
+    }
+
+    pub fn eval(&self, lhs: u64, rhs: u64) -> u64 {
+        match self {
+            Self::Xor => lhs ^ rhs,
+            Self::And => lhs & rhs,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitwiseConfig {
+    table_tag: TableColumn,
+    table_lhs: TableColumn,
+    table_rhs: TableColumn,
+    table_out: TableColumn,
+    z_lhs: Column<Advice>,
+    z_rhs: Column<Advice>,
+    z_out: Column<Advice>,
+    tag: Column<Fixed>,
+    q_lookup: Column<Fixed>,
+    q_running: Column<Fixed>,
+}
+
+/// Chip for [`BitwiseOp`] over nibble decompositions, see [`crate::gadgets::integer::bitwise`]
+#[derive(Clone, Debug)]
+pub struct BitwiseChip<F: PrimeField> {
+    config: BitwiseConfig,
+    _p: std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> BitwiseChip<F> {
+    /// The table has `2 * 2^8 + 1` rows, so the circuit must have `k > 9`
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BitwiseConfig {
+        let [table_tag, table_lhs, table_rhs, table_out] =
+            std::array::from_fn(|_| meta.lookup_table_column());
+
+        let [z_lhs, z_rhs, z_out] = std::array::from_fn(|_| {
+            let column = meta.advice_column();
+            meta.enable_equality(column);
+            column
+        });
+
+        let [tag, q_lookup, q_running] = std::array::from_fn(|_| meta.fixed_column());
+
+        meta.lookup("bitwise nibbles", |meta| {
+            let q_lookup = meta.query_fixed(q_lookup, Rotation::cur());
+            let q_running = meta.query_fixed(q_running, Rotation::cur());
+
+            let mut word = |z| {
+                q_lookup.clone() * meta.query_advice(z, Rotation::cur())
+                    - q_running.clone() * meta.query_advice(z, Rotation::next())
+            };
+            let (lhs, rhs, out) = (word(z_lhs), word(z_rhs), word(z_out));
+
+            vec![
+                (meta.query_fixed(tag, Rotation::cur()), table_tag),
+                (lhs, table_lhs),
+                (rhs, table_rhs),
+                (out, table_out),
+            ]
+        });
+
+        BitwiseConfig {
+            table_tag,
+            table_lhs,
+            table_rhs,
+            table_out,
+            z_lhs,
+            z_rhs,
+            z_out,
+            tag,
+            q_lookup,
+            q_running,
+        }
+    }
+
+    pub fn new(config: BitwiseConfig) -> Self {
+        Self {
+            config,
+            _p: std::marker::PhantomData,
+        }
+    }
+
+    /// Loads the lookup table, should be called once per circuit
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let BitwiseConfig {
+            table_tag,
+            table_lhs,
+            table_rhs,
+            table_out,
+            ..
+        } = self.config;
+
+        layouter.assign_table(
+            || "bitwise table",
+            |mut table| {
+                let rows =
+                    std::iter::once((0, 0, 0, 0)).chain(BitwiseOp::ALL.iter().flat_map(|op| {
+                        (0..=NIBBLE_MASK).flat_map(move |lhs| {
+                            (0..=NIBBLE_MASK)
+                                .map(move |rhs| (op.tag(), lhs, rhs, op.eval(lhs, rhs)))
+                        })
+                    }));
+
+                for (offset, (tag, lhs, rhs, out)) in rows.enumerate() {
+                    for (column, value) in [
+                        (table_tag, tag),
+                        (table_lhs, lhs),
+                        (table_rhs, rhs),
+                        (table_out, out),
+                    ] {
+                        table.assign_cell(
+                            || "bitwise table",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns `lhs op rhs`, both of them are checked to be less than `2^bits`
+    ///
+    /// `bits` must be a multiple of [`NIBBLE_BITS`]
+    pub fn apply(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        op: BitwiseOp,
+        lhs: &AssignedValue<F>,
+        rhs: &AssignedValue<F>,
+        bits: usize,
+    ) -> Result<AssignedValue<F>, Error> {
+        if bits == 0 || bits % NIBBLE_BITS != 0 || bits > u64::BITS as usize {
+            error!("bitwise operation over {bits} bits isn't supported");
+            return Err(Error::Synthesis);
+        }
+
+        let lhs_value = lhs.value().map(fe_to_u64);
+        let rhs_value = rhs.value().map(fe_to_u64);
+        let out_value = lhs_value.zip(rhs_value).map(|(lhs, rhs)| op.eval(lhs, rhs));
+
+        let nibbles_count = bits / NIBBLE_BITS;
+
+        let mut out = None;
+        for nibble_index in 0..nibbles_count {
+            let shift = nibble_index * NIBBLE_BITS;
+            let z = |value: Value<u64>| value.map(|value| F::from(value >> shift));
+
+            let z_lhs = ctx.assign_advice(|| "z_lhs", self.config.z_lhs, z(lhs_value))?;
+            let z_rhs = ctx.assign_advice(|| "z_rhs", self.config.z_rhs, z(rhs_value))?;
+            let z_out = ctx.assign_advice(|| "z_out", self.config.z_out, z(out_value))?;
+
+            if nibble_index == 0 {
+                ctx.constrain_equal(z_lhs.cell(), lhs.cell())?;
+                ctx.constrain_equal(z_rhs.cell(), rhs.cell())?;
+                out = Some(z_out);
+            }
+
+            ctx.assign_fixed(|| "tag", self.config.tag, F::from(op.tag()))?;
+            ctx.assign_fixed(|| "q_lookup", self.config.q_lookup, F::ONE)?;
+            if nibble_index + 1 != nibbles_count {
+                ctx.assign_fixed(
+                    || "q_running",
+                    self.config.q_running,
+                    F::from(1 << NIBBLE_BITS),
+                )?;
+            }
+
+            ctx.next();
+        }
+
+        Ok(out.expect("bits count is not zero"))
+    }
+}
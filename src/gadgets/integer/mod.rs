@@ -0,0 +1,528 @@
+//! Typed unsigned integers on top of [`MainGate`]
+//!
+//! [`AssignedUint`] is a cell whose value is proven to be less than `2^BITS`. Every operation of
+//! [`IntegerChip`] constrains its result to the same range: checked operations (`add`, `sub`,
+//! `mul`) make the circuit unsatisfiable on overflow, wrapping ones return the carry as well.
+//!
+//! Range checks are done by [`RangeCheckChip`], bitwise operations by [`BitwiseChip`], so both
+//! tables must be loaded by [`IntegerChip::load_tables`].
+
+use std::num::NonZeroUsize;
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+use tracing::*;
+
+use crate::{
+    ff::{PrimeField, PrimeFieldBits},
+    gadgets::range_check::{RangeCheckChip, RangeCheckConfig},
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx},
+    util,
+};
+
+pub mod bitwise;
+
+use bitwise::{BitwiseChip, BitwiseConfig, BitwiseOp};
+
+/// Cell with a value less than `2^BITS`, `BITS` is at most 64
+#[derive(Clone, Debug)]
+pub struct AssignedUint<F: PrimeFieldBits, const BITS: usize> {
+    cell: AssignedValue<F>,
+}
+
+pub type AssignedU8<F> = AssignedUint<F, 8>;
+pub type AssignedU32<F> = AssignedUint<F, 32>;
+pub type AssignedU64<F> = AssignedUint<F, 64>;
+
+impl<F: PrimeFieldBits, const BITS: usize> AssignedUint<F, BITS> {
+    pub fn cell(&self) -> &AssignedValue<F> {
+        &self.cell
+    }
+
+    pub fn value(&self) -> Value<u64> {
+        self.cell.value().map(fe_to_u64)
+    }
+}
+
+impl<F: PrimeFieldBits, const BITS: usize> From<AssignedUint<F, BITS>> for AssignedValue<F> {
+    fn from(value: AssignedUint<F, BITS>) -> Self {
+        value.cell
+    }
+}
+
+fn fe_to_u64<F: PrimeField>(value: &F) -> u64 {
+    util::fe_to_big(value)
+        .iter_u64_digits()
+        .next()
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Debug)]
+pub struct IntegerConfig<const T: usize> {
+    main_gate: MainGateConfig<T>,
+    range_check: RangeCheckConfig,
+    bitwise: BitwiseConfig,
+}
+
+/// Arithmetic, comparison & bitwise operations over [`AssignedUint`]
+///
+/// Requires `T >= 3`
+#[derive(Debug)]
+pub struct IntegerChip<F: PrimeFieldBits, const T: usize> {
+    main_gate: MainGate<F, T>,
+    range_check: RangeCheckChip<F>,
+    bitwise: BitwiseChip<F>,
+}
+
+impl<F: PrimeFieldBits, const T: usize> IntegerChip<F, T> {
+    /// Configures the lookups over `main_gate` columns, see [`RangeCheckChip::configure`] for
+    /// `lookup_bits`
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        main_gate: MainGateConfig<T>,
+        lookup_bits: NonZeroUsize,
+    ) -> IntegerConfig<T> {
+        IntegerConfig {
+            main_gate,
+            range_check: RangeCheckChip::configure(meta, lookup_bits),
+            bitwise: BitwiseChip::configure(meta),
+        }
+    }
+
+    pub fn new(config: IntegerConfig<T>) -> Self {
+        Self {
+            main_gate: MainGate::new(config.main_gate),
+            range_check: RangeCheckChip::new(config.range_check),
+            bitwise: BitwiseChip::new(config.bitwise),
+        }
+    }
+
+    pub fn main_gate(&self) -> &MainGate<F, T> {
+        &self.main_gate
+    }
+
+    /// Loads the range check & bitwise tables, should be called once per circuit
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.range_check.load_table(layouter)?;
+        self.bitwise.load_table(layouter)
+    }
+
+    fn bits<const BITS: usize>() -> Result<NonZeroUsize, Error> {
+        match NonZeroUsize::new(BITS) {
+            Some(bits) if BITS <= u64::BITS as usize => Ok(bits),
+            _ => {
+                error!("integer of {BITS} bits isn't supported");
+                Err(Error::Synthesis)
+            }
+        }
+    }
+
+    fn range_checked<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        cell: AssignedValue<F>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        self.range_check
+            .range_check(ctx, &cell, Self::bits::<BITS>()?)?;
+        Ok(AssignedUint { cell })
+    }
+
+    pub fn assign_uint<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<u64>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let cell = self.range_check.assign_range_checked(
+            ctx,
+            value.map(F::from),
+            Self::bits::<BITS>()?,
+        )?;
+        Ok(AssignedUint { cell })
+    }
+
+    pub fn assign_constant_uint<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: u64,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        Self::bits::<BITS>()?;
+        if BITS < u64::BITS as usize && value >> BITS != 0 {
+            error!("constant {value} doesn't fit into {BITS} bits");
+            return Err(Error::Synthesis);
+        }
+
+        let value = F::from(value);
+        let cell = self.main_gate.apply(
+            ctx,
+            (None, None, None),
+            Some(value),
+            (-F::ONE, value.into()),
+        )?;
+        Ok(AssignedUint { cell })
+    }
+
+    /// Range checks the `cell` to be used as an integer
+    pub fn from_cell<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        cell: &AssignedValue<F>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        self.range_checked(ctx, cell.clone())
+    }
+
+    /// `a + b`, unsatisfiable on overflow
+    pub fn add<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let sum = self.main_gate.add(ctx, &a.cell, &b.cell)?;
+        self.range_checked(ctx, sum)
+    }
+
+    /// `a - b`, unsatisfiable on underflow
+    pub fn sub<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let diff = self.main_gate.sub(ctx, &a.cell, &b.cell)?;
+        self.range_checked(ctx, diff)
+    }
+
+    /// `a * b`, unsatisfiable on overflow
+    pub fn mul<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let product = self.main_gate.mul(ctx, &a.cell, &b.cell)?;
+        self.range_checked(ctx, product)
+    }
+
+    /// `(a + b) mod 2^BITS` & the carry bit
+    pub fn wrapping_add<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<(AssignedUint<F, BITS>, AssignedBit<F>), Error> {
+        let sum = a.value().zip(b.value()).map(|(a, b)| a as u128 + b as u128);
+
+        let carry = self
+            .main_gate
+            .assign_bit(ctx, sum.map(|sum| F::from((sum >> BITS) as u64)))?;
+        let result =
+            self.assign_uint::<BITS>(ctx, sum.map(|sum| (sum & ((1 << BITS) - 1)) as u64))?;
+
+        // a + b - carry * 2^BITS - result = 0
+        self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ONE, F::ONE, -pow_of_two::<F>(BITS)]),
+                None,
+                Some(vec![a.cell().into(), b.cell().into(), carry.clone().into()]),
+            ),
+            None,
+            (-F::ONE, result.cell().into()),
+        )?;
+
+        Ok((result, carry))
+    }
+
+    /// `(a / b, a % b)`, unsatisfiable if `b` is zero
+    pub fn div_rem<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<(AssignedUint<F, BITS>, AssignedUint<F, BITS>), Error> {
+        let (quotient, remainder) = a
+            .value()
+            .zip(b.value())
+            .map(|(a, b)| a.checked_div(b).zip(a.checked_rem(b)).unwrap_or((0, a)))
+            .unzip();
+
+        let quotient = self.assign_uint::<BITS>(ctx, quotient)?;
+        let remainder = self.assign_uint::<BITS>(ctx, remainder)?;
+
+        // quotient * b + remainder - a = 0
+        self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ZERO, F::ZERO, F::ONE]),
+                Some(vec![F::ONE]),
+                Some(vec![
+                    quotient.cell().into(),
+                    b.cell().into(),
+                    remainder.cell().into(),
+                ]),
+            ),
+            None,
+            (-F::ONE, a.cell().into()),
+        )?;
+
+        let is_less = self.lt(ctx, &remainder, b)?;
+        self.main_gate.assert_equal_const(ctx, is_less, F::ONE)?;
+
+        Ok((quotient, remainder))
+    }
+
+    /// 1 if `a < b`, 0 otherwise
+    ///
+    /// Lookup-based version of [`MainGate::less_than`]
+    pub fn lt<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedBit<F>, Error> {
+        let is_less = a
+            .value()
+            .zip(b.value())
+            .map(|(a, b)| if a < b { F::ONE } else { F::ZERO });
+        let is_less = self.main_gate.assign_bit(ctx, is_less)?;
+
+        // a - b + is_less * 2^BITS is in range only for the right `is_less`
+        let shift = pow_of_two::<F>(BITS);
+        let diff = a.cell().value().copied() - b.cell().value().copied()
+            + is_less.value().copied() * Value::known(shift);
+        let diff = self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ONE, -F::ONE, shift]),
+                None,
+                Some(vec![
+                    a.cell().into(),
+                    b.cell().into(),
+                    is_less.clone().into(),
+                ]),
+            ),
+            None,
+            (-F::ONE, diff.into()),
+        )?;
+        self.range_checked::<BITS>(ctx, diff)?;
+
+        Ok(is_less)
+    }
+
+    /// 1 if `a <= b`, 0 otherwise
+    pub fn le<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedBit<F>, Error> {
+        let is_greater = self.lt(ctx, b, a)?;
+
+        // 1 - is_greater
+        let value = Value::known(F::ONE) - is_greater.value().copied();
+        self.main_gate.apply(
+            ctx,
+            (Some(vec![-F::ONE]), None, Some(vec![is_greater.into()])),
+            Some(F::ONE),
+            (-F::ONE, value.into()),
+        )
+    }
+
+    pub fn xor<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let cell = self
+            .bitwise
+            .apply(ctx, BitwiseOp::Xor, &a.cell, &b.cell, BITS)?;
+        Ok(AssignedUint { cell })
+    }
+
+    pub fn and<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let cell = self
+            .bitwise
+            .apply(ctx, BitwiseOp::And, &a.cell, &b.cell, BITS)?;
+        Ok(AssignedUint { cell })
+    }
+
+    /// `a | b = a + b - (a & b)`
+    pub fn or<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        b: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let and = self.and(ctx, a, b)?;
+
+        let value = a.cell.value().copied() + b.cell.value().copied() - and.cell.value().copied();
+        let cell = self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ONE, F::ONE, -F::ONE]),
+                None,
+                Some(vec![a.cell().into(), b.cell().into(), and.cell.into()]),
+            ),
+            None,
+            (-F::ONE, value.into()),
+        )?;
+        Ok(AssignedUint { cell })
+    }
+
+    /// `!a = 2^BITS - 1 - a`
+    pub fn not<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let max = pow_of_two::<F>(BITS) - F::ONE;
+
+        let value = Value::known(max) - a.cell.value().copied();
+        let cell = self.main_gate.apply(
+            ctx,
+            (Some(vec![-F::ONE]), None, Some(vec![a.cell().into()])),
+            Some(max),
+            (-F::ONE, value.into()),
+        )?;
+        Ok(AssignedUint { cell })
+    }
+
+    /// Splits `a` into `(hi, lo)`, such that `a = hi * 2^at + lo` & `lo < 2^at`
+    ///
+    /// `at` must be in `1..BITS`
+    fn split<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        at: usize,
+    ) -> Result<(AssignedValue<F>, AssignedValue<F>), Error> {
+        let bits = |count| NonZeroUsize::new(count).expect("`at` is in `1..BITS`");
+
+        let hi = self.range_check.assign_range_checked(
+            ctx,
+            a.value().map(|a| F::from(a >> at)),
+            bits(BITS - at),
+        )?;
+        let lo = self.range_check.assign_range_checked(
+            ctx,
+            a.value().map(|a| F::from(a & ((1 << at) - 1))),
+            bits(at),
+        )?;
+
+        self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![pow_of_two::<F>(at), F::ONE]),
+                None,
+                Some(vec![hi.clone().into(), lo.clone().into()]),
+            ),
+            None,
+            (-F::ONE, a.cell().into()),
+        )?;
+
+        Ok((hi, lo))
+    }
+
+    /// `hi * 2^hi_shift + lo * 2^lo_shift`
+    fn combine<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        (hi, hi_shift): (&AssignedValue<F>, usize),
+        (lo, lo_shift): (&AssignedValue<F>, usize),
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        let (hi_shift, lo_shift) = (pow_of_two::<F>(hi_shift), pow_of_two::<F>(lo_shift));
+
+        let value = hi.value().copied() * Value::known(hi_shift)
+            + lo.value().copied() * Value::known(lo_shift);
+        let cell = self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![hi_shift, lo_shift]),
+                None,
+                Some(vec![hi.into(), lo.into()]),
+            ),
+            None,
+            (-F::ONE, value.into()),
+        )?;
+        Ok(AssignedUint { cell })
+    }
+
+    /// `(a << shift) mod 2^BITS`
+    pub fn shl<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        shift: usize,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        match shift {
+            0 => Ok(a.clone()),
+            shift if shift >= BITS => self.assign_constant_uint(ctx, 0),
+            shift => {
+                let (_, lo) = self.split(ctx, a, BITS - shift)?;
+                self.main_gate
+                    .mul_by_const(ctx, &lo, pow_of_two::<F>(shift))
+                    .map(|cell| AssignedUint { cell })
+            }
+        }
+    }
+
+    /// `a >> shift`
+    pub fn shr<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        shift: usize,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        match shift {
+            0 => Ok(a.clone()),
+            shift if shift >= BITS => self.assign_constant_uint(ctx, 0),
+            shift => {
+                let (hi, _) = self.split(ctx, a, shift)?;
+                Ok(AssignedUint { cell: hi })
+            }
+        }
+    }
+
+    /// Rotates `a` left by `shift`
+    pub fn rotl<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        shift: usize,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        Self::bits::<BITS>()?;
+        match shift % BITS {
+            0 => Ok(a.clone()),
+            shift => {
+                let (hi, lo) = self.split(ctx, a, BITS - shift)?;
+                self.combine(ctx, (&lo, shift), (&hi, 0))
+            }
+        }
+    }
+
+    /// Rotates `a` right by `shift`
+    pub fn rotr<const BITS: usize>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedUint<F, BITS>,
+        shift: usize,
+    ) -> Result<AssignedUint<F, BITS>, Error> {
+        Self::bits::<BITS>()?;
+        self.rotl(ctx, a, BITS - shift % BITS)
+    }
+}
+
+fn pow_of_two<F: PrimeFieldBits>(exp: usize) -> F {
+    F::from(2).pow_vartime([exp as u64])
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,270 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::Circuit,
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{ff::Field, halo2curves::bn256::Fr, run_mock_prover_test};
+
+const K: u32 = 13;
+const T: usize = 4;
+
+const LOOKUP_BITS: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(8) };
+
+fn mask(bits: usize) -> u64 {
+    u64::MAX >> (u64::BITS as usize - bits)
+}
+
+/// Checks every operation of [`IntegerChip`] over `a` & `b` against the off-circuit result
+fn check_ops<const BITS: usize>(
+    chip: &IntegerChip<Fr, T>,
+    ctx: &mut RegionCtx<'_, Fr>,
+    a: u64,
+    b: u64,
+) -> Result<(), Error> {
+    let check = |ctx: &mut RegionCtx<'_, Fr>, actual: &AssignedValue<Fr>, expected: u64| {
+        chip.main_gate()
+            .assert_equal_const(ctx, actual.clone(), Fr::from(expected))
+    };
+
+    let a_cell = chip.assign_uint::<BITS>(ctx, Value::known(a))?;
+    let b_cell = chip.assign_uint::<BITS>(ctx, Value::known(b))?;
+
+    let (sum, carry) = chip.wrapping_add(ctx, &a_cell, &b_cell)?;
+    let expected = a as u128 + b as u128;
+    check(ctx, sum.cell(), (expected as u64) & mask(BITS))?;
+    check(ctx, &carry, (expected >> BITS) as u64)?;
+
+    let (max, min) = (a.max(b), a.min(b));
+    let (max_cell, min_cell) = if a >= b {
+        (&a_cell, &b_cell)
+    } else {
+        (&b_cell, &a_cell)
+    };
+    let diff = chip.sub(ctx, max_cell, min_cell)?;
+    check(ctx, diff.cell(), max - min)?;
+
+    let (quotient, remainder) = chip.div_rem(ctx, &a_cell, &b_cell)?;
+    check(ctx, quotient.cell(), a / b)?;
+    check(ctx, remainder.cell(), a % b)?;
+
+    let lt = chip.lt(ctx, &a_cell, &b_cell)?;
+    check(ctx, &lt, (a < b) as u64)?;
+    let le = chip.le(ctx, &a_cell, &a_cell)?;
+    check(ctx, &le, 1)?;
+    let le = chip.le(ctx, &a_cell, &b_cell)?;
+    check(ctx, &le, (a <= b) as u64)?;
+
+    let xor = chip.xor(ctx, &a_cell, &b_cell)?;
+    check(ctx, xor.cell(), a ^ b)?;
+    let and = chip.and(ctx, &a_cell, &b_cell)?;
+    check(ctx, and.cell(), a & b)?;
+    let or = chip.or(ctx, &a_cell, &b_cell)?;
+    check(ctx, or.cell(), a | b)?;
+    let not = chip.not(ctx, &a_cell)?;
+    check(ctx, not.cell(), !a & mask(BITS))?;
+
+    for shift in [0, 1, 3, BITS - 1, BITS, BITS + 5] {
+        let shl = chip.shl(ctx, &a_cell, shift)?;
+        check(
+            ctx,
+            shl.cell(),
+            a.checked_shl(shift as u32).unwrap_or(0) & mask(BITS),
+        )?;
+
+        let shr = chip.shr(ctx, &a_cell, shift)?;
+        check(ctx, shr.cell(), a.checked_shr(shift as u32).unwrap_or(0))?;
+
+        let shift = shift % BITS;
+        let rotl = chip.rotl(ctx, &a_cell, shift)?;
+        let rotr = chip.rotr(ctx, &a_cell, shift)?;
+        let (rotl_expected, rotr_expected) = if shift == 0 {
+            (a, a)
+        } else {
+            (
+                ((a << shift) | (a >> (BITS - shift))) & mask(BITS),
+                ((a >> shift) | (a << (BITS - shift))) & mask(BITS),
+            )
+        };
+        check(ctx, rotl.cell(), rotl_expected)?;
+        check(ctx, rotr.cell(), rotr_expected)?;
+    }
+
+    Ok(())
+}
+
+struct OpsCircuit;
+
+impl Circuit<Fr> for OpsCircuit {
+    type Config = IntegerConfig<T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let main_gate = MainGate::configure(meta);
+        IntegerChip::configure(meta, main_gate, LOOKUP_BITS)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = IntegerChip::<Fr, T>::new(config);
+        chip.load_tables(&mut layouter)?;
+
+        layouter.assign_region(
+            || "integer ops",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                check_ops::<8>(&chip, &mut ctx, 0xb7, 0x1d)?;
+                check_ops::<32>(&chip, &mut ctx, 0xdead_beef, 0xffff_0001)?;
+                check_ops::<64>(&chip, &mut ctx, u64::MAX - 2, 0xc0ffee)?;
+
+                let a = chip.assign_uint::<32>(&mut ctx, Value::known(40_000))?;
+                let b = chip.assign_uint::<32>(&mut ctx, Value::known(50_000))?;
+                let sum = chip.add(&mut ctx, &a, &b)?;
+                let product = chip.mul(&mut ctx, &a, &b)?;
+                chip.main_gate()
+                    .assert_equal_const(&mut ctx, sum.into(), Fr::from(90_000))?;
+                chip.main_gate().assert_equal_const(
+                    &mut ctx,
+                    product.into(),
+                    Fr::from(2_000_000_000),
+                )?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn integer_ops() {
+    run_mock_prover_test!(K, OpsCircuit, vec![]);
+}
+
+#[derive(Clone, Copy)]
+enum Invalid {
+    AddOverflow,
+    SubUnderflow,
+    MulOverflow,
+    DivisionByZero,
+}
+
+struct InvalidCircuit(Invalid);
+
+impl Circuit<Fr> for InvalidCircuit {
+    type Config = IntegerConfig<T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        OpsCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = IntegerChip::<Fr, T>::new(config);
+        chip.load_tables(&mut layouter)?;
+
+        layouter.assign_region(
+            || "invalid integer op",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let a = chip.assign_uint::<8>(&mut ctx, Value::known(200))?;
+                let b = chip.assign_uint::<8>(&mut ctx, Value::known(100))?;
+                let zero = chip.assign_constant_uint::<8>(&mut ctx, 0)?;
+
+                match self.0 {
+                    Invalid::AddOverflow => chip.add(&mut ctx, &a, &b).map(|_| ()),
+                    Invalid::SubUnderflow => chip.sub(&mut ctx, &b, &a).map(|_| ()),
+                    Invalid::MulOverflow => chip.mul(&mut ctx, &a, &b).map(|_| ()),
+                    Invalid::DivisionByZero => chip.div_rem(&mut ctx, &a, &zero).map(|_| ()),
+                }
+            },
+        )
+    }
+}
+
+#[test]
+fn integer_invalid_ops() {
+    for invalid in [
+        Invalid::AddOverflow,
+        Invalid::SubUnderflow,
+        Invalid::MulOverflow,
+        Invalid::DivisionByZero,
+    ] {
+        let prover = MockProver::run(K, &InvalidCircuit(invalid), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+/// Compares `a` with `b` by [`MainGate::less_than`] & [`MainGate::less_equal`]
+struct CompareCircuit {
+    a: u64,
+    b: u64,
+}
+
+impl Circuit<Fr> for CompareCircuit {
+    type Config = MainGateConfig<T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let main_gate = MainGate::<Fr, T>::new(config);
+        let bits = NonZeroUsize::new(64).unwrap();
+
+        layouter.assign_region(
+            || "compare",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let a = main_gate.assign_value(&mut ctx, Value::known(Fr::from(self.a)))?;
+                let b = main_gate.assign_value(&mut ctx, Value::known(Fr::from(self.b)))?;
+
+                let lt = main_gate.less_than(&mut ctx, &a, &b, bits)?;
+                main_gate.assert_equal_const(&mut ctx, lt, Fr::from((self.a < self.b) as u64))?;
+
+                let le = main_gate.less_equal(&mut ctx, &a, &b, bits)?;
+                main_gate.assert_equal_const(&mut ctx, le, Fr::from((self.a <= self.b) as u64))?;
+
+                let le = main_gate.less_equal(&mut ctx, &b, &b, bits)?;
+                main_gate.assert_equal_const(&mut ctx, le, Fr::ONE)
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn main_gate_comparison() {
+    for (a, b) in [(0, 0), (1, 2), (2, 1), (u64::MAX, 0), (0xc0ffee, u64::MAX)] {
+        run_mock_prover_test!(10, CompareCircuit { a, b }, vec![]);
+    }
+}
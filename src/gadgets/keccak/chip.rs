@@ -0,0 +1,619 @@
+use std::{array, iter, ops};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+
+use super::{
+    off_circuit,
+    table::{Func, TableConfig, PIECE_SIZE, SLOT_BITS},
+    DIGEST_SIZE, RATE, ROTATIONS, ROUND_CONSTANTS, STATE_SIZE,
+};
+use crate::{ff::PrimeField, util::fe_to_bits_le};
+
+/// Number of bits in a lane
+const LANE_SIZE: usize = 64;
+
+/// Maximum number of terms of a linear combination normalized by one transformation
+const TERMS: usize = 5;
+
+/// Sparse representation of the lane, see [`super`]
+fn sparse<F: PrimeField>(lane: u64) -> F {
+    let base = F::from(1 << SLOT_BITS);
+    (0..LANE_SIZE)
+        .rev()
+        .fold(F::ZERO, |acc, i| acc * base + F::from((lane >> i) & 1))
+}
+
+/// Lane as a linear combination of assigned cells with a constant
+#[derive(Clone, Debug)]
+struct Combination<F: PrimeField> {
+    terms: Vec<(F, AssignedCell<F, F>)>,
+    constant: F,
+}
+
+impl<F: PrimeField> Combination<F> {
+    fn constant(constant: F) -> Self {
+        Self {
+            terms: vec![],
+            constant,
+        }
+    }
+
+    fn cell(cell: AssignedCell<F, F>) -> Self {
+        Self {
+            terms: vec![(F::ONE, cell)],
+            constant: F::ZERO,
+        }
+    }
+
+    fn value(&self) -> Value<F> {
+        self.terms
+            .iter()
+            .fold(Value::known(self.constant), |acc, (weight, cell)| {
+                acc + cell.value().map(|value| *value * weight)
+            })
+    }
+}
+
+impl<F: PrimeField> ops::Add for Combination<F> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self.terms.extend(rhs.terms);
+        self.constant += rhs.constant;
+        self
+    }
+}
+
+impl<F: PrimeField> ops::Mul<F> for Combination<F> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: F) -> Self {
+        self.terms.iter_mut().for_each(|(weight, _)| *weight *= rhs);
+        self.constant *= rhs;
+        self
+    }
+}
+
+/// Configuration of [`KeccakChip`]
+///
+/// All the transformations are made by one region layout: a linear combination of lanes is
+/// decomposed into pieces of at most [`PIECE_SIZE`] slots, each piece is looked up in the table
+/// and the outputs are recombined, once or twice with different rotations.
+///
+/// | row | input   | output   | acc_input   | acc_output   | acc_rotated   | terms   |
+/// |-----|---------|----------|-------------|--------------|---------------|---------|
+/// | 0   | piece_0 | lookup_0 | 0           | 0            | 0             |         |
+/// | ... | ...     | ...      | ...         | ...          | ...           |         |
+/// | n   |         |          | combination | result       | rotated       | t_0,... |
+#[derive(Clone, Debug)]
+pub struct KeccakConfig {
+    table: TableConfig,
+
+    input: Column<Advice>,
+    output: Column<Advice>,
+    acc_input: Column<Advice>,
+    acc_output: Column<Advice>,
+    acc_rotated: Column<Advice>,
+    terms: [Column<Advice>; TERMS],
+
+    tag: Column<Fixed>,
+    coeff_input: Column<Fixed>,
+    coeff_output: Column<Fixed>,
+    coeff_rotated: Column<Fixed>,
+    weights: [Column<Fixed>; TERMS],
+    constant: Column<Fixed>,
+
+    s_piece: Selector,
+    s_start: Selector,
+    s_combination: Selector,
+}
+
+/// On-circuit Keccak-f[1600] permutation & Keccak-256 of messages of 64-bit lanes
+///
+/// Lanes passed to & returned by the chip are dense. All of them are range checked on
+/// conversion into the sparse representation.
+#[derive(Clone, Debug)]
+pub struct KeccakChip<F: PrimeField> {
+    config: KeccakConfig,
+    _p: std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakChip<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> KeccakConfig {
+        assert!(
+            F::NUM_BITS as usize > LANE_SIZE * SLOT_BITS,
+            "field is too small for sparse lanes"
+        );
+
+        let table = TableConfig::configure(meta);
+
+        let input = meta.advice_column();
+        let output = meta.advice_column();
+        let acc_input = meta.advice_column();
+        let acc_output = meta.advice_column();
+        let acc_rotated = meta.advice_column();
+        let terms = array::from_fn(|_| meta.advice_column());
+
+        for column in terms.iter().chain([&acc_output, &acc_rotated]) {
+            meta.enable_equality(*column);
+        }
+
+        let tag = meta.fixed_column();
+        let coeff_input = meta.fixed_column();
+        let coeff_output = meta.fixed_column();
+        let coeff_rotated = meta.fixed_column();
+        let weights = array::from_fn(|_| meta.fixed_column());
+        let constant = meta.fixed_column();
+
+        let s_piece = meta.complex_selector();
+        let s_start = meta.selector();
+        let s_combination = meta.selector();
+
+        meta.lookup("keccak piece", |meta| {
+            let s_piece = meta.query_selector(s_piece);
+            let tag = meta.query_fixed(tag, Rotation::cur());
+            let input = meta.query_advice(input, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+
+            vec![
+                (s_piece.clone() * tag, table.tag),
+                (s_piece.clone() * input, table.input),
+                (s_piece * output, table.output),
+            ]
+        });
+
+        meta.create_gate("keccak piece", |meta| {
+            let s_piece = meta.query_selector(s_piece);
+            let input = meta.query_advice(input, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+
+            let acc = |meta: &mut VirtualCells<'_, F>,
+                       column: Column<Advice>,
+                       coeff: Column<Fixed>,
+                       value: Expression<F>| {
+                let acc_cur = meta.query_advice(column, Rotation::cur());
+                let acc_next = meta.query_advice(column, Rotation::next());
+                let coeff = meta.query_fixed(coeff, Rotation::cur());
+                acc_next - acc_cur - coeff * value
+            };
+
+            Constraints::with_selector(
+                s_piece,
+                [
+                    ("input", acc(meta, acc_input, coeff_input, input)),
+                    (
+                        "output",
+                        acc(meta, acc_output, coeff_output, output.clone()),
+                    ),
+                    ("rotated", acc(meta, acc_rotated, coeff_rotated, output)),
+                ],
+            )
+        });
+
+        meta.create_gate("keccak start", |meta| {
+            let s_start = meta.query_selector(s_start);
+
+            Constraints::with_selector(
+                s_start,
+                [acc_input, acc_output, acc_rotated]
+                    .map(|column| meta.query_advice(column, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("keccak combination", |meta| {
+            let s_combination = meta.query_selector(s_combination);
+            let acc_input = meta.query_advice(acc_input, Rotation::cur());
+            let constant = meta.query_fixed(constant, Rotation::cur());
+
+            let combination =
+                terms
+                    .iter()
+                    .zip(weights.iter())
+                    .fold(constant, |combination, (term, weight)| {
+                        combination
+                            + meta.query_advice(*term, Rotation::cur())
+                                * meta.query_fixed(*weight, Rotation::cur())
+                    });
+
+            Constraints::with_selector(s_combination, [acc_input - combination])
+        });
+
+        KeccakConfig {
+            table,
+            input,
+            output,
+            acc_input,
+            acc_output,
+            acc_rotated,
+            terms,
+            tag,
+            coeff_input,
+            coeff_output,
+            coeff_rotated,
+            weights,
+            constant,
+            s_piece,
+            s_start,
+            s_combination,
+        }
+    }
+
+    pub fn construct(config: KeccakConfig) -> Self {
+        Self {
+            config,
+            _p: std::marker::PhantomData,
+        }
+    }
+
+    /// Loads the lookup table, should be called once per circuit
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.load(layouter)
+    }
+
+    /// Assigns witnessed lanes
+    ///
+    /// They are range checked only when passed to the chip
+    pub fn assign_lanes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lanes: &[Value<u64>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "lanes",
+            |mut region| {
+                lanes
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, lane)| {
+                        region.assign_advice(
+                            || "lane",
+                            self.config.terms[0],
+                            offset,
+                            || lane.map(F::from),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Normalizes the linear combination of lanes by `func`, the output is returned once for
+    /// each of `rotations` (rotation to the left, as in ρ)
+    fn transform<const N: usize>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        func: Func,
+        combination: &Combination<F>,
+        rotations: [u32; N],
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        assert!(N == 1 || N == 2, "only two outputs are supported");
+        assert!(combination.terms.len() <= TERMS, "too many terms");
+
+        let config = &self.config;
+        let input_base = F::from(func.input_base());
+        let output_base = F::from(func.output_base());
+        let pow = |base: F, exp: usize| base.pow_vartime([(exp % LANE_SIZE) as u64]);
+
+        // Pieces mustn't cross the boundary of rotation, so that each of them is rotated as a
+        // whole
+        let mut bounds = (0..LANE_SIZE)
+            .step_by(PIECE_SIZE)
+            .chain(
+                rotations
+                    .iter()
+                    .map(|rotation| (LANE_SIZE - *rotation as usize) % LANE_SIZE),
+            )
+            .chain(iter::once(LANE_SIZE))
+            .collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let slot_bits = func.input_base().trailing_zeros() as usize;
+        let slots = combination.value().map(|value| {
+            let bits = fe_to_bits_le(&value);
+            (0..LANE_SIZE)
+                .map(|slot| {
+                    (0..slot_bits).rev().fold(0u64, |acc, bit| {
+                        let bit = bits.get(slot * slot_bits + bit).copied().unwrap_or(false);
+                        (acc << 1) | bit as u64
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        layouter.assign_region(
+            || format!("keccak {func:?}"),
+            |mut region| {
+                let mut acc_input = Value::known(F::ZERO);
+                let mut acc_output = Value::known(F::ZERO);
+                let mut acc_rotated = Value::known(F::ZERO);
+
+                config.s_start.enable(&mut region, 0)?;
+
+                for (offset, (start, end)) in bounds.iter().zip(bounds.iter().skip(1)).enumerate() {
+                    let size = end - start;
+                    config.s_piece.enable(&mut region, offset)?;
+
+                    let coeff_input = pow(input_base, *start);
+                    let coeff_output = pow(output_base, start + rotations[0] as usize);
+                    let coeff_rotated = rotations
+                        .get(1)
+                        .map(|rotation| pow(output_base, start + *rotation as usize))
+                        .unwrap_or(F::ZERO);
+
+                    for (annotation, column, value) in [
+                        ("tag", config.tag, F::from(func.tag(size))),
+                        ("coeff_input", config.coeff_input, coeff_input),
+                        ("coeff_output", config.coeff_output, coeff_output),
+                        ("coeff_rotated", config.coeff_rotated, coeff_rotated),
+                    ] {
+                        region.assign_fixed(
+                            || annotation,
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+
+                    let input = slots.as_ref().map(|slots| {
+                        slots[*start..*end]
+                            .iter()
+                            .rev()
+                            .fold(0, |acc, slot| acc * func.input_base() + slot)
+                    });
+                    let output = input.map(|input| func.apply(input, size));
+                    let (input, output) = (input.map(F::from), output.map(F::from));
+
+                    region.assign_advice(|| "input", config.input, offset, || input)?;
+                    region.assign_advice(|| "output", config.output, offset, || output)?;
+                    region.assign_advice(|| "acc_input", config.acc_input, offset, || acc_input)?;
+                    region.assign_advice(
+                        || "acc_output",
+                        config.acc_output,
+                        offset,
+                        || acc_output,
+                    )?;
+                    region.assign_advice(
+                        || "acc_rotated",
+                        config.acc_rotated,
+                        offset,
+                        || acc_rotated,
+                    )?;
+
+                    acc_input = acc_input + input.map(|input| input * coeff_input);
+                    acc_output = acc_output + output.map(|output| output * coeff_output);
+                    acc_rotated = acc_rotated + output.map(|output| output * coeff_rotated);
+                }
+
+                let offset = bounds.len() - 1;
+                config.s_combination.enable(&mut region, offset)?;
+
+                for (index, (weight, cell)) in combination.terms.iter().enumerate() {
+                    cell.copy_advice(|| "term", &mut region, config.terms[index], offset)?;
+                    region.assign_fixed(
+                        || "weight",
+                        config.weights[index],
+                        offset,
+                        || Value::known(*weight),
+                    )?;
+                }
+                region.assign_fixed(
+                    || "constant",
+                    config.constant,
+                    offset,
+                    || Value::known(combination.constant),
+                )?;
+
+                region.assign_advice(|| "acc_input", config.acc_input, offset, || acc_input)?;
+                let output = region.assign_advice(
+                    || "acc_output",
+                    config.acc_output,
+                    offset,
+                    || acc_output,
+                )?;
+                let rotated = region.assign_advice(
+                    || "acc_rotated",
+                    config.acc_rotated,
+                    offset,
+                    || acc_rotated,
+                )?;
+
+                Ok([output, rotated][..N].to_vec().try_into().expect("N <= 2"))
+            },
+        )
+    }
+
+    fn to_sparse(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lane: &AssignedCell<F, F>,
+    ) -> Result<Combination<F>, Error> {
+        let [sparse] = self.transform(
+            layouter,
+            Func::FromDense,
+            &Combination::cell(lane.clone()),
+            [0],
+        )?;
+        Ok(Combination::cell(sparse))
+    }
+
+    fn to_dense(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lane: &Combination<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [dense] = self.transform(layouter, Func::ToDense, lane, [0])?;
+        Ok(dense)
+    }
+
+    fn xor(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lhs: Combination<F>,
+        rhs: Combination<F>,
+    ) -> Result<Combination<F>, Error> {
+        let [xor] = self.transform(layouter, Func::Parity, &(lhs + rhs), [0])?;
+        Ok(Combination::cell(xor))
+    }
+
+    /// Keccak-f[1600] round in the sparse representation, ι is postponed to the next
+    /// normalization of the lane
+    fn round(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [Combination<F>; STATE_SIZE],
+        round_constant: u64,
+    ) -> Result<[Combination<F>; STATE_SIZE], Error> {
+        let cell = |cell: &AssignedCell<F, F>| Combination::cell(cell.clone());
+
+        // θ: parity of columns, once rotated by one for `D[x]`
+        let c = (0..5)
+            .map(|x| {
+                let column = (0..5).fold(Combination::constant(F::ZERO), |acc, y| {
+                    acc + state[x + 5 * y].clone()
+                });
+                self.transform(layouter, Func::Parity, &column, [0, 1])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // θ, ρ & π
+        let mut b = vec![Combination::constant(F::ZERO); STATE_SIZE];
+        for x in 0..5 {
+            for y in 0..5 {
+                let lane =
+                    state[x + 5 * y].clone() + cell(&c[(x + 4) % 5][0]) + cell(&c[(x + 1) % 5][1]);
+                let [lane] = self.transform(layouter, Func::Parity, &lane, [ROTATIONS[x][y]])?;
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = Combination::cell(lane);
+            }
+        }
+
+        // χ
+        let three = Combination::constant(sparse::<F>(u64::MAX) * F::from(3));
+        let mut output = (0..STATE_SIZE)
+            .map(|index| {
+                let (x, y) = (index % 5, index / 5);
+                let lane = three.clone()
+                    + b[x + 5 * y].clone() * -F::from(2)
+                    + b[(x + 1) % 5 + 5 * y].clone()
+                    + b[(x + 2) % 5 + 5 * y].clone() * -F::ONE;
+                let [lane] = self.transform(layouter, Func::Chi, &lane, [0])?;
+                Ok(Combination::cell(lane))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // ι
+        output[0].constant += sparse::<F>(round_constant);
+
+        Ok(output.try_into().expect("output.len() == STATE_SIZE"))
+    }
+
+    fn keccak_f(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [Combination<F>; STATE_SIZE],
+    ) -> Result<[Combination<F>; STATE_SIZE], Error> {
+        ROUND_CONSTANTS
+            .iter()
+            .try_fold(state, |state, round_constant| {
+                self.round(layouter, state, *round_constant)
+            })
+    }
+
+    fn absorb_sparse(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        mut state: [Combination<F>; STATE_SIZE],
+        block: &[Combination<F>],
+    ) -> Result<[Combination<F>; STATE_SIZE], Error> {
+        for (lane, word) in state.iter_mut().zip(block.iter()) {
+            *lane = self.xor(layouter, lane.clone(), word.clone())?;
+        }
+        self.keccak_f(layouter, state)
+    }
+
+    /// The Keccak-f[1600] permutation of dense lanes
+    pub fn permute(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &[AssignedCell<F, F>; STATE_SIZE],
+    ) -> Result<[AssignedCell<F, F>; STATE_SIZE], Error> {
+        self.absorb(layouter, state, &[])
+    }
+
+    /// XORs the block into the rate part of the state & applies the Keccak-f[1600] permutation
+    ///
+    /// `block` may be shorter than [`RATE`], then the rest of the block is zero
+    pub fn absorb(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &[AssignedCell<F, F>; STATE_SIZE],
+        block: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; STATE_SIZE], Error> {
+        assert!(block.len() <= RATE);
+
+        let state = state
+            .iter()
+            .map(|lane| self.to_sparse(layouter, lane))
+            .collect::<Result<Vec<_>, _>>()?;
+        let block = block
+            .iter()
+            .map(|lane| self.to_sparse(layouter, lane))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let state = self.absorb_sparse(
+            layouter,
+            state.try_into().expect("state.len() == STATE_SIZE"),
+            &block,
+        )?;
+
+        Ok(state
+            .iter()
+            .map(|lane| self.to_dense(layouter, lane))
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .expect("state.len() == STATE_SIZE"))
+    }
+
+    /// Keccak-256 digest of the message, padding included
+    ///
+    /// The padding depends only on the length of the message, so it's assigned as constants.
+    /// The state stays sparse between blocks, only the digest is converted into dense lanes.
+    pub fn digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        message: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], Error> {
+        let padding = off_circuit::to_lanes(&off_circuit::padding(
+            message.len() * off_circuit::LANE_BYTES,
+        ));
+
+        let padded = message
+            .iter()
+            .map(|lane| self.to_sparse(layouter, lane))
+            .chain(
+                padding
+                    .into_iter()
+                    .map(|lane| Ok(Combination::constant(sparse(lane)))),
+            )
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let state = padded.chunks_exact(RATE).try_fold(
+            array::from_fn(|_| Combination::constant(F::ZERO)),
+            |state, block| self.absorb_sparse(layouter, state, block),
+        )?;
+
+        Ok(state[..DIGEST_SIZE]
+            .iter()
+            .map(|lane| self.to_dense(layouter, lane))
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .expect("DIGEST_SIZE <= STATE_SIZE"))
+    }
+}
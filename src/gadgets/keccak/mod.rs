@@ -0,0 +1,83 @@
+//! # Keccak-256
+//!
+//! Keccak-f[1600] permutation and the Keccak-256 sponge (the Ethereum variant), over messages of
+//! 64-bit lanes, each lane is stored in a separate cell.
+//!
+//! - [`KeccakChip`] - on-circuit permutation & digest of an assigned message, padding included
+//! - [`off_circuit`] - reference implementation
+//! - [`KeccakStepCircuit`] - [`crate::ivc::StepCircuit`] absorbing one block per step
+//!
+//! ## Sparse representation
+//!
+//! As with the spread table of SHA-256, bitwise operations are replaced by additions of lanes
+//! where each bit occupies its own slot, here of three bits: a bit `b_i` is stored as
+//! `b_i * 8^i`. Several sparse lanes can be added without carries between slots, and the sum is
+//! normalized by a lookup of small pieces of it:
+//! - θ: XOR is the parity of the slot
+//! - ρ: the normalized pieces are recombined with rotated coefficients, so rotation is free
+//! - π: is only a renaming of lanes
+//! - χ: `a ^ (!b & c)` is a function of the slot of `3 - 2a + b - c`
+//! - ι: the round constant is added to the next sum containing the lane
+//!
+//! Lookups are ordinary `meta.lookup`s into a fixed table, so they are folded by
+//! [`crate::plonk::lookup::Arguments`] as any other lookup. The table has about `2^14` rows and
+//! one permutation takes about `2^15` rows, so the circuit should have at least `k = 16`.
+
+pub mod chip;
+pub mod off_circuit;
+pub mod step_circuit;
+mod table;
+
+pub use chip::{KeccakChip, KeccakConfig};
+pub use off_circuit::keccak256;
+pub use step_circuit::KeccakStepCircuit;
+
+/// The number of lanes of the Keccak-f[1600] state
+pub const STATE_SIZE: usize = 25;
+/// The number of lanes absorbed per block by Keccak-256
+pub const RATE: usize = 17;
+/// The number of lanes of Keccak-256 digest
+pub const DIGEST_SIZE: usize = 4;
+/// The number of rounds of Keccak-f[1600]
+pub const ROUNDS: usize = 24;
+
+/// ρ offsets, indexed by `[x][y]`
+pub const ROTATIONS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// ι constants
+#[allow(clippy::unreadable_literal)]
+pub const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+#[cfg(test)]
+mod tests;
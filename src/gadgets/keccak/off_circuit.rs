@@ -0,0 +1,149 @@
+//! Reference Keccak-256 (the Ethereum variant, with the original `0x01` padding)
+
+use super::{DIGEST_SIZE, RATE, ROTATIONS, ROUND_CONSTANTS, STATE_SIZE};
+
+/// Size of a lane in bytes
+pub const LANE_BYTES: usize = 8;
+
+/// The Keccak-f[1600] permutation, lanes are indexed by `x + 5 * y`
+pub fn keccak_f(state: &mut [u64; STATE_SIZE]) {
+    for rc in ROUND_CONSTANTS {
+        // θ
+        let c: [u64; 5] = std::array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // ρ & π
+        let mut b = [0u64; STATE_SIZE];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y].rotate_left(ROTATIONS[x][y]);
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] =
+                    b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+
+        // ι
+        state[0] ^= rc;
+    }
+}
+
+/// XORs the block into the rate part of the state & applies [`keccak_f`]
+pub fn absorb(state: &mut [u64; STATE_SIZE], block: &[u64; RATE]) {
+    for (lane, word) in state.iter_mut().zip(block.iter()) {
+        *lane ^= word;
+    }
+    keccak_f(state);
+}
+
+/// Padding (`pad10*1` with the domain bit) of a message of `len` bytes, so that the padded
+/// message is a multiple of [`RATE`] lanes
+pub fn padding(len: usize) -> Vec<u8> {
+    const RATE_BYTES: usize = RATE * LANE_BYTES;
+
+    let mut padding = vec![0u8; RATE_BYTES - len % RATE_BYTES];
+    padding[0] |= 0x01;
+    *padding.last_mut().expect("padding can't be empty") |= 0x80;
+    padding
+}
+
+/// Little-endian lanes of bytes
+///
+/// # Panics
+///
+/// If the length of `bytes` isn't a multiple of [`LANE_BYTES`]
+pub fn to_lanes(bytes: &[u8]) -> Vec<u64> {
+    assert_eq!(bytes.len() % LANE_BYTES, 0, "bytes must consist of lanes");
+
+    bytes
+        .chunks_exact(LANE_BYTES)
+        .map(|lane| u64::from_le_bytes(lane.try_into().unwrap()))
+        .collect()
+}
+
+/// The message with its padding, as lanes
+pub fn pad(bytes: &[u8]) -> Vec<u64> {
+    to_lanes(&[bytes, &padding(bytes.len())].concat())
+}
+
+/// Digest lanes of the message of whole lanes, padding included
+pub fn digest(message: &[u64]) -> [u64; DIGEST_SIZE] {
+    let bytes = message
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let mut state = [0; STATE_SIZE];
+    for block in pad(&bytes).chunks_exact(RATE) {
+        absorb(&mut state, block.try_into().expect("chunk.len() == RATE"));
+    }
+
+    std::array::from_fn(|i| state[i])
+}
+
+/// Keccak-256 of bytes
+pub fn keccak256(bytes: &[u8]) -> [u8; DIGEST_SIZE * LANE_BYTES] {
+    let mut state = [0; STATE_SIZE];
+    for block in pad(bytes).chunks_exact(RATE) {
+        absorb(&mut state, block.try_into().expect("chunk.len() == RATE"));
+    }
+
+    state[..DIGEST_SIZE]
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(
+            hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex(&keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // Two blocks
+        assert_eq!(
+            hex(&keccak256(&[0xab; 200])),
+            "8ca353cee0a5c5b999a0916e88da37a16293cf14a73c735d6b5197b50c3d6656"
+        );
+    }
+
+    #[test]
+    fn digest_of_lanes() {
+        let message = (0..20).collect::<Vec<u64>>();
+        let bytes = message
+            .iter()
+            .flat_map(|lane| lane.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let digest = digest(&message)
+            .iter()
+            .flat_map(|lane| lane.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        assert_eq!(digest, keccak256(&bytes));
+    }
+}
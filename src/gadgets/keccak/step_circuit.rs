@@ -0,0 +1,96 @@
+//! [`StepCircuit`] absorbing one block of Keccak-256 per step
+//!
+//! The state of the sponge is carried between steps as `z_i`, so starting with the
+//! [zero state](KeccakStepCircuit::initial_state) and folding the circuits returned by
+//! [`KeccakStepCircuit::for_bytes`] one after another, the first [`super::DIGEST_SIZE`] lanes
+//! of the last `z_i` are the Keccak-256 digest of the message.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+use num_traits::ToPrimitive;
+use tracing::*;
+
+use super::{
+    chip::{KeccakChip, KeccakConfig},
+    off_circuit, RATE, STATE_SIZE,
+};
+use crate::{
+    ff::PrimeField,
+    ivc::{StepCircuit, SynthesisError},
+    util::fe_to_big,
+};
+
+/// `z_{i+1} = keccak_f(z_i ^ block)`, where `z_i` is the state of the sponge as 64-bit lanes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeccakStepCircuit<F: PrimeField> {
+    block: [u64; RATE],
+    _p: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakStepCircuit<F> {
+    pub fn new(block: [u64; RATE]) -> Self {
+        Self {
+            block,
+            _p: PhantomData,
+        }
+    }
+
+    /// Circuits for each block of the padded message
+    pub fn for_bytes(bytes: &[u8]) -> Vec<Self> {
+        off_circuit::pad(bytes)
+            .chunks_exact(RATE)
+            .map(|block| Self::new(block.try_into().expect("chunk.len() == RATE")))
+            .collect()
+    }
+
+    /// The zero state of the sponge, `z_0` of the chain
+    pub fn initial_state() -> [F; STATE_SIZE] {
+        [F::ZERO; STATE_SIZE]
+    }
+}
+
+impl<F: PrimeField> StepCircuit<STATE_SIZE, F> for KeccakStepCircuit<F> {
+    type Config = KeccakConfig;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        KeccakChip::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; STATE_SIZE],
+    ) -> Result<[AssignedCell<F, F>; STATE_SIZE], SynthesisError> {
+        let chip = KeccakChip::construct(config);
+        chip.load(layouter)?;
+
+        let block = chip.assign_lanes(layouter, &self.block.map(Value::known))?;
+
+        Ok(chip.absorb(layouter, z_i, &block)?)
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; STATE_SIZE],
+        _k_table_size: u32,
+    ) -> Result<[F; STATE_SIZE], SynthesisError> {
+        let mut state = z_i
+            .iter()
+            .map(|value| fe_to_big(value).to_u64())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|state| <[u64; STATE_SIZE]>::try_from(state).ok())
+            .ok_or_else(|| {
+                error!("`z_i` of keccak step circuit must consist of 64-bit lanes");
+                SynthesisError::Halo2(Error::Synthesis)
+            })?;
+
+        off_circuit::absorb(&mut state, &self.block);
+
+        Ok(state.map(F::from))
+    }
+}
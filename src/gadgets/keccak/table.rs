@@ -0,0 +1,144 @@
+//! Lookup table of all the transformations of lane pieces used by [`super::KeccakChip`]
+//!
+//! Each row is `(tag, input, output)`, where the tag is made of the [`Func`] & the number of
+//! slots in the piece. The size is a part of the tag, so pieces shorter than [`PIECE_SIZE`] are
+//! range checked as well, which makes the decomposition of a lane into pieces unique.
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+use crate::ff::PrimeField;
+
+/// The maximum number of slots in a piece
+pub(super) const PIECE_SIZE: usize = 4;
+
+/// Number of bits in a slot of sparse representation
+pub(super) const SLOT_BITS: usize = 3;
+
+/// Transformation of each slot of a piece
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Func {
+    /// Sparse to sparse parity of the slot
+    Parity,
+    /// Sparse to sparse χ of the slot, where the slot is `3 - 2a + b - c`
+    Chi,
+    /// Sparse to dense parity of the slot
+    ToDense,
+    /// Dense to sparse
+    FromDense,
+}
+
+impl Func {
+    const ALL: [Func; 4] = [Func::Parity, Func::Chi, Func::ToDense, Func::FromDense];
+
+    /// Base of the input, i.e. number of possible values of the slot
+    pub(super) fn input_base(&self) -> u64 {
+        match self {
+            Func::FromDense => 2,
+            _ => 1 << SLOT_BITS,
+        }
+    }
+
+    pub(super) fn output_base(&self) -> u64 {
+        match self {
+            Func::ToDense => 2,
+            _ => 1 << SLOT_BITS,
+        }
+    }
+
+    fn apply_to_slot(&self, slot: u64) -> u64 {
+        match self {
+            Func::Parity | Func::ToDense | Func::FromDense => slot & 1,
+            // `a ^ (!b & c)` for `slot = 3 - 2a + b - c`, slots above 4 are never reached
+            Func::Chi => [0, 1, 1, 0, 0, 0, 0, 0][slot as usize],
+        }
+    }
+
+    /// Output for the piece of `size` slots, the slots above `size` are ignored
+    pub(super) fn apply(&self, input: u64, size: usize) -> u64 {
+        (0..size as u32).rev().fold(0, |output, i| {
+            let slot = (input / self.input_base().pow(i)) % self.input_base();
+            output * self.output_base() + self.apply_to_slot(slot)
+        })
+    }
+
+    pub(super) fn tag(&self, size: usize) -> u64 {
+        assert!((1..=PIECE_SIZE).contains(&size));
+        let index = Self::ALL
+            .iter()
+            .position(|func| func == self)
+            .expect("all funcs are listed");
+
+        1 + (index * PIECE_SIZE + size - 1) as u64
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct TableConfig {
+    pub(super) tag: TableColumn,
+    pub(super) input: TableColumn,
+    pub(super) output: TableColumn,
+}
+
+impl TableConfig {
+    pub(super) fn configure<F: PrimeField>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            tag: meta.lookup_table_column(),
+            input: meta.lookup_table_column(),
+            output: meta.lookup_table_column(),
+        }
+    }
+
+    /// Rows of the table, starting with the zero row used by disabled lookups
+    fn rows() -> impl Iterator<Item = (u64, u64, u64)> {
+        std::iter::once((0, 0, 0)).chain(Func::ALL.into_iter().flat_map(|func| {
+            (1..=PIECE_SIZE).flat_map(move |size| {
+                (0..func.input_base().pow(size as u32))
+                    .map(move |input| (func.tag(size), input, func.apply(input, size)))
+            })
+        }))
+    }
+
+    pub(super) fn load<F: PrimeField>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "keccak table",
+            |mut table| {
+                for (offset, (tag, input, output)) in Self::rows().enumerate() {
+                    table.assign_cell(|| "tag", self.tag, offset, || Value::known(F::from(tag)))?;
+                    table.assign_cell(
+                        || "input",
+                        self.input,
+                        offset,
+                        || Value::known(F::from(input)),
+                    )?;
+                    table.assign_cell(
+                        || "output",
+                        self.output,
+                        offset,
+                        || Value::known(F::from(output)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        // slots `[1, 2, 3, 0]` from the little end
+        let input = 1 + 2 * 8 + 3 * 64;
+        assert_eq!(Func::Parity.apply(input, 4), 1 + 64);
+        assert_eq!(Func::Chi.apply(input, 4), 1 + 8);
+        assert_eq!(Func::ToDense.apply(input, 4), 0b0101);
+        assert_eq!(Func::FromDense.apply(0b1011, 4), 1 + 8 + 512);
+
+        assert_eq!(TableConfig::rows().count(), 1 + 3 * 4680 + 30);
+    }
+}
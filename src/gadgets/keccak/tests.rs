@@ -0,0 +1,126 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{halo2curves::bn256::Fr, ivc::StepCircuit, run_mock_prover_test};
+
+const K: u32 = 16;
+
+#[derive(Clone, Debug)]
+struct TestCircuitConfig {
+    keccak: KeccakConfig,
+    instance: Column<Instance>,
+}
+
+/// Public inputs: digest of the message
+struct TestCircuit {
+    message: Vec<u64>,
+}
+
+impl TestCircuit {
+    fn new(message: Vec<u64>) -> (Self, Vec<Fr>) {
+        let digest = off_circuit::digest(&message).map(Fr::from).to_vec();
+
+        (Self { message }, digest)
+    }
+}
+
+impl Circuit<Fr> for TestCircuit {
+    type Config = TestCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::Config {
+            keccak: KeccakChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = KeccakChip::construct(config.keccak);
+        chip.load(&mut layouter)?;
+
+        let message = chip.assign_lanes(
+            &mut layouter,
+            &self
+                .message
+                .iter()
+                .map(|lane| Value::known(*lane))
+                .collect::<Vec<_>>(),
+        )?;
+        let digest = chip.digest(&mut layouter, &message)?;
+
+        for (row, lane) in digest.iter().enumerate() {
+            layouter.constrain_instance(lane.cell(), config.instance, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[traced_test]
+#[test]
+fn chip_matches_off_circuit() {
+    let (circuit, public_inputs) = TestCircuit::new(vec![u64::MAX, 0x0123_4567_89ab_cdef, 1]);
+    run_mock_prover_test!(K, circuit, vec![public_inputs]);
+}
+
+#[traced_test]
+#[test]
+fn chip_rejects_wrong_digest() {
+    use halo2_proofs::dev::MockProver;
+
+    let (circuit, mut public_inputs) = TestCircuit::new(vec![]);
+    public_inputs[3] += Fr::from(1);
+
+    let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[traced_test]
+#[test]
+fn step_circuit_chain() {
+    use crate::util::mock_prover::MockProver;
+
+    let message = b"two blocks of keccak-256 are absorbed by two steps of the step circuit, \
+        each step takes a block of 136 bytes and applies one keccak-f permutation to the state";
+
+    let steps = KeccakStepCircuit::<Fr>::for_bytes(message);
+    assert_eq!(steps.len(), 2);
+
+    let z_out = steps
+        .iter()
+        .try_fold(KeccakStepCircuit::initial_state(), |z_i, step_circuit| {
+            let z_out = step_circuit.process_step(&z_i, K)?;
+
+            MockProver::run(K, step_circuit, vec![], z_i)
+                .unwrap()
+                .verify(z_out)
+                .unwrap();
+
+            Ok::<_, crate::ivc::SynthesisError>(z_out)
+        })
+        .unwrap();
+
+    let digest = off_circuit::keccak256(message);
+    assert_eq!(
+        z_out[..DIGEST_SIZE],
+        off_circuit::to_lanes(&digest)
+            .into_iter()
+            .map(Fr::from)
+            .collect::<Vec<_>>()
+    );
+}
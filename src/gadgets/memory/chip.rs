@@ -0,0 +1,335 @@
+use std::num::NonZeroUsize;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Value},
+    plonk::Error,
+};
+use tracing::*;
+
+use super::{off_circuit::MemoryOp, MEMORY_STATE_SIZE, TIMESTAMP_BITS};
+use crate::{
+    ff::{FromUniformBytes, PrimeFieldBits},
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx, WrapValue},
+    poseidon::{ROCircuitTrait, ROPair},
+};
+
+/// On-circuit [`super::MemoryState`]
+#[derive(Clone, Debug)]
+pub struct AssignedMemoryState<F: PrimeFieldBits> {
+    pub alpha: AssignedValue<F>,
+    pub gamma: AssignedValue<F>,
+    pub acc: AssignedValue<F>,
+    pub time: AssignedValue<F>,
+    pub hash: AssignedValue<F>,
+}
+
+impl<F: PrimeFieldBits> From<&[AssignedCell<F, F>; MEMORY_STATE_SIZE]> for AssignedMemoryState<F> {
+    fn from([alpha, gamma, acc, time, hash]: &[AssignedCell<F, F>; MEMORY_STATE_SIZE]) -> Self {
+        Self {
+            alpha: alpha.clone(),
+            gamma: gamma.clone(),
+            acc: acc.clone(),
+            time: time.clone(),
+            hash: hash.clone(),
+        }
+    }
+}
+
+impl<F: PrimeFieldBits> From<AssignedMemoryState<F>> for [AssignedCell<F, F>; MEMORY_STATE_SIZE] {
+    fn from(state: AssignedMemoryState<F>) -> Self {
+        [state.alpha, state.gamma, state.acc, state.time, state.hash]
+    }
+}
+
+/// Assigned access returned by [`MemoryChip::apply_ops`], to be bound by the caller to the rest
+/// of the step
+#[derive(Clone, Debug)]
+pub struct AssignedMemoryOp<F: PrimeFieldBits> {
+    pub addr: AssignedValue<F>,
+    pub is_write: AssignedBit<F>,
+    /// The value stored after the access
+    pub value: AssignedValue<F>,
+    /// The value read by the access
+    pub old_value: AssignedValue<F>,
+}
+
+/// On-circuit memory accesses & the final consistency check, see [`crate::gadgets::memory`]
+///
+/// Requires `T >= 4` because of [`MainGate::conditional_select`]
+pub struct MemoryChip<F, RO, const T: usize>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    main_gate: MainGate<F, T>,
+    ro_args: RO::Args,
+}
+
+impl<F, RO, const T: usize> MemoryChip<F, RO, T>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    pub fn new(config: MainGateConfig<T>, ro_args: RO::Args) -> Self {
+        Self {
+            main_gate: MainGate::new(config),
+            ro_args,
+        }
+    }
+
+    fn ro(&self) -> RO::OnCircuit {
+        RO::OnCircuit::new(self.main_gate.config().clone(), self.ro_args.clone())
+    }
+
+    fn assign_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: F,
+    ) -> Result<AssignedValue<F>, Error> {
+        self.main_gate.apply(
+            ctx,
+            (None, None, None),
+            Some(value),
+            (-F::ONE, value.into()),
+        )
+    }
+
+    /// `1 / (gamma - fp(addr, value, timestamp))`, unsatisfiable for the zero denominator
+    fn fingerprint_inverse(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: &AssignedMemoryState<F>,
+        addr: &AssignedValue<F>,
+        value: &AssignedValue<F>,
+        timestamp: &AssignedValue<F>,
+    ) -> Result<AssignedValue<F>, Error> {
+        let alpha = &state.alpha;
+
+        // inner = value + alpha * timestamp
+        let inner_value = value.value().copied() + alpha.value().copied() * timestamp.value();
+        let inner = self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ZERO, F::ZERO, F::ONE]),
+                Some(vec![F::ONE]),
+                Some(vec![alpha.into(), timestamp.into(), value.into()]),
+            ),
+            None,
+            (-F::ONE, inner_value.into()),
+        )?;
+
+        // denominator = gamma - addr - alpha * inner
+        let denominator_value = state.gamma.value().copied()
+            - addr.value().copied()
+            - alpha.value().copied() * inner.value();
+        let denominator = self.main_gate.apply(
+            ctx,
+            (
+                Some(vec![F::ZERO, F::ZERO, F::ONE, -F::ONE]),
+                Some(vec![-F::ONE]),
+                Some(vec![
+                    alpha.into(),
+                    inner.into(),
+                    (&state.gamma).into(),
+                    addr.into(),
+                ]),
+            ),
+            None,
+            (-F::ONE, denominator_value.into()),
+        )?;
+
+        let inverse = self.main_gate.assign_value(
+            ctx,
+            denominator
+                .value()
+                .map(|value| Option::from(value.invert()).unwrap_or(F::ZERO)),
+        )?;
+        let product = self.main_gate.mul(ctx, &denominator, &inverse)?;
+        self.main_gate.assert_equal_const(ctx, product, F::ONE)?;
+
+        Ok(inverse)
+    }
+
+    /// Applies accesses one by one & returns the new state with assigned accesses
+    ///
+    /// Each access is checked to read a cell with a timestamp less than the current time & its
+    /// terms are added to `acc`, all accesses are absorbed into `hash`.
+    pub fn apply_ops(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: &AssignedMemoryState<F>,
+        ops: &[MemoryOp<F>],
+    ) -> Result<(AssignedMemoryState<F>, Vec<AssignedMemoryOp<F>>), Error> {
+        let timestamp_bits = NonZeroUsize::new(TIMESTAMP_BITS).expect("not zero");
+
+        let mut acc = state.acc.clone();
+        let mut time = state.time.clone();
+        let mut assigned_ops = Vec::with_capacity(ops.len());
+
+        let mut ro = self.ro();
+        ro.absorb_base(WrapValue::Assigned(state.hash.clone()));
+
+        for op in ops {
+            let addr = self
+                .main_gate
+                .assign_value(ctx, Value::known(F::from(op.addr)))?;
+            let is_write = self
+                .main_gate
+                .assign_bit(ctx, Value::known(F::from(op.is_write as u64)))?;
+            let new_value = self.main_gate.assign_value(ctx, Value::known(op.value))?;
+            let old_value = self
+                .main_gate
+                .assign_value(ctx, Value::known(op.old_value))?;
+            let old_timestamp = self
+                .main_gate
+                .assign_value(ctx, Value::known(F::from(op.old_timestamp)))?;
+
+            // reads put back the old value
+            let value = self
+                .main_gate
+                .conditional_select(ctx, &new_value, &old_value, &is_write)?;
+
+            // time = time + 1
+            let next_time = time.value().map(|time| *time + F::ONE);
+            time = self.main_gate.apply(
+                ctx,
+                (Some(vec![F::ONE]), None, Some(vec![time.into()])),
+                Some(F::ONE),
+                (-F::ONE, next_time.into()),
+            )?;
+
+            let is_earlier =
+                self.main_gate
+                    .less_than(ctx, &old_timestamp, &time, timestamp_bits)?;
+            self.main_gate.assert_equal_const(ctx, is_earlier, F::ONE)?;
+
+            let write = self.fingerprint_inverse(ctx, state, &addr, &value, &time)?;
+            let read = self.fingerprint_inverse(ctx, state, &addr, &old_value, &old_timestamp)?;
+
+            // acc = acc + write - read
+            let acc_value = acc.value().copied() + write.value() - read.value();
+            acc = self.main_gate.apply(
+                ctx,
+                (
+                    Some(vec![F::ONE, F::ONE, -F::ONE]),
+                    None,
+                    Some(vec![acc.into(), write.into(), read.into()]),
+                ),
+                None,
+                (-F::ONE, acc_value.into()),
+            )?;
+
+            ro.absorb_iter(
+                [&addr, &old_value, &old_timestamp, &value]
+                    .into_iter()
+                    .map(|cell| WrapValue::Assigned(cell.clone())),
+            );
+
+            assigned_ops.push(AssignedMemoryOp {
+                addr,
+                is_write,
+                value,
+                old_value,
+            });
+        }
+
+        let hash = ro.squeeze_native(ctx)?;
+
+        Ok((
+            AssignedMemoryState {
+                alpha: state.alpha.clone(),
+                gamma: state.gamma.clone(),
+                acc,
+                time,
+                hash,
+            },
+            assigned_ops,
+        ))
+    }
+
+    /// Checks that the accesses accumulated in `state` are consistent with the memory, which
+    /// started with `initial` values & ended with `values` at `timestamps`
+    ///
+    /// Constrains challenges of `state` to be derived from its hash & the final memory.
+    pub fn check_consistency(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: &AssignedMemoryState<F>,
+        initial: &[F],
+        values: &[F],
+        timestamps: &[u64],
+    ) -> Result<(), Error> {
+        if initial.len() != values.len() || values.len() != timestamps.len() {
+            error!(
+                "memory sizes don't match: {} initial, {} final values, {} timestamps",
+                initial.len(),
+                values.len(),
+                timestamps.len()
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let zero = self.assign_constant(ctx, F::ZERO)?;
+
+        let mut ro = self.ro();
+        ro.absorb_base(WrapValue::Assigned(state.hash.clone()));
+
+        let mut initial_terms = vec![];
+        let mut final_terms = vec![];
+        for (addr, (initial, (value, timestamp))) in initial
+            .iter()
+            .zip(values.iter().zip(timestamps.iter()))
+            .enumerate()
+        {
+            let addr = self.assign_constant(ctx, F::from(addr as u64))?;
+
+            let initial = self.assign_constant(ctx, *initial)?;
+            initial_terms.push(self.fingerprint_inverse(ctx, state, &addr, &initial, &zero)?);
+
+            let value = self.main_gate.assign_value(ctx, Value::known(*value))?;
+            let timestamp = self
+                .main_gate
+                .assign_value(ctx, Value::known(F::from(*timestamp)))?;
+            final_terms.push(self.fingerprint_inverse(ctx, state, &addr, &value, &timestamp)?);
+
+            ro.absorb_base(WrapValue::Assigned(value))
+                .absorb_base(WrapValue::Assigned(timestamp));
+        }
+
+        let final_hash = ro.squeeze_native(ctx)?;
+        let alpha = self
+            .ro()
+            .absorb_base(WrapValue::Assigned(final_hash))
+            .squeeze_native(ctx)?;
+        let gamma = self
+            .ro()
+            .absorb_base(WrapValue::Assigned(alpha.clone()))
+            .squeeze_native(ctx)?;
+        ctx.constrain_equal(alpha.cell(), state.alpha.cell())?;
+        ctx.constrain_equal(gamma.cell(), state.gamma.cell())?;
+
+        // acc + sum(initial) - sum(final) = 0
+        let sum = |ctx: &mut RegionCtx<'_, F>,
+                   acc: AssignedValue<F>,
+                   terms: &[AssignedValue<F>],
+                   sign: F| {
+            terms.iter().try_fold(acc, |acc, term| {
+                let value = acc.value().copied() + term.value().copied() * Value::known(sign);
+                self.main_gate.apply(
+                    ctx,
+                    (
+                        Some(vec![F::ONE, sign]),
+                        None,
+                        Some(vec![acc.into(), term.into()]),
+                    ),
+                    None,
+                    (-F::ONE, value.into()),
+                )
+            })
+        };
+        let total = sum(ctx, state.acc.clone(), &initial_terms, F::ONE)?;
+        let total = sum(ctx, total, &final_terms, -F::ONE)?;
+
+        self.main_gate.assert_equal_const(ctx, total, F::ZERO)
+    }
+}
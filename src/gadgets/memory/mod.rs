@@ -0,0 +1,76 @@
+//! # Read/write memory by offline memory checking
+//!
+//! The memory is a set of cells `(addr, value, timestamp)`. Each access, read or write, removes
+//! the cell from the set & puts it back with the current global time, so over the whole
+//! execution:
+//!
+//! `Init ∪ Writes = Reads ∪ Final`,
+//!
+//! where `Init` are the initial cells with zero timestamps & `Final` are the cells after the last
+//! step. Reads are non-deterministic, but together with `old_timestamp < time` of each access
+//! this multiset equality is enough for all reads to return the last written values.
+//!
+//! The equality is checked by the log-derivative, as in [`crate::plonk::lookup`]:
+//! `sum 1/(gamma - fp(cell))` over both sides must be equal, where
+//! [`fp`](off_circuit::fingerprint) compresses the cell by `alpha`. Each step adds its terms
+//! `Writes - Reads` into the accumulator carried in `z_i`, together with a hash chain of all
+//! accesses. [`MemoryChip::check_consistency`] adds `Init - Final` terms at the end, while
+//! challenges are proven to be derived from the hash of all accesses & the final memory, i.e.
+//! they are chosen after the accesses are fixed.
+//!
+//! - [`Memory`] - off-circuit memory producing [`MemoryOp`]s
+//! - [`MemoryChip`] - on-circuit accesses & the final check
+//! - [`MemoryStepCircuit`] - [`crate::ivc::StepCircuit`] applying a batch of accesses per step
+//! - [`MemoryConsistencyCircuit`] - circuit with the final check of the last `z_i`
+
+use crate::ff::PrimeField;
+
+pub mod chip;
+pub mod off_circuit;
+pub mod step_circuit;
+
+pub use chip::{AssignedMemoryOp, AssignedMemoryState, MemoryChip};
+pub use off_circuit::{Memory, MemoryOp};
+pub use step_circuit::{MemoryConsistencyCircuit, MemoryStepCircuit};
+
+/// The count of elements in `z_i` carrying [`MemoryState`]
+pub const MEMORY_STATE_SIZE: usize = 5;
+
+/// Timestamps are checked in this many bits, so the memory supports up to `2^32 - 1` accesses
+pub const TIMESTAMP_BITS: usize = 32;
+
+/// The state of the memory argument carried between steps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryState<F: PrimeField> {
+    /// Challenge to compress the cell
+    pub alpha: F,
+    /// Challenge of the log-derivative
+    pub gamma: F,
+    /// `sum 1/(gamma - fp(cell))` over writes minus the same over reads
+    pub acc: F,
+    /// The count of accesses so far
+    pub time: F,
+    /// Hash chain of all accesses
+    pub hash: F,
+}
+
+impl<F: PrimeField> From<[F; MEMORY_STATE_SIZE]> for MemoryState<F> {
+    fn from([alpha, gamma, acc, time, hash]: [F; MEMORY_STATE_SIZE]) -> Self {
+        Self {
+            alpha,
+            gamma,
+            acc,
+            time,
+            hash,
+        }
+    }
+}
+
+impl<F: PrimeField> From<MemoryState<F>> for [F; MEMORY_STATE_SIZE] {
+    fn from(state: MemoryState<F>) -> Self {
+        [state.alpha, state.gamma, state.acc, state.time, state.hash]
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,266 @@
+use super::{MemoryState, MEMORY_STATE_SIZE};
+use crate::{
+    ff::{FromUniformBytes, PrimeFieldBits},
+    poseidon::{ROPair, ROTrait},
+};
+
+/// One access to the memory, either read or write
+///
+/// Each access reads the old `(addr, value, timestamp)` & writes `(addr, value, time)` back,
+/// where `time` is the global counter of accesses. For reads `value` is equal to `old_value`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryOp<F: PrimeFieldBits> {
+    pub addr: u64,
+    pub is_write: bool,
+    pub value: F,
+    pub old_value: F,
+    pub old_timestamp: u64,
+}
+
+impl<F: PrimeFieldBits> MemoryOp<F> {
+    /// Access that changes nothing, to fill batches with fewer operations
+    pub fn noop(memory: &mut Memory<F>) -> Self {
+        memory.read(0).expect("memory isn't empty")
+    }
+}
+
+/// Off-circuit memory of fixed size, which keeps the timestamp of the last access to each cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memory<F: PrimeFieldBits> {
+    initial: Vec<F>,
+    values: Vec<F>,
+    timestamps: Vec<u64>,
+    time: u64,
+}
+
+impl<F: PrimeFieldBits> Memory<F> {
+    /// # Panics
+    /// If `initial` is empty
+    pub fn new(initial: Vec<F>) -> Self {
+        assert!(!initial.is_empty(), "memory can't be empty");
+
+        Self {
+            values: initial.clone(),
+            timestamps: vec![0; initial.len()],
+            initial,
+            time: 0,
+        }
+    }
+
+    pub fn initial(&self) -> &[F] {
+        &self.initial
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    pub fn timestamps(&self) -> &[u64] {
+        &self.timestamps
+    }
+
+    /// The count of accesses so far
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Returns `None` if `addr` is out of the memory
+    pub fn read(&mut self, addr: u64) -> Option<MemoryOp<F>> {
+        let value = *self.values.get(addr as usize)?;
+        Some(self.access(addr, false, value))
+    }
+
+    /// Returns `None` if `addr` is out of the memory
+    pub fn write(&mut self, addr: u64, value: F) -> Option<MemoryOp<F>> {
+        self.values.get(addr as usize)?;
+        Some(self.access(addr, true, value))
+    }
+
+    fn access(&mut self, addr: u64, is_write: bool, value: F) -> MemoryOp<F> {
+        let index = addr as usize;
+        self.time += 1;
+
+        let op = MemoryOp {
+            addr,
+            is_write,
+            value,
+            old_value: self.values[index],
+            old_timestamp: self.timestamps[index],
+        };
+
+        self.values[index] = value;
+        self.timestamps[index] = self.time;
+
+        op
+    }
+}
+
+/// `addr + alpha * (value + alpha * timestamp)`, the compressed tuple of the multisets
+pub fn fingerprint<F: PrimeFieldBits>(alpha: F, addr: F, value: F, timestamp: F) -> F {
+    addr + alpha * (value + alpha * timestamp)
+}
+
+/// `1 / (gamma - fingerprint)`, the term of the log-derivative sum
+///
+/// Zero for the zero denominator, the circuit is unsatisfiable in this case
+pub fn fingerprint_inverse<F: PrimeFieldBits>(
+    alpha: F,
+    gamma: F,
+    addr: F,
+    value: F,
+    timestamp: F,
+) -> F {
+    Option::from((gamma - fingerprint(alpha, addr, value, timestamp)).invert()).unwrap_or(F::ZERO)
+}
+
+/// Hash of the batch of operations chained to the previous hash
+pub fn absorb_ops<F, RO>(ro_args: &RO::Args, hash: F, ops: &[MemoryOp<F>]) -> F
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    RO::OffCircuit::new(ro_args.clone())
+        .absorb_field(hash)
+        .absorb_field_iter(ops.iter().flat_map(|op| {
+            [
+                F::from(op.addr),
+                op.old_value,
+                F::from(op.old_timestamp),
+                op.value,
+            ]
+        }))
+        .squeeze_native()
+}
+
+/// Hash of the final memory chained to the hash of all operations
+pub fn absorb_final<F, RO>(ro_args: &RO::Args, hash: F, memory: &Memory<F>) -> F
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    RO::OffCircuit::new(ro_args.clone())
+        .absorb_field(hash)
+        .absorb_field_iter(
+            memory
+                .values
+                .iter()
+                .zip(memory.timestamps.iter())
+                .flat_map(|(value, timestamp)| [*value, F::from(*timestamp)]),
+        )
+        .squeeze_native()
+}
+
+/// `(alpha, gamma)` derived from the hash of all operations & the final memory
+pub fn challenges<F, RO>(ro_args: &RO::Args, final_hash: F) -> (F, F)
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    let squeeze = |value| {
+        RO::OffCircuit::new(ro_args.clone())
+            .absorb_field(value)
+            .squeeze_native()
+    };
+
+    let alpha = squeeze(final_hash);
+    (alpha, squeeze(alpha))
+}
+
+/// Applies the batch to the state, see [`super::MemoryChip::apply_ops`]
+pub fn apply_ops<F, RO>(
+    ro_args: &RO::Args,
+    state: &MemoryState<F>,
+    ops: &[MemoryOp<F>],
+) -> MemoryState<F>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    let MemoryState {
+        alpha,
+        gamma,
+        mut acc,
+        mut time,
+        hash,
+    } = *state;
+
+    for op in ops {
+        let addr = F::from(op.addr);
+        time += F::ONE;
+
+        acc += fingerprint_inverse(alpha, gamma, addr, op.value, time);
+        acc -= fingerprint_inverse(alpha, gamma, addr, op.old_value, F::from(op.old_timestamp));
+    }
+
+    MemoryState {
+        alpha,
+        gamma,
+        acc,
+        time,
+        hash: absorb_ops::<F, RO>(ro_args, hash, ops),
+    }
+}
+
+/// `z_0` of the chain of [`super::MemoryStepCircuit`] over `batches`, where `memory` is the
+/// memory after all of them
+///
+/// Challenges depend on all operations & the final memory, so they are known only after the
+/// whole execution.
+pub fn initial_state<F, RO>(
+    ro_args: &RO::Args,
+    batches: &[impl AsRef<[MemoryOp<F>]>],
+    memory: &Memory<F>,
+) -> [F; MEMORY_STATE_SIZE]
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    let hash = batches.iter().fold(F::ZERO, |hash, ops| {
+        absorb_ops::<F, RO>(ro_args, hash, ops.as_ref())
+    });
+    let (alpha, gamma) = challenges::<F, RO>(ro_args, absorb_final::<F, RO>(ro_args, hash, memory));
+
+    MemoryState {
+        alpha,
+        gamma,
+        acc: F::ZERO,
+        time: F::ZERO,
+        hash: F::ZERO,
+    }
+    .into()
+}
+
+/// Off-circuit version of [`super::MemoryChip::check_consistency`]
+pub fn is_consistent<F, RO>(ro_args: &RO::Args, state: &MemoryState<F>, memory: &Memory<F>) -> bool
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    let MemoryState {
+        alpha,
+        gamma,
+        acc,
+        hash,
+        ..
+    } = *state;
+
+    let sum = |values: &[F], timestamps: &mut dyn Iterator<Item = F>| {
+        values
+            .iter()
+            .zip(timestamps)
+            .enumerate()
+            .map(|(addr, (value, timestamp))| {
+                fingerprint_inverse(alpha, gamma, F::from(addr as u64), *value, timestamp)
+            })
+            .sum::<F>()
+    };
+
+    let initial = sum(&memory.initial, &mut std::iter::repeat(F::ZERO));
+    let last = sum(
+        &memory.values,
+        &mut memory.timestamps.iter().map(|t| F::from(*t)),
+    );
+
+    challenges::<F, RO>(ro_args, absorb_final::<F, RO>(ro_args, hash, memory)) == (alpha, gamma)
+        && initial + acc == last
+}
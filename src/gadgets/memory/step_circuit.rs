@@ -0,0 +1,184 @@
+//! [`StepCircuit`] applying a batch of memory accesses per step & the circuit of the final check
+//!
+//! The chain is built in two passes, since challenges depend on the whole execution: accesses
+//! are collected by [`super::Memory`] first, then `z_0` is
+//! [calculated](super::off_circuit::initial_state) from all of them & the final memory, then
+//! the steps are folded one by one. The last `z_i` is checked by [`MemoryConsistencyCircuit`].
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use super::{
+    off_circuit::{self, Memory, MemoryOp},
+    AssignedMemoryState, MemoryChip, MemoryState, MEMORY_STATE_SIZE,
+};
+use crate::{
+    ff::{FromUniformBytes, PrimeFieldBits},
+    ivc::{StepCircuit, SynthesisError},
+    main_gate::{MainGate, MainGateConfig, RegionCtx},
+    poseidon::ROPair,
+};
+
+/// Applies `OPS` accesses to the [`MemoryState`] carried in `z_i`
+///
+/// Requires `T >= 4`, see [`MemoryChip`]
+pub struct MemoryStepCircuit<F, RO, const T: usize, const OPS: usize>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    ro_args: RO::Args,
+    ops: [MemoryOp<F>; OPS],
+}
+
+impl<F, RO, const T: usize, const OPS: usize> MemoryStepCircuit<F, RO, T, OPS>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    pub fn new(ro_args: RO::Args, ops: [MemoryOp<F>; OPS]) -> Self {
+        Self { ro_args, ops }
+    }
+}
+
+impl<F, RO, const T: usize, const OPS: usize> StepCircuit<MEMORY_STATE_SIZE, F>
+    for MemoryStepCircuit<F, RO, T, OPS>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    type Config = MainGateConfig<T>;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        MainGate::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; MEMORY_STATE_SIZE],
+    ) -> Result<[AssignedCell<F, F>; MEMORY_STATE_SIZE], SynthesisError> {
+        let chip = MemoryChip::<F, RO, T>::new(config, self.ro_args.clone());
+
+        let state = layouter.assign_region(
+            || "memory accesses",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                chip.apply_ops(&mut ctx, &AssignedMemoryState::from(z_i), &self.ops)
+                    .map(|(state, _)| state)
+            },
+        )?;
+
+        Ok(state.into())
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; MEMORY_STATE_SIZE],
+        _k_table_size: u32,
+    ) -> Result<[F; MEMORY_STATE_SIZE], SynthesisError> {
+        Ok(
+            off_circuit::apply_ops::<F, RO>(&self.ro_args, &MemoryState::from(*z_i), &self.ops)
+                .into(),
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoryConsistencyConfig<const T: usize> {
+    main_gate: MainGateConfig<T>,
+    instance: Column<Instance>,
+}
+
+/// Checks the [`MemoryState`] of the last step against the final `memory`
+///
+/// Public inputs are the last `z_i` of [`MemoryStepCircuit`] chain
+pub struct MemoryConsistencyCircuit<F, RO, const T: usize>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    ro_args: RO::Args,
+    memory: Memory<F>,
+    _p: PhantomData<RO>,
+}
+
+impl<F, RO, const T: usize> MemoryConsistencyCircuit<F, RO, T>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    pub fn new(ro_args: RO::Args, memory: Memory<F>) -> Self {
+        Self {
+            ro_args,
+            memory,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<F, RO, const T: usize> Circuit<F> for MemoryConsistencyCircuit<F, RO, T>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    type Config = MemoryConsistencyConfig<T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.ro_args.clone(), self.memory.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        MemoryConsistencyConfig {
+            main_gate: MainGate::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MemoryChip::<F, RO, T>::new(config.main_gate.clone(), self.ro_args.clone());
+
+        layouter.assign_region(
+            || "memory consistency",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let z = (0..MEMORY_STATE_SIZE)
+                    .map(|index| {
+                        let cell = ctx.assign_advice_from_instance(
+                            || "z",
+                            config.main_gate.out,
+                            config.instance,
+                            index,
+                        )?;
+                        ctx.next();
+                        Ok(cell)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let z: &[_; MEMORY_STATE_SIZE] = z.as_slice().try_into().expect("exact size");
+
+                chip.check_consistency(
+                    &mut ctx,
+                    &AssignedMemoryState::from(z),
+                    self.memory.initial(),
+                    self.memory.values(),
+                    self.memory.timestamps(),
+                )
+            },
+        )
+    }
+}
@@ -0,0 +1,126 @@
+use halo2_proofs::dev::MockProver;
+use tracing_test::traced_test;
+
+use super::{off_circuit::*, *};
+use crate::{
+    ff::Field,
+    halo2curves::bn256::Fr,
+    ivc::StepCircuit,
+    poseidon::{PoseidonRO, ROPair},
+    run_mock_prover_test,
+    util::mock_prover::MockProver as StepMockProver,
+};
+
+const T: usize = 5;
+const RATE: usize = 4;
+const OPS: usize = 3;
+const K: u32 = 13;
+
+type RO = PoseidonRO<T, RATE>;
+type Spec = <RO as ROPair<Fr>>::Args;
+
+fn spec() -> Spec {
+    Spec::new(10, 10)
+}
+
+/// Swaps the first two cells of the memory, then doubles the first one, by batches of [`OPS`]
+fn program(memory: &mut Memory<Fr>) -> Vec<[MemoryOp<Fr>; OPS]> {
+    let a = memory.read(0).unwrap();
+    let b = memory.read(1).unwrap();
+    let first = [a, b, memory.write(0, b.value).unwrap()];
+
+    let second = [
+        memory.write(1, a.value).unwrap(),
+        memory.write(0, b.value.double()).unwrap(),
+        MemoryOp::noop(memory),
+    ];
+
+    let third = [
+        memory.read(3).unwrap(),
+        MemoryOp::noop(memory),
+        MemoryOp::noop(memory),
+    ];
+
+    vec![first, second, third]
+}
+
+fn initial_memory() -> Memory<Fr> {
+    Memory::new([7, 11, 0, 42].map(Fr::from).to_vec())
+}
+
+/// Runs the chain of steps off-circuit & returns the last `z_i`
+fn run(batches: &[[MemoryOp<Fr>; OPS]], memory: &Memory<Fr>) -> [Fr; MEMORY_STATE_SIZE] {
+    batches.iter().fold(
+        initial_state::<Fr, RO>(&spec(), batches, memory),
+        |z_i, ops| {
+            MemoryStepCircuit::<Fr, RO, T, OPS>::new(spec(), *ops)
+                .process_step(&z_i, K)
+                .unwrap()
+        },
+    )
+}
+
+#[test]
+fn memory_off_circuit() {
+    let mut memory = initial_memory();
+    let batches = program(&mut memory);
+
+    assert_eq!(memory.values(), [22, 7, 0, 42].map(Fr::from));
+    assert_eq!(memory.time(), 9);
+
+    let z = run(&batches, &memory);
+    assert!(is_consistent::<Fr, RO>(&spec(), &z.into(), &memory));
+
+    // the final memory is bound to challenges
+    let mut tampered = memory.clone();
+    tampered.write(2, Fr::ONE);
+    assert!(!is_consistent::<Fr, RO>(&spec(), &z.into(), &tampered));
+}
+
+#[traced_test]
+#[test]
+fn memory_step_circuit() {
+    let mut memory = initial_memory();
+    let batches = program(&mut memory);
+
+    let mut z_i = initial_state::<Fr, RO>(&spec(), &batches, &memory);
+    for ops in batches {
+        let step_circuit = MemoryStepCircuit::<Fr, RO, T, OPS>::new(spec(), ops);
+        let z_out = step_circuit.process_step(&z_i, K).unwrap();
+
+        StepMockProver::run(K, &step_circuit, vec![], z_i)
+            .unwrap()
+            .verify(z_out)
+            .unwrap();
+
+        z_i = z_out;
+    }
+
+    run_mock_prover_test!(
+        K,
+        MemoryConsistencyCircuit::<Fr, RO, T>::new(spec(), memory),
+        vec![z_i.to_vec()]
+    );
+}
+
+#[traced_test]
+#[test]
+fn memory_wrong_read() {
+    let mut memory = initial_memory();
+    let mut batches = program(&mut memory);
+
+    // the read of the cell 1 returns a value that was never written there
+    batches[0][1].old_value = Fr::from(12);
+    batches[0][1].value = Fr::from(12);
+
+    let z = run(&batches, &memory);
+    assert!(!is_consistent::<Fr, RO>(&spec(), &z.into(), &memory));
+
+    let prover = MockProver::run(
+        K,
+        &MemoryConsistencyCircuit::<Fr, RO, T>::new(spec(), memory),
+        vec![z.to_vec()],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+}
@@ -0,0 +1,209 @@
+use halo2_proofs::{
+    circuit::{Chip, Value},
+    plonk::Error,
+};
+use itertools::Itertools;
+
+use super::off_circuit::{MembershipProof, Proof};
+use crate::{
+    ff::{FromUniformBytes, PrimeFieldBits},
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx, WrapValue},
+    poseidon::{ROCircuitTrait, ROPair},
+};
+
+/// Assigned result of [`MerkleTreeChip::verify_membership`]
+#[derive(Debug, Clone)]
+pub struct AssignedMembership<F: PrimeFieldBits> {
+    pub index: AssignedValue<F>,
+    pub value: AssignedValue<F>,
+    pub root: AssignedValue<F>,
+}
+
+/// Assigned result of [`MerkleTreeChip::verify_update`]
+#[derive(Debug, Clone)]
+pub struct AssignedUpdate<F: PrimeFieldBits> {
+    pub index: AssignedValue<F>,
+    pub old_value: AssignedValue<F>,
+    pub new_value: AssignedValue<F>,
+    pub old_root: AssignedValue<F>,
+    pub new_root: AssignedValue<F>,
+}
+
+/// On-circuit verification of [`super::Tree`] proofs
+///
+/// The position of the leaf is constrained by the bits of its index, so the returned index can be
+/// checked by the caller as well as the leaf value & roots.
+///
+/// Requires `T >= 4` because of [`MainGate::conditional_select`]
+pub struct MerkleTreeChip<F, RO, const T: usize>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    main_gate: MainGate<F, T>,
+    ro_args: RO::Args,
+}
+
+impl<F, RO, const T: usize> MerkleTreeChip<F, RO, T>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    pub fn new(config: MainGateConfig<T>, ro_args: RO::Args) -> Self {
+        Self {
+            main_gate: MainGate::new(config),
+            ro_args,
+        }
+    }
+
+    fn hash(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        l: &AssignedValue<F>,
+        r: &AssignedValue<F>,
+    ) -> Result<AssignedValue<F>, Error> {
+        RO::OnCircuit::new(self.main_gate.config().clone(), self.ro_args.clone())
+            .absorb_base(WrapValue::Assigned(l.clone()))
+            .absorb_base(WrapValue::Assigned(r.clone()))
+            .squeeze_native(region)
+    }
+
+    /// Hash of the node & its sibling, where `is_right` is the bit of the node index at this level
+    fn hash_with_sibling(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        node: &AssignedValue<F>,
+        sibling: &AssignedValue<F>,
+        is_right: &AssignedBit<F>,
+    ) -> Result<AssignedValue<F>, Error> {
+        let left = self
+            .main_gate
+            .conditional_select(region, sibling, node, is_right)?;
+        let right = self
+            .main_gate
+            .conditional_select(region, node, sibling, is_right)?;
+
+        self.hash(region, &left, &right)
+    }
+
+    /// Assigns little-endian bits of the leaf index, one per level, and the index itself
+    fn assign_index(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        index: u64,
+        depth: u8,
+    ) -> Result<(AssignedValue<F>, Vec<AssignedBit<F>>), Error> {
+        let bits = (0..depth)
+            .map(|level| (index >> level) & 1 == 1)
+            .collect::<Vec<_>>();
+
+        let bits = self.main_gate.assign_bits(region, &bits)?;
+        let index = self.main_gate.le_bits_to_num(region, &bits)?;
+
+        Ok((index, bits))
+    }
+
+    fn assign_values(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        values: impl IntoIterator<Item = F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        values
+            .into_iter()
+            .map(|value| self.main_gate.assign_value(region, Value::known(value)))
+            .collect()
+    }
+
+    /// Returns the assigned leaf index & value together with the root calculated from them
+    pub fn verify_membership(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        proof: &MembershipProof<F>,
+    ) -> Result<AssignedMembership<F>, Error> {
+        let (index, bits) = self.assign_index(region, proof.index, proof.depth())?;
+        let value = self
+            .main_gate
+            .assign_value(region, Value::known(proof.value))?;
+        let siblings = self.assign_values(region, proof.siblings.iter().copied())?;
+
+        let root = siblings
+            .iter()
+            .zip_eq(bits.iter())
+            .try_fold(value.clone(), |node, (sibling, is_right)| {
+                self.hash_with_sibling(region, &node, sibling, is_right)
+            })?;
+
+        Ok(AssignedMembership { index, value, root })
+    }
+
+    /// Same as [`MerkleTreeChip::verify_membership`], but also constrains the leaf to be empty
+    pub fn verify_non_membership(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        proof: &MembershipProof<F>,
+    ) -> Result<AssignedMembership<F>, Error> {
+        let membership = self.verify_membership(region, proof)?;
+        self.main_gate
+            .assert_equal_const(region, membership.value.clone(), F::ZERO)?;
+        Ok(membership)
+    }
+
+    /// Returns the assigned leaf index & values together with the roots calculated from them
+    ///
+    /// The old and new roots are calculated with the same siblings, so only one leaf differs
+    pub fn verify_update(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        proof: &Proof<F>,
+    ) -> Result<AssignedUpdate<F>, Error> {
+        let (index, bits) = self.assign_index(region, proof.index(), proof.depth())?;
+        let leaf = proof.leaf();
+        let old_value = self
+            .main_gate
+            .assign_value(region, Value::known(leaf.old))?;
+        let new_value = self
+            .main_gate
+            .assign_value(region, Value::known(leaf.new))?;
+        let siblings = self.assign_values(
+            region,
+            proof.iter().filter_map(|(_, update)| update.sibling),
+        )?;
+
+        let (old_root, new_root) = siblings.iter().zip_eq(bits.iter()).try_fold(
+            (old_value.clone(), new_value.clone()),
+            |(old, new), (sibling, is_right)| {
+                Ok::<_, Error>((
+                    self.hash_with_sibling(region, &old, sibling, is_right)?,
+                    self.hash_with_sibling(region, &new, sibling, is_right)?,
+                ))
+            },
+        )?;
+
+        Ok(AssignedUpdate {
+            index,
+            old_value,
+            new_value,
+            old_root,
+            new_root,
+        })
+    }
+
+    /// Verifies all updates and constrains them to be applied one after another, i.e. the new root
+    /// of each update is the old root of the next one
+    pub fn verify_batch_update(
+        &self,
+        region: &mut RegionCtx<'_, F>,
+        proofs: &[Proof<F>],
+    ) -> Result<Vec<AssignedUpdate<F>>, Error> {
+        let updates = proofs
+            .iter()
+            .map(|proof| self.verify_update(region, proof))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (prev, next) in updates.iter().tuple_windows() {
+            region.constrain_equal(prev.new_root.cell(), next.old_root.cell())?;
+        }
+
+        Ok(updates)
+    }
+}
@@ -0,0 +1,27 @@
+//! # Sparse Merkle Tree
+//!
+//! Binary Merkle tree of configurable depth with `2^depth` leaves, where each node is the hash of
+//! its children. Any random oracle ([`ROPair`]) can be used as a hash function, both off-circuit &
+//! on-circuit versions are taken from the same pair.
+//!
+//! The tree is sparse: empty leaves are equal to zero, so the values of nodes of empty subtrees
+//! depend only on their level and are cached once for each level. Only non-default nodes are
+//! stored, which makes it possible to use trees with a depth up to [`MAX_DEPTH`].
+//!
+//! - [`Tree`] - off-circuit tree, produces update & membership proofs
+//! - [`MerkleTreeChip`] - on-circuit verification of these proofs
+//!
+//! The non-membership of a leaf is proved by the membership of zero at its index.
+//!
+//! [`ROPair`]: crate::poseidon::ROPair
+
+pub mod chip;
+pub mod off_circuit;
+
+pub use chip::{AssignedMembership, AssignedUpdate, MerkleTreeChip};
+pub use off_circuit::{
+    hash, verify_batch, Index, MembershipProof, NodeUpdate, Proof, Sibling, Tree, MAX_DEPTH,
+};
+
+#[cfg(test)]
+mod tests;
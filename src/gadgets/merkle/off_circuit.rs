@@ -0,0 +1,542 @@
+use std::{collections::HashMap, fmt, iter};
+
+use itertools::Itertools;
+use tracing::*;
+
+use crate::{
+    ff::{FromUniformBytes, PrimeFieldBits},
+    poseidon::{ROPair, ROTrait},
+};
+
+/// Maximum supported depth of the tree, so that the index of a leaf fits into `u64`
+pub const MAX_DEPTH: u8 = 63;
+
+/// Hash of two children nodes, the value of their parent
+pub fn hash<F, RO>(ro_args: &RO::Args, l: F, r: F) -> F
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    RO::OffCircuit::new(ro_args.clone())
+        .absorb_field(l)
+        .absorb_field(r)
+        .squeeze_native()
+}
+
+/// Position of the node in the tree
+///
+/// Level `0` is the level of leaves, level `depth` is the level of the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    pub level: u8,
+    pub index: u64,
+}
+
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}][{}]", self.level, self.index)
+    }
+}
+
+impl Index {
+    pub fn leaf(index: u64) -> Self {
+        Self { level: 0, index }
+    }
+
+    pub fn root(depth: u8) -> Self {
+        Self {
+            level: depth,
+            index: 0,
+        }
+    }
+
+    pub fn parent(&self) -> Self {
+        Self {
+            level: self.level + 1,
+            index: self.index / 2,
+        }
+    }
+
+    pub fn get_sibling(&self) -> Sibling<Self> {
+        let level = self.level;
+
+        if self.index % 2 == 0 {
+            Sibling::Right(Self {
+                level,
+                index: self.index + 1,
+            })
+        } else {
+            Sibling::Left(Self {
+                level,
+                index: self.index - 1,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sibling<V> {
+    Left(V),
+    Right(V),
+}
+
+impl<V> Sibling<V> {
+    pub fn map<T>(self, f: impl FnOnce(V) -> T) -> Sibling<T> {
+        match self {
+            Sibling::Left(l) => Sibling::Left(f(l)),
+            Sibling::Right(r) => Sibling::Right(f(r)),
+        }
+    }
+
+    pub fn unwrap(self) -> V {
+        match self {
+            Sibling::Left(l) => l,
+            Sibling::Right(r) => r,
+        }
+    }
+}
+
+impl<F: PrimeFieldBits + FromUniformBytes<64>> Sibling<F> {
+    /// Hash of the node with this sibling in the right order
+    fn hash_with<RO: ROPair<F>>(&self, ro_args: &RO::Args, node: F) -> F {
+        match self {
+            Sibling::Left(left) => hash::<F, RO>(ro_args, *left, node),
+            Sibling::Right(right) => hash::<F, RO>(ro_args, node, *right),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeUpdate<F> {
+    /// Index of node in a level
+    pub index: u64,
+    /// Old value, before update
+    pub old: F,
+    /// New value, after update
+    pub new: F,
+    /// Sibling of this node, to calculate the next level value
+    /// None for root
+    pub sibling: Option<F>,
+}
+
+impl<F> NodeUpdate<F> {
+    pub fn map<T>(self, mut f: impl FnMut(F) -> T) -> NodeUpdate<T> {
+        NodeUpdate {
+            index: self.index,
+            old: f(self.old),
+            new: f(self.new),
+            sibling: self.sibling.map(f),
+        }
+    }
+
+    pub fn try_map<T, E>(self, mut f: impl FnMut(F) -> Result<T, E>) -> Result<NodeUpdate<T>, E> {
+        Ok(NodeUpdate {
+            index: self.index,
+            old: f(self.old)?,
+            new: f(self.new)?,
+            sibling: self.sibling.map(f).transpose()?,
+        })
+    }
+}
+
+/// Proof of the leaf update
+///
+/// Contains the updates of all nodes on the path from the leaf (first) to the root (last)
+#[derive(Debug, Clone)]
+pub struct Proof<F> {
+    path: Box<[NodeUpdate<F>]>,
+}
+
+impl<F: PrimeFieldBits + FromUniformBytes<64>> Proof<F> {
+    pub fn depth(&self) -> u8 {
+        (self.path.len() - 1) as u8
+    }
+
+    /// Index of the updated leaf
+    pub fn index(&self) -> u64 {
+        self.leaf().index
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &NodeUpdate<F>)> {
+        (0..).zip(self.path.iter()).map(|(level, update)| {
+            (
+                Index {
+                    level,
+                    index: update.index,
+                },
+                update,
+            )
+        })
+    }
+
+    pub fn leaf(&self) -> &NodeUpdate<F> {
+        self.path.first().unwrap()
+    }
+
+    pub fn root(&self) -> &NodeUpdate<F> {
+        self.path.last().unwrap()
+    }
+
+    pub fn verify<RO: ROPair<F>>(&self, ro_args: &RO::Args) -> bool {
+        if self.root().sibling.is_some() || self.root().index != 0 {
+            error!("root must be without sibling and at zero index");
+            return false;
+        }
+
+        for ((index, update), (next_index, next_update)) in self.iter().tuple_windows() {
+            debug!("start work with index: {index}");
+
+            let Some(sibling) = update.sibling else {
+                error!("sibling is missing at {index}");
+                return false;
+            };
+            if next_index != index.parent() {
+                error!("{next_index} is not a parent of {index}");
+                return false;
+            }
+
+            let sibling = index.get_sibling().map(|_| sibling);
+
+            let old_next_value = sibling.hash_with::<RO>(ro_args, update.old);
+            if next_update.old != old_next_value {
+                error!(
+                    "`old` not match {:?} != {old_next_value:?}",
+                    next_update.old
+                );
+                return false;
+            }
+
+            let new_next_value = sibling.hash_with::<RO>(ro_args, update.new);
+            if next_update.new != new_next_value {
+                error!(
+                    "`new` not match {:?} != {new_next_value:?}",
+                    next_update.new
+                );
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Checks that all proofs are valid and applied one after another, i.e. the new root of each
+/// proof is the old root of the next one
+pub fn verify_batch<F, RO>(ro_args: &RO::Args, proofs: &[Proof<F>]) -> bool
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    proofs.iter().all(|proof| proof.verify::<RO>(ro_args))
+        && proofs
+            .iter()
+            .tuple_windows()
+            .all(|(prev, next)| prev.root().new == next.root().old)
+}
+
+/// Proof of the value of the leaf
+///
+/// If `value` is zero, this is a proof of non-membership
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipProof<F> {
+    pub index: u64,
+    pub value: F,
+    /// Siblings of all nodes on the path from the leaf to the root (exclusive)
+    pub siblings: Box<[F]>,
+}
+
+impl<F: PrimeFieldBits + FromUniformBytes<64>> MembershipProof<F> {
+    pub fn depth(&self) -> u8 {
+        self.siblings.len() as u8
+    }
+
+    pub fn is_non_membership(&self) -> bool {
+        self.value.is_zero_vartime()
+    }
+
+    /// Root of the tree, calculated from the leaf & its siblings
+    pub fn root<RO: ROPair<F>>(&self, ro_args: &RO::Args) -> F {
+        iter::successors(Some(Index::leaf(self.index)), |index| Some(index.parent()))
+            .zip(self.siblings.iter())
+            .fold(self.value, |node, (index, sibling)| {
+                index
+                    .get_sibling()
+                    .map(|_| *sibling)
+                    .hash_with::<RO>(ro_args, node)
+            })
+    }
+
+    pub fn verify<RO: ROPair<F>>(&self, ro_args: &RO::Args, root: &F) -> bool {
+        self.root::<RO>(ro_args).eq(root)
+    }
+}
+
+pub struct Tree<F, RO>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    depth: u8,
+    ro_args: RO::Args,
+    /// Only nodes, which differ from the default value of their level
+    filled_nodes: HashMap<Index, F>,
+    /// Values of nodes of an empty subtree for each level, from leaves to root
+    default_values: Box<[F]>,
+}
+
+impl<F, RO> Tree<F, RO>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    RO: ROPair<F>,
+{
+    /// Creates an empty tree with `2^depth` leaves
+    ///
+    /// # Panics
+    /// If `depth` is zero or greater than [`MAX_DEPTH`]
+    pub fn new(depth: u8, ro_args: RO::Args) -> Self {
+        assert!(
+            (1..=MAX_DEPTH).contains(&depth),
+            "depth must be in 1..={MAX_DEPTH}, but {depth}"
+        );
+
+        let default_values = iter::successors(Some(F::ZERO), |prev| {
+            Some(hash::<F, RO>(&ro_args, *prev, *prev))
+        })
+        .take(depth as usize + 1)
+        .collect();
+
+        Self {
+            depth,
+            ro_args,
+            filled_nodes: HashMap::new(),
+            default_values,
+        }
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Number of leaves
+    pub fn capacity(&self) -> u64 {
+        1 << self.depth
+    }
+
+    pub fn ro_args(&self) -> &RO::Args {
+        &self.ro_args
+    }
+
+    pub fn get_root(&self) -> &F {
+        self.get_node(Index::root(self.depth))
+    }
+
+    pub fn get_leaf(&self, index: u64) -> &F {
+        self.get_node(Index::leaf(index))
+    }
+
+    fn get_default_value(&self, level: u8) -> &F {
+        &self.default_values[level as usize]
+    }
+
+    fn get_node(&self, index: Index) -> &F {
+        self.filled_nodes
+            .get(&index)
+            .unwrap_or_else(|| self.get_default_value(index.level))
+    }
+
+    /// Stores the node, if it differs from the default one & returns the previous value
+    fn update_node(&mut self, index: Index, new_value: F) -> F {
+        let default = *self.get_default_value(index.level);
+
+        let old = if new_value == default {
+            self.filled_nodes.remove(&index)
+        } else {
+            self.filled_nodes.insert(index, new_value)
+        };
+
+        old.unwrap_or(default)
+    }
+
+    fn check_index(&self, index: u64) {
+        assert!(
+            index < self.capacity(),
+            "leaf index {index} out of tree with depth {}",
+            self.depth
+        );
+    }
+
+    /// Siblings of all nodes on the path from the leaf to the root (exclusive)
+    fn siblings(&self, index: u64) -> impl '_ + Iterator<Item = F> {
+        iter::successors(Some(Index::leaf(index)), |index| Some(index.parent()))
+            .take(self.depth as usize)
+            .map(|index| *self.get_node(index.get_sibling().unwrap()))
+    }
+
+    #[instrument(skip(self))]
+    pub fn update_leaf(&mut self, index: u64, value: F) -> Proof<F> {
+        self.check_index(index);
+
+        let mut current = Index::leaf(index);
+        let mut path = Vec::with_capacity(self.depth as usize + 1);
+        let mut new_value = value;
+
+        loop {
+            let old_value = self.update_node(current, new_value);
+
+            if current.level == self.depth {
+                path.push(NodeUpdate {
+                    index: current.index,
+                    old: old_value,
+                    new: new_value,
+                    sibling: None,
+                });
+                break;
+            }
+
+            let sibling = current.get_sibling().map(|s| *self.get_node(s));
+            debug!("update {current}: sib:{sibling:?} from {old_value:?} to {new_value:?}");
+
+            path.push(NodeUpdate {
+                index: current.index,
+                old: old_value,
+                new: new_value,
+                sibling: Some(sibling.clone().unwrap()),
+            });
+
+            new_value = sibling.hash_with::<RO>(&self.ro_args, new_value);
+            current = current.parent();
+        }
+
+        Proof {
+            path: path.into_boxed_slice(),
+        }
+    }
+
+    /// Applies all updates one after another
+    ///
+    /// The returned proofs can be checked together by [`verify_batch`]
+    pub fn update_leaves(
+        &mut self,
+        updates: impl IntoIterator<Item = (u64, F)>,
+    ) -> Box<[Proof<F>]> {
+        updates
+            .into_iter()
+            .map(|(index, value)| self.update_leaf(index, value))
+            .collect()
+    }
+
+    /// Proof of the current value of the leaf, for an empty leaf this is proof of non-membership
+    pub fn prove_membership(&self, index: u64) -> MembershipProof<F> {
+        self.check_index(index);
+
+        MembershipProof {
+            index,
+            value: *self.get_leaf(index),
+            siblings: self.siblings(index).collect(),
+        }
+    }
+
+    /// Proof that the leaf is empty, `None` if it is filled
+    pub fn prove_non_membership(&self, index: u64) -> Option<MembershipProof<F>> {
+        Some(self.prove_membership(index)).filter(MembershipProof::is_non_membership)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{ff::Field, halo2curves::bn256::Fr, poseidon::PoseidonRO};
+
+    type RO = PoseidonRO<5, 4>;
+    type Spec = <RO as ROPair<Fr>>::Args;
+
+    fn spec() -> Spec {
+        Spec::new(10, 10)
+    }
+
+    #[traced_test]
+    #[test]
+    fn simple_test() {
+        const DEPTH: u8 = 31;
+
+        let mut tr = Tree::<Fr, RO>::new(DEPTH, spec());
+        let mut rng = rand::thread_rng();
+
+        let pr1 = tr.update_leaf(3, Fr::random(&mut rng));
+        assert!(pr1.verify::<RO>(&spec()));
+
+        let pr2 = tr.update_leaf(3, Fr::random(&mut rng));
+        assert!(pr2.verify::<RO>(&spec()));
+
+        pr1.path
+            .iter()
+            .zip(pr2.path.iter())
+            .for_each(|(upd1, upd2)| {
+                assert_eq!(upd1.index, upd2.index);
+                assert_eq!(upd1.new, upd2.old);
+                assert_eq!(upd1.sibling, upd2.sibling);
+            });
+
+        let pr3 = tr.update_leaf((1 << DEPTH) - 1, Fr::random(&mut rng));
+        assert!(pr3.verify::<RO>(&spec()));
+
+        pr3.iter().for_each(|(index, upd)| {
+            let default = *tr.get_default_value(index.level);
+
+            // all nodes, but root, changed
+            if index.level != DEPTH {
+                assert_eq!(upd.old, default, "at {index}");
+            }
+
+            // sibling not filled only for root
+            if let Some(sibling) = upd.sibling {
+                // only sibling of the node under the root is not default
+                if index.level != DEPTH - 1 {
+                    assert_eq!(sibling, default, "at {index}");
+                }
+            }
+        });
+    }
+
+    #[traced_test]
+    #[test]
+    fn membership() {
+        let mut tr = Tree::<Fr, RO>::new(8, spec());
+        let value = Fr::from(42);
+
+        let empty = tr.prove_non_membership(7).unwrap();
+        assert!(empty.verify::<RO>(&spec(), tr.get_root()));
+
+        tr.update_leaf(7, value);
+        assert_eq!(tr.prove_non_membership(7), None);
+        assert!(!empty.verify::<RO>(&spec(), tr.get_root()));
+
+        let proof = tr.prove_membership(7);
+        assert_eq!(proof.value, value);
+        assert!(proof.verify::<RO>(&spec(), tr.get_root()));
+
+        let neighbour = tr.prove_non_membership(6).unwrap();
+        assert!(neighbour.verify::<RO>(&spec(), tr.get_root()));
+    }
+
+    #[traced_test]
+    #[test]
+    fn batch_and_sparsity() {
+        let mut tr = Tree::<Fr, RO>::new(16, spec());
+        let empty_root = *tr.get_root();
+
+        let proofs = tr.update_leaves([(1, Fr::ONE), (1000, Fr::from(2)), (1, Fr::ZERO)]);
+        assert!(verify_batch::<Fr, RO>(&spec(), &proofs));
+        assert!(!verify_batch::<Fr, RO>(
+            &spec(),
+            &[proofs[0].clone(), proofs[2].clone()]
+        ));
+        assert_eq!(proofs.first().unwrap().root().old, empty_root);
+
+        tr.update_leaf(1000, Fr::ZERO);
+        assert_eq!(*tr.get_root(), empty_root);
+        assert!(tr.filled_nodes.is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{
+    ff::Field,
+    halo2curves::bn256::Fr,
+    main_gate::{MainGate, MainGateConfig, RegionCtx},
+    poseidon::{PoseidonRO, ROPair},
+    run_mock_prover_test,
+};
+
+const T: usize = 5;
+const RATE: usize = 4;
+const DEPTH: u8 = 8;
+const K: u32 = 14;
+
+type RO = PoseidonRO<T, RATE>;
+type Spec = <RO as ROPair<Fr>>::Args;
+
+fn spec() -> Spec {
+    Spec::new(10, 10)
+}
+
+#[derive(Clone, Debug)]
+struct TestCircuitConfig {
+    main_gate: MainGateConfig<T>,
+    instance: Column<Instance>,
+}
+
+/// Applies batch of updates & checks membership of one leaf and non-membership of another one in
+/// the resulting tree
+///
+/// Public inputs: `[old_root, new_root, membership_index]`
+struct TestCircuit {
+    updates: Box<[Proof<Fr>]>,
+    membership: MembershipProof<Fr>,
+    non_membership: MembershipProof<Fr>,
+}
+
+impl TestCircuit {
+    fn new(tree: &mut Tree<Fr, RO>, updates: &[(u64, Fr)], non_member: u64) -> (Self, Vec<Fr>) {
+        let old_root = *tree.get_root();
+        let updates = tree.update_leaves(updates.iter().copied());
+        let membership = tree.prove_membership(updates.last().unwrap().index());
+        let non_membership = tree.prove_non_membership(non_member).unwrap();
+
+        let public_inputs = vec![old_root, *tree.get_root(), Fr::from(membership.index)];
+
+        (
+            Self {
+                updates,
+                membership,
+                non_membership,
+            },
+            public_inputs,
+        )
+    }
+}
+
+impl Circuit<Fr> for TestCircuit {
+    type Config = TestCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::Config {
+            main_gate: MainGate::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = MerkleTreeChip::<Fr, RO, T>::new(config.main_gate, spec());
+
+        let [old_root, new_root, index] = layouter.assign_region(
+            || "merkle tree",
+            |region| {
+                let region = &mut RegionCtx::new(region, 0);
+
+                let updates = chip.verify_batch_update(region, &self.updates)?;
+                let membership = chip.verify_membership(region, &self.membership)?;
+                let non_membership = chip.verify_non_membership(region, &self.non_membership)?;
+
+                let new_root = updates.last().unwrap().new_root.clone();
+                region.constrain_equal(new_root.cell(), membership.root.cell())?;
+                region.constrain_equal(new_root.cell(), non_membership.root.cell())?;
+                region.constrain_equal(
+                    updates.last().unwrap().new_value.cell(),
+                    membership.value.cell(),
+                )?;
+
+                Ok([
+                    updates.first().unwrap().old_root.clone(),
+                    new_root,
+                    membership.index,
+                ])
+            },
+        )?;
+
+        layouter.constrain_instance(old_root.cell(), config.instance, 0)?;
+        layouter.constrain_instance(new_root.cell(), config.instance, 1)?;
+        layouter.constrain_instance(index.cell(), config.instance, 2)?;
+
+        Ok(())
+    }
+}
+
+#[traced_test]
+#[test]
+fn chip_matches_tree() {
+    let mut tree = Tree::<Fr, RO>::new(DEPTH, spec());
+    tree.update_leaf(100, Fr::from(7));
+
+    let (circuit, public_inputs) = TestCircuit::new(
+        &mut tree,
+        &[
+            (3, Fr::from(1)),
+            (255, Fr::from(2)),
+            (100, Fr::ZERO),
+            (3, Fr::from(3)),
+        ],
+        100,
+    );
+
+    run_mock_prover_test!(K, circuit, vec![public_inputs]);
+}
+
+#[traced_test]
+#[test]
+fn chip_binds_index() {
+    use halo2_proofs::dev::MockProver;
+
+    let mut tree = Tree::<Fr, RO>::new(DEPTH, spec());
+    let (circuit, mut public_inputs) = TestCircuit::new(&mut tree, &[(6, Fr::ONE)], 7);
+
+    public_inputs[2] = Fr::from(7);
+
+    let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+    assert!(prover.verify().is_err());
+}
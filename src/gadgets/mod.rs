@@ -1,3 +1,10 @@
 pub mod ecc;
+pub mod integer;
+pub mod keccak;
+pub mod memory;
+pub mod merkle;
 pub mod nonnative;
+pub mod range_check;
+pub mod sha256;
+pub mod signature;
 pub(crate) mod util;
@@ -14,6 +14,7 @@ use tracing::*;
 use super::big_uint::{self, BigUint};
 use crate::{
     ff::{PrimeField, PrimeFieldBits},
+    gadgets::range_check::RangeCheckChip,
     main_gate::{AssignAdviceFrom, MainGate, MainGateConfig, RegionCtx},
     util,
 };
@@ -49,6 +50,7 @@ pub struct BigUintMulModChip<F: PrimeField> {
     main_gate: MainGate<F, MAIN_GATE_T>,
     limb_width: NonZeroUsize,
     limbs_count: NonZeroUsize,
+    range_check: Option<RangeCheckChip<F>>,
 }
 
 impl<F: PrimeField> BigUintMulModChip<F> {
@@ -61,9 +63,19 @@ impl<F: PrimeField> BigUintMulModChip<F> {
             main_gate: MainGate::new(config),
             limbs_count,
             limb_width,
+            range_check: None,
         }
     }
 
+    /// Makes range checks of [`Self::range_check`] by lookups of `range_check`, instead of the
+    /// decomposition into bits
+    ///
+    /// The table of `range_check` must be loaded by the circuit
+    pub fn with_range_check(mut self, range_check: RangeCheckChip<F>) -> Self {
+        self.range_check = Some(range_check);
+        self
+    }
+
     pub fn to_bignat(&self, input: &BigUintRaw) -> Result<BigUint<F>, Error> {
         Ok(BigUint::<F>::from_biguint(
             input,
@@ -538,7 +550,7 @@ impl<F: PrimeField> BigUintMulModChip<F> {
     ///   fits ([`BigUintMulModChip::check_fits_in_bits`]) into the carry
     ///   bits ([`calc_carry_bits`]).
     /// - in the last step, we check that `carry[n] = m[n]`
-    fn is_equal(
+    pub(crate) fn is_equal(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         input_lhs: OverflowingBigUint<F>,
@@ -731,7 +743,7 @@ impl<F: PrimeField> BigUintMulModChip<F> {
                 if limb_index != max_cells_len - 1 {
                     prev_carry_cell = Some({
                         ctx.next();
-                        self.decompose_in_bits(ctx, carry_cell.clone(), carry_bits_len)?;
+                        self.range_check(ctx, carry_cell.clone(), carry_bits_len)?;
 
                         carry_cell
                     });
@@ -765,6 +777,25 @@ impl<F: PrimeField> BigUintMulModChip<F> {
         Ok(())
     }
 
+    /// Checks that the `cell` takes up no more than `expected_bits_count` bits
+    ///
+    /// By the [`RangeCheckChip`] if the chip was made [`Self::with_range_check`], otherwise by
+    /// [`Self::decompose_in_bits`]
+    pub fn range_check(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        cell: AssignedCell<F, F>,
+        expected_bits_count: NonZeroUsize,
+    ) -> Result<(), Error> {
+        match &self.range_check {
+            Some(range_check) => range_check.range_check(ctx, &cell, expected_bits_count)?,
+            None => {
+                self.decompose_in_bits(ctx, cell, expected_bits_count)?;
+            }
+        }
+        Ok(())
+    }
+
     /// From slice bytes, creates bit cells and verifies that they are indeed bits.
     /// Takes the first `expected_bits_count`.
     ///
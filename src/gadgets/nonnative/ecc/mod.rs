@@ -0,0 +1,371 @@
+//! Elliptic curve arithmetic over a curve, whose base field is foreign to the circuit field
+//!
+//! Coordinates are [`AssignedForeignField`] elements, so each operation on points is a handful of
+//! [`ForeignFieldChip`] operations. Unlike [`crate::gadgets::ecc::EccChip`], the point at
+//! infinity has no representation here & the formulas are incomplete: adding points with equal
+//! `x` or doubling a point with zero `y` makes the circuit unsatisfiable.
+//!
+//! To avoid such cases, [`ForeignEccChip::msm`] starts the accumulator from auxiliary points,
+//! which have no known relation with the user points, & subtracts their contribution at the end.
+
+use std::{marker::PhantomData, num::NonZeroUsize};
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{Chip, Value},
+};
+use tracing::*;
+
+use super::{
+    bn::big_uint_mul_mod_chip::MAIN_GATE_T,
+    foreign_field::{self, AssignedForeignField, ForeignFieldChip},
+};
+use crate::{
+    ff::PrimeField,
+    group::{prime::PrimeCurveAffine, Curve},
+    main_gate::{AssignedValue, MainGateConfig, RegionCtx},
+};
+
+/// Number of scalar bits processed by one step of [`ForeignEccChip::msm`]
+pub const WINDOW_SIZE: usize = 4;
+
+/// Seed of the auxiliary point of [`ForeignEccChip::msm`], `AUX_SEED * G`
+const AUX_SEED: u128 = 0x243f_6a88_85a3_08d3_1319_8a2e_0370_7344;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    ForeignField(#[from] foreign_field::Error),
+    #[error(transparent)]
+    Halo2(#[from] halo2_proofs::plonk::Error),
+    #[error("The point at infinity can't be assigned")]
+    PointAtInfinity,
+    #[error("Multi-scalar multiplication of zero terms")]
+    EmptyMsm,
+}
+
+/// Affine point of `C` with coordinates as foreign field elements, never the point at infinity
+#[derive(Clone, Debug)]
+pub struct AssignedForeignPoint<F: PrimeField, C: CurveAffine> {
+    x: AssignedForeignField<F, C::Base>,
+    y: AssignedForeignField<F, C::Base>,
+}
+
+impl<F: PrimeField, C: CurveAffine> AssignedForeignPoint<F, C> {
+    pub fn coordinates(
+        &self,
+    ) -> (
+        &AssignedForeignField<F, C::Base>,
+        &AssignedForeignField<F, C::Base>,
+    ) {
+        (&self.x, &self.y)
+    }
+
+    pub fn to_curve(&self) -> Value<Option<C>> {
+        self.x
+            .value()
+            .zip(self.y.value())
+            .map(|(x, y)| C::from_xy(x, y).into())
+    }
+}
+
+fn affine_coordinates<C: CurveAffine>(point: &C) -> Result<(C::Base, C::Base), Error> {
+    Option::from(point.coordinates().map(|c| (*c.x(), *c.y()))).ok_or(Error::PointAtInfinity)
+}
+
+/// Chip for arithmetic of [`AssignedForeignPoint`]
+///
+/// Both base & scalar fields of `C` use the same limbs layout, so the coordinates of points can
+/// be reduced into scalars with [`ForeignFieldChip::reduce_limbs`]
+#[derive(Debug)]
+pub struct ForeignEccChip<F: PrimeField, C: CurveAffine> {
+    base: ForeignFieldChip<F, C::Base>,
+    scalar: ForeignFieldChip<F, C::Scalar>,
+    _p: PhantomData<C>,
+}
+
+impl<F: PrimeField, C: CurveAffine> ForeignEccChip<F, C> {
+    pub fn new(
+        config: MainGateConfig<MAIN_GATE_T>,
+        limb_width: NonZeroUsize,
+        limbs_count: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base: ForeignFieldChip::new(config.clone(), limb_width, limbs_count)?,
+            scalar: ForeignFieldChip::new(config, limb_width, limbs_count)?,
+            _p: PhantomData,
+        })
+    }
+
+    pub fn base_field(&self) -> &ForeignFieldChip<F, C::Base> {
+        &self.base
+    }
+
+    pub fn scalar_field(&self) -> &ForeignFieldChip<F, C::Scalar> {
+        &self.scalar
+    }
+
+    /// Assigns the point & checks that it's on the curve
+    pub fn assign_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: Value<C>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let coordinates = match point.unwrap() {
+            Some(point) => Value::known(affine_coordinates(&point)?),
+            None => Value::unknown(),
+        };
+
+        let point = AssignedForeignPoint {
+            x: self.base.assign(ctx, coordinates.map(|(x, _)| x))?,
+            y: self.base.assign(ctx, coordinates.map(|(_, y)| y))?,
+        };
+        self.assert_on_curve(ctx, &point)?;
+
+        Ok(point)
+    }
+
+    /// Assigns the point bound to the constant
+    pub fn assign_constant_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: C,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let (x, y) = affine_coordinates(&point)?;
+
+        Ok(AssignedForeignPoint {
+            x: self.base.assign_constant(ctx, x)?,
+            y: self.base.assign_constant(ctx, y)?,
+        })
+    }
+
+    /// Checks `y^2 = x^3 + a * x + b`
+    pub fn assert_on_curve(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedForeignPoint<F, C>,
+    ) -> Result<(), Error> {
+        let y_square = self.base.square(ctx, &point.y)?;
+
+        let x_square = self.base.square(ctx, &point.x)?;
+        let mut rhs = self.base.mul(ctx, &x_square, &point.x)?;
+        if C::a() != C::Base::ZERO {
+            let a = self.base.assign_constant(ctx, C::a())?;
+            let a_x = self.base.mul(ctx, &a, &point.x)?;
+            rhs = self.base.add(ctx, &rhs, &a_x)?;
+        }
+        let b = self.base.assign_constant(ctx, C::b())?;
+        let rhs = self.base.add(ctx, &rhs, &b)?;
+
+        Ok(self.base.assert_equal(ctx, &y_square, &rhs)?)
+    }
+
+    pub fn assert_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignPoint<F, C>,
+        rhs: &AssignedForeignPoint<F, C>,
+    ) -> Result<(), Error> {
+        self.base.assert_equal(ctx, &lhs.x, &rhs.x)?;
+        Ok(self.base.assert_equal(ctx, &lhs.y, &rhs.y)?)
+    }
+
+    /// `lhs` if `condition` is `1` & `rhs` if it's `0`, the `condition` must be a bit
+    pub fn select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignPoint<F, C>,
+        rhs: &AssignedForeignPoint<F, C>,
+        condition: &AssignedValue<F>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        Ok(AssignedForeignPoint {
+            x: self.base.select(ctx, &lhs.x, &rhs.x, condition)?,
+            y: self.base.select(ctx, &lhs.y, &rhs.y, condition)?,
+        })
+    }
+
+    pub fn negate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedForeignPoint<F, C>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        Ok(AssignedForeignPoint {
+            x: point.x.clone(),
+            y: self.base.neg(ctx, &point.y)?,
+        })
+    }
+
+    /// Point `(x_r, lambda * (x - x_r) - y)`, where `x_r = lambda^2 - x - other_x`
+    fn add_by_slope(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lambda: &AssignedForeignField<F, C::Base>,
+        point: &AssignedForeignPoint<F, C>,
+        other_x: &AssignedForeignField<F, C::Base>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let lambda_square = self.base.square(ctx, lambda)?;
+        let x = self.base.sub(ctx, &lambda_square, &point.x)?;
+        let x = self.base.sub(ctx, &x, other_x)?;
+
+        let dx = self.base.sub(ctx, &point.x, &x)?;
+        let y = self.base.mul(ctx, lambda, &dx)?;
+        let y = self.base.sub(ctx, &y, &point.y)?;
+
+        Ok(AssignedForeignPoint { x, y })
+    }
+
+    /// `lhs + rhs`, the circuit is unsatisfiable if `lhs.x == rhs.x`
+    pub fn add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignPoint<F, C>,
+        rhs: &AssignedForeignPoint<F, C>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let dy = self.base.sub(ctx, &rhs.y, &lhs.y)?;
+        let dx = self.base.sub(ctx, &rhs.x, &lhs.x)?;
+        // `div` alone accepts `0 / 0`, so the inversion is what rejects equal `x`
+        let dx_inv = self.base.invert(ctx, &dx)?;
+        let lambda = self.base.mul(ctx, &dy, &dx_inv)?;
+
+        self.add_by_slope(ctx, &lambda, lhs, &rhs.x)
+    }
+
+    /// `2 * point`, the circuit is unsatisfiable if `point.y == 0`
+    pub fn double(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedForeignPoint<F, C>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let x_square = self.base.square(ctx, &point.x)?;
+        let mut numerator = self.base.add(ctx, &x_square, &x_square)?;
+        numerator = self.base.add(ctx, &numerator, &x_square)?;
+        if C::a() != C::Base::ZERO {
+            let a = self.base.assign_constant(ctx, C::a())?;
+            numerator = self.base.add(ctx, &numerator, &a)?;
+        }
+        let double_y = self.base.add(ctx, &point.y, &point.y)?;
+        let double_y_inv = self.base.invert(ctx, &double_y)?;
+        let lambda = self.base.mul(ctx, &numerator, &double_y_inv)?;
+
+        self.add_by_slope(ctx, &lambda, point, &point.x)
+    }
+
+    /// Selects `table[index]`, where `index` is given by little-endian `bits`
+    fn select_from_table(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        table: &[AssignedForeignPoint<F, C>],
+        bits: &[AssignedValue<F>],
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        let mut layer = table[..1 << bits.len()].to_vec();
+        for bit in bits {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.select(ctx, &pair[1], &pair[0], bit))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        Ok(layer.pop().expect("table size is a power of two"))
+    }
+
+    /// `scalar * point`, see [`Self::msm`]
+    pub fn scalar_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedForeignPoint<F, C>,
+        scalar: &AssignedForeignField<F, C::Scalar>,
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        self.msm(ctx, &[(point, scalar)])
+    }
+
+    /// `sum(scalar_i * point_i)` by the windowed method with shared doublings
+    ///
+    /// For each term the table `[aux_i, aux_i + point_i, ..., aux_i + (2^w - 1) * point_i]` is
+    /// built, where `aux_i = (i + 1) * AUX_SEED * G`. Then for each window of [`WINDOW_SIZE`]
+    /// bits, starting from the most significant one, the accumulator is doubled `w` times & the
+    /// table entries of all terms are added to it. Finally, the sum of all auxiliary points,
+    /// multiplied by their shifts, is subtracted as a constant.
+    ///
+    /// The result can't be the point at infinity, which would make the circuit unsatisfiable.
+    pub fn msm(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        terms: &[(
+            &AssignedForeignPoint<F, C>,
+            &AssignedForeignField<F, C::Scalar>,
+        )],
+    ) -> Result<AssignedForeignPoint<F, C>, Error> {
+        if terms.is_empty() {
+            return Err(Error::EmptyMsm);
+        }
+
+        let aux = C::generator() * C::Scalar::from_u128(AUX_SEED);
+
+        let mut tables = Vec::with_capacity(terms.len());
+        let mut windows = Vec::with_capacity(terms.len());
+        for (index, (point, scalar)) in terms.iter().enumerate() {
+            let aux = (aux * C::Scalar::from(index as u64 + 1)).to_affine();
+
+            let mut table = vec![self.assign_constant_point(ctx, aux)?];
+            for _ in 1..(1 << WINDOW_SIZE) {
+                let last = table.last().expect("table is not empty");
+                table.push(self.add(ctx, last, point)?);
+            }
+            tables.push(table);
+
+            let bits = self.scalar.to_le_bits(ctx, scalar)?;
+            windows.push(
+                bits.chunks(WINDOW_SIZE)
+                    .map(|window| window.to_vec())
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let windows_count = windows[0].len();
+        debug!("msm of {} terms by {windows_count} windows", terms.len());
+
+        let mut acc = Option::<AssignedForeignPoint<F, C>>::None;
+        for window_index in (0..windows_count).rev() {
+            if let Some(mut point) = acc.take() {
+                for _ in 0..windows[0][window_index].len() {
+                    point = self.double(ctx, &point)?;
+                }
+                acc = Some(point);
+            }
+
+            for (table, windows) in tables.iter().zip(windows.iter()) {
+                let entry = self.select_from_table(ctx, table, &windows[window_index])?;
+                acc = Some(match acc {
+                    Some(acc) => self.add(ctx, &acc, &entry)?,
+                    None => entry,
+                });
+            }
+        }
+
+        // sum((i + 1) * aux) * sum(2^(w * j))
+        let shifts = (0..windows_count).fold(C::Scalar::ZERO, |sum, window_index| {
+            sum + C::Scalar::from(2).pow_vartime([(WINDOW_SIZE * window_index) as u64])
+        });
+        let aux_count = (terms.len() * (terms.len() + 1) / 2) as u64;
+        let correction = -(aux * (shifts * C::Scalar::from(aux_count))).to_affine();
+        let correction = self.assign_constant_point(ctx, correction)?;
+
+        self.add(ctx, &acc.expect("terms is not empty"), &correction)
+    }
+}
+
+impl<F: PrimeField, C: CurveAffine> Chip<F> for ForeignEccChip<F, C> {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        self.base.config()
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,233 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error as Halo2Error},
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{
+    ff::Field,
+    group::Group,
+    halo2curves::{
+        bn256::Fr,
+        secp256k1::{Fp as Base, Fq as Scalar, Secp256k1Affine as C},
+    },
+    main_gate::MainGate,
+    run_mock_prover_test,
+};
+
+const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(64) };
+const LIMBS_COUNT: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(4) };
+
+fn point(scalar: u64) -> C {
+    (C::generator() * Scalar::from(scalar)).to_affine()
+}
+
+/// Checks `add`, `double`, `negate` & `select` of two points against off-circuit results
+struct PointOpsCircuit {
+    lhs: C,
+    rhs: C,
+}
+
+impl Circuit<Fr> for PointOpsCircuit {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Halo2Error> {
+        let chip = ForeignEccChip::<Fr, C>::new(config.clone(), LIMB_WIDTH, LIMBS_COUNT).unwrap();
+        let main_gate = MainGate::<Fr, MAIN_GATE_T>::new(config);
+
+        layouter.assign_region(
+            || "point ops",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let lhs = chip.assign_point(&mut ctx, Value::known(self.lhs)).unwrap();
+                let rhs = chip.assign_point(&mut ctx, Value::known(self.rhs)).unwrap();
+
+                let bit = main_gate.assign_bit(&mut ctx, Value::known(Fr::ONE))?;
+
+                for (actual, expected) in [
+                    (
+                        chip.add(&mut ctx, &lhs, &rhs).unwrap(),
+                        (self.lhs.to_curve() + self.rhs).to_affine(),
+                    ),
+                    (
+                        chip.double(&mut ctx, &lhs).unwrap(),
+                        self.lhs.to_curve().double().to_affine(),
+                    ),
+                    (chip.negate(&mut ctx, &rhs).unwrap(), -self.rhs),
+                    (chip.select(&mut ctx, &lhs, &rhs, &bit).unwrap(), self.lhs),
+                ] {
+                    let expected = chip.assign_constant_point(&mut ctx, expected).unwrap();
+                    chip.assert_equal(&mut ctx, &actual, &expected).unwrap();
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn point_ops() {
+    run_mock_prover_test!(
+        17,
+        PointOpsCircuit {
+            lhs: point(7),
+            rhs: point(0xdead_beef),
+        },
+        vec![]
+    );
+}
+
+#[test]
+fn point_not_on_curve() {
+    use halo2_proofs::dev::MockProver;
+
+    /// Assigns coordinates of `(1, 1)`, bypassing the check of [`ForeignEccChip::assign_point`]
+    struct NotOnCurveCircuit;
+
+    impl Circuit<Fr> for NotOnCurveCircuit {
+        type Config = MainGateConfig<MAIN_GATE_T>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            todo!()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            MainGate::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Halo2Error> {
+            let chip = ForeignEccChip::<Fr, C>::new(config, LIMB_WIDTH, LIMBS_COUNT).unwrap();
+
+            layouter.assign_region(
+                || "not on curve",
+                |region| {
+                    let mut ctx = RegionCtx::new(region, 0);
+
+                    let point = AssignedForeignPoint {
+                        x: chip
+                            .base_field()
+                            .assign(&mut ctx, Value::known(Base::ONE))
+                            .unwrap(),
+                        y: chip
+                            .base_field()
+                            .assign(&mut ctx, Value::known(Base::ONE))
+                            .unwrap(),
+                    };
+                    chip.assert_on_curve(&mut ctx, &point).unwrap();
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    let prover = MockProver::run(16, &NotOnCurveCircuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+/// `sum(scalar_i * point_i)` compared with the off-circuit result
+struct MsmCircuit {
+    terms: Vec<(C, Scalar)>,
+}
+
+impl Circuit<Fr> for MsmCircuit {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Halo2Error> {
+        let chip = ForeignEccChip::<Fr, C>::new(config, LIMB_WIDTH, LIMBS_COUNT).unwrap();
+
+        layouter.assign_region(
+            || "msm",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let terms = self
+                    .terms
+                    .iter()
+                    .map(|(point, scalar)| {
+                        (
+                            chip.assign_point(&mut ctx, Value::known(*point)).unwrap(),
+                            chip.scalar_field()
+                                .assign(&mut ctx, Value::known(*scalar))
+                                .unwrap(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let actual = chip
+                    .msm(
+                        &mut ctx,
+                        &terms
+                            .iter()
+                            .map(|(point, scalar)| (point, scalar))
+                            .collect::<Vec<_>>(),
+                    )
+                    .unwrap();
+
+                let expected = self
+                    .terms
+                    .iter()
+                    .fold(
+                        <C as CurveAffine>::CurveExt::identity(),
+                        |sum, (point, scalar)| sum + *point * *scalar,
+                    )
+                    .to_affine();
+                let expected = chip.assign_constant_point(&mut ctx, expected).unwrap();
+                chip.assert_equal(&mut ctx, &actual, &expected).unwrap();
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+#[ignore = "cause it takes a few minutes to run"]
+fn msm() {
+    run_mock_prover_test!(
+        24,
+        MsmCircuit {
+            terms: vec![
+                (C::generator(), -Scalar::from(3)),
+                (point(0xdead_beef), Scalar::from_u128(u128::MAX)),
+            ],
+        },
+        vec![]
+    );
+}
@@ -0,0 +1,474 @@
+//! Arithmetic over a foreign (non-native) prime field `FF` inside a circuit over `F`
+//!
+//! An element of `FF` is represented by [`AssignedForeignField`], which is `limbs_count` limbs
+//! of `limb_width` bits each, the same representation [`BigUintMulModChip`] works with. Unlike
+//! the raw [`BigUintMulModChip`] API, every element returned by [`ForeignFieldChip`] is kept in
+//! canonical form: each limb is range-checked and the value itself is less than the modulus of
+//! `FF`. Thanks to it, equality of two elements is just equality of their limbs.
+//!
+//! The limbs must be able to fit the modulus, i.e. `limb_width * limbs_count >= FF::NUM_BITS`,
+//! for example `4 x 64` for secp256k1 or `6 x 64` for BLS12-381.
+
+use std::{marker::PhantomData, num::NonZeroUsize};
+
+use halo2_proofs::circuit::{Chip, Value};
+use num_bigint::BigUint as BigUintRaw;
+use num_traits::One;
+use tracing::*;
+
+use super::bn::{
+    big_uint::{self, BigUint},
+    big_uint_mul_mod_chip::{
+        self, BigUintMulModChip, ModOperationResult, OverflowingBigUint, MAIN_GATE_T,
+    },
+};
+use crate::{
+    ff::PrimeField,
+    gadgets::range_check::RangeCheckChip,
+    main_gate::{AssignedValue, MainGate, MainGateConfig, RegionCtx},
+    util,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    BigUint(#[from] big_uint::Error),
+    #[error(transparent)]
+    BigUintMulMod(#[from] big_uint_mul_mod_chip::Error),
+    #[error(transparent)]
+    Halo2(#[from] halo2_proofs::plonk::Error),
+    #[error(
+        "Modulus of {modulus_bits} bits doesn't fit into {limbs_count} limbs of {limb_width} bits"
+    )]
+    ModulusTooBig {
+        modulus_bits: usize,
+        limb_width: NonZeroUsize,
+        limbs_count: NonZeroUsize,
+    },
+}
+
+/// Element of the foreign field `FF` as limbs in the native field `F`
+///
+/// Limbs are little-endian and each of them is less than `2 ^ limb_width`
+#[derive(Clone, Debug)]
+pub struct AssignedForeignField<F: PrimeField, FF: PrimeField> {
+    limbs: Vec<AssignedValue<F>>,
+    limb_width: NonZeroUsize,
+    _p: PhantomData<FF>,
+}
+
+impl<F: PrimeField, FF: PrimeField> AssignedForeignField<F, FF> {
+    fn new(limbs: Vec<AssignedValue<F>>, limb_width: NonZeroUsize) -> Self {
+        Self {
+            limbs,
+            limb_width,
+            _p: PhantomData,
+        }
+    }
+
+    pub fn limbs(&self) -> &[AssignedValue<F>] {
+        &self.limbs
+    }
+
+    pub fn value(&self) -> Value<FF> {
+        self.limbs
+            .iter()
+            .rev()
+            .fold(Value::known(BigUintRaw::default()), |acc, limb| {
+                acc.zip(limb.value())
+                    .map(|(acc, limb)| (acc << self.limb_width.get()) + util::fe_to_big(limb))
+            })
+            .map(|value| {
+                util::fe_from_big(value % util::modulus::<FF>()).expect("reduced by modulus")
+            })
+    }
+
+    fn as_overflowing(&self) -> OverflowingBigUint<F> {
+        OverflowingBigUint::new(self.limbs.clone(), self.limb_width)
+    }
+}
+
+/// Chip for arithmetic of [`AssignedForeignField`]
+///
+/// Operations which can't be expressed directly by [`BigUintMulModChip`] (subtraction, inversion
+/// & division) are done by witnessing the result & checking it with the inverse operation, e.g.
+/// `c = a - b` is proven by `b + c = a`
+#[derive(Debug)]
+pub struct ForeignFieldChip<F: PrimeField, FF: PrimeField> {
+    bn_chip: BigUintMulModChip<F>,
+    main_gate: MainGate<F, MAIN_GATE_T>,
+    modulus: BigUint<F>,
+    limb_width: NonZeroUsize,
+    limbs_count: NonZeroUsize,
+    _p: PhantomData<FF>,
+}
+
+impl<F: PrimeField, FF: PrimeField> ForeignFieldChip<F, FF> {
+    pub fn new(
+        config: MainGateConfig<MAIN_GATE_T>,
+        limb_width: NonZeroUsize,
+        limbs_count: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        let modulus = util::modulus::<FF>();
+        if modulus.bits() as usize > limb_width.get() * limbs_count.get() {
+            return Err(Error::ModulusTooBig {
+                modulus_bits: modulus.bits() as usize,
+                limb_width,
+                limbs_count,
+            });
+        }
+
+        Ok(Self {
+            bn_chip: BigUintMulModChip::new(config.clone(), limb_width, limbs_count),
+            main_gate: MainGate::new(config),
+            modulus: BigUint::from_biguint(&modulus, limb_width, limbs_count)?,
+            limb_width,
+            limbs_count,
+            _p: PhantomData,
+        })
+    }
+
+    /// Range checks limbs by lookups of `range_check`, see
+    /// [`BigUintMulModChip::with_range_check`]
+    pub fn with_range_check(mut self, range_check: RangeCheckChip<F>) -> Self {
+        self.bn_chip = self.bn_chip.with_range_check(range_check);
+        self
+    }
+
+    fn to_limbs(&self, value: &BigUintRaw) -> Result<Vec<F>, Error> {
+        Ok(
+            BigUint::from_biguint(value, self.limb_width, self.limbs_count)?
+                .limbs()
+                .to_vec(),
+        )
+    }
+
+    /// Limbs of the value or zeros, if the value is unknown
+    fn value_to_limbs(&self, value: Value<BigUintRaw>) -> Result<Vec<F>, Error> {
+        match value.unwrap() {
+            Some(value) => self.to_limbs(&value),
+            None => Ok(vec![F::ZERO; self.limbs_count.get()]),
+        }
+    }
+
+    fn assign_limbs(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        limbs: Vec<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        Ok(limbs
+            .into_iter()
+            .map(|limb| self.main_gate.assign_value(ctx, Value::known(limb)))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Limbs bound to the constant by the fixed column
+    fn assign_constant_limbs(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &BigUintRaw,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        Ok(self
+            .to_limbs(value)?
+            .into_iter()
+            .map(|limb| {
+                // limb = rc
+                self.main_gate.apply(
+                    ctx,
+                    (None, None, None),
+                    Some(limb),
+                    (-F::ONE, Value::known(limb).into()),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn range_check_limbs(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        limbs: &[AssignedValue<F>],
+    ) -> Result<(), Error> {
+        for limb in limbs {
+            self.bn_chip
+                .range_check(ctx, limb.clone(), self.limb_width)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the limbs of `value` are in range & `value` is less than the modulus
+    ///
+    /// The second one is proven by a range-checked `d` such that `value + d = modulus - 1`
+    pub fn assert_canonical(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+    ) -> Result<(), Error> {
+        self.range_check_limbs(ctx, &value.limbs)?;
+
+        let max = self.modulus.into_bigint() - BigUintRaw::one();
+
+        let diff =
+            self.value_to_limbs(value.value().map(|value| &max - util::fe_to_big(&value)))?;
+        let diff = self.assign_limbs(ctx, diff)?;
+        self.range_check_limbs(ctx, &diff)?;
+
+        let sum = self
+            .bn_chip
+            .assign_sum(ctx, &value.as_overflowing(), &diff)?
+            .res;
+        let max = OverflowingBigUint::new(self.assign_constant_limbs(ctx, &max)?, self.limb_width);
+
+        Ok(self.bn_chip.is_equal(ctx, sum, max)?)
+    }
+
+    /// Assigns a witness element in canonical form
+    pub fn assign(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let limbs = self.value_to_limbs(value.map(|value| util::fe_to_big(&value)))?;
+        let assigned = AssignedForeignField::new(self.assign_limbs(ctx, limbs)?, self.limb_width);
+
+        self.assert_canonical(ctx, &assigned)?;
+
+        Ok(assigned)
+    }
+
+    /// Assigns an element bound to the constant, it's canonical by construction
+    pub fn assign_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: FF,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        Ok(AssignedForeignField::new(
+            self.assign_constant_limbs(ctx, &util::fe_to_big(&value))?,
+            self.limb_width,
+        ))
+    }
+
+    /// Range-checks the quotient & makes the remainder canonical
+    fn reduced(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        result: ModOperationResult<F>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        self.range_check_limbs(ctx, &result.quotient)?;
+
+        let remainder = AssignedForeignField::new(result.remainder, self.limb_width);
+        self.assert_canonical(ctx, &remainder)?;
+
+        Ok(remainder)
+    }
+
+    pub fn add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let sum = self
+            .bn_chip
+            .assign_sum(ctx, &lhs.as_overflowing(), &rhs.limbs)?
+            .res;
+        let result = self.bn_chip.red_mod(ctx, sum, &self.modulus)?;
+
+        self.reduced(ctx, result)
+    }
+
+    /// `lhs - rhs`, proven by `rhs + result = lhs`
+    pub fn sub(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let diff = self.assign(ctx, lhs.value() - rhs.value())?;
+
+        let sum = self.add(ctx, rhs, &diff)?;
+        self.assert_equal(ctx, &sum, lhs)?;
+
+        Ok(diff)
+    }
+
+    pub fn neg(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let zero = self.assign_constant(ctx, FF::ZERO)?;
+        self.sub(ctx, &zero, value)
+    }
+
+    pub fn mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let result = self
+            .bn_chip
+            .mult_mod(ctx, &lhs.limbs, &rhs.limbs, &self.modulus)?;
+
+        self.reduced(ctx, result)
+    }
+
+    pub fn square(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        self.mul(ctx, value, value)
+    }
+
+    /// `lhs / rhs`, proven by `rhs * result = lhs`
+    ///
+    /// If `rhs` is zero, the circuit is unsatisfiable, unless `lhs` is zero too
+    pub fn div(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let quotient = lhs.value().zip(rhs.value()).map(|(lhs, rhs)| {
+            let rhs_inv: Option<FF> = rhs.invert().into();
+            if rhs_inv.is_none() {
+                warn!("division by zero in foreign field, the circuit will be unsatisfiable");
+            }
+            lhs * rhs_inv.unwrap_or(FF::ZERO)
+        });
+        let quotient = self.assign(ctx, quotient)?;
+
+        let product = self.mul(ctx, rhs, &quotient)?;
+        self.assert_equal(ctx, &product, lhs)?;
+
+        Ok(quotient)
+    }
+
+    /// `1 / value`, proven by `value * result = 1`
+    ///
+    /// If `value` is zero, the circuit is unsatisfiable
+    pub fn invert(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let one = self.assign_constant(ctx, FF::ONE)?;
+        self.div(ctx, &one, value)
+    }
+
+    /// Constrains `lhs == rhs`, limb by limb, since both are canonical
+    pub fn assert_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<(), Error> {
+        for (lhs, rhs) in lhs.limbs.iter().zip(rhs.limbs.iter()) {
+            ctx.constrain_equal(lhs.cell(), rhs.cell())?;
+        }
+        Ok(())
+    }
+
+    pub fn assert_equal_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+        constant: FF,
+    ) -> Result<(), Error> {
+        for (limb, constant) in value
+            .limbs
+            .iter()
+            .zip(self.to_limbs(&util::fe_to_big(&constant))?)
+        {
+            self.main_gate
+                .assert_equal_const(ctx, limb.clone(), constant)?;
+        }
+        Ok(())
+    }
+
+    /// `lhs` if `condition` is `1` & `rhs` if it's `0`, the `condition` must be a bit
+    pub fn select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+        condition: &AssignedValue<F>,
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let limbs = lhs
+            .limbs
+            .iter()
+            .zip(rhs.limbs.iter())
+            .map(|(lhs, rhs)| self.main_gate.conditional_select(ctx, lhs, rhs, condition))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(AssignedForeignField::new(limbs, self.limb_width))
+    }
+
+    /// Little-endian bits of the value, `limb_width` bits per limb
+    pub fn to_le_bits(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedForeignField<F, FF>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let mut bits = Vec::with_capacity(self.limb_width.get() * self.limbs_count.get());
+        for limb in &value.limbs {
+            bits.extend(
+                self.bn_chip
+                    .decompose_in_bits(ctx, limb.clone(), self.limb_width)?,
+            );
+        }
+        Ok(bits)
+    }
+
+    /// Reduces range-checked limbs of the same layout by the modulus of `FF`
+    ///
+    /// Allows to move an element of another foreign field into `FF`, e.g. the `x` coordinate of a
+    /// point into the scalar field
+    pub fn reduce_limbs(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        limbs: &[AssignedValue<F>],
+    ) -> Result<AssignedForeignField<F, FF>, Error> {
+        let result = self.bn_chip.red_mod(
+            ctx,
+            OverflowingBigUint::new(limbs.to_vec(), self.limb_width),
+            &self.modulus,
+        )?;
+
+        self.reduced(ctx, result)
+    }
+
+    /// Returns a bit, which is `1` if `lhs == rhs` & `0` otherwise
+    pub fn is_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lhs: &AssignedForeignField<F, FF>,
+        rhs: &AssignedForeignField<F, FF>,
+    ) -> Result<AssignedValue<F>, Error> {
+        let mut limbs = lhs.limbs.iter().zip(rhs.limbs.iter());
+        let (lhs_limb, rhs_limb) = limbs.next().expect("limbs count is non zero");
+
+        let first = self.main_gate.is_equal_term(ctx, lhs_limb, rhs_limb)?;
+        Ok(limbs.try_fold(first, |acc, (lhs_limb, rhs_limb)| {
+            let is_equal = self.main_gate.is_equal_term(ctx, lhs_limb, rhs_limb)?;
+            self.main_gate.mul(ctx, &acc, &is_equal)
+        })?)
+    }
+}
+
+impl<F: PrimeField, FF: PrimeField> Chip<F> for ForeignFieldChip<F, FF> {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        self.main_gate.config()
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests;
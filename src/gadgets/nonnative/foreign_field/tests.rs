@@ -0,0 +1,199 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error as Halo2Error},
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{
+    ff::Field,
+    halo2curves::{bn256::Fr, secp256k1::Fp as Secp256k1Base},
+    run_mock_prover_test,
+};
+
+const K: u32 = 16;
+
+const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(64) };
+const LIMBS_COUNT: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(4) };
+
+type FF = Secp256k1Base;
+
+#[derive(Clone, Debug)]
+struct Expected {
+    sum: FF,
+    diff: FF,
+    neg: FF,
+    product: FF,
+    square: FF,
+    quotient: FF,
+    inverse: FF,
+}
+
+struct TestCircuit {
+    lhs: FF,
+    rhs: FF,
+    expected: Expected,
+}
+
+impl TestCircuit {
+    fn new(lhs: FF, rhs: FF) -> Self {
+        let rhs_inv = rhs.invert().unwrap();
+
+        Self {
+            lhs,
+            rhs,
+            expected: Expected {
+                sum: lhs + rhs,
+                diff: lhs - rhs,
+                neg: -lhs,
+                product: lhs * rhs,
+                square: lhs.square(),
+                quotient: lhs * rhs_inv,
+                inverse: rhs_inv,
+            },
+        }
+    }
+}
+
+impl Circuit<Fr> for TestCircuit {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Halo2Error> {
+        let chip = ForeignFieldChip::<Fr, FF>::new(config, LIMB_WIDTH, LIMBS_COUNT).unwrap();
+
+        layouter.assign_region(
+            || "foreign field ops",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let lhs = chip.assign(&mut ctx, Value::known(self.lhs)).unwrap();
+                let rhs = chip.assign(&mut ctx, Value::known(self.rhs)).unwrap();
+
+                let Expected {
+                    sum,
+                    diff,
+                    neg,
+                    product,
+                    square,
+                    quotient,
+                    inverse,
+                } = self.expected.clone();
+
+                for (actual, expected) in [
+                    (chip.add(&mut ctx, &lhs, &rhs).unwrap(), sum),
+                    (chip.sub(&mut ctx, &lhs, &rhs).unwrap(), diff),
+                    (chip.neg(&mut ctx, &lhs).unwrap(), neg),
+                    (chip.mul(&mut ctx, &lhs, &rhs).unwrap(), product),
+                    (chip.square(&mut ctx, &lhs).unwrap(), square),
+                    (chip.div(&mut ctx, &lhs, &rhs).unwrap(), quotient),
+                    (chip.invert(&mut ctx, &rhs).unwrap(), inverse),
+                ] {
+                    chip.assert_equal_constant(&mut ctx, &actual, expected)
+                        .unwrap();
+                }
+
+                let main_gate = MainGate::<Fr, MAIN_GATE_T>::new(chip.config().clone());
+
+                let is_equal = chip.is_equal(&mut ctx, &lhs, &lhs).unwrap();
+                main_gate.assert_equal_const(&mut ctx, is_equal, Fr::ONE)?;
+
+                let is_equal = chip.is_equal(&mut ctx, &lhs, &rhs).unwrap();
+                main_gate.assert_equal_const(&mut ctx, is_equal, Fr::ZERO)?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn ops_match_off_circuit() {
+    let lhs = -FF::from(0xdead_beef);
+    let rhs = FF::from_u128(u128::MAX).square();
+
+    run_mock_prover_test!(K, TestCircuit::new(lhs, rhs), vec![]);
+}
+
+#[traced_test]
+#[test]
+fn wrong_product_rejected() {
+    let mut circuit = TestCircuit::new(FF::from(3), FF::from(5));
+    circuit.expected.product += FF::ONE;
+
+    let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+/// Limbs of the modulus itself, which is not a canonical element
+struct NonCanonicalCircuit;
+
+impl Circuit<Fr> for NonCanonicalCircuit {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Halo2Error> {
+        let chip = ForeignFieldChip::<Fr, FF>::new(config, LIMB_WIDTH, LIMBS_COUNT).unwrap();
+
+        layouter.assign_region(
+            || "non canonical",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let limbs = chip.to_limbs(&util::modulus::<FF>()).unwrap();
+                let value = AssignedForeignField::new(
+                    chip.assign_limbs(&mut ctx, limbs).unwrap(),
+                    LIMB_WIDTH,
+                );
+
+                chip.assert_canonical(&mut ctx, &value).unwrap();
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn non_canonical_rejected() {
+    let prover = MockProver::run(K, &NonCanonicalCircuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn modulus_too_big() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let config = MainGate::<Fr, MAIN_GATE_T>::configure(&mut meta);
+
+    assert!(matches!(
+        ForeignFieldChip::<Fr, FF>::new(config, LIMB_WIDTH, NonZeroUsize::new(3).unwrap()),
+        Err(Error::ModulusTooBig { .. })
+    ));
+}
@@ -1 +1,3 @@
 pub mod bn;
+pub mod ecc;
+pub mod foreign_field;
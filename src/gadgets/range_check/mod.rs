@@ -0,0 +1,216 @@
+//! Lookup-based range check
+//!
+//! [`RangeCheckChip`] proves `value < 2^bits` by a running-sum decomposition into words of
+//! `lookup_bits` bits, each of them looked up in a fixed table of `0..2^lookup_bits`. It takes
+//! one row per word instead of at least one row per bit, as
+//! [`crate::main_gate::MainGate::le_num_to_bits`] & [`BigUintMulModChip::decompose_in_bits`] do.
+//!
+//! For the value `v` the column `z` is filled with `z_0 = v`, `z_{i+1} = (z_i - k_i) / 2^K`,
+//! where `k_i` is the `i`-th word & `K` is `lookup_bits`:
+//!
+//! | row   | z         | q_lookup | q_running | q_short         |
+//! |-------|-----------|----------|-----------|-----------------|
+//! | 0     | z_0       | 1        | 2^K       | 0               |
+//! | ...   | ...       | ...      | ...       | ...             |
+//! | n - 1 | z_{n - 1} | 1        | 0         | 2^(K - s) or 0  |
+//!
+//! Each row looks up `q_lookup * z_cur - q_running * z_next`, which is `k_i` & is `z_{n - 1}`
+//! itself on the last row, so the decomposition ends with zero. If the last word has `s < K`
+//! bits, `q_short * z_cur` is looked up as well, which makes the last word less than `2^s`.
+//! Rows out of the range check have zero fixed values & look up zero.
+//!
+//! [`BigUintMulModChip::decompose_in_bits`]:
+//!     crate::gadgets::nonnative::bn::big_uint_mul_mod_chip::BigUintMulModChip::decompose_in_bits
+
+use std::num::NonZeroUsize;
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, TableColumn},
+    poly::Rotation,
+};
+use num_bigint::BigUint as BigUintRaw;
+use tracing::*;
+
+use crate::{
+    ff::PrimeField,
+    main_gate::{AssignedValue, RegionCtx},
+    util,
+};
+
+/// Upper bound of [`RangeCheckConfig::lookup_bits`], the circuit can't be that big anyway
+pub const MAX_LOOKUP_BITS: usize = 24;
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckConfig {
+    table: TableColumn,
+    z: Column<Advice>,
+    q_lookup: Column<Fixed>,
+    q_running: Column<Fixed>,
+    q_short: Column<Fixed>,
+    lookup_bits: NonZeroUsize,
+}
+
+impl RangeCheckConfig {
+    pub fn lookup_bits(&self) -> NonZeroUsize {
+        self.lookup_bits
+    }
+}
+
+/// Chip for range checks by lookups of [`RangeCheckConfig::lookup_bits`] words, see [`crate::gadgets::range_check`]
+#[derive(Clone, Debug)]
+pub struct RangeCheckChip<F: PrimeField> {
+    config: RangeCheckConfig,
+    _p: std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> RangeCheckChip<F> {
+    /// The table has `2^lookup_bits` rows, so the circuit must have `k > lookup_bits`
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lookup_bits: NonZeroUsize,
+    ) -> RangeCheckConfig {
+        assert!(
+            lookup_bits.get() <= MAX_LOOKUP_BITS,
+            "table of 2^{lookup_bits} rows is too big"
+        );
+
+        let table = meta.lookup_table_column();
+        let z = meta.advice_column();
+        meta.enable_equality(z);
+
+        let q_lookup = meta.fixed_column();
+        let q_running = meta.fixed_column();
+        let q_short = meta.fixed_column();
+
+        meta.lookup("range check word", |meta| {
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let q_lookup = meta.query_fixed(q_lookup, Rotation::cur());
+            let q_running = meta.query_fixed(q_running, Rotation::cur());
+
+            vec![(q_lookup * z_cur - q_running * z_next, table)]
+        });
+
+        meta.lookup("range check short word", |meta| {
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let q_short = meta.query_fixed(q_short, Rotation::cur());
+
+            vec![(q_short * z_cur, table)]
+        });
+
+        RangeCheckConfig {
+            table,
+            z,
+            q_lookup,
+            q_running,
+            q_short,
+            lookup_bits,
+        }
+    }
+
+    pub fn new(config: RangeCheckConfig) -> Self {
+        Self {
+            config,
+            _p: std::marker::PhantomData,
+        }
+    }
+
+    pub fn config(&self) -> &RangeCheckConfig {
+        &self.config
+    }
+
+    /// Loads the lookup table, should be called once per circuit
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "range check table",
+            |mut table| {
+                for value in 0..1u64 << self.config.lookup_bits.get() {
+                    table.assign_cell(
+                        || "word",
+                        self.config.table,
+                        value as usize,
+                        || Value::known(F::from(value)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assigns the `value` & checks that it's less than `2^bits`
+    pub fn assign_range_checked(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<F>,
+        bits: NonZeroUsize,
+    ) -> Result<AssignedValue<F>, Error> {
+        self.decompose(ctx, value, bits)
+    }
+
+    /// Checks that `value < 2^bits`
+    pub fn range_check(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedValue<F>,
+        bits: NonZeroUsize,
+    ) -> Result<(), Error> {
+        let z_0 = self.decompose(ctx, value.value().copied(), bits)?;
+        ctx.constrain_equal(z_0.cell(), value.cell())
+    }
+
+    /// Fills the running sum of `value` & returns `z_0`
+    fn decompose(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<F>,
+        bits: NonZeroUsize,
+    ) -> Result<AssignedValue<F>, Error> {
+        if bits.get() >= F::NUM_BITS as usize {
+            error!(
+                "range check of {bits} bits doesn't fit into a field of {} bits",
+                F::NUM_BITS
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let lookup_bits = self.config.lookup_bits.get();
+        let words_count = bits.get().div_ceil(lookup_bits);
+        let short_bits = bits.get() - (words_count - 1) * lookup_bits;
+
+        let value = value.map(|value| util::fe_to_big(&value));
+
+        let mut z_0 = None;
+        for word_index in 0..words_count {
+            let z = value.as_ref().map(|value| {
+                let z: BigUintRaw = value >> (word_index * lookup_bits);
+                util::fe_from_big::<F>(z).expect("less than the value")
+            });
+            let z = ctx.assign_advice(|| format!("z_{word_index}"), self.config.z, z)?;
+            z_0.get_or_insert(z);
+
+            ctx.assign_fixed(|| "q_lookup", self.config.q_lookup, F::ONE)?;
+
+            if word_index + 1 != words_count {
+                ctx.assign_fixed(
+                    || "q_running",
+                    self.config.q_running,
+                    F::from(1 << lookup_bits),
+                )?;
+            } else if short_bits != lookup_bits {
+                ctx.assign_fixed(
+                    || "q_short",
+                    self.config.q_short,
+                    F::from(1 << (lookup_bits - short_bits)),
+                )?;
+            }
+
+            ctx.next();
+        }
+
+        Ok(z_0.expect("bits count is not zero"))
+    }
+}
+
+#[cfg(test)]
+mod tests;
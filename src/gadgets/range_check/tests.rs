@@ -0,0 +1,179 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem},
+};
+use rand_core::OsRng;
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{
+    ff::Field,
+    gadgets::nonnative::{bn::big_uint_mul_mod_chip::MAIN_GATE_T, foreign_field::ForeignFieldChip},
+    halo2curves::{bn256::Fr, secp256k1::Fp as Secp256k1Base},
+    main_gate::{MainGate, MainGateConfig},
+    run_mock_prover_test,
+};
+
+const K: u32 = 10;
+
+const LOOKUP_BITS: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(8) };
+
+#[derive(Clone, Debug)]
+struct Config {
+    main_gate: MainGateConfig<MAIN_GATE_T>,
+    range_check: RangeCheckConfig,
+}
+
+fn configure(meta: &mut ConstraintSystem<Fr>) -> Config {
+    Config {
+        main_gate: MainGate::configure(meta),
+        range_check: RangeCheckChip::configure(meta, LOOKUP_BITS),
+    }
+}
+
+/// Range checks each of `(value, bits)` both ways: as an assigned cell & on assignment
+struct RangeCheckCircuit {
+    values: Vec<(u64, usize)>,
+}
+
+impl Circuit<Fr> for RangeCheckCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let main_gate = MainGate::<Fr, MAIN_GATE_T>::new(config.main_gate);
+        let chip = RangeCheckChip::new(config.range_check);
+        chip.load_table(&mut layouter)?;
+
+        layouter.assign_region(
+            || "range check",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                for (value, bits) in &self.values {
+                    let bits = NonZeroUsize::new(*bits).unwrap();
+                    let value = Value::known(Fr::from(*value));
+
+                    let assigned = main_gate.assign_value(&mut ctx, value)?;
+                    chip.range_check(&mut ctx, &assigned, bits)?;
+
+                    chip.assign_range_checked(&mut ctx, value, bits)?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn values_in_range() {
+    run_mock_prover_test!(
+        K,
+        RangeCheckCircuit {
+            values: vec![
+                (0, 1),
+                (1, 1),
+                (0xff, 8),
+                (0x1fff, 13),
+                (0xdead_beef, 32),
+                (u64::MAX, 64),
+            ],
+        },
+        vec![]
+    );
+}
+
+#[test]
+fn value_out_of_range() {
+    for (value, bits) in [(2, 1), (0x100, 8), (0x2000, 13), (1 << 32, 32)] {
+        let prover = MockProver::run(
+            K,
+            &RangeCheckCircuit {
+                values: vec![(value, bits)],
+            },
+            vec![],
+        )
+        .unwrap();
+        assert!(prover.verify().is_err(), "{value} of {bits} bits");
+    }
+}
+
+/// [`ForeignFieldChip`] with limbs range checked by lookups
+struct ForeignFieldCircuit {
+    lhs: Secp256k1Base,
+    rhs: Secp256k1Base,
+}
+
+impl Circuit<Fr> for ForeignFieldCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(64) };
+        const LIMBS_COUNT: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(4) };
+
+        let range_check = RangeCheckChip::new(config.range_check);
+        range_check.load_table(&mut layouter)?;
+
+        let chip =
+            ForeignFieldChip::<Fr, Secp256k1Base>::new(config.main_gate, LIMB_WIDTH, LIMBS_COUNT)
+                .unwrap()
+                .with_range_check(range_check);
+
+        layouter.assign_region(
+            || "foreign field mul",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let lhs = chip.assign(&mut ctx, Value::known(self.lhs)).unwrap();
+                let rhs = chip.assign(&mut ctx, Value::known(self.rhs)).unwrap();
+                let product = chip.mul(&mut ctx, &lhs, &rhs).unwrap();
+
+                chip.assert_equal_constant(&mut ctx, &product, self.lhs * self.rhs)
+                    .unwrap();
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn foreign_field_with_lookup_range_check() {
+    run_mock_prover_test!(
+        14,
+        ForeignFieldCircuit {
+            lhs: Secp256k1Base::random(&mut OsRng),
+            rhs: Secp256k1Base::random(&mut OsRng),
+        },
+        vec![]
+    );
+}
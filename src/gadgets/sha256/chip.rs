@@ -0,0 +1,128 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+
+use super::{
+    padding::padding,
+    table16::{field_to_word, BlockWord, Table16Chip, Table16Config, IV},
+    Sha256Instructions, BLOCK_SIZE, DIGEST_SIZE,
+};
+use crate::ff::PrimeField;
+
+pub type Sha256Config = Table16Config;
+
+/// On-circuit SHA-256 of messages of 32-bit words
+///
+/// Each block is compressed starting from a state constrained to be equal to the output of the
+/// previous block (or the initialization vector), and the feed-forward of this state into the
+/// compressed one is constrained as well, so the result is the real SHA-256 digest of the message.
+#[derive(Clone, Debug)]
+pub struct Sha256Chip<F: PrimeField> {
+    table16: Table16Chip<F>,
+}
+
+impl<F: PrimeField> Sha256Chip<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Sha256Config {
+        Table16Chip::configure(meta)
+    }
+
+    pub fn construct(config: Sha256Config) -> Self {
+        Self {
+            table16: Table16Chip::construct(config),
+        }
+    }
+
+    /// Loads the spread lookup table, should be called once per circuit
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        Table16Chip::load(self.table16.config().clone(), layouter)
+    }
+
+    /// Assigns witnessed message words
+    ///
+    /// They are range checked only when hashed by [`Sha256Chip::digest`]
+    pub fn assign_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[Value<u32>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.table16.assign_words(layouter, words)
+    }
+
+    /// Assigns the SHA-256 initialization vector as constants
+    pub fn initial_state(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], Error> {
+        Ok(self
+            .table16
+            .assign_constants(layouter, &IV)?
+            .try_into()
+            .expect("IV.len() == DIGEST_SIZE"))
+    }
+
+    /// Appends the padding of the message, see [`padding`]
+    ///
+    /// The padding depends only on the length of the message, so it's assigned as constants
+    pub fn pad(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        message: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let padding = self
+            .table16
+            .assign_constants(layouter, &padding(message.len()))?;
+
+        Ok(message.iter().cloned().chain(padding).collect())
+    }
+
+    /// The SHA-256 compression function, feed-forward of `state` included
+    ///
+    /// `state` is expected to be range checked, e.g. the output of the previous call
+    pub fn compress(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &[AssignedCell<F, F>; DIGEST_SIZE],
+        block: &[AssignedCell<F, F>; BLOCK_SIZE],
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], Error> {
+        let initialized = self.table16.initialization_from_cells(layouter, state)?;
+
+        let compressed = self.table16.compress(
+            layouter,
+            &initialized,
+            block
+                .each_ref()
+                .map(|cell| BlockWord(cell.value().map(field_to_word))),
+            block.clone(),
+        )?;
+        let compressed = self.table16.digest_cells(layouter, &compressed)?;
+
+        Ok(self
+            .table16
+            .add_words(layouter, state, &compressed)?
+            .try_into()
+            .expect("state.len() == DIGEST_SIZE"))
+    }
+
+    /// SHA-256 digest of the message, padding included
+    ///
+    /// Each word of the message is range checked as a part of the message schedule
+    pub fn digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        message: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], Error> {
+        let padded = self.pad(layouter, message)?;
+        let initial_state = self.initial_state(layouter)?;
+
+        padded
+            .chunks_exact(BLOCK_SIZE)
+            .try_fold(initial_state, |state, block| {
+                self.compress(
+                    layouter,
+                    &state,
+                    block.try_into().expect("chunk.len() == BLOCK_SIZE"),
+                )
+            })
+    }
+}
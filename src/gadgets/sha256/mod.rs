@@ -0,0 +1,84 @@
+//! # SHA-256
+//!
+//! The [SHA-256] hash function over messages of 32-bit words, each word is stored in a separate
+//! cell. The compression function is implemented by [`Table16Chip`] with a spread lookup table of
+//! `2^16` rows, so the circuit should have at least `k = 17`.
+//!
+//! - [`Sha256Chip`] - digest of an assigned message of any length, padding included
+//! - [`off_circuit`] - reference implementation with the same interface
+//! - [`Sha256StepCircuit`] - [`crate::ivc::StepCircuit`] hashing a chain of blocks
+//!
+//! The length of the message is a part of the circuit shape, so the same circuit can't be used
+//! for messages of different lengths, but any length can be chosen at configuration time.
+//!
+//! [SHA-256]: https://tools.ietf.org/html/rfc6234
+
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::Error,
+};
+
+pub mod chip;
+pub mod off_circuit;
+pub mod padding;
+pub mod step_circuit;
+pub mod table16;
+
+pub use chip::{Sha256Chip, Sha256Config};
+pub use padding::padding;
+pub use step_circuit::Sha256StepCircuit;
+pub use table16::{BlockWord, Table16Chip, Table16Config};
+
+/// The size of a SHA-256 block, in 32-bit words.
+pub const BLOCK_SIZE: usize = 16;
+/// The size of a SHA-256 digest, in 32-bit words.
+pub const DIGEST_SIZE: usize = 8;
+
+/// The set of circuit instructions required to implement the SHA-256 compression function.
+pub trait Sha256Instructions<F: Field>: Chip<F> {
+    /// Variable representing the SHA-256 internal state.
+    type State: Clone + fmt::Debug;
+    /// Variable representing a 32-bit word of the input block to the SHA-256 compression
+    /// function.
+    type BlockWord: Copy + fmt::Debug + Default;
+
+    /// Places the SHA-256 IV in the circuit, returning the initial state variable.
+    fn initialization_vector(&self, layouter: &mut impl Layouter<F>) -> Result<Self::State, Error>;
+
+    /// Creates an initial state from the output state of a previous block
+    fn initialization(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        init_state: &Self::State,
+    ) -> Result<Self::State, Error>;
+
+    /// Starting from the given initialized state, processes a block of input and returns the
+    /// final state.
+    fn compress(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        initialized_state: &Self::State,
+        input: [Self::BlockWord; BLOCK_SIZE],
+        input_cells: [AssignedCell<F, F>; BLOCK_SIZE],
+    ) -> Result<Self::State, Error>;
+
+    /// Converts the given state into a message digest.
+    fn digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &Self::State,
+    ) -> Result<[Self::BlockWord; DIGEST_SIZE], Error>;
+
+    /// Converts the given state into a message digest.
+    fn digest_cells(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &Self::State,
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], Error>;
+}
+
+#[cfg(test)]
+mod tests;
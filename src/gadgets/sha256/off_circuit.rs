@@ -0,0 +1,117 @@
+//! Reference SHA-256 over messages of 32-bit words, matching [`super::Sha256Chip`]
+
+use super::{
+    padding::padding,
+    table16::{IV, ROUND_CONSTANTS},
+    BLOCK_SIZE, DIGEST_SIZE,
+};
+
+/// The SHA-256 compression function, feed-forward of `state` included
+pub fn compress(state: &[u32; DIGEST_SIZE], block: &[u32; BLOCK_SIZE]) -> [u32; DIGEST_SIZE] {
+    let mut w = [0u32; 64];
+    w[..BLOCK_SIZE].copy_from_slice(block);
+    for i in BLOCK_SIZE..w.len() {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in ROUND_CONSTANTS.iter().zip(w.iter()) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(*w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    let mut output = *state;
+    for (output, word) in output.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *output = output.wrapping_add(word);
+    }
+    output
+}
+
+/// SHA-256 digest of the message, padding included
+pub fn digest(message: &[u32]) -> [u32; DIGEST_SIZE] {
+    let padded = message
+        .iter()
+        .copied()
+        .chain(padding(message.len()))
+        .collect::<Vec<_>>();
+
+    padded.chunks_exact(BLOCK_SIZE).fold(IV, |state, block| {
+        compress(&state, block.try_into().expect("chunk.len() == BLOCK_SIZE"))
+    })
+}
+
+/// SHA-256 digest of bytes, for messages of a whole number of words
+///
+/// # Panics
+///
+/// If the length of `bytes` isn't a multiple of 4
+pub fn digest_bytes(bytes: &[u8]) -> [u32; DIGEST_SIZE] {
+    assert_eq!(bytes.len() % 4, 0, "message must consist of 32-bit words");
+
+    digest(
+        &bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(
+            digest(&[]),
+            [
+                0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+                0x7852b855,
+            ]
+        );
+        // "abcd"
+        assert_eq!(
+            digest_bytes(b"abcd"),
+            [
+                0x88d4266f, 0xd4e6338d, 0x13b845fc, 0xf289579d, 0x209c8978, 0x23b9217d, 0xa3e16193,
+                0x6f031589,
+            ]
+        );
+        // Two blocks
+        assert_eq!(
+            digest_bytes(
+                &b"01234567"
+                    .repeat(8)
+                    .into_iter()
+                    .chain(*b"0123")
+                    .collect::<Vec<_>>()
+            ),
+            [
+                0x147ad0cf, 0x81bd4bb3, 0xa566bc1c, 0xa8200dc4, 0xc5ca74d5, 0x88af7b3d, 0xe6fd5c43,
+                0x1f4a88c8,
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Padding of messages of 32-bit words up to a multiple of [`BLOCK_SIZE`]
+
+use super::BLOCK_SIZE;
+
+/// The first padding word, with the single bit appended to the message
+pub const PADDING_START: u32 = 0x8000_0000;
+
+/// Number of words taken by the message length at the end of the padding
+const LENGTH_SIZE: usize = 2;
+
+/// Padding words for a message of `len` 32-bit words
+///
+/// The padding is [`PADDING_START`], zeros & the 64-bit big-endian length of the message in
+/// bits, so that the padded message is a multiple of [`BLOCK_SIZE`]. It depends only on the
+/// length, so on-circuit it consists of constants.
+pub fn padding(len: usize) -> Vec<u32> {
+    let zeros = (BLOCK_SIZE - (len + 1 + LENGTH_SIZE) % BLOCK_SIZE) % BLOCK_SIZE;
+    let bit_len = (len as u64) * 32;
+
+    [PADDING_START]
+        .into_iter()
+        .chain(std::iter::repeat(0).take(zeros))
+        .chain([(bit_len >> 32) as u32, bit_len as u32])
+        .collect()
+}
+
+/// Number of blocks in the padded message of `len` words
+pub fn blocks_count(len: usize) -> usize {
+    (len + padding(len).len()) / BLOCK_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_len() {
+        for len in 0..100 {
+            let padding = padding(len);
+
+            assert_eq!((len + padding.len()) % BLOCK_SIZE, 0);
+            assert!(padding.len() > LENGTH_SIZE);
+            assert!(padding.len() <= BLOCK_SIZE + LENGTH_SIZE);
+            assert_eq!(padding[padding.len() - 1], (len * 32) as u32);
+        }
+
+        assert_eq!(blocks_count(0), 1);
+        assert_eq!(blocks_count(13), 1);
+        assert_eq!(blocks_count(14), 2);
+        assert_eq!(blocks_count(16), 2);
+    }
+}
@@ -0,0 +1,107 @@
+//! [`StepCircuit`] applying the SHA-256 compression function to one block per step
+//!
+//! The state of the hash function is carried between steps as `z_i`, so starting with the
+//! [initialization vector](Sha256StepCircuit::initial_state) and folding the circuits
+//! returned by [`Sha256StepCircuit::for_message`] one after another, the last `z_i` is the
+//! SHA-256 digest of the message.
+
+use std::{array, marker::PhantomData};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+use num_traits::ToPrimitive;
+use tracing::*;
+
+use super::{
+    chip::{Sha256Chip, Sha256Config},
+    off_circuit,
+    padding::padding,
+    table16::IV,
+    BLOCK_SIZE, DIGEST_SIZE,
+};
+use crate::{
+    ff::PrimeField,
+    ivc::{StepCircuit, SynthesisError},
+    util::fe_to_big,
+};
+
+/// `z_{i+1} = compress(z_i, block)`, where `z_i` is the state of SHA-256 as 32-bit words
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sha256StepCircuit<F: PrimeField> {
+    block: [u32; BLOCK_SIZE],
+    _p: PhantomData<F>,
+}
+
+impl<F: PrimeField> Sha256StepCircuit<F> {
+    pub fn new(block: [u32; BLOCK_SIZE]) -> Self {
+        Self {
+            block,
+            _p: PhantomData,
+        }
+    }
+
+    /// Circuits for each block of the padded message
+    pub fn for_message(message: &[u32]) -> Vec<Self> {
+        let padded = message
+            .iter()
+            .copied()
+            .chain(padding(message.len()))
+            .collect::<Vec<_>>();
+
+        padded
+            .chunks_exact(BLOCK_SIZE)
+            .map(|block| Self::new(block.try_into().expect("chunk.len() == BLOCK_SIZE")))
+            .collect()
+    }
+
+    /// The SHA-256 initialization vector, `z_0` of the hash chain
+    pub fn initial_state() -> [F; DIGEST_SIZE] {
+        IV.map(|word| F::from(word as u64))
+    }
+}
+
+impl<F: PrimeField> StepCircuit<DIGEST_SIZE, F> for Sha256StepCircuit<F> {
+    type Config = Sha256Config;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        Sha256Chip::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; DIGEST_SIZE],
+    ) -> Result<[AssignedCell<F, F>; DIGEST_SIZE], SynthesisError> {
+        let chip = Sha256Chip::construct(config);
+        chip.load(layouter)?;
+
+        let block: [AssignedCell<F, F>; BLOCK_SIZE] = chip
+            .assign_words(layouter, &self.block.map(Value::known))?
+            .try_into()
+            .expect("block.len() == BLOCK_SIZE");
+
+        Ok(chip.compress(layouter, z_i, &block)?)
+    }
+
+    fn process_step(
+        &self,
+        z_i: &[F; DIGEST_SIZE],
+        _k_table_size: u32,
+    ) -> Result<[F; DIGEST_SIZE], SynthesisError> {
+        let state = z_i
+            .iter()
+            .map(|value| fe_to_big(value).to_u32())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                error!("`z_i` of sha256 step circuit must consist of 32-bit words");
+                SynthesisError::Halo2(Error::Synthesis)
+            })?;
+
+        let output = off_circuit::compress(&array::from_fn(|i| state[i]), &self.block);
+
+        Ok(output.map(|word| F::from(word as u64)))
+    }
+}
@@ -1,17 +1,19 @@
 use std::{convert::TryInto, ops::Range};
 
+use crate::ff::PrimeField;
 use halo2_proofs::{
     circuit::{Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
-use sirius::ff::PrimeField;
 
 use super::{
     util::{i2lebsp, lebs2ip},
     AssignedBits, BlockWord, SpreadInputs, SpreadVar, Table16Assignment, ROUNDS, STATE,
 };
-use crate::{AssignedCell, DIGEST_SIZE};
+use halo2_proofs::circuit::AssignedCell;
+
+use super::super::DIGEST_SIZE;
 
 mod compression_gates;
 mod compression_util;
@@ -878,6 +880,24 @@ impl CompressionConfig {
         Ok(new_state)
     }
 
+    /// Initialize compression with witnessed 32-bit words. The returned state isn't bound to
+    /// the words, it's up to the caller to constrain its digest.
+    pub(super) fn initialize_with_words<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        init_state: [Value<u32>; STATE],
+    ) -> Result<State<F>, Error> {
+        let mut new_state = State::empty_state();
+        layouter.assign_region(
+            || "initialize_with_words",
+            |mut region| {
+                new_state = self.initialize_words(&mut region, init_state)?;
+                Ok(())
+            },
+        )?;
+        Ok(new_state)
+    }
+
     /// Initialize compression with some initialized state. This could be a state
     /// output from a previous compression round.
     pub(super) fn initialize_with_state<F: PrimeField>(
@@ -952,14 +972,15 @@ impl CompressionConfig {
 mod tests {
     use std::marker::PhantomData;
 
-    use ff::PrimeField;
+    use crate::ff::PrimeField;
+    use crate::halo2curves::pasta::{pallas, Fp};
     use halo2_proofs::{
         circuit::{Layouter, SimpleFloorPlanner},
-        dev::MockProver,
         plonk::{Circuit, ConstraintSystem, Error},
     };
-    use halo2curves::pasta::{pallas, Fp};
-    use sirius::run_mock_prover_test;
+    use tracing_test::traced_test;
+
+    use crate::run_mock_prover_test;
 
     use super::super::{
         super::BLOCK_SIZE, msg_schedule_test_input, BlockWord, Table16Chip, Table16Config, IV,
@@ -974,8 +995,6 @@ mod tests {
         impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
             type Config = Table16Config;
             type FloorPlanner = SimpleFloorPlanner;
-            #[cfg(feature = "circuit-params")]
-            type Params = ();
 
             fn without_witnesses(&self) -> Self {
                 MyCircuit {
@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
+use crate::ff::PrimeField;
 use halo2_proofs::plonk::{Constraint, Constraints, Expression};
-use sirius::ff::PrimeField;
 
 use super::super::{util::*, Gate};
 
@@ -1,15 +1,17 @@
+use crate::ff::PrimeField;
 use halo2_proofs::{
     circuit::{Region, Value},
     plonk::{Advice, Column, Error},
 };
-use sirius::ff::PrimeField;
 
 use super::{
     super::{BlockWord, RoundWordDense},
     compression_util::*,
     CompressionConfig, State,
 };
-use crate::{AssignedCell, DIGEST_SIZE};
+use halo2_proofs::circuit::AssignedCell;
+
+use super::super::super::DIGEST_SIZE;
 
 impl CompressionConfig {
     #[allow(clippy::many_single_char_names)]
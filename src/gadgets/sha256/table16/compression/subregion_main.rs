@@ -1,5 +1,5 @@
+use crate::ff::PrimeField;
 use halo2_proofs::{circuit::Region, plonk::Error};
-use sirius::ff::PrimeField;
 
 use super::{
     super::{AssignedBits, RoundWord, RoundWordA, RoundWordE, StateWord, ROUND_CONSTANTS},
@@ -1,14 +1,14 @@
 use std::convert::TryInto;
 
+use crate::ff::PrimeField;
 use halo2_proofs::{
     circuit::Layouter,
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
-use sirius::ff::PrimeField;
 
+use super::BlockWord;
 use super::{super::BLOCK_SIZE, AssignedBits, SpreadInputs, Table16Assignment, ROUNDS};
-use crate::BlockWord;
 
 mod schedule_gates;
 mod schedule_util;
@@ -396,14 +396,15 @@ impl MessageScheduleConfig {
 mod tests {
     use std::marker::PhantomData;
 
-    use ff::PrimeField;
+    use crate::ff::PrimeField;
+    use crate::halo2curves::pasta::pallas;
     use halo2_proofs::{
         circuit::{Layouter, SimpleFloorPlanner},
-        dev::MockProver,
         plonk::{Circuit, ConstraintSystem, Error},
     };
-    use halo2curves::pasta::pallas;
-    use sirius::run_mock_prover_test;
+    use tracing_test::traced_test;
+
+    use crate::run_mock_prover_test;
 
     use super::{
         super::{
@@ -422,8 +423,6 @@ mod tests {
         impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
             type Config = Table16Config;
             type FloorPlanner = SimpleFloorPlanner;
-            #[cfg(feature = "circuit-params")]
-            type Params = ();
 
             fn without_witnesses(&self) -> Self {
                 MyCircuit {
@@ -1,10 +1,10 @@
 use std::convert::TryInto;
 
+use crate::ff::PrimeField;
 use halo2_proofs::{
     circuit::{Region, Value},
     plonk::Error,
 };
-use sirius::ff::PrimeField;
 
 use super::{
     super::{util::*, AssignedBits, Bits, SpreadVar, SpreadWord, Table16Assignment},
@@ -1,24 +1,33 @@
+//! SHA-256 chip with a maximum lookup table size of `2^16`, adapted from `halo2_gadgets`
+
+// Not all helpers of the original chip are used
+#![allow(dead_code)]
+
 use std::convert::TryInto;
 use std::marker::PhantomData;
 
-use super::sha256::Sha256Instructions;
+use super::Sha256Instructions;
+use crate::ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Chip, Layouter, Region, Value},
     plonk::{Advice, Any, Assigned, Column, ConstraintSystem, Error},
 };
-use sirius::ff::PrimeField;
 
 mod compression;
 mod gates;
 mod message_schedule;
 mod spread_table;
 mod util;
+mod word_ops;
 
 use compression::*;
 use gates::*;
 use message_schedule::*;
 use spread_table::*;
 use util::*;
+use word_ops::*;
+
+pub(crate) use util::field_to_word;
 
 const ROUNDS: usize = 64;
 const STATE: usize = 8;
@@ -35,7 +44,7 @@ pub(crate) const ROUND_CONSTANTS: [u32; ROUNDS] = [
     0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
 ];
 
-const IV: [u32; STATE] = [
+pub(crate) const IV: [u32; STATE] = [
     0x6a09_e667,
     0xbb67_ae85,
     0x3c6e_f372,
@@ -233,6 +242,7 @@ pub struct Table16Config {
     lookup: SpreadTableConfig,
     message_schedule: MessageScheduleConfig,
     compression: CompressionConfig,
+    words: WordOpsConfig,
 }
 
 /// A chip that implements SHA-256 with a maximum lookup table size of $2^16$.
@@ -305,6 +315,8 @@ impl<F: PrimeField> Table16Chip<F> {
         let compression =
             CompressionConfig::configure(meta, lookup_inputs.clone(), message_schedule, extras);
 
+        let words = WordOpsConfig::configure(meta, lookup_inputs.clone(), message_schedule, extras);
+
         let message_schedule =
             MessageScheduleConfig::configure(meta, lookup_inputs, message_schedule, extras);
 
@@ -312,6 +324,7 @@ impl<F: PrimeField> Table16Chip<F> {
             lookup,
             message_schedule,
             compression,
+            words,
         }
     }
 
@@ -319,6 +332,60 @@ impl<F: PrimeField> Table16Chip<F> {
     pub fn load(config: Table16Config, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         SpreadTableChip::load(config.lookup, layouter)
     }
+
+    /// Assigns words constrained to be equal to the given constants.
+    pub fn assign_constants(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[u32],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config().words.assign_constants(layouter, words)
+    }
+
+    /// Assigns witnessed words. They are range checked only after being used in a message block.
+    pub fn assign_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[Value<u32>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config().words.assign_words(layouter, words)
+    }
+
+    /// Pairwise addition of range checked words modulo `2^32`.
+    pub fn add_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lhs: &[AssignedCell<F, F>],
+        rhs: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config().words.add(layouter, lhs, rhs)
+    }
+
+    /// Creates an initial state from the assigned words, e.g. the output of a previous block
+    /// after feed-forward. The words are bound to the state through its digest.
+    pub fn initialization_from_cells(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[AssignedCell<F, F>; super::DIGEST_SIZE],
+    ) -> Result<State<F>, Error> {
+        let state = self.config().compression.initialize_with_words(
+            layouter,
+            words.each_ref().map(|word| word.value().map(field_to_word)),
+        )?;
+
+        let digest = self.digest_cells(layouter, &state)?;
+        layouter.assign_region(
+            || "check initial state equality",
+            |mut region| {
+                digest
+                    .iter()
+                    .zip(words.iter())
+                    .try_for_each(|(lhs, rhs)| region.constrain_equal(lhs.cell(), rhs.cell()))
+            },
+        )?;
+
+        Ok(state)
+    }
 }
 
 impl<F: PrimeField> Sha256Instructions<F> for Table16Chip<F> {
@@ -465,71 +532,3 @@ trait Table16Assignment<F: PrimeField> {
         Ok(even)
     }
 }
-
-#[cfg(test)]
-#[cfg(feature = "test-dev-graph")]
-mod tests {
-    use super::super::{Sha256, BLOCK_SIZE};
-    use super::{message_schedule::msg_schedule_test_input, Table16Chip, Table16Config};
-    use halo2_proofs::{
-        circuit::{Layouter, SimpleFloorPlanner},
-        plonk::{Circuit, ConstraintSystem, Error},
-    };
-    use halo2curves::pasta::pallas;
-
-    #[traced_test]
-    #[test]
-    fn print_sha256_circuit() {
-        use plotters::prelude::*;
-        struct MyCircuit {}
-
-        impl Circuit<F> for MyCircuit {
-            type Config = Table16Config;
-            type FloorPlanner = SimpleFloorPlanner;
-            #[cfg(feature = "circuit-params")]
-            type Params = ();
-
-            fn without_witnesses(&self) -> Self {
-                MyCircuit {}
-            }
-
-            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-                Table16Chip::configure(meta)
-            }
-
-            fn synthesize(
-                &self,
-                config: Self::Config,
-                mut layouter: impl Layouter<F>,
-            ) -> Result<(), Error> {
-                let table16_chip = Table16Chip::construct(config.clone());
-                Table16Chip::load(config, &mut layouter)?;
-
-                // Test vector: "abc"
-                let test_input = msg_schedule_test_input();
-
-                // Create a message of length 31 blocks
-                let mut input = Vec::with_capacity(31 * BLOCK_SIZE);
-                for _ in 0..31 {
-                    input.extend_from_slice(&test_input);
-                }
-
-                Sha256::digest(table16_chip, layouter.namespace(|| "'abc' * 31"), &input)?;
-
-                Ok(())
-            }
-        }
-
-        let root =
-            BitMapBackend::new("sha-256-table16-chip-layout.png", (1024, 3480)).into_drawing_area();
-        root.fill(&WHITE).unwrap();
-        let root = root
-            .titled("16-bit Table SHA-256 Chip", ("sans-serif", 60))
-            .unwrap();
-
-        let circuit = MyCircuit {};
-        halo2_proofs::dev::CircuitLayout::default()
-            .render::<F, _, _>(17, &circuit, &root)
-            .unwrap();
-    }
-}
@@ -1,12 +1,12 @@
 use std::{convert::TryInto, marker::PhantomData};
 
+use crate::ff::PrimeField;
 use halo2_proofs::{
     arithmetic::Field,
     circuit::{Chip, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, TableColumn},
     poly::Rotation,
 };
-use sirius::ff::PrimeField;
 
 use super::{util::*, AssignedBits};
 
@@ -289,15 +289,16 @@ impl SpreadTableConfig {
 mod tests {
     use std::marker::PhantomData;
 
-    use ff::PrimeField;
+    use crate::ff::PrimeField;
+    use crate::halo2curves::pasta::Fp;
     use halo2_proofs::{
         circuit::{Layouter, SimpleFloorPlanner, Value},
-        dev::MockProver,
         plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
     };
-    use halo2curves::pasta::Fp;
     use rand::Rng;
-    use sirius::run_mock_prover_test;
+    use tracing_test::traced_test;
+
+    use crate::run_mock_prover_test;
 
     use super::{get_tag, SpreadTableChip, SpreadTableConfig};
 
@@ -315,8 +316,6 @@ mod tests {
         impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
             type Config = SpreadTableConfig;
             type FloorPlanner = SimpleFloorPlanner;
-            #[cfg(feature = "circuit-params")]
-            type Params = ();
 
             fn without_witnesses(&self) -> Self {
                 MyCircuit {
@@ -1,3 +1,4 @@
+use crate::{ff::PrimeField, util::fe_to_big};
 use halo2_proofs::circuit::Value;
 
 pub const MASK_EVEN_32: u32 = 0x55555555;
@@ -115,3 +116,14 @@ pub fn sum_with_carry(words: Vec<(Value<u16>, Value<u16>)>) -> (Value<u32>, Valu
 
     (sum, carry)
 }
+
+/// The 32-bit word stored in a field element.
+///
+/// Only the low 32 bits are taken, so the copy of a larger element into a range checked
+/// word fails.
+pub fn field_to_word<F: PrimeField>(value: &F) -> u32 {
+    fe_to_big(value)
+        .iter_u32_digits()
+        .next()
+        .unwrap_or_default()
+}
@@ -0,0 +1,218 @@
+//! Gates on dense 32-bit words, which are not part of the original chip, but are required to chain
+//! compressions of several blocks:
+//! - constant words (initialization vector & padding)
+//! - addition modulo `2^32` (feed-forward of the initial state into the compressed one)
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    util::{field_to_word, i2lebsp},
+    SpreadInputs, SpreadVar, SpreadWord,
+};
+use crate::ff::PrimeField;
+
+#[derive(Clone, Debug)]
+pub(super) struct WordOpsConfig {
+    lookup: SpreadInputs,
+    message_schedule: Column<Advice>,
+    extras: [Column<Advice>; 6],
+    constant: Column<Fixed>,
+
+    s_constant: Selector,
+    s_add: Selector,
+}
+
+impl WordOpsConfig {
+    pub(super) fn configure<F: PrimeField>(
+        meta: &mut ConstraintSystem<F>,
+        lookup: SpreadInputs,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+    ) -> Self {
+        let s_constant = meta.selector();
+        let s_add = meta.selector();
+
+        // Fixed columns can't be a part of permutation, so constants are bound to advice cells
+        // by a gate
+        let constant = meta.fixed_column();
+
+        // Rename these here for ease of matching the gates to the specification.
+        let a_1 = lookup.dense;
+        let a_5 = message_schedule;
+        let a_6 = extras[2];
+        let a_7 = extras[3];
+        let a_8 = extras[4];
+
+        meta.create_gate("constant word", |meta| {
+            let s_constant = meta.query_selector(s_constant);
+            let word = meta.query_advice(a_5, Rotation::cur());
+            let constant = meta.query_fixed(constant, Rotation::cur());
+
+            Constraints::with_selector(s_constant, [("constant", word - constant)])
+        });
+
+        // `lhs + rhs = word + carry * 2^32`, where the 16-bit halves of `word` are range checked
+        // by the lookup in the current & next rows
+        meta.create_gate("add words", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let lo = meta.query_advice(a_1, Rotation::cur());
+            let hi = meta.query_advice(a_1, Rotation::next());
+            let word = meta.query_advice(a_5, Rotation::cur());
+            let lhs = meta.query_advice(a_6, Rotation::cur());
+            let rhs = meta.query_advice(a_7, Rotation::cur());
+            let carry = meta.query_advice(a_8, Rotation::cur());
+
+            Constraints::with_selector(
+                s_add,
+                [
+                    ("check_lo_hi", lo + hi * F::from(1 << 16) - word.clone()),
+                    ("sum", lhs + rhs - word - carry.clone() * F::from(1 << 32)),
+                    (
+                        "carry_bool",
+                        carry.clone() * (Expression::Constant(F::ONE) - carry),
+                    ),
+                ],
+            )
+        });
+
+        WordOpsConfig {
+            lookup,
+            message_schedule,
+            extras,
+            constant,
+            s_constant,
+            s_add,
+        }
+    }
+
+    /// Assigns words constrained to be equal to the given constants
+    pub(super) fn assign_constants<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[u32],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "constant words",
+            |mut region| {
+                words
+                    .iter()
+                    .enumerate()
+                    .map(|(row, word)| {
+                        self.s_constant.enable(&mut region, row)?;
+                        let word = F::from(*word as u64);
+                        region.assign_fixed(
+                            || "constant",
+                            self.constant,
+                            row,
+                            || Value::known(word),
+                        )?;
+                        region.assign_advice(
+                            || "word",
+                            self.message_schedule,
+                            row,
+                            || Value::known(word),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assigns words without any constraints, they are expected to be copied into the message
+    /// schedule, which range checks them
+    pub(super) fn assign_words<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[Value<u32>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "words",
+            |mut region| {
+                words
+                    .iter()
+                    .enumerate()
+                    .map(|(row, word)| {
+                        region.assign_advice(
+                            || "word",
+                            self.message_schedule,
+                            row,
+                            || word.map(|word| F::from(word as u64)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Pairwise addition of words modulo `2^32`
+    ///
+    /// The summands are expected to be range checked words
+    pub(super) fn add<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lhs: &[AssignedCell<F, F>],
+        rhs: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert_eq!(lhs.len(), rhs.len());
+
+        let a_5 = self.message_schedule;
+        let a_6 = self.extras[2];
+        let a_7 = self.extras[3];
+        let a_8 = self.extras[4];
+
+        layouter.assign_region(
+            || "add words",
+            |mut region| {
+                lhs.iter()
+                    .zip(rhs.iter())
+                    .enumerate()
+                    .map(|(idx, (lhs, rhs))| {
+                        let row = 2 * idx;
+                        self.s_add.enable(&mut region, row)?;
+
+                        lhs.copy_advice(|| "lhs", &mut region, a_6, row)?;
+                        rhs.copy_advice(|| "rhs", &mut region, a_7, row)?;
+
+                        let sum = lhs
+                            .value()
+                            .zip(rhs.value())
+                            .map(|(l, r)| field_to_word(l) as u64 + field_to_word(r) as u64);
+                        let word = sum.map(|sum| sum as u32);
+
+                        SpreadVar::with_lookup(
+                            &mut region,
+                            &self.lookup,
+                            row,
+                            word.map(|word| SpreadWord::<16, 32>::new(i2lebsp(word as u64))),
+                        )?;
+                        SpreadVar::with_lookup(
+                            &mut region,
+                            &self.lookup,
+                            row + 1,
+                            word.map(|word| {
+                                SpreadWord::<16, 32>::new(i2lebsp((word >> 16) as u64))
+                            }),
+                        )?;
+
+                        region.assign_advice(
+                            || "carry",
+                            a_8,
+                            row,
+                            || sum.map(|sum| F::from(sum >> 32)),
+                        )?;
+                        region.assign_advice(
+                            || "word",
+                            a_5,
+                            row,
+                            || word.map(|word| F::from(word as u64)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+}
@@ -0,0 +1,123 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{halo2curves::bn256::Fr, ivc::StepCircuit, run_mock_prover_test};
+
+const K: u32 = 17;
+
+#[derive(Clone, Debug)]
+struct TestCircuitConfig {
+    sha256: Sha256Config,
+    instance: Column<Instance>,
+}
+
+/// Public inputs: digest of the message
+struct TestCircuit {
+    message: Vec<u32>,
+}
+
+impl TestCircuit {
+    fn new(message: Vec<u32>) -> (Self, Vec<Fr>) {
+        let digest = off_circuit::digest(&message)
+            .map(|word| Fr::from(word as u64))
+            .to_vec();
+
+        (Self { message }, digest)
+    }
+}
+
+impl Circuit<Fr> for TestCircuit {
+    type Config = TestCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::Config {
+            sha256: Sha256Chip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = Sha256Chip::construct(config.sha256);
+        chip.load(&mut layouter)?;
+
+        let message = chip.assign_words(
+            &mut layouter,
+            &self
+                .message
+                .iter()
+                .map(|word| Value::known(*word))
+                .collect::<Vec<_>>(),
+        )?;
+        let digest = chip.digest(&mut layouter, &message)?;
+
+        for (row, word) in digest.iter().enumerate() {
+            layouter.constrain_instance(word.cell(), config.instance, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[traced_test]
+#[test]
+fn chip_matches_off_circuit() {
+    // Single block & the padding in a separate block
+    for message in [vec![0x61626364], (0..15).collect()] {
+        let (circuit, public_inputs) = TestCircuit::new(message);
+        run_mock_prover_test!(K, circuit, vec![public_inputs]);
+    }
+}
+
+#[traced_test]
+#[test]
+fn chip_rejects_wrong_digest() {
+    use halo2_proofs::dev::MockProver;
+
+    let (circuit, mut public_inputs) = TestCircuit::new(vec![1, 2, 3]);
+    public_inputs[0] += Fr::from(1);
+
+    let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[traced_test]
+#[test]
+fn step_circuit_chain() {
+    use crate::util::mock_prover::MockProver;
+
+    let message = (0..20).collect::<Vec<u32>>();
+
+    let z_out = Sha256StepCircuit::<Fr>::for_message(&message)
+        .iter()
+        .try_fold(Sha256StepCircuit::initial_state(), |z_i, step_circuit| {
+            let z_out = step_circuit.process_step(&z_i, K)?;
+
+            MockProver::run(K, step_circuit, vec![], z_i)
+                .unwrap()
+                .verify(z_out)
+                .unwrap();
+
+            Ok::<_, crate::ivc::SynthesisError>(z_out)
+        })
+        .unwrap();
+
+    assert_eq!(
+        z_out,
+        off_circuit::digest(&message).map(|word| Fr::from(word as u64))
+    );
+}
@@ -0,0 +1,149 @@
+//! ECDSA over a curve with a foreign base field, e.g. secp256k1
+//!
+//! The message is given by its hash as a scalar, see [`digest_to_scalar`] for the Ethereum way
+//! of getting it from a Keccak-256 digest.
+
+use std::num::NonZeroUsize;
+
+use halo2_proofs::{arithmetic::CurveAffine, circuit::Value};
+use num_bigint::BigUint;
+
+use crate::{
+    ff::PrimeField,
+    gadgets::nonnative::{
+        bn::big_uint_mul_mod_chip::MAIN_GATE_T,
+        ecc::{AssignedForeignPoint, Error, ForeignEccChip},
+        foreign_field::AssignedForeignField,
+    },
+    group::{prime::PrimeCurveAffine, Curve},
+    main_gate::{MainGateConfig, RegionCtx},
+    util,
+};
+
+/// `(r, s)` pair of scalars
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature<S: PrimeField> {
+    pub r: S,
+    pub s: S,
+}
+
+/// Big-endian digest reduced by the order of the group
+pub fn digest_to_scalar<S: PrimeField>(digest: &[u8]) -> S {
+    util::fe_from_big(BigUint::from_bytes_be(digest) % util::modulus::<S>())
+        .expect("reduced by modulus")
+}
+
+/// `x` coordinate of the point reduced by the order of the group
+fn x_to_scalar<C: CurveAffine>(point: &C) -> Option<C::Scalar> {
+    let x: C::Base = Option::from(point.coordinates().map(|c| *c.x()))?;
+    util::fe_to_fe(&x)
+}
+
+pub fn public_key<C: CurveAffine>(secret: &C::Scalar) -> C {
+    (C::generator() * *secret).to_affine()
+}
+
+/// Returns `None` if the `nonce` leads to a degenerate signature, another nonce should be used
+pub fn sign<C: CurveAffine>(
+    secret: &C::Scalar,
+    msg_hash: &C::Scalar,
+    nonce: &C::Scalar,
+) -> Option<Signature<C::Scalar>> {
+    let r = x_to_scalar(&public_key::<C>(nonce))?;
+    let nonce_inv: C::Scalar = Option::from(nonce.invert())?;
+    let s = nonce_inv * (*msg_hash + r * secret);
+
+    (!bool::from(r.is_zero()) && !bool::from(s.is_zero())).then_some(Signature { r, s })
+}
+
+pub fn verify<C: CurveAffine>(
+    public_key: &C,
+    msg_hash: &C::Scalar,
+    signature: &Signature<C::Scalar>,
+) -> bool {
+    let Some(s_inv) = Option::<C::Scalar>::from(signature.s.invert()) else {
+        return false;
+    };
+    if bool::from(signature.r.is_zero()) {
+        return false;
+    }
+
+    let point =
+        (C::generator() * (*msg_hash * s_inv) + *public_key * (signature.r * s_inv)).to_affine();
+
+    x_to_scalar(&point) == Some(signature.r)
+}
+
+#[derive(Clone, Debug)]
+pub struct AssignedSignature<F: PrimeField, S: PrimeField> {
+    pub r: AssignedForeignField<F, S>,
+    pub s: AssignedForeignField<F, S>,
+}
+
+/// On-circuit version of [`verify`]
+#[derive(Debug)]
+pub struct EcdsaChip<F: PrimeField, C: CurveAffine> {
+    ecc: ForeignEccChip<F, C>,
+}
+
+impl<F: PrimeField, C: CurveAffine> EcdsaChip<F, C> {
+    pub fn new(
+        config: MainGateConfig<MAIN_GATE_T>,
+        limb_width: NonZeroUsize,
+        limbs_count: NonZeroUsize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            ecc: ForeignEccChip::new(config, limb_width, limbs_count)?,
+        })
+    }
+
+    /// The chip to assign public keys & message hashes with
+    pub fn ecc(&self) -> &ForeignEccChip<F, C> {
+        &self.ecc
+    }
+
+    pub fn assign_signature(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        signature: Value<Signature<C::Scalar>>,
+    ) -> Result<AssignedSignature<F, C::Scalar>, Error> {
+        let scalar = self.ecc.scalar_field();
+
+        Ok(AssignedSignature {
+            r: scalar.assign(ctx, signature.map(|signature| signature.r))?,
+            s: scalar.assign(ctx, signature.map(|signature| signature.s))?,
+        })
+    }
+
+    /// Constrains `x(u_1 * G + u_2 * public_key) mod n == r`, where `u_1 = msg_hash / s` &
+    /// `u_2 = r / s`
+    ///
+    /// The `public_key` is expected to be assigned by [`ForeignEccChip::assign_point`], so it's
+    /// checked to be on the curve
+    pub fn verify(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        public_key: &AssignedForeignPoint<F, C>,
+        msg_hash: &AssignedForeignField<F, C::Scalar>,
+        signature: &AssignedSignature<F, C::Scalar>,
+    ) -> Result<(), Error> {
+        let scalar = self.ecc.scalar_field();
+
+        // The inversion of zero is unsatisfiable, so it also checks `r != 0`
+        scalar.invert(ctx, &signature.r)?;
+        let s_inv = scalar.invert(ctx, &signature.s)?;
+
+        let u_1 = scalar.mul(ctx, msg_hash, &s_inv)?;
+        let u_2 = scalar.mul(ctx, &signature.r, &s_inv)?;
+
+        let generator = self.ecc.assign_constant_point(ctx, C::generator())?;
+        let point = self
+            .ecc
+            .msm(ctx, &[(&generator, &u_1), (public_key, &u_2)])?;
+
+        let (x, _) = point.coordinates();
+        let x = scalar.reduce_limbs(ctx, x.limbs())?;
+
+        Ok(scalar.assert_equal(ctx, &x, &signature.r)?)
+    }
+}
@@ -0,0 +1,21 @@
+//! # Signatures
+//!
+//! Verification gadgets of signatures, each with off-circuit `sign` & `verify` helpers:
+//! - [`ecdsa`] - ECDSA over a foreign curve, e.g. secp256k1 of Ethereum, on top of
+//!   [`crate::gadgets::nonnative::ecc::ForeignEccChip`]
+//! - [`schnorr`] - Schnorr (EdDSA-like) over the native curve, on top of
+//!   [`crate::gadgets::ecc::EccChip`] & the random oracle
+//! - [`EcdsaBatchStepCircuit`] - [`crate::ivc::StepCircuit`] verifying a batch of ECDSA
+//!   signatures per step
+//!
+//! Non-native arithmetic is costly: one ECDSA verification over secp256k1 takes millions of
+//! rows, so keep the batch small & `k` large.
+
+pub mod ecdsa;
+pub mod schnorr;
+pub mod step_circuit;
+
+pub use step_circuit::{EcdsaBatchStepCircuit, SignedMessage};
+
+#[cfg(test)]
+mod tests;
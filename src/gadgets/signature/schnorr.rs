@@ -0,0 +1,159 @@
+//! Schnorr signatures over the native curve, i.e. the curve whose base field is the circuit field
+//!
+//! A signature is `(R, s)` such that `s * G = R + e * P`, where `P` is the public key & `e` is
+//! the challenge derived by the random oracle from `R`, `P` & the message. It's the same
+//! equation as in EdDSA, but over a short Weierstrass curve, since [`EccChip`] has no twisted
+//! Edwards arithmetic.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{arithmetic::CurveAffine, circuit::Chip, plonk::Error};
+
+use crate::{
+    constants::NUM_CHALLENGE_BITS,
+    ff::{FromUniformBytes, PrimeField, PrimeFieldBits},
+    gadgets::ecc::{AssignedPoint, EccChip, DEFAULT_WINDOW_SIZE},
+    group::{prime::PrimeCurveAffine, Curve},
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx, WrapValue},
+    poseidon::{ROCircuitTrait, ROPair, ROTrait},
+    util,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature<C: CurveAffine> {
+    pub r: C,
+    pub s: C::Scalar,
+}
+
+pub fn challenge<F, C, RO>(ro_args: &RO::Args, r: &C, public_key: &C, message: &F) -> C::Scalar
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    C: CurveAffine<Base = F>,
+    RO: ROPair<F>,
+{
+    RO::OffCircuit::new(ro_args.clone())
+        .absorb_point(r)
+        .absorb_point(public_key)
+        .absorb_field(*message)
+        .squeeze::<C>(NUM_CHALLENGE_BITS)
+}
+
+pub fn public_key<C: CurveAffine>(secret: &C::Scalar) -> C {
+    (C::generator() * *secret).to_affine()
+}
+
+pub fn sign<F, C, RO>(
+    ro_args: &RO::Args,
+    secret: &C::Scalar,
+    message: &F,
+    nonce: &C::Scalar,
+) -> Signature<C>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    C: CurveAffine<Base = F>,
+    RO: ROPair<F>,
+{
+    let r = public_key::<C>(nonce);
+    let e = challenge::<F, C, RO>(ro_args, &r, &public_key::<C>(secret), message);
+
+    Signature {
+        r,
+        s: *nonce + e * secret,
+    }
+}
+
+pub fn verify<F, C, RO>(
+    ro_args: &RO::Args,
+    public_key: &C,
+    message: &F,
+    signature: &Signature<C>,
+) -> bool
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    C: CurveAffine<Base = F>,
+    RO: ROPair<F>,
+{
+    let e = challenge::<F, C, RO>(ro_args, &signature.r, public_key, message);
+
+    C::generator() * signature.s == signature.r.to_curve() + *public_key * e
+}
+
+#[derive(Clone, Debug)]
+pub struct AssignedSignature<C: CurveAffine> {
+    pub r: AssignedPoint<C>,
+    /// Little-endian bits of `s`
+    pub s: Vec<AssignedBit<C::Base>>,
+}
+
+/// On-circuit version of [`verify`]
+///
+/// Requires `T >= 4` because of [`EccChip`]
+pub struct SchnorrChip<F, C, RO, const T: usize>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    C: CurveAffine<Base = F>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    main_gate: MainGate<F, T>,
+    ro_args: RO::Args,
+    _p: PhantomData<C>,
+}
+
+impl<F, C, RO, const T: usize> SchnorrChip<F, C, RO, T>
+where
+    F: PrimeFieldBits + FromUniformBytes<64>,
+    C: CurveAffine<Base = F>,
+    RO: ROPair<F, Config = MainGateConfig<T>>,
+{
+    pub fn new(config: MainGateConfig<T>, ro_args: RO::Args) -> Self {
+        Self {
+            main_gate: MainGate::new(config),
+            ro_args,
+            _p: PhantomData,
+        }
+    }
+
+    fn ecc(&self) -> EccChip<C, F, T> {
+        EccChip::new(self.main_gate.config().clone())
+    }
+
+    pub fn assign_signature(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        signature: &Signature<C>,
+    ) -> Result<AssignedSignature<C>, Error> {
+        let mut bits = util::fe_to_bits_le(&signature.s);
+        bits.resize(C::Scalar::NUM_BITS as usize, false);
+
+        Ok(AssignedSignature {
+            r: self.ecc().assign_from_curve(ctx, || "r", &signature.r)?,
+            s: self.main_gate.assign_bits(ctx, &bits)?,
+        })
+    }
+
+    /// Constrains `s * G == R + e * public_key`
+    pub fn verify(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        public_key: &AssignedPoint<C>,
+        message: &AssignedValue<F>,
+        signature: &AssignedSignature<C>,
+    ) -> Result<(), Error> {
+        let e = RO::OnCircuit::new(self.main_gate.config().clone(), self.ro_args.clone())
+            .absorb_point(WrapValue::from_assigned_point(&signature.r))
+            .absorb_point(WrapValue::from_assigned_point(public_key))
+            .absorb_base(WrapValue::Assigned(message.clone()))
+            .squeeze_n_bits(ctx, NUM_CHALLENGE_BITS)?;
+
+        let ecc = self.ecc();
+
+        let lhs =
+            ecc.fixed_base_scalar_mul(ctx, &C::generator(), &signature.s, DEFAULT_WINDOW_SIZE)?;
+
+        let challenged = ecc.scalar_mul_windowed(ctx, public_key, &e, DEFAULT_WINDOW_SIZE)?;
+        let rhs = ecc.add(ctx, &signature.r, &challenged)?;
+
+        ctx.constrain_equal(lhs.x.cell(), rhs.x.cell())?;
+        ctx.constrain_equal(lhs.y.cell(), rhs.y.cell())
+    }
+}
@@ -0,0 +1,140 @@
+//! [`StepCircuit`] verifying a batch of ECDSA signatures per step
+//!
+//! `z_i` is the number of signatures verified so far, so a chain of `n` steps proves `n * BATCH`
+//! signatures at once.
+
+use std::{marker::PhantomData, num::NonZeroUsize};
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+use tracing::*;
+
+use super::ecdsa::{self, EcdsaChip, Signature};
+use crate::{
+    ff::PrimeField,
+    gadgets::nonnative::{bn::big_uint_mul_mod_chip::MAIN_GATE_T, ecc},
+    ivc::{StepCircuit, SynthesisError},
+    main_gate::{MainGate, MainGateConfig, RegionCtx, WrapValue},
+};
+
+pub const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(64) };
+
+/// The message hash signed by the owner of the public key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedMessage<C: CurveAffine> {
+    pub public_key: C,
+    pub msg_hash: C::Scalar,
+    pub signature: Signature<C::Scalar>,
+}
+
+impl<C: CurveAffine> SignedMessage<C> {
+    pub fn verify(&self) -> bool {
+        ecdsa::verify(&self.public_key, &self.msg_hash, &self.signature)
+    }
+}
+
+/// `z_{i+1} = z_i + BATCH`, if all signatures of the batch are valid
+#[derive(Clone, Debug)]
+pub struct EcdsaBatchStepCircuit<F: PrimeField, C: CurveAffine, const BATCH: usize> {
+    batch: [SignedMessage<C>; BATCH],
+    _p: PhantomData<F>,
+}
+
+impl<F: PrimeField, C: CurveAffine, const BATCH: usize> EcdsaBatchStepCircuit<F, C, BATCH> {
+    pub fn new(batch: [SignedMessage<C>; BATCH]) -> Self {
+        Self {
+            batch,
+            _p: PhantomData,
+        }
+    }
+
+    /// Enough limbs of [`LIMB_WIDTH`] for both the base & the scalar field of `C`
+    fn limbs_count() -> NonZeroUsize {
+        let bits = C::Base::NUM_BITS.max(C::Scalar::NUM_BITS) as usize;
+        NonZeroUsize::new(bits.div_ceil(LIMB_WIDTH.get())).expect("field can't be empty")
+    }
+
+    fn verify_batch(
+        &self,
+        chip: &EcdsaChip<F, C>,
+        ctx: &mut RegionCtx<'_, F>,
+    ) -> Result<(), ecc::Error> {
+        self.batch.iter().try_for_each(|signed| {
+            let public_key = chip
+                .ecc()
+                .assign_point(ctx, Value::known(signed.public_key))?;
+            let msg_hash = chip
+                .ecc()
+                .scalar_field()
+                .assign(ctx, Value::known(signed.msg_hash))?;
+            let signature = chip.assign_signature(ctx, Value::known(signed.signature))?;
+
+            chip.verify(ctx, &public_key, &msg_hash, &signature)
+        })
+    }
+}
+
+impl<F: PrimeField, C: CurveAffine, const BATCH: usize> StepCircuit<1, F>
+    for EcdsaBatchStepCircuit<F, C, BATCH>
+{
+    type Config = MainGateConfig<MAIN_GATE_T>;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+        MainGate::configure(cs)
+    }
+
+    fn synthesize_step(
+        &self,
+        config: Self::Config,
+        layouter: &mut impl Layouter<F>,
+        z_i: &[AssignedCell<F, F>; 1],
+    ) -> Result<[AssignedCell<F, F>; 1], SynthesisError> {
+        let chip = EcdsaChip::<F, C>::new(config.clone(), LIMB_WIDTH, Self::limbs_count())
+            .map_err(|err| {
+                error!("while creating ecdsa chip: {err:?}");
+                Error::Synthesis
+            })?;
+        let main_gate = MainGate::<F, MAIN_GATE_T>::new(config);
+
+        let z_out = layouter.assign_region(
+            || "ecdsa batch",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                self.verify_batch(&chip, &mut ctx).map_err(|err| {
+                    error!("while verifying ecdsa batch: {err:?}");
+                    Error::Synthesis
+                })?;
+
+                let batch = F::from(BATCH as u64);
+                main_gate.apply(
+                    &mut ctx,
+                    (
+                        Some(vec![F::ONE]),
+                        None,
+                        Some(vec![WrapValue::Assigned(z_i[0].clone())]),
+                    ),
+                    Some(batch),
+                    (
+                        -F::ONE,
+                        WrapValue::Unassigned(z_i[0].value().map(|z| *z + batch)),
+                    ),
+                )
+            },
+        )?;
+
+        Ok([z_out])
+    }
+
+    fn process_step(&self, z_i: &[F; 1], _k_table_size: u32) -> Result<[F; 1], SynthesisError> {
+        if let Some(index) = self.batch.iter().position(|signed| !signed.verify()) {
+            error!("signature #{index} of the batch is invalid");
+            return Err(SynthesisError::Halo2(Error::Synthesis));
+        }
+
+        Ok([z_i[0] + F::from(BATCH as u64)])
+    }
+}
@@ -0,0 +1,299 @@
+use std::num::NonZeroUsize;
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use tracing_test::traced_test;
+
+use super::{
+    ecdsa::{self, EcdsaChip},
+    schnorr::{self, SchnorrChip},
+    *,
+};
+use crate::{
+    ff::{Field, PrimeField},
+    gadgets::{keccak::keccak256, nonnative::bn::big_uint_mul_mod_chip::MAIN_GATE_T},
+    halo2curves::{
+        bn256::Fr,
+        grumpkin,
+        secp256k1::{Fq as Secp256k1Scalar, Secp256k1Affine},
+    },
+    main_gate::{MainGate, MainGateConfig, RegionCtx},
+    poseidon::{PoseidonRO, ROPair},
+    run_mock_prover_test,
+};
+
+const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(64) };
+const LIMBS_COUNT: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(4) };
+
+fn signed_message(secret: u64, nonce: u64, message: &[u8]) -> SignedMessage<Secp256k1Affine> {
+    let secret = Secp256k1Scalar::from(secret);
+    let msg_hash = ecdsa::digest_to_scalar(&keccak256(message));
+
+    SignedMessage {
+        public_key: ecdsa::public_key(&secret),
+        msg_hash,
+        signature: ecdsa::sign::<Secp256k1Affine>(
+            &secret,
+            &msg_hash,
+            &Secp256k1Scalar::from(nonce),
+        )
+        .unwrap(),
+    }
+}
+
+#[test]
+fn ecdsa_off_circuit() {
+    let signed = signed_message(0xc0ffee, 0xdead_beef, b"transfer 1 eth");
+    assert!(signed.verify());
+
+    let tampered = [
+        SignedMessage {
+            msg_hash: signed.msg_hash + Secp256k1Scalar::ONE,
+            ..signed
+        },
+        SignedMessage {
+            public_key: ecdsa::public_key(&Secp256k1Scalar::from(0xc0ffef)),
+            ..signed
+        },
+        SignedMessage {
+            signature: ecdsa::Signature {
+                s: signed.signature.s.double(),
+                ..signed.signature
+            },
+            ..signed
+        },
+    ];
+    assert!(tampered.iter().all(|signed| !signed.verify()));
+}
+
+/// Verifies the signature of the message, all of them are private
+struct EcdsaCircuit {
+    signed: SignedMessage<Secp256k1Affine>,
+}
+
+impl Circuit<Fr> for EcdsaCircuit {
+    type Config = MainGateConfig<MAIN_GATE_T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = EcdsaChip::<Fr, Secp256k1Affine>::new(config, LIMB_WIDTH, LIMBS_COUNT).unwrap();
+
+        layouter.assign_region(
+            || "ecdsa",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let public_key = chip
+                    .ecc()
+                    .assign_point(&mut ctx, Value::known(self.signed.public_key))
+                    .unwrap();
+                let msg_hash = chip
+                    .ecc()
+                    .scalar_field()
+                    .assign(&mut ctx, Value::known(self.signed.msg_hash))
+                    .unwrap();
+                let signature = chip
+                    .assign_signature(&mut ctx, Value::known(self.signed.signature))
+                    .unwrap();
+
+                chip.verify(&mut ctx, &public_key, &msg_hash, &signature)
+                    .unwrap();
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+#[ignore = "cause it takes a few minutes to run"]
+fn ecdsa_circuit() {
+    run_mock_prover_test!(
+        24,
+        EcdsaCircuit {
+            signed: signed_message(0xc0ffee, 0xdead_beef, b"transfer 1 eth"),
+        },
+        vec![]
+    );
+}
+
+#[traced_test]
+#[test]
+#[ignore = "cause it takes a few minutes to run"]
+fn ecdsa_batch_step_circuit() {
+    use crate::{ivc::StepCircuit, util::mock_prover::MockProver};
+
+    const K: u32 = 24;
+
+    let step_circuit = EcdsaBatchStepCircuit::<Fr, Secp256k1Affine, 2>::new([
+        signed_message(1, 2, b"first"),
+        signed_message(3, 4, b"second"),
+    ]);
+
+    let z_i = [Fr::from(40)];
+    let z_out = step_circuit.process_step(&z_i, K).unwrap();
+    assert_eq!(z_out, [Fr::from(42)]);
+
+    MockProver::run(K, &step_circuit, vec![], z_i)
+        .unwrap()
+        .verify(z_out)
+        .unwrap();
+}
+
+#[test]
+fn ecdsa_batch_rejects_invalid() {
+    use crate::ivc::StepCircuit;
+
+    let mut invalid = signed_message(1, 2, b"first");
+    invalid.msg_hash += Secp256k1Scalar::ONE;
+
+    assert!(EcdsaBatchStepCircuit::<Fr, Secp256k1Affine, 2>::new([
+        signed_message(3, 4, b"second"),
+        invalid,
+    ])
+    .process_step(&[Fr::ZERO], 24)
+    .is_err());
+}
+
+const T: usize = 5;
+const RATE: usize = 4;
+
+type RO = PoseidonRO<T, RATE>;
+type Spec = <RO as ROPair<Fr>>::Args;
+type C = grumpkin::G1Affine;
+type Scalar = <C as CurveAffine>::ScalarExt;
+
+fn spec() -> Spec {
+    Spec::new(10, 10)
+}
+
+fn schnorr_signature(secret: u64, message: Fr) -> (C, schnorr::Signature<C>) {
+    let secret = Scalar::from(secret);
+
+    (
+        schnorr::public_key(&secret),
+        schnorr::sign::<Fr, C, RO>(&spec(), &secret, &message, &Scalar::from_u128(u128::MAX)),
+    )
+}
+
+#[test]
+fn schnorr_off_circuit() {
+    let message = Fr::from(0xcafe);
+    let (public_key, signature) = schnorr_signature(0xc0ffee, message);
+
+    assert!(schnorr::verify::<Fr, C, RO>(
+        &spec(),
+        &public_key,
+        &message,
+        &signature
+    ));
+    assert!(!schnorr::verify::<Fr, C, RO>(
+        &spec(),
+        &public_key,
+        &(message + Fr::ONE),
+        &signature
+    ));
+    assert!(!schnorr::verify::<Fr, C, RO>(
+        &spec(),
+        &schnorr::public_key(&Scalar::from(0xc0ffef)),
+        &message,
+        &signature
+    ));
+}
+
+/// Verifies the Schnorr signature of `message` by `public_key`
+struct SchnorrCircuit {
+    public_key: C,
+    message: Fr,
+    signature: schnorr::Signature<C>,
+}
+
+impl Circuit<Fr> for SchnorrCircuit {
+    type Config = MainGateConfig<T>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        todo!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        MainGate::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = SchnorrChip::<Fr, C, RO, T>::new(config.clone(), spec());
+        let main_gate = MainGate::<Fr, T>::new(config.clone());
+        let ecc = crate::gadgets::ecc::EccChip::<C, Fr, T>::new(config);
+
+        layouter.assign_region(
+            || "schnorr",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+
+                let public_key =
+                    ecc.assign_from_curve(&mut ctx, || "public key", &self.public_key)?;
+                let message = main_gate.assign_value(&mut ctx, Value::known(self.message))?;
+                let signature = chip.assign_signature(&mut ctx, &self.signature)?;
+
+                chip.verify(&mut ctx, &public_key, &message, &signature)
+            },
+        )
+    }
+}
+
+#[traced_test]
+#[test]
+fn schnorr_circuit() {
+    let message = Fr::from(0xcafe);
+    let (public_key, signature) = schnorr_signature(0xc0ffee, message);
+
+    run_mock_prover_test!(
+        15,
+        SchnorrCircuit {
+            public_key,
+            message,
+            signature,
+        },
+        vec![]
+    );
+}
+
+#[test]
+fn schnorr_circuit_wrong_message() {
+    use halo2_proofs::dev::MockProver;
+
+    let message = Fr::from(0xcafe);
+    let (public_key, signature) = schnorr_signature(0xc0ffee, message);
+
+    let prover = MockProver::run(
+        15,
+        &SchnorrCircuit {
+            public_key,
+            message: message + Fr::ONE,
+            signature,
+        },
+        vec![],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+}
@@ -1,11 +1,14 @@
+use std::num::NonZeroUsize;
+
 use halo2_proofs::{
     circuit::{Chip, Value},
     plonk::Error,
 };
 
 use crate::{
-    ff::PrimeField,
-    main_gate::{AssignedValue, MainGate, MainGateConfig, RegionCtx},
+    ff::{PrimeField, PrimeFieldBits},
+    main_gate::{AssignedBit, AssignedValue, MainGate, MainGateConfig, RegionCtx},
+    util,
 };
 
 impl<F: PrimeField, const T: usize> MainGate<F, T> {
@@ -272,3 +275,64 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
         self.mul(ctx, a, &b_inv)
     }
 }
+
+impl<F: PrimeFieldBits, const T: usize> MainGate<F, T> {
+    // lt = 1 <=> a < b; lt = 0 <=> a >= b
+    // both a & b must be less than 2^bits, require T >= 3
+    //
+    // proved by range check of `a - b + lt * 2^bits` in `bits`, which holds only for right `lt`
+    pub fn less_than(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedValue<F>,
+        b: &AssignedValue<F>,
+        bits: NonZeroUsize,
+    ) -> Result<AssignedBit<F>, Error> {
+        let lt = a.value().zip(b.value()).map(|(a, b)| {
+            if util::fe_to_big(a) < util::fe_to_big(b) {
+                F::ONE
+            } else {
+                F::ZERO
+            }
+        });
+        let lt = self.assign_bit(ctx, lt)?;
+
+        let shift = F::from(2).pow_vartime([bits.get() as u64]);
+        let diff_val =
+            a.value().copied() - b.value().copied() + lt.value().copied() * Value::known(shift);
+        let diff = self.apply(
+            ctx,
+            (
+                Some(vec![F::ONE, -F::ONE, shift]),
+                None,
+                Some(vec![a.into(), b.into(), lt.clone().into()]),
+            ),
+            None,
+            (-F::ONE, diff_val.into()),
+        )?;
+        self.le_num_to_bits(ctx, diff, bits)?;
+
+        Ok(lt)
+    }
+
+    // le = 1 <=> a <= b; le = 0 <=> a > b
+    // same requirements as `less_than`
+    pub fn less_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedValue<F>,
+        b: &AssignedValue<F>,
+        bits: NonZeroUsize,
+    ) -> Result<AssignedBit<F>, Error> {
+        let gt = self.less_than(ctx, b, a, bits)?;
+
+        // le = 1 - gt
+        let le_val = Value::known(F::ONE) - gt.value().copied();
+        self.apply(
+            ctx,
+            (Some(vec![-F::ONE]), None, Some(vec![gt.into()])),
+            Some(F::ONE),
+            (-F::ONE, le_val.into()),
+        )
+    }
+}
@@ -252,6 +252,8 @@ mod tests {
         let mut td = WitnessCollector {
             instances: vec![vec![]],
             advice: vec![vec![Base::ZERO.into(); 1 << K_TABLE_SIZE]; cs.num_advice_columns()],
+            challenges: vec![],
+            last_ready_phase: None,
         };
 
         let on_circuit_hash = SingleChipLayouter::<'_, Base, _>::new(&mut td, vec![])
@@ -55,7 +55,7 @@ use crate::{
     constants::NUM_CHALLENGE_BITS,
     ff::{Field, FromUniformBytes, PrimeField, PrimeFieldBits},
     gadgets::{
-        ecc::{AssignedPoint, EccChip},
+        ecc::{AssignedPoint, EccChip, DEFAULT_WINDOW_SIZE},
         nonnative::bn::{
             big_uint::{self, BigUint},
             big_uint_mul_mod_chip::{self, BigUintMulModChip, OverflowingBigUint},
@@ -429,7 +429,7 @@ where
     /// # Implementation Details
     ///
     /// 1. **Scalar Multiplication**: Each `W` component from `input_W_commitments` is multiplied
-    ///    by random the scalar `r` (challenge). This is executed using the [`EccChip`] for elliptic curve operations.
+    ///    by random the scalar `r` (challenge). This is executed by [`EccChip::scalar_mul_windowed`].
     /// 2. **Accumulation**: The result of the scalar multiplication is then added to the corresponding component in
     ///    the current `folded_W` accumulator. This is executed using the [`EccChip`] for elliptic curve operations.
     ///
@@ -450,7 +450,7 @@ where
             .zip_eq(input_W_commitments)
             .enumerate()
             .map(|(W_index, (W1, W2))| -> Result<AssignedPoint<C>, Error> {
-                let rW = ecc.scalar_mul(region, W2, r)?;
+                let rW = ecc.scalar_mul_windowed(region, W2, r, DEFAULT_WINDOW_SIZE)?;
                 let res = ecc.add(region, W1, &rW)?;
                 debug!(
                     "W1 = {W1:?}; W2 = {W2:?}; rW2[{W_index}] = {rW:?}; rW1 + rW2 * r = {res:?}"
@@ -472,10 +472,10 @@ where
     /// # Implementation Details
     ///
     /// 1. **Multiplication & Conversion to bits**: Form a vector of degrees `r` and their representations as bits
-    /// 2. **Scalar Multiplication**: Each element of `cross_term_commits` is multiplied by power of random scalar
-    ///    `r` (challenge) in bits representation. This is executed using the [`EccChip`] for elliptic curve operations.
-    /// 3. **Accumulation**: The result of the scalar multiplication is then added to the corresponding component in
-    ///    the current `folded_E` accumulator. This is executed using the [`EccChip`] for elliptic curve operations.
+    /// 2. **Multi-Scalar Multiplication**: Elements of `cross_term_commits` are multiplied by powers of random scalar
+    ///    `r` (challenge) in bits representation & summed by [`EccChip::msm`], which shares doublings between them.
+    /// 3. **Accumulation**: The result of the multi-scalar multiplication is then added to the current `folded_E`
+    ///    accumulator. This is executed using the [`EccChip`] for elliptic curve operations.
     ///
     /// ```markdown
     /// new_folded_E = folded_E + Sum [ cross_term_commits[i] * (r ^ i) ]
@@ -515,15 +515,19 @@ where
 
         let ecc = EccChip::<C, C::Base, T>::new(self.config.clone());
         // TODO Check what with all commits
-        let rT = cross_term_commits
+        let terms = cross_term_commits
             .iter()
-            .zip(powers_of_r.into_iter())
-            .map(|(commit, r_pow_i)| ecc.scalar_mul(region, commit, &r_pow_i))
-            .collect::<Result<Vec<_>, _>>()?;
+            .zip(powers_of_r.iter())
+            .map(|(commit, r_pow_i)| (commit, r_pow_i.as_bits.as_slice()))
+            .collect::<Vec<_>>();
 
-        Ok(rT
-            .into_iter()
-            .try_fold(folded_E, |folded_E, rT_i| ecc.add(region, &folded_E, &rT_i))?)
+        if terms.is_empty() {
+            return Ok(folded_E);
+        }
+
+        let rT = ecc.msm(region, &terms, DEFAULT_WINDOW_SIZE)?;
+
+        Ok(ecc.add(region, &folded_E, &rT)?)
     }
 
     /// Fold `input` with `folded` in bn form
@@ -1142,6 +1146,8 @@ mod tests {
         let witness = WitnessCollector {
             instances: vec![vec![]],
             advice: vec![vec![Base::ZERO.into(); 1 << K]; cs.num_advice_columns()],
+            challenges: vec![],
+            last_ready_phase: None,
         };
 
         (witness, config)
@@ -1756,9 +1762,10 @@ mod tests {
         }
     }
 
-    #[traced_test]
-    #[test]
-    fn fold_all() {
+    /// Folds random instances by the whole chip & compares with the off-circuit fold, while the
+    /// first `infinity_count` of witness & cross term commitments are points at infinity, as
+    /// commitments of zero columns & cross terms in IVC
+    fn fold_rounds(infinity_count: usize) {
         const T: usize = 6;
 
         let Fixture {
@@ -1774,9 +1781,12 @@ mod tests {
         let mut relaxed = RelaxedPlonkInstance::new(NUM_CHALLENGES, NUM_WITNESS);
 
         for _round in 0..=NUM_OF_FOLD_ROUNDS {
-            let input_plonk = generate_random_plonk_instance(&mut rnd);
+            let mut input_plonk = generate_random_plonk_instance(&mut rnd);
             let pp_hash = C1::random(&mut rnd);
-            let cross_term_commits = random_curve_vec(&mut rnd);
+            let mut cross_term_commits = random_curve_vec(&mut rnd);
+
+            input_plonk.W_commitments[..infinity_count].fill(C1::default());
+            cross_term_commits[..infinity_count].fill(C1::default());
 
             let on_circuit_relaxed = layouter
                 .assign_region(
@@ -1822,6 +1832,18 @@ mod tests {
         }
     }
 
+    #[traced_test]
+    #[test]
+    fn fold_all() {
+        fold_rounds(0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn fold_all_with_infinity() {
+        fold_rounds(2);
+    }
+
     fn generate_off_circuit_challenge(
         spec: &Spec<Base, T, { T - 1 }>,
         pp_hash: C1,
@@ -277,12 +277,11 @@ where
             .zip(pp.primary.S().num_io.iter())
             .all(|(instance, expected_len)| { instance.len() == *expected_len }));
 
-        let primary_witness = CircuitRunner::new(
+        let primary_runner = CircuitRunner::new(
             pp.primary.k_table_size(),
             primary_sfc,
             primary_instances.clone(),
-        )
-        .try_collect_witness()?;
+        );
 
         let (primary_nifs_pp, _primary_off_circuit_vp) =
             VanillaFS::setup_params(pp.digest_1(), pp.primary.S().clone())?;
@@ -290,7 +289,7 @@ where
         let primary_plonk_trace = VanillaFS::generate_plonk_trace(
             pp.primary.ck(),
             &primary_instances,
-            &primary_witness,
+            &primary_runner,
             &primary_nifs_pp,
             &mut RP2::OffCircuit::new(pp.secondary.params().ro_constant().clone()),
         )?;
@@ -363,12 +362,11 @@ where
             .zip(pp.secondary.S().num_io.iter())
             .all(|(instance, expected_len)| { instance.len() == *expected_len }));
 
-        let secondary_witness = CircuitRunner::new(
+        let secondary_runner = CircuitRunner::new(
             pp.secondary.k_table_size(),
             secondary_sfc,
             secondary_instances.clone(),
-        )
-        .try_collect_witness()?;
+        );
 
         let (secondary_nifs_pp, _nifs_vp) =
             VanillaFS::setup_params(pp.digest_2(), pp.secondary.S().clone())?;
@@ -376,7 +374,7 @@ where
         let secondary_plonk_trace = VanillaFS::generate_plonk_trace(
             pp.secondary.ck(),
             &secondary_instances,
-            &secondary_witness,
+            &secondary_runner,
             &secondary_nifs_pp,
             &mut RP1::OffCircuit::new(pp.primary.params().ro_constant().clone()),
         )?;
@@ -484,12 +482,11 @@ where
             .zip(pp.primary.S().num_io.iter())
             .all(|(instance, expected_len)| { instance.len() == *expected_len }));
 
-        let primary_witness = CircuitRunner::new(
+        let primary_runner = CircuitRunner::new(
             pp.primary.k_table_size(),
             primary_sfc,
             primary_instances.clone(),
-        )
-        .try_collect_witness()?;
+        );
 
         self.primary.z_i = primary_z_next;
         self.secondary.relaxed_trace = secondary_new_trace;
@@ -497,7 +494,7 @@ where
         let primary_plonk_trace = [VanillaFS::generate_plonk_trace(
             pp.primary.ck(),
             &primary_instances,
-            &primary_witness,
+            &primary_runner,
             &self.primary_nifs_pp,
             &mut RP2::OffCircuit::new(pp.secondary.params().ro_constant().clone()),
         )?];
@@ -571,12 +568,11 @@ where
             .zip(pp.secondary.S().num_io.iter())
             .all(|(instance, expected_len)| { instance.len() == *expected_len }));
 
-        let secondary_witness = CircuitRunner::new(
+        let secondary_runner = CircuitRunner::new(
             pp.secondary.k_table_size(),
             secondary_sfc,
             secondary_instances.clone(),
-        )
-        .try_collect_witness()?;
+        );
 
         self.secondary.z_i = next_secondary_z_i;
         self.primary.relaxed_trace = primary_new_trace;
@@ -584,7 +580,7 @@ where
         self.secondary_trace = [VanillaFS::generate_plonk_trace(
             pp.secondary.ck(),
             &secondary_instances,
-            &secondary_witness,
+            &secondary_runner,
             &self.secondary_nifs_pp,
             &mut RP1::OffCircuit::new(pp.primary.params().ro_constant().clone()),
         )?];
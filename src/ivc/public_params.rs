@@ -51,6 +51,7 @@ where
     C::Scalar: PrimeFieldBits + FromUniformBytes<64> + Serialize,
     RP: ROPair<C::Scalar>,
 {
+    #[serde(serialize_with = "PlonkStructure::serialize_for_digest")]
     S: PlonkStructure<C::Scalar>,
     #[serde(skip_serializing)]
     ck: &'key CommitmentKey<C>,
@@ -338,7 +339,7 @@ where
             let secondary_initial_plonk_trace = VanillaFS::generate_plonk_trace(
                 secondary.commitment_key,
                 &secondary_instances,
-                &secondary_cr.try_collect_witness()?,
+                &secondary_cr,
                 &VanillaFS::setup_params(C2::identity(), secondary_S.clone())?.0,
                 &mut RP1::OffCircuit::new(primary.ro_constant.clone()),
             )?;
@@ -412,7 +413,9 @@ where
     RP1: ROPair<C1::Scalar>,
     RP2: ROPair<C2::Scalar>,
 {
+    #[serde(serialize_with = "PlonkStructure::serialize_for_digest")]
     primary_plonk_struct: PlonkStructure<C1::Scalar>,
+    #[serde(serialize_with = "PlonkStructure::serialize_for_digest")]
     secondary_plonk_struct: PlonkStructure<C2::Scalar>,
     primary_params: &'l StepParams<C1::Scalar, RP1::OnCircuit>,
     secondary_params: &'l StepParams<C2::Scalar, RP2::OnCircuit>,
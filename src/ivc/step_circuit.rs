@@ -9,7 +9,7 @@ use crate::{
     ff::PrimeField,
     halo2_proofs::circuit::{floor_planner::single_pass::SingleChipLayouter, Value},
     main_gate::RegionCtx,
-    table::WitnessCollector,
+    table::SparseWitnessCollector,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -94,10 +94,14 @@ pub trait StepCircuit<const ARITY: usize, F: PrimeField> {
     /// before performing on-circuit calculations. This method will be called to define `z_out` and
     /// use it within the IVC algo.
     ///
-    /// The default implementation includes calling step synthesis on `TableData` where table size is
-    /// equal to that specified in the IVC fold call. However, if these calculations are long and resource
+    /// The default implementation includes calling step synthesis on a sparse witness collector,
+    /// which doesn't store any cells (`z_out` is read from the returned cells), so memory usage
+    /// doesn't depend on table size (it is used only for bounds checking). However, if these calculations are long and resource
     /// intensive, it is possible to implement this logic off-circuit "honestly" with regular code, which may
     /// be more lightweight, but will require consistency testing.
+    ///
+    /// Challenges are unknown at this point, so with the default implementation `z_out` must not
+    /// depend on advice columns of later phases (see [`crate::plonk::phases`]).
     #[instrument(skip_all)]
     fn process_step(
         &self,
@@ -108,10 +112,7 @@ pub trait StepCircuit<const ARITY: usize, F: PrimeField> {
         let col = cs.advice_column();
         let config = Self::configure(&mut cs);
 
-        let mut witness = WitnessCollector {
-            instances: vec![vec![F::ZERO, F::ZERO]],
-            advice: vec![vec![F::ZERO.into(); 1 << k_table_size as usize]; cs.num_advice_columns()],
-        };
+        let mut witness = SparseWitnessCollector::new(k_table_size, vec![vec![F::ZERO, F::ZERO]]);
         let mut layouter =
             SingleChipLayouter::<'_, F, _>::new(&mut witness, vec![]).map_err(|err| {
                 error!("while creation of layouter in `process_step`: {err:?}");
@@ -210,5 +211,19 @@ pub mod trivial {
                 .verify(z_in)
                 .unwrap();
         }
+
+        #[test]
+        fn process_step_with_big_table() {
+            use super::StepCircuit;
+
+            let z_in = array::from_fn(|i| Fq::from(i as u64));
+            // witness is stored sparsely, so table size doesn't affect memory usage
+            assert_eq!(
+                super::Circuit::<10, Fq>::default()
+                    .process_step(&z_in, 26)
+                    .unwrap(),
+                z_in
+            );
+        }
     }
 }
@@ -7,6 +7,7 @@ pub mod commitment;
 pub mod constants;
 pub mod digest;
 pub mod fft;
+pub mod frontend;
 pub mod gadgets;
 pub mod ivc;
 pub mod main_gate;
@@ -0,0 +1,96 @@
+use std::iter;
+
+use crate::{
+    ff::Field,
+    halo2curves::CurveAffine,
+    plonk::{self, PlonkInstance, PlonkTrace, PlonkWitness},
+    poseidon::{AbsorbInRO, ROTrait},
+    util,
+};
+
+/// Linearized committed CCS (LCCCS): the relaxed CCS trace with the claims about evaluations of
+/// the multilinear extensions of `M_j * z` at the point `r`
+#[derive(Clone, Debug)]
+pub struct Accumulator<C: CurveAffine> {
+    /// Folded trace: `z = (W, u, instances, challenges)`, see [`super::ccs`]
+    pub(super) trace: PlonkTrace<C>,
+
+    /// `u`: relaxation factor, equals to one for an ordinary (not folded) trace
+    pub(super) u: C::ScalarExt,
+
+    /// `r`: point of the boolean hypercube extension, at which `v` is claimed
+    pub(super) r: Box<[C::ScalarExt]>,
+
+    /// `v_j`: claimed evaluations of `MLE(M_j * z)(r)`
+    pub(super) v: Box<[C::ScalarExt]>,
+}
+
+impl<C: CurveAffine, RO: ROTrait<C::Base>> AbsorbInRO<C::Base, RO> for Accumulator<C> {
+    fn absorb_into(&self, ro: &mut RO) {
+        ro.absorb(&self.trace.u).absorb_field_iter(
+            iter::once(&self.u)
+                .chain(self.r.iter())
+                .chain(self.v.iter())
+                .map(|v| util::fe_to_fe::<C::ScalarExt, C::Base>(v).unwrap()),
+        );
+    }
+}
+
+pub type AccumulatorArgs = plonk::PlonkTraceArgs;
+
+impl<C: CurveAffine> Accumulator<C> {
+    /// Zero trace with `u = 0`, which satisfies the relation with `r = 0` & `v = 0`
+    pub fn new(args: AccumulatorArgs, num_vars: usize, num_matrices: usize) -> Self {
+        Self {
+            trace: PlonkTrace::new(args),
+            u: C::ScalarExt::ZERO,
+            r: vec![C::ScalarExt::ZERO; num_vars].into_boxed_slice(),
+            v: vec![C::ScalarExt::ZERO; num_matrices].into_boxed_slice(),
+        }
+    }
+}
+
+/// Instance part of [`Accumulator`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct AccumulatorInstance<C: CurveAffine> {
+    pub(super) ins: PlonkInstance<C>,
+    pub(super) u: C::ScalarExt,
+    pub(super) r: Box<[C::ScalarExt]>,
+    pub(super) v: Box<[C::ScalarExt]>,
+}
+
+impl<C: CurveAffine> AccumulatorInstance<C> {
+    pub fn into_acc(self, w: PlonkWitness<C::Scalar>) -> Accumulator<C> {
+        let Self { ins, u, r, v } = self;
+        Accumulator {
+            trace: PlonkTrace { u: ins, w },
+            u,
+            r,
+            v,
+        }
+    }
+}
+
+impl<C: CurveAffine> From<Accumulator<C>> for AccumulatorInstance<C> {
+    fn from(value: Accumulator<C>) -> Self {
+        let Accumulator {
+            trace: PlonkTrace { u: ins, w: _ },
+            u,
+            r,
+            v,
+        } = value;
+
+        AccumulatorInstance { ins, u, r, v }
+    }
+}
+
+impl<C: CurveAffine, RO: ROTrait<C::Base>> AbsorbInRO<C::Base, RO> for AccumulatorInstance<C> {
+    fn absorb_into(&self, ro: &mut RO) {
+        ro.absorb(&self.ins).absorb_field_iter(
+            iter::once(&self.u)
+                .chain(self.r.iter())
+                .chain(self.v.iter())
+                .map(|v| util::fe_to_fe::<C::ScalarExt, C::Base>(v).unwrap()),
+        );
+    }
+}
@@ -0,0 +1,535 @@
+//! # Customizable Constraint System
+//!
+//! CCS ([paper](https://eprint.iacr.org/2023/552), section 2) over `z = (w, u, x)` is satisfied if
+//!
+//! `sum_i c_i * hadamard_{j in S_i} (M_j * z) = 0`
+//!
+//! where `M_j` are sparse matrices with one row per constraint, `S_i` are multisets of matrix
+//! indices & `c_i` are constants. For an ordinary (not accumulated) instance `u = 1`.
+//!
+//! ## Conversion from [`PlonkStructure`]
+//!
+//! `z` is laid out as `(W_0 || W_1 || ... || u || instances || challenges)`, where `W_i` are the
+//! prover rounds of [`crate::plonk::PlonkWitness`], i.e. the advice & lookup columns.
+//!
+//! Each gate is expanded into monomials: `coeff * fixed(row) * w_1(row) * ... * w_d(row)`, where
+//! `fixed` is the product of selectors & fixed columns (known to the structure) and `w_i` are
+//! queries of advice & lookup columns or challenges. Every distinct `w_i` gets a matrix
+//! selecting it on each row of each gate, while the first factor of each monomial is selected
+//! by its own matrix scaled by `coeff * fixed(row)` & limited to the rows of its gate. A
+//! monomial without witness factors selects `u` instead.
+//!
+//! The copy constraints `P * Z - Z = 0` (see [`PlonkStructure::permutation_matrix`])
+//! and the log-derivative sums `sum_i (h_i - g_i) = 0` (see [`crate::plonk::lookup`]) are linear,
+//! so they share one matrix placed after the rows of the gates.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::{
+    ff::PrimeField,
+    plonk::{
+        eval::{witness_location, Error as EvalError},
+        PlonkStructure,
+    },
+    polynomial::{multilinear::MultilinearPoly, sparse::SparseMatrix, Expression, Query},
+};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("CCS relation not satisfied: mismatch_count {mismatch_count}")]
+    EvaluationMismatch { mismatch_count: usize },
+    #[error(transparent)]
+    Eval(#[from] EvalError),
+}
+
+/// Factor of a monomial of a gate, which depends on the trace
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Factor {
+    Query { index: usize, rotation: i32 },
+    Challenge(usize),
+}
+
+/// Positions of the parts of `z`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Layout {
+    /// Offset of each prover round
+    round_offsets: Box<[usize]>,
+    num_witness: usize,
+    num_instances: usize,
+}
+
+impl Layout {
+    fn u(&self) -> usize {
+        self.num_witness
+    }
+
+    fn instance(&self, index: usize) -> usize {
+        self.num_witness + 1 + index
+    }
+
+    fn challenge(&self, index: usize) -> usize {
+        self.num_witness + 1 + self.num_instances + index
+    }
+}
+
+/// Part of [`CCS`] required by the verifier: everything except the matrices
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CCSShape<F: PrimeField> {
+    /// `log2` of the number of constraints, padded to the power of two
+    num_vars: usize,
+    num_matrices: usize,
+    /// `(c_i, S_i)`
+    terms: Vec<(F, Vec<usize>)>,
+}
+
+impl<F: PrimeField> CCSShape<F> {
+    /// Number of variables of `M_j * z` as multilinear polynomial
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn num_matrices(&self) -> usize {
+        self.num_matrices
+    }
+
+    /// `(c_i, S_i)`
+    pub fn terms(&self) -> &[(F, Vec<usize>)] {
+        &self.terms
+    }
+
+    /// Max size of multisets `S_i`
+    pub fn degree(&self) -> usize {
+        self.terms
+            .iter()
+            .map(|(_, multiset)| multiset.len())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// `sum_i c_i * prod_{j in S_i} values[j]`, where `values[j]` is the value of `M_j * z` in
+    /// some row (or the evaluation of its multilinear extension at some point)
+    pub fn combine(&self, values: &[F]) -> F {
+        self.terms
+            .iter()
+            .map(|(c, multiset)| {
+                multiset
+                    .iter()
+                    .fold(*c, |acc, matrix_index| acc * values[*matrix_index])
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CCS<F: PrimeField> {
+    shape: CCSShape<F>,
+    layout: Layout,
+    matrices: Vec<SparseMatrix<F>>,
+}
+
+impl<F: PrimeField> CCS<F> {
+    /// Converts the gates, copy constraints & lookup arguments of `S`, see
+    /// [module-level documentation](self)
+    pub fn new(S: &PlonkStructure<F>) -> Result<Self, EvalError> {
+        let nrow = 1 << S.k;
+        let num_selectors = S.selectors.len();
+        let num_structure_columns = num_selectors + S.fixed_columns.len();
+        let num_rounds = S.round_sizes.len();
+
+        let layout = Layout {
+            round_offsets: S
+                .round_sizes
+                .iter()
+                .scan(0, |offset, size| {
+                    let round_offset = *offset;
+                    *offset += size;
+                    Some(round_offset)
+                })
+                .collect(),
+            num_witness: S.round_sizes.iter().sum(),
+            num_instances: S.num_io.iter().sum(),
+        };
+
+        // position of the witness column `index` at `row` in `z`
+        let column_position = |index: usize, row: usize| -> Result<usize, EvalError> {
+            let (round, column) =
+                witness_location(&S.advice_phases, S.num_lookups(), num_rounds, index)?;
+            Ok(layout.round_offsets[round] + column * nrow + row)
+        };
+        let rotate = |row: usize, rotation: i32| (row as i32 + rotation).rem_euclid(nrow as i32);
+        let factor_position = |factor: &Factor, row: usize| -> Result<usize, EvalError> {
+            match factor {
+                Factor::Query { index, rotation } => column_position(
+                    index - num_structure_columns,
+                    rotate(row, *rotation) as usize,
+                ),
+                Factor::Challenge(index) => Ok(layout.challenge(*index)),
+            }
+        };
+        let structure_value = |index: usize, rotation: i32, row: usize| -> F {
+            let row = rotate(row, rotation) as usize;
+            match S.selectors.get(index) {
+                Some(selector) if selector[row] => F::ONE,
+                Some(_) => F::ZERO,
+                None => S.fixed_columns[index - num_selectors][row],
+            }
+        };
+
+        let num_gate_rows = S.gates.len() * nrow;
+        let mut matrices: Vec<SparseMatrix<F>> = vec![];
+        let mut terms = vec![];
+        let mut selection = HashMap::<Factor, usize>::new();
+
+        for (gate_index, gate) in S.gates.iter().enumerate() {
+            let gate_rows = gate_index * nrow..(gate_index + 1) * nrow;
+
+            for (coeff, factors) in monomials(gate) {
+                let (structure, witness): (Vec<_>, Vec<_>) = factors.into_iter().partition(
+                    |factor| matches!(factor, Factor::Query { index, .. } if *index < num_structure_columns),
+                );
+
+                let scaled = gate_rows
+                    .clone()
+                    .zip(0..nrow)
+                    .map(|(ccs_row, row)| {
+                        let value = structure.iter().fold(coeff, |acc, factor| match factor {
+                            Factor::Query { index, rotation } => {
+                                acc * structure_value(*index, *rotation, row)
+                            }
+                            Factor::Challenge(_) => unreachable!("challenges are trace factors"),
+                        });
+                        let column = match witness.first() {
+                            Some(factor) => factor_position(factor, row)?,
+                            None => layout.u(),
+                        };
+                        Ok((ccs_row, column, value))
+                    })
+                    .filter(
+                        |entry| !matches!(entry, Ok((_, _, value)) if bool::from(value.is_zero())),
+                    )
+                    .collect::<Result<SparseMatrix<F>, EvalError>>()?;
+
+                if scaled.is_empty() {
+                    continue;
+                }
+
+                let mut multiset = vec![matrices.len()];
+                matrices.push(scaled);
+
+                for factor in witness.iter().skip(1) {
+                    let matrix_index = match selection.get(factor) {
+                        Some(matrix_index) => *matrix_index,
+                        None => {
+                            let matrix = (0..num_gate_rows)
+                                .map(|ccs_row| {
+                                    Ok((ccs_row, factor_position(factor, ccs_row % nrow)?, F::ONE))
+                                })
+                                .collect::<Result<SparseMatrix<F>, EvalError>>()?;
+                            matrices.push(matrix);
+                            selection.insert(*factor, matrices.len() - 1);
+                            matrices.len() - 1
+                        }
+                    };
+                    multiset.push(matrix_index);
+                }
+
+                terms.push((F::ONE, multiset));
+            }
+        }
+
+        // `Z = (instances, advice columns)` of the permutation matrix
+        let Z_position = |index: usize| -> Result<usize, EvalError> {
+            match index.checked_sub(layout.num_instances) {
+                None => Ok(layout.instance(index)),
+                Some(advice_index) => column_position(advice_index / nrow, advice_index % nrow),
+            }
+        };
+
+        let mut linear = SparseMatrix::new();
+        let permutation = S.permutation_matrix();
+        for (row, column, value) in permutation.iter() {
+            if row != column {
+                linear.push((num_gate_rows + row, Z_position(*column)?, *value));
+                linear.push((num_gate_rows + row, Z_position(*row)?, -F::ONE));
+            }
+        }
+
+        let lookup_offset = num_gate_rows + layout.num_instances + S.num_advice_columns * nrow;
+        let h_index = |lookup_index: usize| S.num_advice_columns + 5 * lookup_index + 3;
+        for lookup_index in 0..S.num_lookups() {
+            for row in 0..nrow {
+                let ccs_row = lookup_offset + lookup_index;
+                linear.push((
+                    ccs_row,
+                    column_position(h_index(lookup_index), row)?,
+                    F::ONE,
+                ));
+                linear.push((
+                    ccs_row,
+                    column_position(h_index(lookup_index) + 1, row)?,
+                    -F::ONE,
+                ));
+            }
+        }
+
+        if !linear.is_empty() {
+            terms.push((F::ONE, vec![matrices.len()]));
+            matrices.push(linear);
+        }
+
+        let num_rows = lookup_offset + S.num_lookups();
+
+        Ok(Self {
+            shape: CCSShape {
+                num_vars: num_rows.next_power_of_two().trailing_zeros().max(1) as usize,
+                num_matrices: matrices.len(),
+                terms,
+            },
+            layout,
+            matrices,
+        })
+    }
+
+    pub fn shape(&self) -> &CCSShape<F> {
+        &self.shape
+    }
+
+    /// Assembles `z = (W, u, instances, challenges)`
+    pub fn z(&self, u: F, W: &[Vec<F>], instances: &[Vec<F>], challenges: &[F]) -> Vec<F> {
+        W.iter()
+            .flatten()
+            .copied()
+            .chain([u])
+            .chain(instances.iter().flatten().copied())
+            .chain(challenges.iter().copied())
+            .collect()
+    }
+
+    /// `M_j * z` for each matrix, as multilinear polynomials in [`CCSShape::num_vars`] variables
+    pub fn mul_matrices(&self, z: &[F]) -> Vec<MultilinearPoly<F>> {
+        self.matrices
+            .par_iter()
+            .map(|matrix| {
+                let mut result = vec![F::ZERO; 1 << self.shape.num_vars];
+                for (row, column, value) in matrix {
+                    result[*row] += *value * z[*column];
+                }
+                MultilinearPoly::new(self.shape.num_vars, result)
+            })
+            .collect()
+    }
+
+    pub fn is_sat(&self, z: &[F]) -> Result<(), Error> {
+        let products = self.mul_matrices(z);
+
+        let mismatch_count = (0..1 << self.shape.num_vars)
+            .into_par_iter()
+            .filter(|row| {
+                let values = products
+                    .iter()
+                    .map(|product| product.evaluations()[*row])
+                    .collect::<Box<[_]>>();
+                !bool::from(self.shape.combine(&values).is_zero())
+            })
+            .count();
+
+        if mismatch_count == 0 {
+            Ok(())
+        } else {
+            Err(Error::EvaluationMismatch { mismatch_count })
+        }
+    }
+}
+
+/// Expands `expr` into the sum of monomials `(coefficient, factors)`, equal monomials are merged
+fn monomials<F: PrimeField>(expr: &Expression<F>) -> Vec<(F, Vec<Factor>)> {
+    let expanded = expr.evaluate(
+        &|constant| vec![(constant, vec![])],
+        &|Query { index, rotation }| {
+            vec![(
+                F::ONE,
+                vec![Factor::Query {
+                    index,
+                    rotation: rotation.0,
+                }],
+            )]
+        },
+        &|index| vec![(F::ONE, vec![Factor::Challenge(index)])],
+        &|monomials| {
+            monomials
+                .into_iter()
+                .map(|(coeff, factors)| (-coeff, factors))
+                .collect()
+        },
+        &|mut lhs, rhs| {
+            lhs.extend(rhs);
+            lhs
+        },
+        &|lhs, rhs| {
+            lhs.iter()
+                .flat_map(|(lhs_coeff, lhs_factors)| {
+                    rhs.iter().map(move |(rhs_coeff, rhs_factors)| {
+                        let mut factors = lhs_factors.clone();
+                        factors.extend(rhs_factors.iter().copied());
+                        (*lhs_coeff * rhs_coeff, factors)
+                    })
+                })
+                .collect()
+        },
+        &|monomials, scalar| {
+            monomials
+                .into_iter()
+                .map(|(coeff, factors)| (coeff * scalar, factors))
+                .collect()
+        },
+    );
+
+    let mut merged: Vec<(F, Vec<Factor>)> = vec![];
+    let mut positions = HashMap::<Vec<Factor>, usize>::new();
+    for (coeff, mut factors) in expanded {
+        factors.sort();
+        match positions.get(&factors) {
+            Some(position) => merged[*position].0 += coeff,
+            None => {
+                positions.insert(factors.clone(), merged.len());
+                merged.push((coeff, factors));
+            }
+        }
+    }
+    merged.retain(|(coeff, _)| !bool::from(coeff.is_zero()));
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{halo2curves::CurveAffine, plonk::Circuit, poly::Rotation};
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{
+        commitment,
+        ff::Field,
+        halo2curves::bn256::G1Affine as Affine,
+        nifs::tests::{
+            fibo_circuit_with_lookup::{get_sequence, FiboCircuitWithLookup},
+            random_linear_combination_circuit::RandomLinearCombinationCircuit,
+        },
+        plonk::PlonkTrace,
+        poseidon::{PoseidonHash, Spec},
+        table::CircuitRunner,
+    };
+
+    const T: usize = 3;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    type Scalar = <Affine as CurveAffine>::ScalarExt;
+    type Base = <Affine as CurveAffine>::Base;
+
+    fn prepare(
+        k: u32,
+        circuit: impl Circuit<Scalar>,
+        instances: Vec<Vec<Scalar>>,
+    ) -> (CCS<Scalar>, PlonkTrace<Affine>) {
+        let runner = CircuitRunner::new(k, circuit, instances);
+        let ck = commitment::setup_smallest_key(k, &runner.cs, b"");
+        let S = runner.try_collect_plonk_structure().unwrap();
+
+        let trace = S
+            .run_sps_protocol(
+                &ck,
+                &runner.instances,
+                &runner,
+                &mut PoseidonHash::<Base, T, RATE>::new(Spec::new(R_F, R_P)),
+                S.num_challenges,
+            )
+            .unwrap();
+
+        (CCS::new(&S).unwrap(), trace)
+    }
+
+    #[test]
+    fn expand_monomials() {
+        let [a, b] = [0, 1].map(|index| {
+            Expression::<Scalar>::Polynomial(Query {
+                index,
+                rotation: Rotation::cur(),
+            })
+        });
+        let query = |index| Factor::Query { index, rotation: 0 };
+
+        // (a + b) * (a - b) = a^2 - b^2
+        assert_eq!(
+            monomials(&((a.clone() + b.clone()) * (a - b))),
+            vec![
+                (Scalar::ONE, vec![query(0), query(0)]),
+                (-Scalar::ONE, vec![query(1), query(1)])
+            ]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn random_linear_combination() {
+        let (ccs, trace) = prepare(
+            10,
+            RandomLinearCombinationCircuit::new(
+                (1..10).map(Scalar::from).collect(),
+                Scalar::from(2),
+            ),
+            vec![vec![Scalar::from(4097)]],
+        );
+
+        let z = ccs.z(
+            Scalar::ONE,
+            &trace.w.W,
+            &trace.u.instances,
+            &trace.u.challenges,
+        );
+        assert_eq!(ccs.is_sat(&z), Ok(()));
+
+        // breaks the copy constraint with the instance column
+        let mut instances = trace.u.instances.clone();
+        instances[0][0] += Scalar::ONE;
+        let z = ccs.z(Scalar::ONE, &trace.w.W, &instances, &trace.u.challenges);
+        assert!(ccs.is_sat(&z).is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn fibo_lookup() {
+        const SIZE: usize = 7;
+        let seq = get_sequence(1, 3, 2, SIZE);
+
+        let (ccs, trace) = prepare(
+            10,
+            FiboCircuitWithLookup {
+                a: Scalar::from(seq[0]),
+                b: Scalar::from(seq[1]),
+                c: Scalar::from(seq[2]),
+                num: SIZE,
+            },
+            vec![vec![Scalar::ONE]],
+        );
+
+        let z = ccs.z(
+            Scalar::ONE,
+            &trace.w.W,
+            &trace.u.instances,
+            &trace.u.challenges,
+        );
+        assert_eq!(ccs.is_sat(&z), Ok(()));
+
+        // breaks the gates & the log-derivative sum
+        let mut W = trace.w.W.clone();
+        W.iter_mut()
+            .flatten()
+            .for_each(|value| *value += Scalar::ONE);
+        let z = ccs.z(Scalar::ONE, &W, &trace.u.instances, &trace.u.challenges);
+        assert!(ccs.is_sat(&z).is_err());
+    }
+}
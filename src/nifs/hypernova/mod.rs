@@ -0,0 +1,624 @@
+use std::{iter, marker::PhantomData, sync::Arc};
+
+use itertools::Itertools;
+use tracing::{instrument, warn};
+
+use self::{accumulator::AccumulatorInstance, ccs::CCSShape};
+use super::*;
+use crate::{
+    commitment::CommitmentKey,
+    constants::NUM_CHALLENGE_BITS,
+    ff::{Field, PrimeField},
+    halo2_proofs::arithmetic::{self, CurveAffine},
+    plonk::{self, phases::AdviceSource, PlonkInstance, PlonkStructure, PlonkTrace, PlonkWitness},
+    polynomial::{
+        multilinear::{eq_eval, MultilinearPoly},
+        sparse,
+        sumcheck::{self, SumcheckProof, VirtualPoly},
+    },
+    poseidon::AbsorbInRO,
+    sps::{self, SpecialSoundnessVerifier},
+    util,
+};
+
+mod accumulator;
+pub mod ccs;
+
+pub use accumulator::{Accumulator, AccumulatorArgs};
+pub use ccs::CCS;
+
+/// HyperNova: Non-Interactive Folding Scheme that implements the multi-folding of CCS instances
+/// into the linearized CCS (LCCCS), defined in [hypernova.pdf](https://eprint.iacr.org/2023/573),
+/// section 5.
+///
+/// [`PlonkStructure`] is converted to [`CCS`] (see [`ccs`]), the trace keeps the layout of
+/// [`PlonkTrace`], so the commitments & special soundness protocol are shared with other schemes.
+///
+/// # Generic Parameters
+///
+/// - `C`: 'Curve' - represents the elliptic curve used in the protocol.
+///                  Circuit will be proved in `C::Scalar` field
+///
+/// - `L`: 'Length' - constant representing the number of instances to
+///                   fold in a single `prove`
+#[derive(Clone, Debug)]
+pub struct HyperNova<C: CurveAffine, const L: usize> {
+    _marker: PhantomData<C>,
+}
+
+impl<C: CurveAffine, const L: usize> HyperNova<C, L> {
+    /// Generates `gamma` to combine the claims & `beta` to select the row of the CCS relation
+    #[instrument(skip_all)]
+    fn generate_challenges<'i, RO: ROTrait<C::Base>>(
+        pp_digest: &C,
+        ro_acc: &mut RO,
+        accumulator: &impl AbsorbInRO<C::Base, RO>,
+        instances: impl Iterator<Item = &'i PlonkInstance<C>>,
+        num_vars: usize,
+    ) -> (C::ScalarExt, Box<[C::ScalarExt]>) {
+        let gamma = ro_acc
+            .absorb_point(pp_digest)
+            .absorb(accumulator)
+            .absorb_iter(instances)
+            .squeeze::<C>(NUM_CHALLENGE_BITS);
+
+        let beta = iter::repeat_with(|| ro_acc.squeeze::<C>(NUM_CHALLENGE_BITS))
+            .take(num_vars)
+            .collect();
+
+        (gamma, beta)
+    }
+
+    /// Generates `rho` to fold the instances, after absorbing evaluations at the sumcheck point
+    fn generate_rho(
+        ro_acc: &mut impl ROTrait<C::Base>,
+        sigmas: &[C::ScalarExt],
+        thetas: &[Box<[C::ScalarExt]>],
+    ) -> C::ScalarExt {
+        ro_acc
+            .absorb_field_iter(
+                sigmas
+                    .iter()
+                    .chain(thetas.iter().flatten())
+                    .map(|v| util::fe_to_fe::<C::ScalarExt, C::Base>(v).unwrap()),
+            )
+            .squeeze::<C>(NUM_CHALLENGE_BITS)
+    }
+
+    /// Degree of the polynomial proved by sumcheck: `eq * prod_{j in S_i} MLE(M_j * z)`
+    fn sumcheck_degree(shape: &CCSShape<C::ScalarExt>) -> usize {
+        (shape.degree() + 1).max(2)
+    }
+
+    /// Creates an initial accumulator with a zeroed trace
+    ///
+    /// Since `u = 0` as well, all `M_j * z` are zero & the claims `v = 0` hold at any point
+    pub(crate) fn new_accumulator(
+        args: AccumulatorArgs,
+        params: &ProverParam<C>,
+    ) -> Accumulator<C> {
+        let shape = params.ccs.shape();
+        Accumulator::new(args, shape.num_vars(), shape.num_matrices())
+    }
+
+    /// `z = (W, u, instances, challenges)` of the trace, see [`ccs`]
+    fn z(ccs: &CCS<C::ScalarExt>, u: C::ScalarExt, trace: &PlonkTrace<C>) -> Vec<C::ScalarExt> {
+        ccs.z(u, &trace.w.W, &trace.u.instances, &trace.u.challenges)
+    }
+
+    fn fold_witness<'i>(
+        acc: PlonkWitness<C::Scalar>,
+        incoming: impl Iterator<Item = &'i PlonkWitness<C::Scalar>>,
+        rho_powers: impl Iterator<Item = C::Scalar>,
+    ) -> PlonkWitness<C::Scalar> {
+        incoming.zip(rho_powers).fold(acc, |mut acc, (w, rho_pow)| {
+            acc.W
+                .iter_mut()
+                .flatten()
+                .zip_eq(w.W.iter().flatten())
+                .for_each(|(acc_cell, cell)| {
+                    *acc_cell += *cell * rho_pow;
+                });
+
+            acc
+        })
+    }
+
+    fn fold_instance<'i>(
+        acc: PlonkInstance<C>,
+        incoming: impl Iterator<Item = &'i PlonkInstance<C>>,
+        rho_powers: impl Iterator<Item = C::Scalar>,
+    ) -> PlonkInstance<C> {
+        let ecc_mul =
+            |pt: C, val: C::ScalarExt| -> C { arithmetic::best_multiexp(&[val], &[pt]).into() };
+
+        incoming.zip(rho_powers).fold(acc, |mut acc, (u, rho_pow)| {
+            let PlonkInstance {
+                W_commitments,
+                instances,
+                challenges,
+            } = u;
+
+            acc.W_commitments
+                .iter_mut()
+                .zip_eq(W_commitments.iter())
+                .for_each(|(acc_Wc, Wc)| {
+                    *acc_Wc = (*acc_Wc + ecc_mul(*Wc, rho_pow)).into();
+                });
+
+            acc.instances
+                .iter_mut()
+                .flatten()
+                .zip_eq(instances.iter().flatten())
+                .for_each(|(acc_instance, instance)| {
+                    *acc_instance += *instance * rho_pow;
+                });
+
+            acc.challenges
+                .iter_mut()
+                .zip_eq(challenges.iter())
+                .for_each(|(acc_cha, cha)| {
+                    *acc_cha += *cha * rho_pow;
+                });
+
+            acc
+        })
+    }
+
+    /// Folds `u`, `v` & sets `r` to the sumcheck point, incoming instances have `u = 1`
+    fn fold_claims(
+        u: C::ScalarExt,
+        point: Vec<C::ScalarExt>,
+        sigmas: &[C::ScalarExt],
+        thetas: &[Box<[C::ScalarExt]>],
+        rho: C::ScalarExt,
+    ) -> (C::ScalarExt, Box<[C::ScalarExt]>, Box<[C::ScalarExt]>) {
+        let mut u = u;
+        let mut v = sigmas.to_vec().into_boxed_slice();
+
+        thetas
+            .iter()
+            .zip(iter_rho_powers(rho))
+            .for_each(|(theta, rho_pow)| {
+                u += rho_pow;
+                v.iter_mut()
+                    .zip_eq(theta.iter())
+                    .for_each(|(v, theta)| *v += *theta * rho_pow);
+            });
+
+        (u, point.into_boxed_slice(), v)
+    }
+
+    pub fn verify_sps<'l>(
+        incoming: impl Iterator<Item = &'l PlonkInstance<C>>,
+        ro_nark: &mut impl ROTrait<C::Base>,
+    ) -> Result<(), Error> {
+        let errors = incoming
+            .enumerate()
+            .filter_map(|(i, plonk_instance)| Some((i, plonk_instance.sps_verify(ro_nark).err()?)))
+            .collect::<Box<[_]>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::VerifySps(errors))
+        }
+    }
+}
+
+/// `rho, rho^2, rho^3, ...`: folding coefficients of the incoming instances
+fn iter_rho_powers<F: PrimeField>(rho: F) -> impl Iterator<Item = F> {
+    iter::successors(Some(rho), move |pow| Some(*pow * rho))
+}
+
+pub struct ProverParam<C: CurveAffine> {
+    pub(crate) S: PlonkStructure<C::ScalarExt>,
+    ccs: CCS<C::ScalarExt>,
+    /// Digest of public parameter of IVC circuit
+    pp_digest: C,
+}
+
+pub struct VerifierParam<C: CurveAffine> {
+    /// Digest of public parameter of IVC circuit
+    pp_digest: C,
+    shape: CCSShape<C::ScalarExt>,
+}
+
+pub struct HyperNovaProof<F: PrimeField> {
+    pub sumcheck: SumcheckProof<F>,
+    /// `sigma_j = MLE(M_j * z_acc)(r')`
+    pub sigmas: Box<[F]>,
+    /// `theta_{k, j} = MLE(M_j * z_k)(r')` for each incoming trace
+    pub thetas: Box<[Box<[F]>]>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Sps(#[from] sps::Error),
+    #[error(transparent)]
+    Eval(#[from] plonk::eval::Error),
+    #[error(transparent)]
+    Sumcheck(#[from] sumcheck::Error),
+    #[error("Error while verify plonk instance with sps: {0:?}")]
+    VerifySps(Box<[(usize, sps::Error)]>),
+    #[error("Proof contains wrong number of evaluations of `M_j * z`")]
+    WrongNumberOfEvaluations,
+    #[error("Evaluations of `M_j * z` don't match the sumcheck claim")]
+    SumcheckClaimMismatch,
+}
+
+impl<C: CurveAffine, const L: usize> FoldingScheme<C, L> for HyperNova<C, L> {
+    type Error = Error;
+    type ProverParam = ProverParam<C>;
+    type VerifierParam = VerifierParam<C>;
+    type Trace = PlonkTrace<C>;
+    type Instance = PlonkInstance<C>;
+    type Accumulator = Accumulator<C>;
+    type AccumulatorInstance = AccumulatorInstance<C>;
+    type Proof = HyperNovaProof<C::ScalarExt>;
+
+    fn setup_params(
+        pp_digest: C,
+        S: PlonkStructure<C::ScalarExt>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        let ccs = CCS::new(&S)?;
+        let shape = ccs.shape().clone();
+
+        Ok((
+            ProverParam { S, ccs, pp_digest },
+            VerifierParam { pp_digest, shape },
+        ))
+    }
+
+    fn generate_plonk_trace(
+        ck: &CommitmentKey<C>,
+        instances: &[Vec<C::ScalarExt>],
+        witness: &(impl AdviceSource<C::ScalarExt> + ?Sized),
+        pp: &Self::ProverParam,
+        ro_nark: &mut impl ROTrait<C::Base>,
+    ) -> Result<PlonkTrace<C>, Error> {
+        Ok(pp
+            .S
+            .run_sps_protocol(ck, instances, witness, ro_nark, pp.S.num_challenges)?)
+    }
+
+    /// Proves a statement using the HyperNova multi-folding.
+    ///
+    /// # Algorithm
+    ///
+    /// 1. **Generate Gamma & Beta:**
+    ///     - **RO Seeds**: includes all input parameters except `ck` & witness from `incoming`
+    ///     - `gamma = ro_acc.squeeze()`, `beta = [ro_acc.squeeze(); s]`
+    ///
+    /// 2. **Sumcheck:** proves that the sum over the boolean hypercube of
+    ///     - `g(x) = sum_j gamma^j * eq(r, x) * MLE(M_j * z_acc)(x) +
+    ///               sum_k gamma^{t + k} * eq(beta, x) * sum_i c_i * prod_{j in S_i} MLE(M_j * z_k)(x)`
+    ///     - equals to `sum_j gamma^j * v_j`, since the CCS relation holds on each row of `z_k`
+    ///
+    /// 3. **Evaluations:** `sigma_j` & `theta_{k, j}` at the sumcheck point `r'`
+    ///
+    /// 4. **Generate Rho:**
+    ///     - **RO Update**: absorb `sigma` & `theta`
+    ///     - `rho = ro_acc.squeeze()`
+    ///
+    /// 5. **Fold the Trace:** with coefficients `1, rho, rho^2, ...`
+    ///     - [`HyperNova::fold_witness`], [`HyperNova::fold_instance`] & [`HyperNova::fold_claims`]
+    fn prove(
+        _ck: &CommitmentKey<C>,
+        pp: &Self::ProverParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        accumulator: Self::Accumulator,
+        incoming: &[PlonkTrace<C>; L],
+    ) -> Result<(Self::Accumulator, Self::Proof), Error> {
+        let shape = pp.ccs.shape();
+        let num_matrices = shape.num_matrices();
+
+        let (gamma, beta) = Self::generate_challenges(
+            &pp.pp_digest,
+            ro_acc,
+            &accumulator,
+            incoming.iter().map(|t| &t.u),
+            shape.num_vars(),
+        );
+        let gamma_powers = iter::successors(Some(C::ScalarExt::ONE), |pow| Some(*pow * gamma))
+            .take(num_matrices + L)
+            .collect::<Box<[_]>>();
+
+        let acc_products = pp
+            .ccs
+            .mul_matrices(&Self::z(&pp.ccs, accumulator.u, &accumulator.trace))
+            .into_iter()
+            .map(Arc::new)
+            .collect::<Box<[_]>>();
+        let incoming_products = incoming
+            .iter()
+            .map(|trace| {
+                pp.ccs
+                    .mul_matrices(&Self::z(&pp.ccs, C::ScalarExt::ONE, trace))
+                    .into_iter()
+                    .map(Arc::new)
+                    .collect::<Box<[_]>>()
+            })
+            .collect::<Box<[_]>>();
+
+        let mut poly = VirtualPoly::new(shape.num_vars());
+        let eq_r = Arc::new(MultilinearPoly::eq(&accumulator.r));
+        for (product, gamma_pow) in acc_products.iter().zip(gamma_powers.iter()) {
+            poly.add_product(*gamma_pow, [eq_r.clone(), product.clone()]);
+        }
+        let eq_beta = Arc::new(MultilinearPoly::eq(&beta));
+        for (products, gamma_pow) in incoming_products
+            .iter()
+            .zip(gamma_powers.iter().skip(num_matrices))
+        {
+            for (c, multiset) in shape.terms() {
+                poly.add_product(
+                    *gamma_pow * c,
+                    iter::once(eq_beta.clone())
+                        .chain(multiset.iter().map(|j| products[*j].clone())),
+                );
+            }
+        }
+
+        let sumcheck::ProverOutput {
+            proof: sumcheck,
+            point,
+            mle_evaluations: _,
+        } = SumcheckProof::prove::<C>(ro_acc, &poly);
+
+        let sigmas = acc_products
+            .iter()
+            .map(|product| product.evaluate(&point))
+            .collect::<Box<[_]>>();
+        let thetas = incoming_products
+            .iter()
+            .map(|products| {
+                products
+                    .iter()
+                    .map(|product| product.evaluate(&point))
+                    .collect::<Box<[_]>>()
+            })
+            .collect::<Box<[_]>>();
+
+        let rho = Self::generate_rho(ro_acc, &sigmas, &thetas);
+
+        let Accumulator {
+            trace: PlonkTrace { u, w },
+            u: relaxation,
+            r: _,
+            v: _,
+        } = accumulator;
+        let (u_folded, r, v) = Self::fold_claims(relaxation, point, &sigmas, &thetas, rho);
+
+        Ok((
+            Accumulator {
+                trace: PlonkTrace {
+                    u: Self::fold_instance(
+                        u,
+                        incoming.iter().map(|tr| &tr.u),
+                        iter_rho_powers(rho),
+                    ),
+                    w: Self::fold_witness(w, incoming.iter().map(|tr| &tr.w), iter_rho_powers(rho)),
+                },
+                u: u_folded,
+                r,
+                v,
+            },
+            HyperNovaProof {
+                sumcheck,
+                sigmas,
+                thetas,
+            },
+        ))
+    }
+
+    /// Verifies a statement using the HyperNova multi-folding.
+    ///
+    /// # Algorithm
+    ///
+    /// 1. **Verify SPS**
+    ///     - Verify SPS correctness in `incoming` plonk instances
+    ///
+    /// 2. **Generate Gamma & Beta:** same as in [`HyperNova::prove`]
+    ///
+    /// 3. **Verify Sumcheck:** with the claimed sum `sum_j gamma^j * v_j`, reducing it to the
+    ///    evaluation of `g(r')`, which is checked with `proof.sigmas` & `proof.thetas`
+    ///
+    /// 4. **Generate Rho:**
+    ///     - **RO Update**: absorb `proof.sigmas` & `proof.thetas`
+    ///     - `rho = ro_acc.squeeze()`
+    ///
+    /// 5. **Fold the Instance:**
+    ///     - [`HyperNova::fold_instance`] & [`HyperNova::fold_claims`]
+    fn verify(
+        vp: &Self::VerifierParam,
+        ro_nark: &mut impl ROTrait<C::Base>,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        accumulator: &Self::AccumulatorInstance,
+        incoming: &[PlonkInstance<C>; L],
+        proof: &Self::Proof,
+    ) -> Result<Self::AccumulatorInstance, Error> {
+        let num_matrices = vp.shape.num_matrices();
+
+        if proof.sigmas.len() != num_matrices
+            || proof.thetas.len() != L
+            || proof.thetas.iter().any(|theta| theta.len() != num_matrices)
+        {
+            return Err(Error::WrongNumberOfEvaluations);
+        }
+
+        Self::verify_sps(incoming.iter(), ro_nark)?;
+
+        let (gamma, beta) = Self::generate_challenges(
+            &vp.pp_digest,
+            ro_acc,
+            accumulator,
+            incoming.iter(),
+            vp.shape.num_vars(),
+        );
+        let gamma_powers = iter::successors(Some(C::ScalarExt::ONE), |pow| Some(*pow * gamma))
+            .take(num_matrices + L)
+            .collect::<Box<[_]>>();
+
+        let claimed_sum = accumulator
+            .v
+            .iter()
+            .zip(gamma_powers.iter())
+            .map(|(v, gamma_pow)| *v * gamma_pow)
+            .sum();
+
+        let sumcheck::SubClaim {
+            point,
+            expected_evaluation,
+        } = proof.sumcheck.verify::<C>(
+            ro_acc,
+            vp.shape.num_vars(),
+            Self::sumcheck_degree(&vp.shape),
+            claimed_sum,
+        )?;
+
+        let eq_r = eq_eval(&accumulator.r, &point);
+        let eq_beta = eq_eval(&beta, &point);
+        let evaluation = proof
+            .sigmas
+            .iter()
+            .zip(gamma_powers.iter())
+            .map(|(sigma, gamma_pow)| eq_r * gamma_pow * sigma)
+            .chain(
+                proof
+                    .thetas
+                    .iter()
+                    .zip(gamma_powers.iter().skip(num_matrices))
+                    .map(|(theta, gamma_pow)| eq_beta * gamma_pow * vp.shape.combine(theta)),
+            )
+            .sum::<C::ScalarExt>();
+
+        if evaluation != expected_evaluation {
+            return Err(Error::SumcheckClaimMismatch);
+        }
+
+        let rho = Self::generate_rho(ro_acc, &proof.sigmas, &proof.thetas);
+        let (u, r, v) = Self::fold_claims(accumulator.u, point, &proof.sigmas, &proof.thetas, rho);
+
+        Ok(AccumulatorInstance {
+            ins: Self::fold_instance(
+                accumulator.ins.clone(),
+                incoming.iter(),
+                iter_rho_powers(rho),
+            ),
+            u,
+            r,
+            v,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error("Error while evaluate witness: {0:?}")]
+    PlonkEval(plonk::eval::Error),
+    #[error("Claimed evaluations `v_j` don't match `MLE(M_j * z)(r)`: {0:?}")]
+    MismatchV(Box<[usize]>),
+    #[error("Permutation check failed")]
+    PermCheckFailed { mismatch_count: usize },
+    #[error("Commitment of witness doesn't match the accumulator in rounds: {0:?}")]
+    WitnessCommitmentMismatch(Box<[usize]>),
+    #[error("Commitment of witness failed: {0}")]
+    Commitment(#[from] commitment::Error),
+}
+
+impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for HyperNova<C, L> {
+    type VerifyError = VerifyError;
+
+    /// Checks the claims `v_j = MLE(M_j * z)(r)`, which include the gates, the copy constraints &
+    /// the log-derivative sums, see [`ccs`]
+    fn is_sat_accumulation(
+        S: &PlonkStructure<C::ScalarExt>,
+        acc: &Accumulator<C>,
+    ) -> Result<(), Self::VerifyError> {
+        let ccs = CCS::new(S).map_err(VerifyError::PlonkEval)?;
+
+        let mismatches = ccs
+            .mul_matrices(&Self::z(&ccs, acc.u, &acc.trace))
+            .iter()
+            .zip_eq(acc.v.iter())
+            .enumerate()
+            .filter_map(|(j, (product, v))| product.evaluate(&acc.r).ne(v).then_some(j))
+            .collect::<Box<[_]>>();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(VerifyError::MismatchV(mismatches))
+        }
+    }
+
+    fn is_sat_permutation(
+        S: &PlonkStructure<<C as CurveAffine>::ScalarExt>,
+        acc: &Accumulator<C>,
+    ) -> Result<(), Self::VerifyError> {
+        let PlonkTrace { u, w } = &acc.trace;
+
+        let Z = u
+            .instances
+            .iter()
+            .flat_map(|inst| inst.iter())
+            .chain(S.advice_phases.iter_advice(&w.W, 1 << S.k))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mismatch_count = sparse::matrix_multiply(&S.permutation_matrix(), &Z)
+            .into_iter()
+            .zip_eq(Z)
+            .enumerate()
+            .filter_map(|(row, (y, z))| C::ScalarExt::ZERO.ne(&(y - z)).then_some(row))
+            .inspect(|row| {
+                warn!("permutation mismatch at {row}");
+            })
+            .count();
+
+        if mismatch_count == 0 {
+            Ok(())
+        } else {
+            Err(Self::VerifyError::PermCheckFailed { mismatch_count })
+        }
+    }
+
+    fn is_sat_witness_commit(
+        ck: &CommitmentKey<C>,
+        acc: &<Self as FoldingScheme<C, L>>::Accumulator,
+    ) -> Result<(), Self::VerifyError> {
+        let Accumulator {
+            trace: PlonkTrace { u, w },
+            ..
+        } = acc;
+
+        let errors = u
+            .W_commitments
+            .iter()
+            .zip_eq(&w.W)
+            .enumerate()
+            .map(|(i, (Ci, Wi))| Ok(ck.commit(Wi)?.ne(Ci).then_some(i)))
+            .filter_map(Result::transpose)
+            .collect::<Result<Box<[_]>, commitment::Error>>()?;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(VerifyError::WitnessCommitmentMismatch(errors))
+        }
+    }
+
+    fn is_sat_pub_instances(
+        _acc: &<Self as FoldingScheme<C, L>>::Accumulator,
+        _pub_instances: &[Vec<Vec<<C as CurveAffine>::ScalarExt>>],
+    ) -> Result<(), Self::VerifyError> {
+        Ok(())
+    }
+}
+
+impl<C: CurveAffine, const L: usize> BatchVerifyAccumulation<C, L> for HyperNova<C, L> {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,312 @@
+use halo2_proofs::plonk::Circuit;
+use tracing::info_span;
+use tracing_test::traced_test;
+
+use super::*;
+use crate::{
+    halo2curves::bn256::G1Affine as Affine,
+    nifs::tests::{
+        challenge_circuit::ChallengeCircuit,
+        dynamic_table_circuit::MemoryCircuit,
+        fibo_circuit::{get_fibo_seq, FiboCircuit},
+        fibo_circuit_with_lookup::{get_sequence, FiboCircuitWithLookup},
+        mock::{self, ro},
+        random_linear_combination_circuit::RandomLinearCombinationCircuit,
+        range_check_circuit::RangeCheckCircuit,
+        shuffle_circuit::ShuffleCircuit,
+    },
+};
+
+const L: usize = 3;
+
+type Scalar = <Affine as CurveAffine>::ScalarExt;
+
+type HyperNova = crate::nifs::hypernova::HyperNova<Affine, L>;
+
+type Mock<C> = mock::Mock<HyperNova, C, L>;
+
+impl<C: Circuit<Scalar>> Mock<C> {
+    /// Folds `incoming` twice: into the initial accumulator & then into the result, so the second
+    /// fold starts from the accumulator with non-trivial `u`, `r` & `v`
+    pub fn run(mut self) {
+        let incoming = self.generate_plonk_traces();
+        let instances = self.instances();
+
+        let init_accumulator = HyperNova::new_accumulator(AccumulatorArgs::from(&self.S), &self.pp);
+
+        HyperNova::is_sat(&self.ck, &self.S, &init_accumulator, &instances)
+            .expect("The newly created accumulator is not satisfactory");
+
+        let mut accumulator = init_accumulator;
+        for _ in 0..2 {
+            let (accumulator_from_prove, proof) = HyperNova::prove(
+                &self.ck,
+                &self.pp,
+                &mut ro(),
+                accumulator.clone(),
+                &incoming,
+            )
+            .expect("`hypernova::prove` failed");
+
+            HyperNova::is_sat(&self.ck, &self.S, &accumulator_from_prove, &instances)
+                .expect("The accumulator after calling `prove` is not satisfactory");
+
+            let accumulator_from_verify = HyperNova::verify(
+                &self.vp,
+                &mut ro(),
+                &mut ro(),
+                &accumulator.into(),
+                &incoming.clone().map(|tr| tr.u),
+                &proof,
+            )
+            .unwrap();
+
+            assert_eq!(
+                AccumulatorInstance::from(accumulator_from_prove.clone()),
+                accumulator_from_verify
+            );
+
+            accumulator = accumulator_from_prove;
+        }
+    }
+}
+
+#[traced_test]
+#[test]
+fn random_linear_combination() {
+    Mock::new(
+        10,
+        [
+            (
+                RandomLinearCombinationCircuit::new(
+                    (1..10).map(Scalar::from).collect(),
+                    Scalar::from(2),
+                ),
+                vec![Scalar::from(4097)],
+            ),
+            (
+                RandomLinearCombinationCircuit::new(
+                    (1..10).map(Scalar::from).collect(),
+                    Scalar::from(2),
+                ),
+                vec![Scalar::from(4097)],
+            ),
+            (
+                RandomLinearCombinationCircuit::new(
+                    (2..11).map(Scalar::from).collect(),
+                    Scalar::from(3),
+                ),
+                vec![Scalar::from(93494)],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn fibo() {
+    let _s = info_span!("fibo").entered();
+
+    const SIZE: usize = 16;
+
+    let seq1 = get_fibo_seq(1, 1, SIZE);
+    let seq2 = get_fibo_seq(2, 3, SIZE);
+    let seq3 = get_fibo_seq(3, 5, SIZE);
+
+    Mock::new(
+        10,
+        [
+            (
+                FiboCircuit {
+                    a: Scalar::from(seq1[0]),
+                    b: Scalar::from(seq1[1]),
+                    num: SIZE,
+                },
+                vec![Scalar::from(seq1[SIZE - 1])],
+            ),
+            (
+                FiboCircuit {
+                    a: Scalar::from(seq2[0]),
+                    b: Scalar::from(seq2[1]),
+                    num: SIZE,
+                },
+                vec![Scalar::from(seq2[SIZE - 1])],
+            ),
+            (
+                FiboCircuit {
+                    a: Scalar::from(seq3[0]),
+                    b: Scalar::from(seq3[1]),
+                    num: SIZE,
+                },
+                vec![Scalar::from(seq3[SIZE - 1])],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn fibo_lookup() {
+    let _s = info_span!("fibo_lookup").entered();
+
+    const SIZE: usize = 7;
+
+    // circuit 1
+    let seq1 = get_sequence(1, 3, 2, SIZE);
+    let seq2 = get_sequence(3, 2, 2, SIZE);
+    let seq3 = get_sequence(3, 2, 2, SIZE);
+
+    Mock::new(
+        10,
+        [
+            (
+                FiboCircuitWithLookup {
+                    a: Scalar::from(seq1[0]),
+                    b: Scalar::from(seq1[1]),
+                    c: Scalar::from(seq1[2]),
+                    num: SIZE,
+                },
+                vec![Scalar::ONE],
+            ),
+            (
+                FiboCircuitWithLookup {
+                    a: Scalar::from(seq2[0]),
+                    b: Scalar::from(seq2[1]),
+                    c: Scalar::from(seq2[2]),
+                    num: SIZE,
+                },
+                vec![Scalar::ONE],
+            ),
+            (
+                FiboCircuitWithLookup {
+                    a: Scalar::from(seq3[0]),
+                    b: Scalar::from(seq3[1]),
+                    c: Scalar::from(seq3[2]),
+                    num: SIZE,
+                },
+                vec![Scalar::ONE],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn range_check_lookup() {
+    let _s = info_span!("range_check_lookup").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                RangeCheckCircuit::new((0..20).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                RangeCheckCircuit::new((100..120).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                RangeCheckCircuit::new((0..20).map(|v| Scalar::from(v * 12)).collect()),
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn dynamic_table_lookup() {
+    let _s = info_span!("dynamic_table_lookup").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                MemoryCircuit {
+                    memory: (10..20).map(Scalar::from).collect(),
+                    reads: vec![0, 3, 3, 9, 5],
+                },
+                vec![],
+            ),
+            (
+                MemoryCircuit {
+                    memory: (0..10).map(|v| Scalar::from(v * v)).collect(),
+                    reads: vec![1, 1, 2, 8, 7, 0],
+                },
+                vec![],
+            ),
+            (
+                MemoryCircuit {
+                    memory: (0..16).map(Scalar::from).collect(),
+                    reads: vec![15, 14, 13],
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn shuffle() {
+    let _s = info_span!("shuffle").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                ShuffleCircuit::reversed((0..20).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                ShuffleCircuit::reversed((10..40).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                ShuffleCircuit {
+                    input: vec![1, 2, 2, 3].into_iter().map(Scalar::from).collect(),
+                    shuffled: vec![2, 3, 1, 2].into_iter().map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn challenge() {
+    let _s = info_span!("challenge").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                ChallengeCircuit {
+                    inputs: (0..20).map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+            (
+                ChallengeCircuit {
+                    inputs: (10..40).map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+            (
+                ChallengeCircuit {
+                    inputs: vec![Scalar::from(42)],
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
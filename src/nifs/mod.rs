@@ -8,16 +8,22 @@
 //! For more details look at:
 //! - Paragraph '3. Folding scheme' at [Nova whitepaper](https://eprint.iacr.org/2021/370)
 //! - [nifs module](https://github.com/microsoft/Nova/blob/main/src/nifs.rs) at [Nova codebase](https://github.com/microsoft/Nova)
+use std::iter;
+
 use halo2_proofs::{arithmetic::CurveAffine, plonk::Error as Halo2Error};
+use itertools::Itertools;
+use rand_core::RngCore;
 use rayon::prelude::*;
 
 use crate::{
     commitment::{self, CommitmentKey},
-    plonk::{eval::Error as EvalError, PlonkStructure},
+    ff::{Field, PrimeField},
+    plonk::{eval::Error as EvalError, phases::AdviceSource, PlonkStructure},
     poseidon::ROTrait,
     sps::Error as SpsError,
 };
 
+pub mod hypernova;
 pub mod protogalaxy;
 pub mod vanilla;
 
@@ -53,10 +59,14 @@ pub trait FoldingScheme<C: CurveAffine, const L: usize = 1> {
         S: PlonkStructure<C::ScalarExt>,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Self::Error>;
 
+    /// Run special soundness protocol over the `witness`
+    ///
+    /// For multi-phase circuits `witness` must be able to synthesize advice columns of later
+    /// phases with challenges, e.g. [`crate::table::CircuitRunner`], see [`crate::plonk::phases`]
     fn generate_plonk_trace(
         ck: &CommitmentKey<C>,
         instances: &[Vec<C::ScalarExt>],
-        witness: &[Vec<C::ScalarExt>],
+        witness: &(impl AdviceSource<C::ScalarExt> + ?Sized),
         pp: &Self::ProverParam,
         ro_nark: &mut impl ROTrait<C::Base>,
     ) -> Result<Self::Trace, Self::Error>;
@@ -81,6 +91,33 @@ pub trait FoldingScheme<C: CurveAffine, const L: usize = 1> {
     ) -> Result<Self::AccumulatorInstance, Self::Error>;
 }
 
+/// Extension of [`FoldingScheme`] that folds two accumulators into one.
+///
+/// Accumulators produced independently (e.g. on different machines) can be merged pairwise, so
+/// the accumulation takes the shape of a binary tree (tree-shaped PCD) instead of a chain.
+pub trait FoldAccumulators<C: CurveAffine, const L: usize = 1>: FoldingScheme<C, L> {
+    /// The proof send from prover to verifier for folding of two accumulators
+    type AccumulatorsProof;
+
+    /// Perform the folding of `lhs` & `rhs` accumulators as a prover.
+    fn fold_accumulators(
+        ck: &CommitmentKey<C>,
+        pp: &Self::ProverParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: Self::Accumulator,
+        rhs: &Self::Accumulator,
+    ) -> Result<(Self::Accumulator, Self::AccumulatorsProof), Self::Error>;
+
+    /// Perform the folding of `lhs` & `rhs` accumulators as a verifier.
+    fn verify_fold_accumulators(
+        vp: &Self::VerifierParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: &Self::AccumulatorInstance,
+        rhs: &Self::AccumulatorInstance,
+        proof: &Self::AccumulatorsProof,
+    ) -> Result<Self::AccumulatorInstance, Self::Error>;
+}
+
 /// Trait representing the requirements for checking the satisfaction of
 /// accumulation relations in a Non-Interactive Folding Scheme (NIFS).
 pub trait VerifyAccumulation<C: CurveAffine, const L: usize = 1>: FoldingScheme<C, L> {
@@ -169,5 +206,129 @@ pub trait IsSatAccumulator<C: CurveAffine, const L: usize = 1>: VerifyAccumulati
 
 impl<C: CurveAffine, const L: usize, F: VerifyAccumulation<C, L>> IsSatAccumulator<C, L> for F {}
 
+/// Extension of [`VerifyAccumulation`] that checks many accumulators at once
+///
+/// Each method receives one random `coefficients[k]` per accumulator `accs[k]` and is expected to
+/// check the random linear combination of relations instead of each relation separately. The
+/// default implementations simply check the accumulators one by one. An empty batch is
+/// satisfied.
+pub trait BatchVerifyAccumulation<C: CurveAffine, const L: usize = 1>:
+    VerifyAccumulation<C, L>
+{
+    /// Batch version of [`VerifyAccumulation::is_sat_accumulation`]
+    fn is_sat_accumulation_batch(
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[<Self as FoldingScheme<C, L>>::Accumulator],
+        _coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        accs.iter()
+            .try_for_each(|acc| Self::is_sat_accumulation(S, acc))
+    }
+
+    /// Batch version of [`VerifyAccumulation::is_sat_permutation`]
+    fn is_sat_permutation_batch(
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[<Self as FoldingScheme<C, L>>::Accumulator],
+        _coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        accs.iter()
+            .try_for_each(|acc| Self::is_sat_permutation(S, acc))
+    }
+
+    /// Batch version of [`VerifyAccumulation::is_sat_witness_commit`]
+    fn is_sat_witness_commit_batch(
+        ck: &CommitmentKey<C>,
+        accs: &[<Self as FoldingScheme<C, L>>::Accumulator],
+        _coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        accs.iter()
+            .try_for_each(|acc| Self::is_sat_witness_commit(ck, acc))
+    }
+}
+
+/// Trait defining a complete satisfaction check for a batch of accumulators
+pub trait IsSatAccumulatorBatch<C: CurveAffine, const L: usize = 1>:
+    BatchVerifyAccumulation<C, L> + IsSatAccumulator<C, L>
+{
+    /// Comprehensive satisfaction check for many accumulators at once.
+    ///
+    /// Runs [`BatchVerifyAccumulation`] checks over a random linear combination of `accs`, so the
+    /// witness commitments are checked by a single MSM. If the batch check fails, falls back to
+    /// [`IsSatAccumulator::is_sat`] for each accumulator and returns the errors together with the
+    /// index of the failed accumulator.
+    fn is_sat_batch(
+        ck: &CommitmentKey<C>,
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[<Self as FoldingScheme<C, L>>::Accumulator],
+        pub_instances: &[&[Vec<Vec<C::ScalarExt>>]],
+        mut rng: impl RngCore,
+    ) -> Result<(), Vec<(usize, Vec<Self::VerifyError>)>> {
+        assert_eq!(accs.len(), pub_instances.len());
+
+        if accs.is_empty() {
+            return Ok(());
+        }
+
+        let coefficients = iter::repeat_with(|| C::ScalarExt::random(&mut rng))
+            .take(accs.len())
+            .collect::<Box<[_]>>();
+
+        let is_batch_sat = Self::is_sat_accumulation_batch(S, accs, &coefficients).is_ok()
+            && Self::is_sat_permutation_batch(S, accs, &coefficients).is_ok()
+            && Self::is_sat_witness_commit_batch(ck, accs, &coefficients).is_ok()
+            && accs
+                .iter()
+                .zip_eq(pub_instances)
+                .all(|(acc, pub_instances)| Self::is_sat_pub_instances(acc, pub_instances).is_ok());
+
+        if is_batch_sat {
+            return Ok(());
+        }
+
+        let errors = accs
+            .iter()
+            .zip_eq(pub_instances)
+            .enumerate()
+            .filter_map(|(index, (acc, pub_instances))| {
+                Self::is_sat(ck, S, acc, pub_instances)
+                    .err()
+                    .map(|errors| (index, errors))
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<C: CurveAffine, const L: usize, F: BatchVerifyAccumulation<C, L>> IsSatAccumulatorBatch<C, L>
+    for F
+{
+}
+
+/// Random linear combination `sum_k coefficients[k] * vectors[k]` of equal length vectors
+pub(crate) fn linear_combination<'v, F: PrimeField>(
+    vectors: impl Iterator<Item = &'v [F]>,
+    coefficients: &[F],
+) -> Vec<F> {
+    vectors
+        .zip_eq(coefficients)
+        .fold(vec![], |mut result, (vector, coeff)| {
+            if result.is_empty() {
+                result = vec![F::ZERO; vector.len()];
+            }
+
+            result
+                .par_iter_mut()
+                .zip_eq(vector.par_iter())
+                .for_each(|(r, v)| *r += *v * coeff);
+
+            result
+        })
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
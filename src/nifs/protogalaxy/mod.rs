@@ -9,10 +9,13 @@ use crate::{
     commitment::CommitmentKey,
     constants::NUM_CHALLENGE_BITS,
     ff::PrimeField,
+    fft::DomainCache,
     halo2_proofs::arithmetic::{self, CurveAffine, Field},
     nifs::protogalaxy::poly::PolyContext,
-    plonk::{self, PlonkInstance, PlonkStructure, PlonkTrace, PlonkWitness},
-    polynomial::{lagrange, sparse, univariate::UnivariatePoly},
+    plonk::{self, phases::AdviceSource, PlonkInstance, PlonkStructure, PlonkTrace, PlonkWitness},
+    polynomial::{
+        expression::QueryIndexContext, lagrange, sparse, sumcheck, univariate::UnivariatePoly,
+    },
     poseidon::AbsorbInRO,
     sps::{self, SpecialSoundnessVerifier},
     util,
@@ -60,11 +63,16 @@ impl<C: CurveAffine, const L: usize> ProtoGalaxy<C, L> {
         count_of_rows * count_of_gates
     }
 
+    /// Creates an initial accumulator with a zeroed trace
+    ///
+    /// Not all relations are satisfied by a zero trace: for example, the log-derivative lookup
+    /// relation `h * (l + r) - 1` evaluates to `-1` on each row. Therefore `e` is evaluated on
+    /// the zeroed trace instead of being set to zero.
     pub(crate) fn new_accumulator(
         args: AccumulatorArgs,
         params: &ProverParam<C>,
         ro_acc: &mut impl ROTrait<C::Base>,
-    ) -> Accumulator<C> {
+    ) -> Result<Accumulator<C>, Error> {
         let mut accumulator = Accumulator::new(args, Self::get_count_of_valuation(&params.S));
 
         let beta = Self::generate_challenge(&params.pp_digest, ro_acc, &accumulator, iter::empty());
@@ -75,7 +83,51 @@ impl<C: CurveAffine, const L: usize> ProtoGalaxy<C, L> {
             .zip(iter::successors(Some(beta), |acc| Some(acc.double())))
             .for_each(|(b, beta_pow)| *b = beta_pow);
 
-        accumulator
+        accumulator.e = Self::evaluate_e(&params.S, &accumulator.trace, &accumulator.betas)?;
+
+        Ok(accumulator)
+    }
+
+    /// Evaluates `e = sum_i pow_i(beta) * f_i(w)`
+    ///
+    /// - `f_i` - iteratively all gates for all rows sequentially. The order is taken from
+    ///           [`plonk::iter_evaluate_witness`].
+    /// - `pow_i` - `i` degree of challenge, calculated in the same way as in [`poly::compute_F`]
+    fn evaluate_e(
+        S: &PlonkStructure<C::ScalarExt>,
+        trace: &PlonkTrace<C>,
+        betas: &[C::ScalarExt],
+    ) -> Result<C::ScalarExt, plonk::eval::Error> {
+        struct Node<F: PrimeField> {
+            value: F,
+            height: usize,
+        }
+
+        Ok(plonk::iter_evaluate_witness::<C::ScalarExt>(S, trace)
+            .map(|result_with_evaluated_gate| {
+                result_with_evaluated_gate.map(|value| Node { value, height: 0 })
+            })
+            // TODO #324 Migrate to a parallel algorithm
+            // TODO #324 Implement `try_tree_reduce` to stop on the first error
+            .tree_reduce(|left_w, right_w| {
+                let (mut left_n, right_n) = (left_w?, right_w?);
+
+                if left_n.height != right_n.height {
+                    unreachable!(
+                        "must be unreachable, since the number of rows is the degree of 2, but: {l_height} != {r_height}",
+                        l_height = left_n.height,
+                        r_height = right_n.height
+                    )
+                }
+
+                left_n.value += right_n.value * betas[right_n.height];
+                left_n.height += 1;
+
+                Ok(left_n)
+            })
+            .transpose()?
+            .map(|n| n.value)
+            .unwrap_or_default())
     }
 
     fn fold_witness<'i>(
@@ -175,6 +227,80 @@ impl<C: CurveAffine, const L: usize> ProtoGalaxy<C, L> {
             })
     }
 
+    /// Degree of `G(X) = sum_i pow_i(beta(X)) * f_i(w(X))`, see [`ProtoGalaxy::fold_accumulators`]
+    ///
+    /// `f_i` has the max degree of gates & `pow_i` is the product of at most `log2(count of
+    /// valuation)` betas
+    fn get_accumulators_degree(S: &PlonkStructure<C::ScalarExt>) -> usize {
+        let ctx = QueryIndexContext::from(S);
+        let max_gate_degree = S
+            .gates
+            .iter()
+            .map(|gate| gate.degree(&ctx))
+            .max()
+            .unwrap_or_default();
+
+        let count_of_betas = Self::get_count_of_valuation(S)
+            .next_power_of_two()
+            .trailing_zeros() as usize;
+
+        max_gate_degree + count_of_betas
+    }
+
+    /// Absorbs both accumulators & evaluations of `G` into RandomOracle `RO` & generates
+    /// challenge based on that
+    #[instrument(skip_all)]
+    fn generate_accumulators_challenge<RO: ROTrait<C::Base>>(
+        pp_digest: &C,
+        ro_acc: &mut RO,
+        lhs: &impl AbsorbInRO<C::Base, RO>,
+        rhs: &impl AbsorbInRO<C::Base, RO>,
+        poly_G_evaluations: &[C::ScalarExt],
+    ) -> C::ScalarExt {
+        ro_acc
+            .absorb_point(pp_digest)
+            .absorb(lhs)
+            .absorb(rhs)
+            .absorb_field_iter(
+                poly_G_evaluations
+                    .iter()
+                    .map(|v| util::fe_to_fe::<C::ScalarExt, C::Base>(v).unwrap()),
+            )
+            .squeeze::<C>(NUM_CHALLENGE_BITS)
+    }
+
+    /// Coefficients of the line through two accumulators: `(1 - x, x)`
+    fn line_coefficients(x: C::ScalarExt) -> [C::ScalarExt; 2] {
+        [C::ScalarExt::ONE - x, x]
+    }
+
+    fn fold_betas(
+        lhs: &[C::ScalarExt],
+        rhs: &[C::ScalarExt],
+        x: C::ScalarExt,
+    ) -> Box<[C::ScalarExt]> {
+        lhs.iter()
+            .zip_eq(rhs.iter())
+            .map(|(lhs, rhs)| *lhs + x * (*rhs - lhs))
+            .collect()
+    }
+
+    /// `e = G(x)`, where `G(0) = lhs_e`, `G(1) = rhs_e` & `G(2), G(3), ...` are
+    /// `poly_G_evaluations`
+    fn calculate_accumulators_e(
+        lhs_e: C::ScalarExt,
+        rhs_e: C::ScalarExt,
+        poly_G_evaluations: &[C::ScalarExt],
+        x: C::ScalarExt,
+    ) -> C::ScalarExt {
+        let evaluations = [lhs_e, rhs_e]
+            .into_iter()
+            .chain(poly_G_evaluations.iter().copied())
+            .collect::<Box<[_]>>();
+
+        sumcheck::interpolate(&evaluations, x)
+    }
+
     pub fn verify_sps<'l>(
         incoming: impl Iterator<Item = &'l PlonkInstance<C>>,
         ro_nark: &mut impl ROTrait<C::Base>,
@@ -196,11 +322,15 @@ pub struct ProverParam<C: CurveAffine> {
     pub(crate) S: PlonkStructure<C::ScalarExt>,
     /// Digest of public parameter of IVC circuit
     pp_digest: C,
+    /// FFT domains shared by all folds, see [`PolyContext::with_domains`]
+    domains: DomainCache<C::ScalarExt>,
 }
 
 pub struct VerifierParam<C: CurveAffine> {
     /// Digest of public parameter of IVC circuit
     pp_digest: C,
+    /// Degree of the polynomial in [`ProtoGalaxyAccumulatorsProof`]
+    accumulators_degree: usize,
 }
 
 pub struct ProtoGalaxyProof<F: PrimeField> {
@@ -208,14 +338,24 @@ pub struct ProtoGalaxyProof<F: PrimeField> {
     pub poly_K: UnivariatePoly<F>,
 }
 
+/// Proof of the folding of two accumulators, see [`ProtoGalaxy::fold_accumulators`]
+pub struct ProtoGalaxyAccumulatorsProof<F: PrimeField> {
+    /// Evaluations of `G(X)` at `2, 3, ..., deg(G)`
+    pub poly_G_evaluations: Box<[F]>,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     Sps(#[from] sps::Error),
     #[error(transparent)]
     Poly(#[from] poly::Error),
+    #[error(transparent)]
+    Eval(#[from] plonk::eval::Error),
     #[error("Error while verify plonk instance with sps: {0:?}")]
     VerifySps(Box<[(usize, sps::Error)]>),
+    #[error("Expected {expected} evaluations of `G` in proof, but got {actual}")]
+    WrongAccumulatorsProofLength { expected: usize, actual: usize },
 }
 
 impl<C: CurveAffine, const L: usize> FoldingScheme<C, L> for ProtoGalaxy<C, L> {
@@ -232,13 +372,25 @@ impl<C: CurveAffine, const L: usize> FoldingScheme<C, L> for ProtoGalaxy<C, L> {
         pp_digest: C,
         S: PlonkStructure<C::ScalarExt>,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        Ok((ProverParam { S, pp_digest }, VerifierParam { pp_digest }))
+        let accumulators_degree = Self::get_accumulators_degree(&S);
+
+        Ok((
+            ProverParam {
+                S,
+                pp_digest,
+                domains: DomainCache::default(),
+            },
+            VerifierParam {
+                pp_digest,
+                accumulators_degree,
+            },
+        ))
     }
 
     fn generate_plonk_trace(
         ck: &CommitmentKey<C>,
         instances: &[Vec<C::ScalarExt>],
-        witness: &[Vec<C::ScalarExt>],
+        witness: &(impl AdviceSource<C::ScalarExt> + ?Sized),
         pp: &Self::ProverParam,
         ro_nark: &mut impl ROTrait<C::Base>,
     ) -> Result<PlonkTrace<C>, Error> {
@@ -284,7 +436,7 @@ impl<C: CurveAffine, const L: usize> FoldingScheme<C, L> for ProtoGalaxy<C, L> {
         accumulator: Self::Accumulator,
         incoming: &[PlonkTrace<C>; L],
     ) -> Result<(Self::Accumulator, Self::Proof), Error> {
-        let ctx = PolyContext::new(&pp.S, incoming);
+        let ctx = PolyContext::with_domains(&pp.S, &pp.domains, incoming);
 
         let delta = Self::generate_challenge(
             &pp.pp_digest,
@@ -432,16 +584,130 @@ impl<C: CurveAffine, const L: usize> FoldingScheme<C, L> for ProtoGalaxy<C, L> {
     }
 }
 
+impl<C: CurveAffine, const L: usize> FoldAccumulators<C, L> for ProtoGalaxy<C, L> {
+    type AccumulatorsProof = ProtoGalaxyAccumulatorsProof<C::ScalarExt>;
+
+    /// Folds two accumulators with different betas
+    ///
+    /// # Algorithm
+    ///
+    /// Both accumulators lie on the line `acc(X) = (1 - X) * lhs + X * rhs` (betas & trace), so
+    ///
+    /// `G(X) = sum_i pow_i(beta(X)) * f_i(w(X))`
+    ///
+    /// has `G(0) = lhs.e` & `G(1) = rhs.e` for valid accumulators.
+    ///
+    /// 1. **Evaluate G:**
+    ///     - `G(2), ..., G(deg(G))` by [`ProtoGalaxy::evaluate_e`] on `acc(X)`
+    ///
+    /// 2. **Generate Gamma:**
+    ///     - **RO Seeds**: both accumulators & evaluations of `G`
+    ///     - `gamma = ro_acc.squeeze()`
+    ///
+    /// 3. **Fold:**
+    ///     - `acc(gamma)` with `e = G(gamma)`, interpolated by evaluations
+    fn fold_accumulators(
+        _ck: &CommitmentKey<C>,
+        pp: &Self::ProverParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: Self::Accumulator,
+        rhs: &Self::Accumulator,
+    ) -> Result<(Self::Accumulator, Self::AccumulatorsProof), Error> {
+        let fold = |x: C::ScalarExt| -> Accumulator<C> {
+            let [lhs_coeff, rhs_coeff] = Self::line_coefficients(x);
+            let coefficients = [lhs_coeff, rhs_coeff].into_iter();
+
+            Accumulator {
+                trace: PlonkTrace {
+                    u: Self::fold_instance(
+                        lhs.trace.u.clone(),
+                        iter::once(&rhs.trace.u),
+                        coefficients.clone(),
+                    ),
+                    w: Self::fold_witness(
+                        lhs.trace.w.clone(),
+                        iter::once(&rhs.trace.w),
+                        coefficients,
+                    ),
+                },
+                betas: Self::fold_betas(&lhs.betas, &rhs.betas, x),
+                e: C::ScalarExt::ZERO,
+            }
+        };
+
+        let poly_G_evaluations = (2..=Self::get_accumulators_degree(&pp.S) as u64)
+            .map(|x| {
+                let acc = fold(C::ScalarExt::from(x));
+                Self::evaluate_e(&pp.S, &acc.trace, &acc.betas)
+            })
+            .collect::<Result<Box<[_]>, _>>()?;
+
+        let gamma = Self::generate_accumulators_challenge(
+            &pp.pp_digest,
+            ro_acc,
+            &lhs,
+            rhs,
+            &poly_G_evaluations,
+        );
+
+        let mut accumulator = fold(gamma);
+        accumulator.e = Self::calculate_accumulators_e(lhs.e, rhs.e, &poly_G_evaluations, gamma);
+
+        Ok((
+            accumulator,
+            ProtoGalaxyAccumulatorsProof { poly_G_evaluations },
+        ))
+    }
+
+    fn verify_fold_accumulators(
+        vp: &Self::VerifierParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: &Self::AccumulatorInstance,
+        rhs: &Self::AccumulatorInstance,
+        proof: &Self::AccumulatorsProof,
+    ) -> Result<Self::AccumulatorInstance, Error> {
+        let expected = vp.accumulators_degree.saturating_sub(1);
+        if proof.poly_G_evaluations.len() != expected {
+            return Err(Error::WrongAccumulatorsProofLength {
+                expected,
+                actual: proof.poly_G_evaluations.len(),
+            });
+        }
+
+        let gamma = Self::generate_accumulators_challenge(
+            &vp.pp_digest,
+            ro_acc,
+            lhs,
+            rhs,
+            &proof.poly_G_evaluations,
+        );
+
+        Ok(AccumulatorInstance {
+            ins: Self::fold_instance(
+                lhs.ins.clone(),
+                iter::once(&rhs.ins),
+                Self::line_coefficients(gamma).into_iter(),
+            ),
+            betas: Self::fold_betas(&lhs.betas, &rhs.betas, gamma),
+            e: Self::calculate_accumulators_e(lhs.e, rhs.e, &proof.poly_G_evaluations, gamma),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum VerifyError<F: PrimeField> {
     #[error("Error while evaluate witness: {0:?}")]
     PlonkEval(plonk::eval::Error),
     #[error("Expected `e` {expected_e:?}, but evaluated is {evaluated_e:?}")]
     MismatchE { expected_e: F, evaluated_e: F },
+    #[error("Log derivative relation not satisfied")]
+    LogDerivativeNotSat,
     #[error("Permutation check failed")]
     PermCheckFailed { mismatch_count: usize },
     #[error("Commitment of")]
     WitnessCommitmentMismatch(Box<[usize]>),
+    #[error(transparent)]
+    Commitment(#[from] commitment::Error),
 }
 
 impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for ProtoGalaxy<C, L> {
@@ -451,46 +717,22 @@ impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for ProtoGalaxy<C,
         S: &PlonkStructure<C::ScalarExt>,
         acc: &Accumulator<C>,
     ) -> Result<(), Self::VerifyError> {
-        struct Node<F: PrimeField> {
-            value: F,
-            height: usize,
-        }
-
-        let evaluated_e = plonk::iter_evaluate_witness::<C::ScalarExt>(S, &acc.trace)
-            .map(|result_with_evaluated_gate| {
-                result_with_evaluated_gate.map(|value| Node { value, height: 0 })
-            })
-            // TODO #324 Migrate to a parallel algorithm
-            // TODO #324 Implement `try_tree_reduce` to stop on the first error
-            .tree_reduce(|left_w, right_w| {
-                let (mut left_n, right_n) = (left_w?, right_w?);
+        let evaluated_e =
+            Self::evaluate_e(S, &acc.trace, &acc.betas).map_err(VerifyError::PlonkEval)?;
 
-                if left_n.height != right_n.height {
-                    unreachable!(
-                        "must be unreachable, since the number of rows is the degree of 2, but: {l_height} != {r_height}",
-                        l_height = left_n.height,
-                        r_height = right_n.height
-                    )
-                }
-
-                left_n.value += right_n.value * acc.betas[right_n.height];
-                left_n.height += 1;
-
-                Ok(left_n)
-            })
-            .transpose()
-            .map_err(VerifyError::PlonkEval)?
-            .map(|n| n.value)
-            .unwrap_or_default();
-
-        if evaluated_e == acc.e {
-            Ok(())
-        } else {
-            Err(VerifyError::MismatchE {
+        if evaluated_e != acc.e {
+            return Err(VerifyError::MismatchE {
                 expected_e: acc.e,
                 evaluated_e,
-            })
+            });
         }
+
+        // The sum `sum_i (h_i - g_i)` is linear in the witness, so it is preserved by folding
+        if !S.is_sat_log_derivative(&acc.trace.w.W) {
+            return Err(VerifyError::LogDerivativeNotSat);
+        }
+
+        Ok(())
     }
 
     fn is_sat_permutation(
@@ -503,25 +745,11 @@ impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for ProtoGalaxy<C,
             .instances
             .iter()
             .flat_map(|inst| inst.iter())
-            .chain(w.W[0].iter().take((1 << S.k) * S.num_advice_columns))
+            .chain(S.advice_phases.iter_advice(&w.W, 1 << S.k))
             .copied()
             .collect::<Vec<_>>();
 
-        let mismatch_count = sparse::matrix_multiply(&S.permutation_matrix(), &Z)
-            .into_iter()
-            .zip_eq(Z)
-            .enumerate()
-            .filter_map(|(row, (y, z))| C::ScalarExt::ZERO.ne(&(y - z)).then_some(row))
-            .inspect(|row| {
-                warn!("permutation mismatch at {row}");
-            })
-            .count();
-
-        if mismatch_count == 0 {
-            Ok(())
-        } else {
-            Err(Self::VerifyError::PermCheckFailed { mismatch_count })
-        }
+        is_sat_permutation_of_z(S, Z)
     }
 
     fn is_sat_witness_commit(
@@ -538,8 +766,9 @@ impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for ProtoGalaxy<C,
             .iter()
             .zip_eq(&w.W)
             .enumerate()
-            .filter_map(|(i, (Ci, Wi))| ck.commit(Wi).unwrap().ne(Ci).then_some(i))
-            .collect::<Box<[_]>>();
+            .map(|(i, (Ci, Wi))| Ok(ck.commit(Wi)?.ne(Ci).then_some(i)))
+            .filter_map(Result::transpose)
+            .collect::<Result<Box<[_]>, commitment::Error>>()?;
 
         if errors.is_empty() {
             Ok(())
@@ -556,6 +785,91 @@ impl<C: CurveAffine, const L: usize> VerifyAccumulation<C, L> for ProtoGalaxy<C,
     }
 }
 
+fn is_sat_permutation_of_z<F: PrimeField>(
+    S: &PlonkStructure<F>,
+    Z: Vec<F>,
+) -> Result<(), VerifyError<F>> {
+    let mismatch_count = sparse::matrix_multiply(&S.permutation_matrix(), &Z)
+        .into_iter()
+        .zip_eq(Z)
+        .enumerate()
+        .filter_map(|(row, (y, z))| F::ZERO.ne(&(y - z)).then_some(row))
+        .inspect(|row| {
+            warn!("permutation mismatch at {row}");
+        })
+        .count();
+
+    if mismatch_count == 0 {
+        Ok(())
+    } else {
+        Err(VerifyError::PermCheckFailed { mismatch_count })
+    }
+}
+
+/// [`VerifyAccumulation::is_sat_accumulation`] is checked one by one: `e` is a sum over rows
+/// weighted by powers of `betas`, which differ between accumulators, so the relations can't be
+/// merged into a single pass
+impl<C: CurveAffine, const L: usize> BatchVerifyAccumulation<C, L> for ProtoGalaxy<C, L> {
+    fn is_sat_permutation_batch(
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[Accumulator<C>],
+        coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        if accs.is_empty() {
+            return Ok(());
+        }
+
+        let Zs = accs
+            .iter()
+            .map(|acc| {
+                let PlonkTrace { u, w } = &acc.trace;
+
+                u.instances
+                    .iter()
+                    .flat_map(|inst| inst.iter())
+                    .chain(S.advice_phases.iter_advice(&w.W, 1 << S.k))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Box<[_]>>();
+
+        is_sat_permutation_of_z(
+            S,
+            linear_combination(Zs.iter().map(|Z| Z.as_slice()), coefficients),
+        )
+    }
+
+    fn is_sat_witness_commit_batch(
+        ck: &CommitmentKey<C>,
+        accs: &[Accumulator<C>],
+        coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        let (vectors, commitments, coefficients): (Vec<_>, Vec<_>, Vec<_>) = accs
+            .iter()
+            .zip_eq(coefficients)
+            .flat_map(|(acc, coeff)| {
+                let PlonkTrace { u, w } = &acc.trace;
+
+                w.W.iter()
+                    .zip_eq(u.W_commitments.iter())
+                    .zip(iter::successors(Some(*coeff), move |power| {
+                        Some(*power * coeff)
+                    }))
+                    .map(|((Wi, Ci), coeff)| (Wi.as_slice(), *Ci, coeff))
+            })
+            .multiunzip();
+
+        if ck.is_rlc_of_commitments(&vectors, &commitments, &coefficients)? {
+            Ok(())
+        } else {
+            // Indexes of the mismatched commitments are unknown for the batch, so they are found by
+            // checking accumulators one by one
+            accs.iter()
+                .try_for_each(|acc| Self::is_sat_witness_commit(ck, acc))
+        }
+    }
+}
+
 // F(alpha) * L(gamma) + Z(gamma) * K(gamma)
 fn calculate_e<C: CurveAffine>(
     poly_F: &UnivariatePoly<C::Scalar>,
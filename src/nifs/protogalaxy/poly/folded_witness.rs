@@ -5,8 +5,8 @@ use rayon::prelude::*;
 
 use crate::{
     ff::PrimeField,
+    fft::EvaluationDomain,
     plonk::{GetChallenges, GetWitness, PlonkWitness},
-    polynomial::lagrange,
     util::MultiCartesianProduct,
 };
 
@@ -18,17 +18,14 @@ pub(crate) struct FoldedWitness<F: PrimeField> {
 impl<F: PrimeField> FoldedWitness<F> {
     pub(crate) fn new(
         points_for_fft: &[F],
-        lagrange_domain: u32,
+        lagrange_domain: &EvaluationDomain<F>,
         accumulator: &(impl Sync + GetChallenges<F> + GetWitness<F>),
         traces: &[(impl Sync + GetChallenges<F> + GetWitness<F>)],
     ) -> Box<[Self]> {
         let polys_L_in_challenges = points_for_fft
-            .iter()
-            .map(|X| {
-                lagrange::iter_eval_lagrange_poly_for_cyclic_group(*X, lagrange_domain)
-                    .collect::<Box<[_]>>()
-            })
-            .collect::<Box<[_]>>();
+            .par_iter()
+            .map(|X| lagrange_domain.eval_lagrange_basis(*X))
+            .collect::<Vec<_>>();
 
         let folded_witnesses_collection =
             fold_witnesses(&polys_L_in_challenges, accumulator, traces);
@@ -57,7 +54,7 @@ impl<F: PrimeField> GetWitness<F> for FoldedWitness<F> {
 }
 
 /// For each `X` we must perform the operation of sum all all matrices [`PlonkWitness`] with
-/// coefficients taken from [`lagrange::iter_eval_lagrange_polynomials_for_cyclic_group`]
+/// coefficients taken from [`EvaluationDomain::eval_lagrange_basis`]
 ///
 /// Since the number of rows is large, we do this in one pass, counting the points for each
 /// challenge at each iteration, and laying them out in separate [`PlonkWitness`] at the end.
@@ -1,11 +1,11 @@
-use std::{iter, num::NonZeroUsize, ops::Add};
+use std::{iter, num::NonZeroUsize, ops::Add, sync::Arc};
 
 use itertools::*;
 use tracing::*;
 
 use crate::{
     ff::PrimeField,
-    fft,
+    fft::{DomainCache, EvaluationDomain},
     group::ff::WithSmallOrderMulGroup,
     plonk::{self, eval, GetChallenges, GetWitness, PlonkStructure},
     polynomial::{expression::QueryIndexContext, lagrange, univariate::UnivariatePoly},
@@ -96,12 +96,15 @@ pub(crate) fn compute_F<F: PrimeField>(
         .collect::<Box<[_]>>();
     debug!("betas & deltas ready");
 
-    let challenges_powers = lagrange::iter_cyclic_subgroup::<F>(fft_points_count_F.ilog2())
+    let challenges_powers = ctx
+        .domain_F
+        .elements()
+        .iter()
         .map(|X| {
             betas
                 .iter()
                 .zip_eq(deltas.iter())
-                .map(|(beta, delta)| *beta + (X * delta))
+                .map(|(beta, delta)| *beta + (*X * delta))
                 .collect::<Box<_>>()
         })
         .collect::<Box<[_]>>();
@@ -167,7 +170,7 @@ pub(crate) fn compute_F<F: PrimeField>(
 
     match evaluated {
         Some(Ok(Node::Calculated { mut points, .. })) => {
-            fft::ifft(&mut points);
+            ctx.domain_F.ifft(&mut points);
             Ok(UnivariatePoly(points))
         }
         Some(Err(err)) => Err(err.into()),
@@ -188,12 +191,29 @@ pub struct PolyContext<'s, F: PrimeField> {
     fft_points_count_G: usize,
     /// Number of calculations, padding with zeros to the nearest power of two
     count_of_evaluation_with_padding: usize,
+    /// Domain of [`PolyContext::fft_points_count_F`] points, used in [`compute_F`]
+    domain_F: Arc<EvaluationDomain<F>>,
+    /// Domain of [`PolyContext::fft_log_domain_size_G`], used in [`compute_G`]
+    domain_G: Arc<EvaluationDomain<F>>,
+    /// Domain of [`PolyContext::fft_log_domain_size_K`], used in [`compute_K`]
+    domain_K: Arc<EvaluationDomain<F>>,
+    /// Domain of [`PolyContext::lagrange_domain`], used to fold traces
+    domain_L: Arc<EvaluationDomain<F>>,
 }
 
 impl<'s, F: PrimeField> PolyContext<'s, F> {
     pub fn new(
         S: &'s PlonkStructure<F>,
         traces: &[(impl Sync + GetChallenges<F> + GetWitness<F>)],
+    ) -> Self {
+        Self::with_domains(S, &DomainCache::default(), traces)
+    }
+
+    /// Takes domains from `domains`, so they are shared between folds
+    pub fn with_domains(
+        S: &'s PlonkStructure<F>,
+        domains: &DomainCache<F>,
+        traces: &[(impl Sync + GetChallenges<F> + GetWitness<F>)],
     ) -> Self {
         let count_of_evaluation = get_count_of_valuation_with_padding(S).unwrap().get();
 
@@ -202,11 +222,22 @@ impl<'s, F: PrimeField> PolyContext<'s, F> {
 
         let fft_points_count_G = get_points_count(S, traces.len());
 
+        let betas_count = count_of_evaluation.ilog2() as usize;
+        let fft_points_count_F = (betas_count + 1).next_power_of_two();
+        let fft_log_domain_size_K = fft_points_count_G
+            .add(1)
+            .saturating_sub(instances_to_fold)
+            .next_power_of_two() as u32;
+
         Self {
             S,
             instances_to_fold,
             fft_points_count_G,
             count_of_evaluation_with_padding: count_of_evaluation,
+            domain_F: domains.get(fft_points_count_F.ilog2()),
+            domain_G: domains.get(fft_points_count_G.ilog2()),
+            domain_K: domains.get(fft_log_domain_size_K),
+            domain_L: domains.get(instances_to_fold.ilog2()),
         }
     }
 
@@ -233,10 +264,11 @@ impl<'s, F: PrimeField> PolyContext<'s, F> {
     }
 
     pub fn fft_log_domain_size_K(&self) -> u32 {
-        self.fft_points_count_G
-            .add(1)
-            .saturating_sub(self.instances_to_fold)
-            .next_power_of_two() as u32
+        self.domain_K.k()
+    }
+
+    pub fn domain_L(&self) -> &EvaluationDomain<F> {
+        &self.domain_L
     }
 }
 
@@ -290,10 +322,6 @@ pub(crate) fn compute_G<F: PrimeField>(
     let betas_stroke = betas_stroke.take(ctx.betas_count()).collect::<Box<[_]>>();
     assert_eq!(ctx.betas_count(), betas_stroke.len());
 
-    let points_for_fft = lagrange::iter_cyclic_subgroup(ctx.fft_log_domain_size_G())
-        .take(ctx.fft_points_count_G)
-        .collect::<Box<[_]>>();
-
     /// Auxiliary wrapper for using the tree to evaluate polynomials
     #[derive(Debug)]
     struct Node<F: PrimeField> {
@@ -302,7 +330,7 @@ pub(crate) fn compute_G<F: PrimeField>(
     }
 
     let evaluated =
-        FoldedWitness::new(&points_for_fft, ctx.lagrange_domain(), accumulator, traces)
+        FoldedWitness::new(ctx.domain_G.elements(), &ctx.domain_L, accumulator, traces)
         .iter() // folded witness iter per each X
         .map(|folded_trace| plonk::iter_evaluate_witness::<F>(ctx.S, folded_trace)
             .chain(iter::repeat(Ok(F::ZERO)))
@@ -340,7 +368,7 @@ pub(crate) fn compute_G<F: PrimeField>(
         Some(Ok(Node {
             values: mut points, ..
         })) => {
-            fft::ifft(&mut points);
+            ctx.domain_G.ifft(&mut points);
             Ok(UnivariatePoly(points))
         }
         Some(Err(err)) => Err(err.into()),
@@ -398,35 +426,37 @@ fn compute_K_from_G<F: WithSmallOrderMulGroup<3>>(
     poly_G: UnivariatePoly<F>,
     poly_F_in_alpha: F,
 ) -> UnivariatePoly<F> {
-    UnivariatePoly::coset_ifft(
-        lagrange::iter_cyclic_subgroup::<F>(ctx.fft_log_domain_size_K())
-            .map(|X| F::ZETA * X)
-            // TODO #293
-            //.zip(poly_G.coset_fft())
-            //.map(|(X, poly_G_in_X)| {
-            .map(|X| {
-                let poly_G_in_X = poly_G.eval(X);
-
-                let poly_L0_in_X =
-                    lagrange::iter_eval_lagrange_poly_for_cyclic_group(X, ctx.lagrange_domain())
-                        .next()
-                        .unwrap();
-
-                // Z(X) == 0, for X in coset_cyclic_subgroup
-                let poly_Z_in_X = lagrange::eval_vanish_polynomial(ctx.instances_to_fold, X);
-
-                let poly_K_in_X = (poly_G_in_X - (poly_F_in_alpha * poly_L0_in_X))
-                    * poly_Z_in_X.invert().expect("Z(X) must be not equal to 0");
+    let mut points = ctx
+        .domain_K
+        .iter_coset_elements()
+        // TODO #293
+        //.zip(poly_G.coset_fft())
+        //.map(|(X, poly_G_in_X)| {
+        .map(|X| {
+            let poly_G_in_X = poly_G.eval(X);
 
-                assert_eq!(
-                    (poly_F_in_alpha * poly_L0_in_X) + (poly_Z_in_X * poly_K_in_X),
-                    poly_G_in_X
-                );
+            let poly_L0_in_X =
+                lagrange::iter_eval_lagrange_poly_for_cyclic_group(X, ctx.lagrange_domain())
+                    .next()
+                    .unwrap();
 
-                poly_K_in_X
-            })
-            .collect::<Box<[_]>>(),
-    )
+            // Z(X) == 0, for X in coset_cyclic_subgroup
+            let poly_Z_in_X = ctx.domain_L.eval_vanishing(X);
+
+            let poly_K_in_X = (poly_G_in_X - (poly_F_in_alpha * poly_L0_in_X))
+                * poly_Z_in_X.invert().expect("Z(X) must be not equal to 0");
+
+            assert_eq!(
+                (poly_F_in_alpha * poly_L0_in_X) + (poly_Z_in_X * poly_K_in_X),
+                poly_G_in_X
+            );
+
+            poly_K_in_X
+        })
+        .collect::<Box<[_]>>();
+
+    ctx.domain_K.coset_ifft(&mut points);
+    UnivariatePoly(points)
 }
 
 fn get_count_of_valuation<F: PrimeField>(S: &PlonkStructure<F>) -> Option<NonZeroUsize> {
@@ -628,37 +658,32 @@ mod test {
         let points_for_fft =
             lagrange::iter_cyclic_subgroup(ctx.fft_log_domain_size_G()).collect::<Box<[_]>>();
 
-        FoldedWitness::new(
-            &points_for_fft,
-            ctx.lagrange_domain(),
-            &accumulator,
-            &traces,
-        )
-        .iter()
-        .map(|folded_trace| {
-            plonk::iter_evaluate_witness::<Field>(&S, folded_trace)
-                .chain(iter::repeat(Ok(Field::ZERO)))
-                .take(ctx.count_of_evaluation_with_padding)
-        })
-        .zip(points_for_fft.iter().copied().chain(gen.take(10)))
-        .for_each(|(folded_witness, X)| {
-            let result_with_direct_algo = folded_witness
-                .enumerate()
-                .map(|(index, f_i)| {
-                    pow_i(
-                        index,
-                        ctx.count_of_evaluation_with_padding,
-                        beta_stroke.iter(),
-                    ) * f_i.unwrap()
-                })
-                .sum();
+        FoldedWitness::new(&points_for_fft, ctx.domain_L(), &accumulator, &traces)
+            .iter()
+            .map(|folded_trace| {
+                plonk::iter_evaluate_witness::<Field>(&S, folded_trace)
+                    .chain(iter::repeat(Ok(Field::ZERO)))
+                    .take(ctx.count_of_evaluation_with_padding)
+            })
+            .zip(points_for_fft.iter().copied().chain(gen.take(10)))
+            .for_each(|(folded_witness, X)| {
+                let result_with_direct_algo = folded_witness
+                    .enumerate()
+                    .map(|(index, f_i)| {
+                        pow_i(
+                            index,
+                            ctx.count_of_evaluation_with_padding,
+                            beta_stroke.iter(),
+                        ) * f_i.unwrap()
+                    })
+                    .sum();
 
-            assert_eq!(
-                evaluated_poly_G.eval(X),
-                result_with_direct_algo,
-                "for {X:?}"
-            );
-        });
+                assert_eq!(
+                    evaluated_poly_G.eval(X),
+                    result_with_direct_algo,
+                    "for {X:?}"
+                );
+            });
     }
 
     pub fn vanish_poly<F: PrimeField>(degree: usize) -> UnivariatePoly<F> {
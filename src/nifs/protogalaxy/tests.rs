@@ -6,6 +6,7 @@ use halo2_proofs::{
     },
     plonk::Circuit,
 };
+use rand_core::OsRng;
 use tracing::info_span;
 use tracing_test::traced_test;
 
@@ -14,12 +15,16 @@ use crate::{
     commitment,
     halo2curves::bn256::G1Affine as Affine,
     nifs::tests::{
+        challenge_circuit::{ChallengeCircuit, SamePhaseChallengesCircuit},
+        dynamic_table_circuit::MemoryCircuit,
         fibo_circuit::{get_fibo_seq, FiboCircuit},
         fibo_circuit_with_lookup::{get_sequence, FiboCircuitWithLookup},
         random_linear_combination_circuit::RandomLinearCombinationCircuit,
+        range_check_circuit::RangeCheckCircuit,
+        shuffle_circuit::ShuffleCircuit,
     },
     poseidon::{PoseidonHash, Spec},
-    table::{CircuitRunner, Witness},
+    table::CircuitRunner,
 };
 
 const T: usize = 3;
@@ -32,7 +37,6 @@ type Scalar = <Affine as CurveAffine>::ScalarExt;
 type Base = <Affine as CurveAffine>::Base;
 
 type RO<F> = PoseidonHash<F, T, RATE>;
-type Instance<F> = Vec<F>;
 
 type ProtoGalaxy = crate::nifs::protogalaxy::ProtoGalaxy<Affine, L>;
 type ProverParam = <ProtoGalaxy as FoldingScheme<Affine, L>>::ProverParam;
@@ -40,30 +44,15 @@ type VerifierParam = <ProtoGalaxy as FoldingScheme<Affine, L>>::VerifierParam;
 type Proof = <ProtoGalaxy as FoldingScheme<Affine, L>>::Proof;
 type Accumulator = <ProtoGalaxy as FoldingScheme<Affine, L>>::Accumulator;
 
-struct CircuitCtx {
-    witness: Witness<Scalar>,
-    instances: Vec<Instance<Scalar>>,
-}
-
-impl CircuitCtx {
-    fn collect<CIR: Circuit<Scalar>>(cr: CircuitRunner<Scalar, CIR>) -> Self {
-        Self {
-            witness: cr.try_collect_witness().expect("failed to collect witness"),
-            instances: cr.instances,
-        }
-    }
-}
-
 struct Mock<CIRCUIT: Circuit<Scalar>> {
     S: PlonkStructure<Scalar>,
     ck: CommitmentKey<Affine>,
 
-    circuits_ctx: [CircuitCtx; L],
+    /// Runners are used as the source of witness, to support multi-phase circuits
+    circuits_runners: [CircuitRunner<Scalar, CIRCUIT>; L],
 
     pp: ProverParam,
     vp: VerifierParam,
-
-    _p: PhantomData<CIRCUIT>,
 }
 
 fn ro<F: PrimeFieldBits + FromUniformBytes<64>>() -> PoseidonHash<F, T, RATE> {
@@ -95,24 +84,23 @@ impl<C: Circuit<Scalar>> Mock<C> {
 
         Mock {
             ck,
-            circuits_ctx: circuits_runners.map(CircuitCtx::collect),
+            circuits_runners,
             pp,
             vp,
             S,
-            _p: PhantomData,
         }
     }
 
     pub fn generate_plonk_traces(&mut self) -> [PlonkTrace<Affine>; L] {
         let mut generate_ro = ro();
         let mut is_sat_ro = ro();
-        self.circuits_ctx
+        self.circuits_runners
             .iter()
-            .map(|ctx| {
+            .map(|runner| {
                 ProtoGalaxy::generate_plonk_trace(
                     &self.ck,
-                    &ctx.instances,
-                    &ctx.witness,
+                    &runner.instances,
+                    runner,
                     &self.pp,
                     &mut generate_ro,
                 )
@@ -132,7 +120,8 @@ impl<C: Circuit<Scalar>> Mock<C> {
         let incoming = self.generate_plonk_traces();
 
         let init_accumulator =
-            ProtoGalaxy::new_accumulator(AccumulatorArgs::from(&self.S), &self.pp, &mut ro());
+            ProtoGalaxy::new_accumulator(AccumulatorArgs::from(&self.S), &self.pp, &mut ro())
+                .expect("failed to create accumulator");
 
         ProtoGalaxy::is_sat_accumulation(&self.S, &init_accumulator)
             .expect("The newly created accumulator is not satisfactory");
@@ -147,9 +136,9 @@ impl<C: Circuit<Scalar>> Mock<C> {
         .expect("`protogalaxy::prove` failed");
 
         let instances = self
-            .circuits_ctx
+            .circuits_runners
             .iter()
-            .map(|ctx| ctx.instances.clone())
+            .map(|runner| runner.instances.clone())
             .collect::<Box<[_]>>();
 
         ProtoGalaxy::is_sat(&self.ck, &self.S, &accumulator_from_prove, &instances)
@@ -174,6 +163,91 @@ impl<C: Circuit<Scalar>> Mock<C> {
         assert_eq!(accumulator_inst_from_prove.e, accumulator_from_verify.e,);
         assert_eq!(accumulator_inst_from_prove.ins, accumulator_from_verify.ins);
     }
+
+    /// Public instances of all circuits, in the order of the incoming traces
+    fn instances(&self) -> Box<[Vec<Vec<Scalar>>]> {
+        self.circuits_runners
+            .iter()
+            .map(|runner| runner.instances.clone())
+            .collect()
+    }
+
+    /// Builds two accumulators independently, folding traces in direct & reversed order, so
+    /// betas differ
+    fn prove_two_accumulators(&mut self) -> [Accumulator; 2] {
+        let incoming = self.generate_plonk_traces();
+
+        let init_accumulator =
+            ProtoGalaxy::new_accumulator(AccumulatorArgs::from(&self.S), &self.pp, &mut ro())
+                .expect("failed to create accumulator");
+
+        let mut reversed = incoming.clone();
+        reversed.reverse();
+
+        let accumulators = [incoming, reversed].map(|incoming| {
+            ProtoGalaxy::prove(
+                &self.ck,
+                &self.pp,
+                &mut ro(),
+                init_accumulator.clone(),
+                &incoming,
+            )
+            .expect("`protogalaxy::prove` failed")
+            .0
+        });
+        assert_ne!(accumulators[0].betas, accumulators[1].betas);
+
+        accumulators
+    }
+
+    /// Merges two accumulators of [`Mock::prove_two_accumulators`] with [`FoldAccumulators`]
+    pub fn run_fold_accumulators(mut self) {
+        let [lhs, rhs] = self.prove_two_accumulators();
+        let instances = self.instances();
+
+        let (accumulator_from_prove, proof) =
+            ProtoGalaxy::fold_accumulators(&self.ck, &self.pp, &mut ro(), lhs.clone(), &rhs)
+                .expect("`protogalaxy::fold_accumulators` failed");
+
+        ProtoGalaxy::is_sat(&self.ck, &self.S, &accumulator_from_prove, &instances)
+            .expect("The accumulator after calling `fold_accumulators` is not satisfactory");
+
+        let accumulator_from_verify = ProtoGalaxy::verify_fold_accumulators(
+            &self.vp,
+            &mut ro(),
+            &lhs.into(),
+            &rhs.into(),
+            &proof,
+        )
+        .unwrap();
+
+        assert_eq!(
+            AccumulatorInstance::from(accumulator_from_prove),
+            accumulator_from_verify
+        );
+    }
+
+    /// Checks several accumulators with [`IsSatAccumulatorBatch`] & makes sure that a broken one
+    /// is identified by its index
+    pub fn run_is_sat_batch(mut self) {
+        let mut accumulators = self.prove_two_accumulators();
+        let instances = self.instances();
+        let pub_instances = [&*instances, &*instances];
+
+        ProtoGalaxy::is_sat_batch(&self.ck, &self.S, &accumulators, &pub_instances, OsRng)
+            .expect("The accumulators are not satisfactory");
+
+        accumulators[1].trace.w.W[0][0] += Scalar::ONE;
+
+        let failed =
+            ProtoGalaxy::is_sat_batch(&self.ck, &self.S, &accumulators, &pub_instances, OsRng)
+                .expect_err("The broken accumulator is satisfactory");
+
+        assert_eq!(
+            failed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            [1]
+        );
+    }
 }
 
 #[traced_test]
@@ -299,3 +373,267 @@ fn fibo_lookup() {
     )
     .run();
 }
+
+#[traced_test]
+#[test]
+fn fold_accumulators() {
+    Mock::new(
+        10,
+        [(1..10, 2, 4097), (1..10, 2, 4097), (2..11, 3, 93494)].map(|(inputs, r, output)| {
+            (
+                RandomLinearCombinationCircuit::new(
+                    inputs.map(Scalar::from).collect(),
+                    Scalar::from(r),
+                ),
+                vec![Scalar::from(output)],
+            )
+        }),
+    )
+    .run_fold_accumulators();
+}
+
+#[traced_test]
+#[test]
+fn is_sat_batch() {
+    const SIZE: usize = 7;
+
+    Mock::new(
+        10,
+        [(1, 3), (3, 2), (3, 2)].map(|(a, b)| {
+            let seq = get_sequence(a, b, 2, SIZE);
+            (
+                FiboCircuitWithLookup {
+                    a: Scalar::from(seq[0]),
+                    b: Scalar::from(seq[1]),
+                    c: Scalar::from(seq[2]),
+                    num: SIZE,
+                },
+                vec![Scalar::ONE],
+            )
+        }),
+    )
+    .run_is_sat_batch();
+}
+
+#[traced_test]
+#[test]
+fn range_check_lookup() {
+    let _s = info_span!("range_check_lookup").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                RangeCheckCircuit::new((0..20).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                RangeCheckCircuit::new((100..120).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                RangeCheckCircuit::new((0..20).map(|v| Scalar::from(v * 12)).collect()),
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn dynamic_table_lookup() {
+    let _s = info_span!("dynamic_table_lookup").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                MemoryCircuit {
+                    memory: (10..20).map(Scalar::from).collect(),
+                    reads: vec![0, 3, 3, 9, 5],
+                },
+                vec![],
+            ),
+            (
+                MemoryCircuit {
+                    memory: (0..10).map(|v| Scalar::from(v * v)).collect(),
+                    reads: vec![1, 1, 2, 8, 7, 0],
+                },
+                vec![],
+            ),
+            (
+                MemoryCircuit {
+                    memory: (0..16).map(Scalar::from).collect(),
+                    reads: vec![15, 14, 13],
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn shuffle() {
+    let _s = info_span!("shuffle").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                ShuffleCircuit::reversed((0..20).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                ShuffleCircuit::reversed((10..40).map(Scalar::from).collect()),
+                vec![],
+            ),
+            (
+                ShuffleCircuit {
+                    input: vec![1, 2, 2, 3].into_iter().map(Scalar::from).collect(),
+                    shuffled: vec![2, 3, 1, 2].into_iter().map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn shuffle_not_sat() {
+    const K: u32 = 10;
+
+    let runner = CircuitRunner::new(
+        K,
+        ShuffleCircuit {
+            input: vec![1, 2, 2, 3].into_iter().map(Scalar::from).collect(),
+            shuffled: vec![1, 2, 3, 3].into_iter().map(Scalar::from).collect(),
+        },
+        vec![],
+    );
+
+    let ck = commitment::setup_smallest_key(K, &runner.cs, b"");
+    let S = runner.try_collect_plonk_structure().unwrap();
+    assert_eq!(S.num_shuffles(), 1);
+
+    let trace = S
+        .run_sps_protocol(
+            &ck,
+            &[],
+            &runner.try_collect_witness().unwrap(),
+            &mut ro(),
+            S.num_challenges,
+        )
+        .unwrap();
+
+    assert_eq!(
+        S.is_sat(&ck, &mut ro(), &trace.u, &trace.w),
+        Err(plonk::Error::LogDerivativeNotSat)
+    );
+}
+
+#[traced_test]
+#[test]
+fn challenge() {
+    let _s = info_span!("challenge").entered();
+
+    Mock::new(
+        10,
+        [
+            (
+                ChallengeCircuit {
+                    inputs: (0..20).map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+            (
+                ChallengeCircuit {
+                    inputs: (10..40).map(Scalar::from).collect(),
+                },
+                vec![],
+            ),
+            (
+                ChallengeCircuit {
+                    inputs: vec![Scalar::from(42)],
+                },
+                vec![],
+            ),
+        ],
+    )
+    .run();
+}
+
+#[traced_test]
+#[test]
+fn challenge_rounds() {
+    const K: u32 = 10;
+
+    let runner = CircuitRunner::new(
+        K,
+        ChallengeCircuit {
+            inputs: (0..20).map(Scalar::from).collect(),
+        },
+        vec![],
+    );
+
+    let ck = commitment::setup_smallest_key(K, &runner.cs, b"");
+    let S = runner.try_collect_plonk_structure().unwrap();
+    // circuit challenge & gates compression
+    assert_eq!(S.num_challenges, 2);
+    assert_eq!(S.round_sizes, vec![1 << K, 1 << K]);
+
+    // second phase depends on challenge, so it can't be collected in advance
+    assert_eq!(
+        S.run_sps_protocol(
+            &ck,
+            &[],
+            &runner.try_collect_witness().unwrap(),
+            &mut ro(),
+            S.num_challenges,
+        )
+        .err(),
+        Some(sps::Error::WitnessDependsOnChallenges { phase: 1 })
+    );
+
+    let trace = S
+        .run_sps_protocol(&ck, &[], &runner, &mut ro(), S.num_challenges)
+        .unwrap();
+    assert_eq!(trace.u.W_commitments.len(), 2);
+    assert_eq!(trace.u.challenges.len(), 2);
+
+    S.is_sat(&ck, &mut ro(), &trace.u, &trace.w).unwrap();
+}
+
+#[traced_test]
+#[test]
+fn same_phase_challenges_rounds() {
+    const K: u32 = 10;
+
+    let runner = CircuitRunner::new(
+        K,
+        SamePhaseChallengesCircuit {
+            inputs: (0..20).map(Scalar::from).collect(),
+        },
+        vec![],
+    );
+
+    let ck = commitment::setup_smallest_key(K, &runner.cs, b"");
+    let S = runner.try_collect_plonk_structure().unwrap();
+    // three circuit challenges (including unused one) & gates compression
+    assert_eq!(S.num_challenges, 4);
+    // the first phase is committed before the first challenge, rounds of other challenges of
+    // the same phase are empty
+    assert_eq!(S.round_sizes, vec![1 << K, 0, 0, 2 << K]);
+
+    let trace = S
+        .run_sps_protocol(&ck, &[], &runner, &mut ro(), S.num_challenges)
+        .unwrap();
+    assert_eq!(trace.u.W_commitments.len(), 4);
+    assert_eq!(trace.u.challenges.len(), 4);
+
+    S.is_sat(&ck, &mut ro(), &trace.u, &trace.w).unwrap();
+}
@@ -506,3 +506,620 @@ pub(crate) mod fibo_circuit_with_lookup {
         seq
     }
 }
+
+// test non-vector lookup
+pub(crate) mod range_check_circuit {
+    use halo2_proofs::plonk::TableColumn;
+
+    use super::*;
+
+    /// All values are checked to be in `[0, 2^RANGE_BITS)`
+    pub const RANGE_BITS: usize = 8;
+
+    #[derive(Debug, Clone)]
+    pub struct RangeCheckConfig {
+        value: Column<Advice>,
+        s_range: Selector,
+        range_table: TableColumn,
+    }
+
+    #[derive(Default)]
+    pub struct RangeCheckCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: PrimeField> RangeCheckCircuit<F> {
+        pub fn new(values: Vec<F>) -> Self {
+            Self { values }
+        }
+    }
+
+    impl<F: PrimeField> Circuit<F> for RangeCheckCircuit<F> {
+        type Config = RangeCheckConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let s_range = meta.complex_selector();
+            let range_table = meta.lookup_table_column();
+
+            meta.lookup("range", |meta| {
+                let s_range = meta.query_selector(s_range);
+                let value = meta.query_advice(value, Rotation::cur());
+                vec![(s_range * value, range_table)]
+            });
+
+            RangeCheckConfig {
+                value,
+                s_range,
+                range_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            layouter.assign_region(
+                || "values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .try_for_each(|(offset, value)| {
+                            config.s_range.enable(&mut region, offset)?;
+                            region.assign_advice(
+                                || "value",
+                                config.value,
+                                offset,
+                                || Value::known(*value),
+                            )?;
+                            Ok(())
+                        })
+                },
+            )?;
+
+            layouter.assign_table(
+                || "range",
+                |mut table| {
+                    (0..1u64 << RANGE_BITS).try_for_each(|value| {
+                        table.assign_cell(
+                            || "range",
+                            config.range_table,
+                            value as usize,
+                            || Value::known(F::from(value)),
+                        )
+                    })
+                },
+            )
+        }
+    }
+}
+
+// test vector lookup into advice columns (dynamic table)
+pub(crate) mod dynamic_table_circuit {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct MemoryConfig {
+        /// `(address, value)` of written cells, used as a dynamic table
+        write: [Column<Advice>; 2],
+        /// `(address, value)` of read cells, looked up in `write`
+        read: [Column<Advice>; 2],
+        s_write: Selector,
+        s_read: Selector,
+    }
+
+    /// Writes `memory` into the table and checks that every read from `reads` is consistent with
+    /// the written memory
+    #[derive(Default)]
+    pub struct MemoryCircuit<F> {
+        pub memory: Vec<F>,
+        pub reads: Vec<usize>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for MemoryCircuit<F> {
+        type Config = MemoryConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let write = [meta.advice_column(), meta.advice_column()];
+            let read = [meta.advice_column(), meta.advice_column()];
+            let s_write = meta.complex_selector();
+            let s_read = meta.complex_selector();
+
+            meta.lookup_any("memory", |meta| {
+                let s_write = meta.query_selector(s_write);
+                let s_read = meta.query_selector(s_read);
+
+                read.iter()
+                    .zip(write.iter())
+                    .map(|(read, write)| {
+                        (
+                            s_read.clone() * meta.query_advice(*read, Rotation::cur()),
+                            s_write.clone() * meta.query_advice(*write, Rotation::cur()),
+                        )
+                    })
+                    .collect()
+            });
+
+            MemoryConfig {
+                write,
+                read,
+                s_write,
+                s_read,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            layouter.assign_region(
+                || "memory",
+                |mut region| {
+                    for (address, value) in self.memory.iter().enumerate() {
+                        config.s_write.enable(&mut region, address)?;
+                        region.assign_advice(
+                            || "write address",
+                            config.write[0],
+                            address,
+                            || Value::known(F::from(address as u64)),
+                        )?;
+                        region.assign_advice(
+                            || "write value",
+                            config.write[1],
+                            address,
+                            || Value::known(*value),
+                        )?;
+                    }
+
+                    for (offset, address) in self.reads.iter().enumerate() {
+                        config.s_read.enable(&mut region, offset)?;
+                        region.assign_advice(
+                            || "read address",
+                            config.read[0],
+                            offset,
+                            || Value::known(F::from(*address as u64)),
+                        )?;
+                        region.assign_advice(
+                            || "read value",
+                            config.read[1],
+                            offset,
+                            || Value::known(self.memory[*address]),
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+}
+
+// test shuffle argument
+pub(crate) mod shuffle_circuit {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct ShuffleConfig {
+        input: Column<Advice>,
+        shuffled: Column<Advice>,
+        s_shuffle: Selector,
+    }
+
+    /// Checks that `shuffled` is a permutation of `input`
+    #[derive(Default)]
+    pub struct ShuffleCircuit<F> {
+        pub input: Vec<F>,
+        pub shuffled: Vec<F>,
+    }
+
+    impl<F: PrimeField> ShuffleCircuit<F> {
+        /// Creates a circuit where `shuffled` is `input` in reverse order
+        pub fn reversed(input: Vec<F>) -> Self {
+            Self {
+                shuffled: input.iter().rev().copied().collect(),
+                input,
+            }
+        }
+    }
+
+    impl<F: PrimeField> Circuit<F> for ShuffleCircuit<F> {
+        type Config = ShuffleConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let shuffled = meta.advice_column();
+            let s_shuffle = meta.complex_selector();
+
+            meta.shuffle("shuffle", |meta| {
+                let s_shuffle = meta.query_selector(s_shuffle);
+                vec![(
+                    s_shuffle.clone() * meta.query_advice(input, Rotation::cur()),
+                    s_shuffle * meta.query_advice(shuffled, Rotation::cur()),
+                )]
+            });
+
+            ShuffleConfig {
+                input,
+                shuffled,
+                s_shuffle,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            layouter.assign_region(
+                || "shuffle",
+                |mut region| {
+                    self.input
+                        .iter()
+                        .zip(self.shuffled.iter())
+                        .enumerate()
+                        .try_for_each(|(offset, (input, shuffled))| {
+                            config.s_shuffle.enable(&mut region, offset)?;
+                            region.assign_advice(
+                                || "input",
+                                config.input,
+                                offset,
+                                || Value::known(*input),
+                            )?;
+                            region.assign_advice(
+                                || "shuffled",
+                                config.shuffled,
+                                offset,
+                                || Value::known(*shuffled),
+                            )?;
+                            Ok(())
+                        })
+                },
+            )
+        }
+    }
+}
+
+// test multi-phase circuit with challenge
+pub(crate) mod challenge_circuit {
+    use halo2_proofs::plonk::{Challenge, FirstPhase, SecondPhase};
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct ChallengeConfig {
+        input: Column<Advice>,
+        acc: Column<Advice>,
+        r: Challenge,
+        s_first: Selector,
+        s_next: Selector,
+    }
+
+    /// Computes `acc_i = acc_{i-1} * r + input_i` for challenge `r` squeezed after `input` is
+    /// committed, i.e. `acc` is a second phase advice column
+    #[derive(Default)]
+    pub struct ChallengeCircuit<F> {
+        pub inputs: Vec<F>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for ChallengeCircuit<F> {
+        type Config = ChallengeConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column_in(FirstPhase);
+            let r = meta.challenge_usable_after(FirstPhase);
+            let acc = meta.advice_column_in(SecondPhase);
+            let s_first = meta.selector();
+            let s_next = meta.selector();
+
+            meta.create_gate("rlc", |meta| {
+                let s_first = meta.query_selector(s_first);
+                let s_next = meta.query_selector(s_next);
+                let input = meta.query_advice(input, Rotation::cur());
+                let acc_prev = meta.query_advice(acc, Rotation::prev());
+                let acc = meta.query_advice(acc, Rotation::cur());
+                let r = meta.query_challenge(r);
+
+                vec![
+                    s_first * (acc.clone() - input.clone()),
+                    s_next * (acc - (acc_prev * r + input)),
+                ]
+            });
+
+            ChallengeConfig {
+                input,
+                acc,
+                r,
+                s_first,
+                s_next,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            let r = layouter.get_challenge(config.r);
+
+            layouter.assign_region(
+                || "rlc",
+                |mut region| {
+                    let mut acc = Value::known(F::ZERO);
+
+                    self.inputs
+                        .iter()
+                        .enumerate()
+                        .try_for_each(|(offset, input)| {
+                            if offset == 0 {
+                                config.s_first.enable(&mut region, offset)?;
+                            } else {
+                                config.s_next.enable(&mut region, offset)?;
+                            }
+
+                            region.assign_advice(
+                                || "input",
+                                config.input,
+                                offset,
+                                || Value::known(*input),
+                            )?;
+
+                            acc = acc * r + Value::known(*input);
+                            region.assign_advice(|| "acc", config.acc, offset, || acc)?;
+
+                            Ok(())
+                        })
+                },
+            )
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct SamePhaseChallengesConfig {
+        input: Column<Advice>,
+        acc_r: Column<Advice>,
+        acc_s: Column<Advice>,
+        r: Challenge,
+        s: Challenge,
+        s_first: Selector,
+        s_next: Selector,
+    }
+
+    /// Same as [`ChallengeCircuit`], but with two accumulators by challenges `r` & `s`, both
+    /// usable after the first phase, & one more challenge, which is declared but not used
+    #[derive(Default)]
+    pub struct SamePhaseChallengesCircuit<F> {
+        pub inputs: Vec<F>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for SamePhaseChallengesCircuit<F> {
+        type Config = SamePhaseChallengesConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column_in(FirstPhase);
+            let r = meta.challenge_usable_after(FirstPhase);
+            let s = meta.challenge_usable_after(FirstPhase);
+            let _unused = meta.challenge_usable_after(FirstPhase);
+            let acc_r = meta.advice_column_in(SecondPhase);
+            let acc_s = meta.advice_column_in(SecondPhase);
+            let s_first = meta.selector();
+            let s_next = meta.selector();
+
+            meta.create_gate("rlc by two challenges", |meta| {
+                let s_first = meta.query_selector(s_first);
+                let s_next = meta.query_selector(s_next);
+                let input = meta.query_advice(input, Rotation::cur());
+
+                [(acc_r, r), (acc_s, s)]
+                    .into_iter()
+                    .flat_map(|(acc, challenge)| {
+                        let acc_prev = meta.query_advice(acc, Rotation::prev());
+                        let acc = meta.query_advice(acc, Rotation::cur());
+                        let challenge = meta.query_challenge(challenge);
+
+                        [
+                            s_first.clone() * (acc.clone() - input.clone()),
+                            s_next.clone() * (acc - (acc_prev * challenge + input.clone())),
+                        ]
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            SamePhaseChallengesConfig {
+                input,
+                acc_r,
+                acc_s,
+                r,
+                s,
+                s_first,
+                s_next,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            let r = layouter.get_challenge(config.r);
+            let s = layouter.get_challenge(config.s);
+
+            layouter.assign_region(
+                || "rlc by two challenges",
+                |mut region| {
+                    let mut acc_r = Value::known(F::ZERO);
+                    let mut acc_s = Value::known(F::ZERO);
+
+                    self.inputs
+                        .iter()
+                        .enumerate()
+                        .try_for_each(|(offset, input)| {
+                            if offset == 0 {
+                                config.s_first.enable(&mut region, offset)?;
+                            } else {
+                                config.s_next.enable(&mut region, offset)?;
+                            }
+
+                            region.assign_advice(
+                                || "input",
+                                config.input,
+                                offset,
+                                || Value::known(*input),
+                            )?;
+
+                            acc_r = acc_r * r + Value::known(*input);
+                            region.assign_advice(|| "acc_r", config.acc_r, offset, || acc_r)?;
+
+                            acc_s = acc_s * s + Value::known(*input);
+                            region.assign_advice(|| "acc_s", config.acc_s, offset, || acc_s)?;
+
+                            Ok(())
+                        })
+                },
+            )
+        }
+    }
+}
+
+/// Common harness of folding schemes tests
+///
+/// Collects [`PlonkStructure`] & params of the scheme by the first of `L` circuits, while their
+/// runners are used as the source of witness, to support multi-phase circuits
+pub(crate) mod mock {
+    use std::fmt;
+
+    use halo2_proofs::{
+        arithmetic::CurveAffine,
+        dev::MockProver,
+        halo2curves::{ff::PrimeFieldBits, group::prime::PrimeCurveAffine},
+    };
+
+    use super::*;
+    use crate::{
+        commitment::{self, CommitmentKey},
+        halo2curves::bn256::G1Affine as Affine,
+        nifs::FoldingScheme,
+        plonk::{PlonkStructure, PlonkTrace},
+        poseidon::{PoseidonHash, Spec},
+        table::CircuitRunner,
+    };
+
+    const T: usize = 3;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    pub type Scalar = <Affine as CurveAffine>::ScalarExt;
+
+    pub fn ro<F: PrimeFieldBits + FromUniformBytes<64>>() -> PoseidonHash<F, T, RATE> {
+        PoseidonHash::<F, T, RATE>::new(Spec::<F, T, RATE>::new(R_F, R_P))
+    }
+
+    pub struct Mock<FS: FoldingScheme<Affine, L>, CIRCUIT: Circuit<Scalar>, const L: usize> {
+        pub S: PlonkStructure<Scalar>,
+        pub ck: CommitmentKey<Affine>,
+
+        pub circuits_runners: [CircuitRunner<Scalar, CIRCUIT>; L],
+
+        pub pp: FS::ProverParam,
+        pub vp: FS::VerifierParam,
+    }
+
+    impl<FS, C, const L: usize> Mock<FS, C, L>
+    where
+        FS: FoldingScheme<Affine, L, Trace = PlonkTrace<Affine>>,
+        FS::Error: fmt::Debug,
+        C: Circuit<Scalar>,
+    {
+        pub fn new(k_table_size: u32, circuits: [(C, Vec<Scalar>); L]) -> Self {
+            let circuits_runners = circuits.map(|(circuit, instance)| {
+                let instances = if instance.is_empty() {
+                    vec![]
+                } else {
+                    vec![instance.clone()]
+                };
+                MockProver::run(k_table_size, &circuit, instances.clone())
+                    .unwrap()
+                    .verify()
+                    .unwrap();
+
+                CircuitRunner::new(k_table_size, circuit, instances)
+            });
+
+            let ck = commitment::setup_smallest_key(k_table_size, &circuits_runners[0].cs, b"");
+            let S = circuits_runners[0]
+                .try_collect_plonk_structure()
+                .expect("failed to collect plonk structure");
+
+            let (pp, vp) = FS::setup_params(Affine::identity(), S.clone()).unwrap();
+
+            Mock {
+                ck,
+                circuits_runners,
+                pp,
+                vp,
+                S,
+            }
+        }
+
+        pub fn generate_plonk_traces(&mut self) -> [PlonkTrace<Affine>; L] {
+            let mut generate_ro = ro();
+            let mut is_sat_ro = ro();
+            self.circuits_runners
+                .iter()
+                .map(|runner| {
+                    FS::generate_plonk_trace(
+                        &self.ck,
+                        &runner.instances,
+                        runner,
+                        &self.pp,
+                        &mut generate_ro,
+                    )
+                    .unwrap()
+                })
+                .inspect(|trace| {
+                    self.S
+                        .is_sat(&self.ck, &mut is_sat_ro, &trace.u, &trace.w)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        }
+
+        /// Public instances of all circuits, in the order of the incoming traces
+        pub fn instances(&self) -> Box<[Vec<Vec<Scalar>>]> {
+            self.circuits_runners
+                .iter()
+                .map(|runner| runner.instances.clone())
+                .collect()
+        }
+    }
+}
@@ -183,6 +183,72 @@ where
         }
     }
 
+    /// Folds two `RelaxedPlonkInstance`s while preserving their relaxed Plonk relation.
+    ///
+    /// Unlike [`RelaxedPlonkInstance::fold`], `U2` is relaxed too: `u = u1 + r * u2` and the
+    /// commitment of `E2` is used as the last cross term, so `cross_term_commits` contains
+    /// `Comm(T_1), ..., Comm(T_{d-1})` only.
+    ///
+    /// Step circuit instances of `U2` are already hashed, so its hash accumulator is absorbed
+    /// into the one of `self`, making the accumulation of instances tree-shaped as well.
+    #[instrument(name = "fold_relaxed_plonk_instance", skip_all)]
+    pub fn fold_relaxed(
+        &self,
+        U2: &RelaxedPlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Self {
+        let ecc_add_mul = |lhs: &C, rhs: &C, scalar: C::ScalarExt| -> C {
+            (*lhs + best_multiexp(&[scalar], &[*rhs]).into()).into()
+        };
+        let fe_add_mul = |lhs: &C::ScalarExt, rhs: &C::ScalarExt| *lhs + *r * rhs;
+
+        let W_commitments = self
+            .W_commitments
+            .iter()
+            .zip_eq(U2.W_commitments.iter())
+            .map(|(W1, W2)| ecc_add_mul(W1, W2, *r))
+            .collect();
+
+        let consistency_markers = [0, 1].map(|index| {
+            fe_add_mul(
+                &self.consistency_markers[index],
+                &U2.consistency_markers[index],
+            )
+        });
+
+        let challenges = self
+            .challenges
+            .iter()
+            .zip_eq(U2.challenges.iter())
+            .map(|(a, b)| fe_add_mul(a, b))
+            .collect();
+
+        // r^1, ..., r^{d-1} for cross terms & r^d for `E2`
+        let comm_E = cross_term_commits
+            .iter()
+            .chain(iter::once(&U2.E_commitment))
+            .zip(iter::successors(Some(*r), |el| Some(*el * *r)))
+            .fold(self.E_commitment, |acc, (tk, power_of_r)| {
+                ecc_add_mul(&acc, tk, power_of_r)
+            });
+
+        let step_circuit_instances_hash_accumulator =
+            instances_accumulator_computation::absorb_in_sc_instances_accumulator::<C>(
+                &self.step_circuit_instances_hash_accumulator,
+                &[vec![U2.step_circuit_instances_hash_accumulator]],
+            );
+
+        RelaxedPlonkInstance {
+            W_commitments,
+            consistency_markers,
+            challenges,
+            E_commitment: comm_E,
+            u: fe_add_mul(&self.u, &U2.u),
+            step_circuit_instances_hash_accumulator,
+        }
+    }
+
     pub fn instances(&self) -> Vec<Vec<C::ScalarExt>> {
         vec![self.consistency_markers.to_vec()]
     }
@@ -336,6 +402,30 @@ impl<F: PrimeField> RelaxedPlonkWitness<F> {
     }
 }
 
+impl<F: PrimeField> RelaxedPlonkWitness<F> {
+    /// Folds two relaxed witnesses, see [`RelaxedPlonkInstance::fold_relaxed`]
+    ///
+    /// `cross_terms` are `T_1, ..., T_{d-1}`, the last one is `E` of `W2`
+    #[instrument(name = "fold_relaxed_witness", skip_all)]
+    pub fn fold_relaxed(
+        &self,
+        W2: &RelaxedPlonkWitness<F>,
+        cross_terms: &[Box<[F]>],
+        r: &F,
+    ) -> Self {
+        let mut folded = self.fold(W2, cross_terms, r);
+
+        let power_of_r = r.pow_vartime([cross_terms.len() as u64 + 1]);
+        folded
+            .E
+            .par_iter_mut()
+            .zip_eq(W2.E.par_iter())
+            .for_each(|(e, e2)| *e += power_of_r * e2);
+
+        folded
+    }
+}
+
 /// A newtype wrapper around `PlonkInstance` ensuring that the first instance
 /// column has exactly two elements.
 ///
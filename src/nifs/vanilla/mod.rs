@@ -21,6 +21,7 @@ use crate::{
     plonk::{
         self,
         eval::{GetDataForEval, PlonkEvalDomain},
+        phases::AdviceSource,
         PlonkInstance, PlonkStructure, PlonkWitness,
     },
     polynomial::{
@@ -101,7 +102,7 @@ where
         W2: &PlonkWitness<C::ScalarExt>,
     ) -> Result<(CrossTerms<C>, CrossTermCommits<C>), Error> {
         let data = PlonkEvalDomain {
-            num_advice: S.num_advice_columns,
+            advice_phases: &S.advice_phases,
             num_lookup: S.num_lookups(),
             challenges: &concat_vec!(
                 &U1.challenges,
@@ -115,6 +116,52 @@ where
             W2s: &W2.W,
         };
 
+        let num_cross_terms = S
+            .custom_gates_lookup_compressed
+            .grouped()
+            .len()
+            .saturating_sub(1);
+        Self::evaluate_and_commit_cross_terms(ck, S, &data, num_cross_terms)
+    }
+
+    /// Commits to the cross terms between two relaxed Plonk instance-witness pairs.
+    ///
+    /// Unlike [`VanillaFS::commit_cross_terms`], the last cross term `T_d` is not returned: it
+    /// equals `E` of the second pair, which is already committed in `U2`.
+    #[instrument(skip_all)]
+    pub fn commit_accumulators_cross_terms(
+        ck: &CommitmentKey<C>,
+        S: &PlonkStructure<C::ScalarExt>,
+        U1: &RelaxedPlonkInstance<C>,
+        W1: &RelaxedPlonkWitness<C::ScalarExt>,
+        U2: &RelaxedPlonkInstance<C>,
+        W2: &RelaxedPlonkWitness<C::ScalarExt>,
+    ) -> Result<(CrossTerms<C>, CrossTermCommits<C>), Error> {
+        let data = PlonkEvalDomain {
+            advice_phases: &S.advice_phases,
+            num_lookup: S.num_lookups(),
+            challenges: &concat_vec!(&U1.challenges, &[U1.u], &U2.challenges, &[U2.u]),
+            selectors: &S.selectors,
+            fixed: &S.fixed_columns,
+            W1s: &W1.W,
+            W2s: &W2.W,
+        };
+
+        let num_cross_terms = S
+            .custom_gates_lookup_compressed
+            .grouped()
+            .len()
+            .saturating_sub(2);
+        Self::evaluate_and_commit_cross_terms(ck, S, &data, num_cross_terms)
+    }
+
+    /// Evaluates & commits first `num_cross_terms` cross terms `T_1, T_2, ...`
+    fn evaluate_and_commit_cross_terms(
+        ck: &CommitmentKey<C>,
+        S: &PlonkStructure<C::ScalarExt>,
+        data: &PlonkEvalDomain<C::ScalarExt>,
+        num_cross_terms: usize,
+    ) -> Result<(CrossTerms<C>, CrossTermCommits<C>), Error> {
         let row_size = data.row_size();
 
         let evaluation_span = info_span!("evaluation").entered();
@@ -122,6 +169,7 @@ where
             .custom_gates_lookup_compressed
             .grouped()
             .iter_from_first()
+            .take(num_cross_terms)
             .map(|optional_expr| match optional_expr {
                 Some(expr) => {
                     let evaluator = GraphEvaluator::new(expr);
@@ -129,7 +177,7 @@ where
                     (0..row_size)
                         .into_par_iter()
                         .map(|row_index| {
-                            let evaluated = evaluator.evaluate(&data, row_index)?;
+                            let evaluated = evaluator.evaluate(data, row_index)?;
                             trace!("row {row_index} evaluated: {evaluated:?}");
                             Result::<_, Error>::Ok(evaluated)
                         })
@@ -166,6 +214,23 @@ where
             .absorb_point_iter(cross_term_commits.iter())
             .squeeze::<C>(NUM_CHALLENGE_BITS))
     }
+
+    /// Same as [`VanillaFS::generate_challenge`], but for two relaxed instances
+    #[instrument(skip_all)]
+    pub(crate) fn generate_accumulators_challenge(
+        pp_digest: &C,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        U1: &RelaxedPlonkInstance<C>,
+        U2: &RelaxedPlonkInstance<C>,
+        cross_term_commits: &[C],
+    ) -> <C as CurveAffine>::ScalarExt {
+        ro_acc
+            .absorb_point(pp_digest)
+            .absorb(U1)
+            .absorb(U2)
+            .absorb_point_iter(cross_term_commits.iter())
+            .squeeze::<C>(NUM_CHALLENGE_BITS)
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -208,7 +273,7 @@ where
     fn generate_plonk_trace(
         ck: &CommitmentKey<C>,
         instances: &[Vec<C::ScalarExt>],
-        witness: &[Vec<C::ScalarExt>],
+        witness: &(impl AdviceSource<C::ScalarExt> + ?Sized),
         pp: &VanillaFSProverParam<C>,
         ro_nark: &mut impl ROTrait<C::Base>,
     ) -> Result<FoldablePlonkTrace<C>, Error> {
@@ -293,6 +358,55 @@ where
     }
 }
 
+impl<C: CurveAffine> FoldAccumulators<C> for VanillaFS<C>
+where
+    C::Base: PrimeFieldBits + FromUniformBytes<64>,
+{
+    type AccumulatorsProof = CrossTermCommits<C>;
+
+    /// Folds two relaxed Plonk instance-witness pairs.
+    ///
+    /// `E = E1 + sum_{k=1}^{d-1} r^k * T_k + r^d * E2` & `u = u1 + r * u2`, where cross terms
+    /// `T_k` are calculated by [`VanillaFS::commit_accumulators_cross_terms`]
+    #[instrument(skip_all)]
+    fn fold_accumulators(
+        ck: &CommitmentKey<C>,
+        pp: &Self::ProverParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: Self::Accumulator,
+        rhs: &Self::Accumulator,
+    ) -> Result<(Self::Accumulator, Self::AccumulatorsProof), Error> {
+        let (cross_terms, cross_term_commits) =
+            Self::commit_accumulators_cross_terms(ck, &pp.S, &lhs.U, &lhs.W, &rhs.U, &rhs.W)?;
+
+        let r = VanillaFS::generate_accumulators_challenge(
+            &pp.pp_digest,
+            ro_acc,
+            &lhs.U,
+            &rhs.U,
+            &cross_term_commits,
+        );
+
+        let U = lhs.U.fold_relaxed(&rhs.U, &cross_term_commits, &r);
+        let W = lhs.W.fold_relaxed(&rhs.W, &cross_terms, &r);
+
+        Ok((RelaxedPlonkTrace { U, W }, cross_term_commits))
+    }
+
+    fn verify_fold_accumulators(
+        vp: &Self::VerifierParam,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: &Self::AccumulatorInstance,
+        rhs: &Self::AccumulatorInstance,
+        cross_term_commits: &CrossTermCommits<C>,
+    ) -> Result<Self::AccumulatorInstance, Error> {
+        let r =
+            VanillaFS::generate_accumulators_challenge(vp, ro_acc, lhs, rhs, cross_term_commits);
+
+        Ok(lhs.fold_relaxed(rhs, cross_term_commits, &r))
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum VerifyError {
     #[error(transparent)]
@@ -307,6 +421,8 @@ pub enum VerifyError {
     PermCheckFail { mismatch_count: usize },
     #[error("Instance mismatch")]
     InstanceMismatch,
+    #[error(transparent)]
+    Commitment(#[from] commitment::Error),
 }
 
 impl<C: CurveAffine> VerifyAccumulation<C> for VanillaFS<C>
@@ -323,7 +439,7 @@ where
 
         let total_row = 1 << S.k;
         let data = PlonkEvalDomain {
-            num_advice: S.num_advice_columns,
+            advice_phases: &S.advice_phases,
             num_lookup: S.num_lookups(),
             challenges: &concat_vec!(&U.challenges, &[U.u]),
             selectors: &S.selectors,
@@ -370,70 +486,13 @@ where
         S: &PlonkStructure<C::ScalarExt>,
         acc: &<Self as FoldingScheme<C>>::Accumulator,
     ) -> Result<(), Self::VerifyError> {
-        /// Under this collapsing scheme, `instance` columns other than consistency markers are not
-        /// foldeded, but accumulated using hash. Therefore, they need to be cut out for
-        /// `is_sat_permutation`.
-        ///
-        /// To account for these permutations in the `Relaxed` version, we add them to StepFoldingCircuit
-        /// as a copy constraint with private input (witness)
-        fn permutation_data_without_step_circuit_instances<F: PrimeField>(
-            S: &PlonkStructure<F>,
-        ) -> SparseMatrix<F> {
-            S.permutation_data
-                .clone()
-                .rm_copy_constraints(1..S.num_io.len())
-                .matrix(S.k, &S.num_io, S.num_advice_columns)
-        }
-
-        /// While checking permutations, we need to line up all instance columns one after the other, but
-        /// since we cut out all instance columns except the null column (consistency_marker) for the
-        /// `Relaxed*` version we need to augment them based on [`PlonkStructure::num_io`].
-        fn iter_flat_instances_with_padding<'b, C: CurveAffine>(
-            U: &'b RelaxedPlonkInstance<C>,
-            S: &'b PlonkStructure<C::ScalarExt>,
-        ) -> impl 'b + Iterator<Item = C::ScalarExt> {
-            U.consistency_markers.iter().copied().chain(
-                S.num_io
-                    .iter()
-                    .skip(1)
-                    // Use 0xfffffff only for easy debug
-                    .flat_map(|len| iter::repeat(C::ScalarExt::from_u128(0xfffffff)).take(*len)),
-            )
-        }
-
         let RelaxedPlonkTrace { U, W } = acc;
 
         let Z = iter_flat_instances_with_padding(U, S)
-            .chain(
-                W.W[0]
-                    .iter()
-                    .take((1 << S.k) * S.num_advice_columns)
-                    .copied(),
-            )
+            .chain(S.advice_phases.iter_advice(&W.W, 1 << S.k).copied())
             .collect::<Vec<_>>();
 
-        let mismatch_count =
-            sparse::matrix_multiply(&permutation_data_without_step_circuit_instances(S), &Z)
-                .into_iter()
-                .zip_eq(Z)
-                .enumerate()
-                .filter(|(row, (y, z))| {
-                    let diff = *y - *z;
-
-                    if diff.is_zero().into() {
-                        false
-                    } else {
-                        warn!("permutation mismatch at {row} with: {y:?} - {z:?} = {diff:?}");
-                        true
-                    }
-                })
-                .count();
-
-        if mismatch_count == 0 {
-            Ok(())
-        } else {
-            Err(Self::VerifyError::PermCheckFail { mismatch_count })
-        }
+        is_sat_permutation_of_z(S, Z)
     }
 
     fn is_sat_witness_commit(
@@ -445,12 +504,15 @@ where
         U.W_commitments
             .iter()
             .zip_eq(W.W.iter())
-            .filter_map(|(Ci, Wi)| ck.commit(Wi).unwrap().ne(Ci).then_some(()))
+            .map(|(Ci, Wi)| Ok(ck.commit(Wi)?.ne(Ci)))
+            .collect::<Result<Vec<_>, commitment::Error>>()?
+            .into_iter()
+            .filter(|is_mismatch| *is_mismatch)
             .count_to_non_zero()
             .map(|mismatch_count| plonk::Error::CommitmentMismatch { mismatch_count })
             .err_or(())?;
 
-        if ck.commit(&W.E).unwrap().ne(&U.E_commitment) {
+        if ck.commit(&W.E)?.ne(&U.E_commitment) {
             return Err(Self::VerifyError::ECommitmentMismatch);
         }
 
@@ -478,6 +540,206 @@ where
     }
 }
 
+/// Under this collapsing scheme, `instance` columns other than consistency markers are not
+/// foldeded, but accumulated using hash. Therefore, they need to be cut out for
+/// `is_sat_permutation`.
+///
+/// To account for these permutations in the `Relaxed` version, we add them to StepFoldingCircuit
+/// as a copy constraint with private input (witness)
+fn permutation_data_without_step_circuit_instances<F: PrimeField>(
+    S: &PlonkStructure<F>,
+) -> SparseMatrix<F> {
+    S.permutation_data
+        .clone()
+        .rm_copy_constraints(1..S.num_io.len())
+        .matrix(S.k, &S.num_io, S.num_advice_columns)
+}
+
+/// While checking permutations, we need to line up all instance columns one after the other, but
+/// since we cut out all instance columns except the null column (consistency_marker) for the
+/// `Relaxed*` version we need to augment them based on [`PlonkStructure::num_io`].
+fn iter_flat_instances_with_padding<'b, C: CurveAffine>(
+    U: &'b RelaxedPlonkInstance<C>,
+    S: &'b PlonkStructure<C::ScalarExt>,
+) -> impl 'b + Iterator<Item = C::ScalarExt> {
+    U.consistency_markers.iter().copied().chain(
+        S.num_io
+            .iter()
+            .skip(1)
+            // Use 0xfffffff only for easy debug
+            .flat_map(|len| iter::repeat(C::ScalarExt::from_u128(0xfffffff)).take(*len)),
+    )
+}
+
+fn is_sat_permutation_of_z<F: PrimeField>(
+    S: &PlonkStructure<F>,
+    Z: Vec<F>,
+) -> Result<(), VerifyError> {
+    let mismatch_count =
+        sparse::matrix_multiply(&permutation_data_without_step_circuit_instances(S), &Z)
+            .into_iter()
+            .zip_eq(Z)
+            .enumerate()
+            .filter(|(row, (y, z))| {
+                let diff = *y - *z;
+
+                if diff.is_zero().into() {
+                    false
+                } else {
+                    warn!("permutation mismatch at {row} with: {y:?} - {z:?} = {diff:?}");
+                    true
+                }
+            })
+            .count();
+
+    if mismatch_count == 0 {
+        Ok(())
+    } else {
+        Err(VerifyError::PermCheckFail { mismatch_count })
+    }
+}
+
+impl<C: CurveAffine> BatchVerifyAccumulation<C> for VanillaFS<C>
+where
+    C::Base: PrimeFieldBits + FromUniformBytes<64>,
+{
+    fn is_sat_accumulation_batch(
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[RelaxedPlonkTrace<C>],
+        coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        if accs.is_empty() {
+            return Ok(());
+        }
+
+        let total_row = 1 << S.k;
+        let challenges = accs
+            .iter()
+            .map(|acc| concat_vec!(&acc.U.challenges, &[acc.U.u]))
+            .collect::<Box<[_]>>();
+        let data = accs
+            .iter()
+            .zip_eq(challenges.iter())
+            .map(|(acc, challenges)| PlonkEvalDomain {
+                advice_phases: &S.advice_phases,
+                num_lookup: S.num_lookups(),
+                challenges,
+                selectors: &S.selectors,
+                fixed: &S.fixed_columns,
+                W1s: &acc.W.W,
+                W2s: &[],
+            })
+            .collect::<Box<[_]>>();
+
+        // For each row `sum_k coeff_k * (P(W_k, row) - E_k[row]) == 0`, so all accumulators are
+        // checked in one pass over the rows
+        let evaluator = GraphEvaluator::new(S.custom_gates_lookup_compressed.homogeneous());
+        (0..total_row)
+            .into_par_iter()
+            .map(|row| {
+                data.iter()
+                    .zip_eq(accs)
+                    .zip_eq(coefficients)
+                    .try_fold(C::ScalarExt::ZERO, |sum, ((data, acc), coeff)| {
+                        Ok::<_, plonk::eval::Error>(
+                            sum + (evaluator.evaluate(data, row)? - acc.W.E[row]) * coeff,
+                        )
+                    })
+                    .map(|sum| {
+                        if sum.is_zero_vartime() {
+                            0
+                        } else {
+                            warn!("row {row} invalid in batch");
+                            1
+                        }
+                    })
+            })
+            .try_reduce(
+                || 0,
+                |mismatch_count, is_missed| Ok(mismatch_count + is_missed),
+            )
+            .map(|mismatch_count| {
+                Some(plonk::Error::EvaluationMismatch {
+                    mismatch_count: NonZeroUsize::new(mismatch_count)?,
+                    total_row,
+                })
+            })?
+            .err_or(())?;
+
+        // The sum `sum_i (h_i - g_i)` is linear in the witness
+        let W = (0..S.round_sizes.len())
+            .map(|round| {
+                linear_combination(
+                    accs.iter().map(|acc| acc.W.W[round].as_slice()),
+                    coefficients,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !S.is_sat_log_derivative(&W) {
+            return Err(plonk::Error::LogDerivativeNotSat.into());
+        }
+
+        Ok(())
+    }
+
+    fn is_sat_permutation_batch(
+        S: &PlonkStructure<C::ScalarExt>,
+        accs: &[RelaxedPlonkTrace<C>],
+        coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        if accs.is_empty() {
+            return Ok(());
+        }
+
+        // Permutation relation `P * Z - Z = 0` is linear in `Z`
+        let Zs = accs
+            .iter()
+            .map(|RelaxedPlonkTrace { U, W }| {
+                iter_flat_instances_with_padding(U, S)
+                    .chain(S.advice_phases.iter_advice(&W.W, 1 << S.k).copied())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Box<[_]>>();
+
+        is_sat_permutation_of_z(
+            S,
+            linear_combination(Zs.iter().map(|Z| Z.as_slice()), coefficients),
+        )
+    }
+
+    fn is_sat_witness_commit_batch(
+        ck: &CommitmentKey<C>,
+        accs: &[RelaxedPlonkTrace<C>],
+        coefficients: &[C::ScalarExt],
+    ) -> Result<(), Self::VerifyError> {
+        // Each accumulator has several commitments, so for the `k`-th accumulator the powers of
+        // `coefficients[k]` are used
+        let (vectors, commitments, coefficients): (Vec<_>, Vec<_>, Vec<_>) = accs
+            .iter()
+            .zip_eq(coefficients)
+            .flat_map(|(RelaxedPlonkTrace { U, W }, coeff)| {
+                W.W.iter()
+                    .chain(iter::once(&W.E))
+                    .zip_eq(U.W_commitments.iter().chain(iter::once(&U.E_commitment)))
+                    .zip(iter::successors(Some(*coeff), move |power| {
+                        Some(*power * coeff)
+                    }))
+                    .map(|((vector, commitment), coeff)| (vector.as_slice(), *commitment, coeff))
+            })
+            .multiunzip();
+
+        if ck.is_rlc_of_commitments(&vectors, &commitments, &coefficients)? {
+            Ok(())
+        } else {
+            Err(plonk::Error::CommitmentMismatch {
+                mismatch_count: NonZeroUsize::MIN,
+            }
+            .into())
+        }
+    }
+}
+
 /// Number of consistency markers in instance column
 pub const CONSISTENCY_MARKERS_COUNT: usize = 2;
 
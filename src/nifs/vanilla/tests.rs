@@ -1,4 +1,5 @@
 use halo2_proofs::plonk::{self, Circuit};
+use rand_core::OsRng;
 use some_to_err::*;
 use tracing_test::traced_test;
 
@@ -233,6 +234,152 @@ where
     }
 }
 
+/// Folds `pairs` one by one into the default relaxed trace
+fn accumulate<C, F1, F2>(
+    ck: &CommitmentKey<C>,
+    S: &PlonkStructure<F1>,
+    pp: &VanillaFSProverParam<C>,
+    pairs: impl IntoIterator<Item = FoldablePlonkTrace<C>>,
+) -> Result<RelaxedPlonkTrace<C>, Error<C>>
+where
+    C: CurveAffine<ScalarExt = F1, Base = F2>,
+    F1: PrimeField,
+    F2: PrimeFieldBits + FromUniformBytes<64>,
+{
+    const T: usize = 3;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    let mut ro_acc_prover = create_ro::<C::Base, T, RATE, R_F, R_P>();
+
+    pairs
+        .into_iter()
+        .try_fold(RelaxedPlonkTrace::from(S), |acc, pair| {
+            VanillaFS::prove(ck, pp, &mut ro_acc_prover, acc, &[pair])
+                .map(|(acc, _cross_term_commits)| acc)
+        })
+        .map_err(Error::from)
+}
+
+/// Folds both pairs into two accumulators in direct & reversed order & then merges them with
+/// [`FoldAccumulators`], as in two independent branches of the folding tree
+///
+/// Public instances are accumulated in a tree-shaped hash, so only the folding relations are
+/// checked for the merged accumulator
+fn fold_accumulators<C, F1, F2>(
+    ck: &CommitmentKey<C>,
+    S: &PlonkStructure<F1>,
+    pair1: FoldablePlonkTrace<C>,
+    pair2: FoldablePlonkTrace<C>,
+    pp_digest: C,
+) -> Result<(), Error<C>>
+where
+    C: CurveAffine<ScalarExt = F1, Base = F2>,
+    F1: PrimeField,
+    F2: PrimeFieldBits + FromUniformBytes<64>,
+{
+    const T: usize = 3;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    let (pp, vp) = VanillaFS::setup_params(pp_digest, S.clone())?;
+
+    let acc1 = accumulate(ck, S, &pp, [pair1.clone(), pair2.clone()])?;
+    let acc2 = accumulate(ck, S, &pp, [pair2, pair1])?;
+
+    // `E` of a trace folded into the default one is zero, so the `E2` term of the merge is
+    // checked only for accumulators folded at least twice
+    assert!([&acc1, &acc2]
+        .iter()
+        .all(|acc| acc.W.E.iter().any(|e| !e.is_zero_vartime())));
+
+    let (acc, cross_term_commits) = VanillaFS::fold_accumulators(
+        ck,
+        &pp,
+        &mut create_ro::<C::Base, T, RATE, R_F, R_P>(),
+        acc1.clone(),
+        &acc2,
+    )?;
+
+    let U_from_verify = VanillaFS::verify_fold_accumulators(
+        &vp,
+        &mut create_ro::<C::Base, T, RATE, R_F, R_P>(),
+        &acc1.U,
+        &acc2.U,
+        &cross_term_commits,
+    )?;
+    Error::check_equality(&U_from_verify, &acc.U)?;
+
+    let errors = [
+        (
+            "is_sat_accumulation",
+            VanillaFS::is_sat_accumulation(S, &acc),
+        ),
+        ("is_sat_permutation", VanillaFS::is_sat_permutation(S, &acc)),
+        (
+            "is_sat_witness_commit",
+            VanillaFS::is_sat_witness_commit(ck, &acc),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, result)| Some((name, result.err()?)))
+    .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Verify { errors })
+    }
+}
+
+/// Checks two accumulators with [`IsSatAccumulatorBatch`] & makes sure that, when one of them is
+/// broken, the fallback to per-accumulator checks reports its index
+fn is_sat_batch<C, F1, F2>(
+    ck: &CommitmentKey<C>,
+    S: &PlonkStructure<F1>,
+    pair1: FoldablePlonkTrace<C>,
+    pair2: FoldablePlonkTrace<C>,
+    pp_digest: C,
+) -> Result<(), Error<C>>
+where
+    C: CurveAffine<ScalarExt = F1, Base = F2>,
+    F1: PrimeField,
+    F2: PrimeFieldBits + FromUniformBytes<64>,
+{
+    let (pp, _vp) = VanillaFS::setup_params(pp_digest, S.clone())?;
+
+    let instances = [pair1.u.instances.clone(), pair2.u.instances.clone()];
+    let reversed_instances = [pair2.u.instances.clone(), pair1.u.instances.clone()];
+    let pub_instances = [instances.as_slice(), reversed_instances.as_slice()];
+
+    let mut accs = [
+        accumulate(ck, S, &pp, [pair1.clone(), pair2.clone()])?,
+        accumulate(ck, S, &pp, [pair2, pair1])?,
+    ];
+
+    VanillaFS::is_sat_batch(ck, S, &accs, &pub_instances, OsRng).map_err(|failed| {
+        Error::Verify {
+            errors: failed
+                .into_iter()
+                .flat_map(|(_index, errors)| errors.into_iter().map(|err| ("is_sat_batch", err)))
+                .collect(),
+        }
+    })?;
+
+    accs[1].W.E[0] += F1::ONE;
+
+    let failed = VanillaFS::is_sat_batch(ck, S, &accs, &pub_instances, OsRng)
+        .expect_err("The broken accumulator is satisfactory");
+    assert_eq!(
+        failed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        [1]
+    );
+
+    Ok(())
+}
+
 // test with single custom gate without lookup
 
 use super::super::tests::{
@@ -297,7 +444,8 @@ fn one_round_test() -> Result<(), Error<G1Affine>> {
         vec![public_inputs2],
         G1Affine::default(),
     )?;
-    fold_instances(&ck, &S, pair1, pair2, G1Affine::default())
+    fold_instances(&ck, &S, pair1.clone(), pair2.clone(), G1Affine::default())?;
+    fold_accumulators(&ck, &S, pair1, pair2, G1Affine::default())
 }
 
 #[traced_test]
@@ -332,5 +480,86 @@ fn three_rounds_test() -> Result<(), Error<G1Affine>> {
         vec![vec![Fr::from(seq[0]), Fr::from(seq[0])]],
         G1Affine::default(),
     )?;
-    fold_instances(&ck, &S, pair1, pair2, G1Affine::default())
+    fold_instances(&ck, &S, pair1.clone(), pair2.clone(), G1Affine::default())?;
+    fold_accumulators(&ck, &S, pair1, pair2, G1Affine::default())
+}
+
+#[traced_test]
+#[test]
+fn is_sat_batch_test() -> Result<(), Error<G1Affine>> {
+    const K: u32 = 4;
+    const SIZE: usize = 16;
+
+    let [(circuit1, public_inputs1), (circuit2, public_inputs2)] =
+        [(1, 1), (2, 3)].map(|(a, b)| {
+            let seq = get_fibo_seq(a, b, SIZE);
+            (
+                FiboCircuit {
+                    a: Fr::from(seq[0]),
+                    b: Fr::from(seq[1]),
+                    num: SIZE,
+                },
+                vec![Fr::from(seq[SIZE - 1]), Fr::ZERO],
+            )
+        });
+
+    let (ck, S, pair1, pair2) = prepare_trace(
+        K,
+        circuit1,
+        circuit2,
+        vec![public_inputs1],
+        vec![public_inputs2],
+        G1Affine::default(),
+    )?;
+    is_sat_batch(&ck, &S, pair1, pair2, G1Affine::default())
+}
+
+#[traced_test]
+#[test]
+fn is_sat_batch_empty_test() -> Result<(), Error<G1Affine>> {
+    const K: u32 = 4;
+    const SIZE: usize = 16;
+
+    let seq = get_fibo_seq(1, 1, SIZE);
+    let public_inputs = vec![Fr::from(seq[SIZE - 1]), Fr::ZERO];
+    let circuit = || FiboCircuit {
+        a: Fr::from(seq[0]),
+        b: Fr::from(seq[1]),
+        num: SIZE,
+    };
+
+    let (ck, S, _pair1, _pair2) = prepare_trace(
+        K,
+        circuit(),
+        circuit(),
+        vec![public_inputs.clone()],
+        vec![public_inputs],
+        G1Affine::default(),
+    )?;
+
+    let errors = [
+        (
+            "is_sat_accumulation_batch",
+            VanillaFS::<G1Affine>::is_sat_accumulation_batch(&S, &[], &[]),
+        ),
+        (
+            "is_sat_permutation_batch",
+            VanillaFS::<G1Affine>::is_sat_permutation_batch(&S, &[], &[]),
+        ),
+        (
+            "is_sat_witness_commit_batch",
+            VanillaFS::<G1Affine>::is_sat_witness_commit_batch(&ck, &[], &[]),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, result)| Some((name, result.err()?)))
+    .collect::<Vec<_>>();
+
+    if !errors.is_empty() {
+        return Err(Error::Verify { errors });
+    }
+
+    assert!(VanillaFS::<G1Affine>::is_sat_batch(&ck, &S, &[], &[], OsRng).is_ok());
+
+    Ok(())
 }
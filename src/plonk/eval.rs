@@ -1,4 +1,4 @@
-use crate::{ff::PrimeField, polynomial::ColumnIndex};
+use crate::{ff::PrimeField, plonk::phases::AdvicePhases, polynomial::ColumnIndex};
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
 pub enum Error {
@@ -91,7 +91,7 @@ pub struct LookupEvalDomain<'a, F: PrimeField> {
 
 /// Used for evaluate cross terms T[i]
 pub struct PlonkEvalDomain<'a, F: PrimeField> {
-    pub(crate) num_advice: usize,
+    pub(crate) advice_phases: &'a AdvicePhases,
     pub(crate) num_lookup: usize,
     // concatenation of challenges from two RelaxedPlonkInstance
     pub(crate) challenges: &'a [F],
@@ -152,7 +152,7 @@ impl<'a, F: PrimeField> GetDataForEval<F> for PlonkEvalDomain<'a, F> {
 
     fn eval_advice_var(&self, row: usize, index: usize) -> Result<F, Error> {
         let row_size = self.row_size();
-        let num_advice = self.num_advice;
+        let num_advice = self.advice_phases.num_advice();
         let num_lookup = self.num_lookup();
         // maximum index for one instance
         let max_width = num_advice + num_lookup * 5;
@@ -167,43 +167,7 @@ impl<'a, F: PrimeField> GetDataForEval<F> for PlonkEvalDomain<'a, F> {
             self.W2s.len()
         };
 
-        let index_map = |index: usize| -> Result<(usize, usize), Error> {
-            if index < num_advice {
-                return Ok((0, index));
-            }
-
-            let lookup_index = (index - num_advice) / 5;
-            let lookup_sub_index = (index - num_advice) % 5;
-            let (is_first_round, lookup_sub_index) = if lookup_sub_index < 3 {
-                (true, lookup_sub_index)
-            } else {
-                (false, lookup_sub_index - 3)
-            };
-            match num_witness {
-                2 => {
-                    if is_first_round {
-                        Ok((0, num_advice + lookup_index * 3 + lookup_sub_index))
-                    } else {
-                        Ok((1, lookup_index * 2 + lookup_sub_index))
-                    }
-                }
-                3 => {
-                    if is_first_round {
-                        Ok((1, lookup_index * 3 + lookup_sub_index))
-                    } else {
-                        Ok((2, lookup_index * 2 + lookup_sub_index))
-                    }
-                }
-                num_witness => Err(Error::InvalidWitnessIndex {
-                    num_witness,
-                    num_advice,
-                    num_lookup,
-                    index,
-                }),
-            }
-        };
-
-        let (i, j) = index_map(index)?;
+        let (i, j) = witness_location(self.advice_phases, num_lookup, num_witness, index)?;
         if is_first_instance {
             if self.W1s.len() <= i || self.W1s[i].len() <= j * row_size + row {
                 Err(Error::InvalidWitnessIndex {
@@ -227,3 +191,58 @@ impl<'a, F: PrimeField> GetDataForEval<F> for PlonkEvalDomain<'a, F> {
         }
     }
 }
+
+/// Location `(round, column inside of round)` in [`super::PlonkWitness::W`] of the advice or
+/// lookup column `index` (i.e. index of query without selectors & fixed columns)
+///
+/// `num_witness` is the number of prover rounds, it depends on the kind of lookup arguments
+pub(crate) fn witness_location(
+    advice_phases: &AdvicePhases,
+    num_lookup: usize,
+    num_witness: usize,
+    index: usize,
+) -> Result<(usize, usize), Error> {
+    let num_advice = advice_phases.num_advice();
+    // all phases except the last one are committed in separate rounds, see [`AdvicePhases`]
+    let num_phase_rounds = advice_phases.num_challenges();
+    let last_phase_size = advice_phases.last_phase_size();
+
+    let invalid_index = || Error::InvalidWitnessIndex {
+        num_witness,
+        num_advice,
+        num_lookup,
+        index,
+    };
+
+    if index < num_advice {
+        return advice_phases.location(index).ok_or_else(invalid_index);
+    }
+
+    let lookup_index = (index - num_advice) / 5;
+    let lookup_sub_index = (index - num_advice) % 5;
+    let (is_first_round, lookup_sub_index) = if lookup_sub_index < 3 {
+        (true, lookup_sub_index)
+    } else {
+        (false, lookup_sub_index - 3)
+    };
+    match num_witness.checked_sub(num_phase_rounds) {
+        Some(2) => {
+            if is_first_round {
+                Ok((
+                    num_phase_rounds,
+                    last_phase_size + lookup_index * 3 + lookup_sub_index,
+                ))
+            } else {
+                Ok((num_phase_rounds + 1, lookup_index * 2 + lookup_sub_index))
+            }
+        }
+        Some(3) => {
+            if is_first_round {
+                Ok((num_phase_rounds + 1, lookup_index * 3 + lookup_sub_index))
+            } else {
+                Ok((num_phase_rounds + 2, lookup_index * 2 + lookup_sub_index))
+            }
+        }
+        _ => Err(invalid_index()),
+    }
+}
@@ -18,6 +18,14 @@
 //! - [`Argument`]: Represents the lookup argument with compressed polynomials
 //!   for both the lookup vector and the table vector.
 //!
+//! ## Shuffle Arguments
+//!
+//! A shuffle (multiset-equality) argument `{a_i}` is a permutation of `{s_i}` is a special case
+//! of the log-derivative relation: `sum_i 1/(a_i + r) = sum_i 1/(s_i + r)`, i.e. a lookup of
+//! `a_i` into the table `s_i` with all multiplicities `m_i` equal to one. Therefore shuffles are
+//! stored together with lookups and share their columns `(l_i, t_i, m_i, h_i, g_i)` & challenges,
+//! with an additional constraint `m_i - 1 = 0`.
+//!
 //! ## Functionality
 //!
 //! The module provides functions to:
@@ -39,11 +47,12 @@
 use std::{
     array,
     collections::{HashMap, HashSet},
+    iter,
 };
 
 use halo2_proofs::{plonk::ConstraintSystem, poly::Rotation};
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use crate::{
@@ -64,12 +73,16 @@ use crate::{
 /// - a_i are expressions over columns (x_1, ..., x_a)
 /// - t_i are expressions over columns (y_1, ..., y_b)
 ///
-/// We assume (y_1,...,y_b) are fixed columns.
+/// (y_1,...,y_b) can be fixed columns (static table) or advice columns (dynamic table, e.g.
+/// memory-consistency lookups where the table is witnessed per step). For a dynamic table,
+/// `t_i` depends on the witness, so it is committed in the same prover round as `l_i` & `m_i`,
+/// i.e. after advice columns have been committed and `r1` has been squeezed (if needed).
+///
 /// Compress them
 /// into a single (i.e. non-vector) Expression:
 /// - lookup_poly = L(x_1,...,x_a) = a_1 + a_2*r + a_3*r^2 + ...
 /// - table_poly  = T(y_1,...,y_b) = t_1 + t_2*r + t_3*r^2 + ...
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Arguments<F: PrimeField> {
     /// vector of the compressed lookup expressions
     /// L_i(x_1,...,x_{a_i})
@@ -79,53 +92,80 @@ pub struct Arguments<F: PrimeField> {
     pub(crate) table_polys: Vec<Expression<F>>,
     /// has_vector_lookup = true if one of a_i > 1
     pub(crate) has_vector_lookup: bool,
+    /// is_shuffle[i] = true if i-th argument is a shuffle argument, i.e. `T_i` is a permutation
+    /// of `L_i` rather than a table. All shuffles are placed after all lookups
+    pub(crate) is_shuffle: Vec<bool>,
 }
 
 impl<F: PrimeField> Arguments<F> {
-    /// Compresses a potentially vector Lookup Argument from a constraint system into non-vector expression.
+    /// Compresses a potentially vector Lookup & Shuffle Arguments from a constraint system into
+    /// non-vector expression.
     #[instrument(name = "lookup", skip_all)]
     pub fn compress_from(cs: &ConstraintSystem<F>) -> Option<Self> {
         let max_lookup_len = cs
             .lookups()
             .iter()
             .map(|arg| arg.input_expressions().len())
+            .chain(
+                cs.shuffles()
+                    .iter()
+                    .map(|arg| arg.input_expressions().len()),
+            )
             .max()
             .filter(|l| *l != 0)?;
 
         let has_vector_lookup = max_lookup_len > 1;
 
-        let (lookup_polys, table_polys) = cs
+        let compress = |exprs: &[halo2_proofs::plonk::Expression<F>]| {
+            compress_halo2_expression(
+                exprs,
+                cs.num_selectors,
+                cs.num_fixed_columns(),
+                // compress vector lookups & table items with r1, which is placed right after
+                // challenges of the circuit itself, see [`crate::plonk::phases`]
+                cs.num_challenges(),
+            )
+        };
+
+        let (lookup_polys, table_polys): (Vec<_>, Vec<_>) = cs
             .lookups()
             .iter()
             .map(|arg| {
                 (
-                    compress_halo2_expression(
+                    compress(
                         arg.input_expressions()
                             .iter()
                             .flatten()
                             .cloned()
                             .collect::<Vec<_>>()
                             .as_slice(),
-                        cs.num_selectors,
-                        cs.num_fixed_columns(),
-                        // compress vector table items with r1 (challenge_index = 0)
-                        0,
-                    ),
-                    compress_halo2_expression(
-                        arg.table_expressions(),
-                        cs.num_selectors,
-                        cs.num_fixed_columns(),
-                        // compress vector lookups with r1 (challenge_index = 0)
-                        0,
                     ),
+                    compress(arg.table_expressions()),
                 )
             })
+            .chain(cs.shuffles().iter().map(|arg| {
+                (
+                    compress(arg.input_expressions()),
+                    compress(arg.shuffle_expressions()),
+                )
+            }))
             .unzip();
 
+        let is_shuffle = iter::repeat(false)
+            .take(cs.lookups().len())
+            .chain(iter::repeat(true).take(cs.shuffles().len()))
+            .collect::<Vec<_>>();
+        debug!(
+            "num lookups: {}, num shuffles: {}",
+            cs.lookups().len(),
+            cs.shuffles().len()
+        );
+
         Some(Self {
             lookup_polys,
             table_polys,
             has_vector_lookup,
+            is_shuffle,
         })
     }
 
@@ -133,6 +173,7 @@ impl<F: PrimeField> Arguments<F> {
     pub fn to_expressions(&self, cs: &ConstraintSystem<F>) -> impl Iterator<Item = Expression<F>> {
         self.vanishing_lookup_polys(cs)
             .into_iter()
+            .chain(self.shuffle_multiplicity_polys(cs))
             .chain(self.log_derivative_lhs_and_rhs(cs))
     }
 
@@ -168,10 +209,35 @@ impl<F: PrimeField> Arguments<F> {
         ls.chain(ts).collect()
     }
 
+    /// m_i - 1 which evaluates to zero on every row for each shuffle argument
+    pub fn shuffle_multiplicity_polys(&self, cs: &ConstraintSystem<F>) -> Vec<Expression<F>> {
+        let lookup_offset = cs.num_selectors + cs.num_fixed_columns() + cs.num_advice_columns();
+
+        self.is_shuffle
+            .iter()
+            .enumerate()
+            .filter(|(_, is_shuffle)| **is_shuffle)
+            .map(|(lookup_index, _)| {
+                Expression::Polynomial(Query {
+                    index: lookup_offset + lookup_index * 5 + 2,
+                    rotation: Rotation(0),
+                }) - Expression::Constant(F::ONE)
+            })
+            .collect()
+    }
+
+    /// Number of all log-derivative arguments, including shuffles
     pub fn num_lookups(&self) -> usize {
         self.lookup_polys.len()
     }
 
+    pub fn num_shuffles(&self) -> usize {
+        self.is_shuffle
+            .iter()
+            .filter(|is_shuffle| **is_shuffle)
+            .count()
+    }
+
     /// calculate lhs and rhs of log-derivative relation
     /// each lookup argument introduces 1 extra "fixed" variables, 4 extra "advice" variables
     pub fn log_derivative_expr(
@@ -199,7 +265,7 @@ impl<F: PrimeField> Arguments<F> {
 
     /// collect the lhs and rhs of log-derivative relations from all lookup arguments
     pub fn log_derivative_lhs_and_rhs(&self, cs: &ConstraintSystem<F>) -> Vec<Expression<F>> {
-        let challenge_index = if self.has_vector_lookup { 1 } else { 0 };
+        let challenge_index = cs.num_challenges() + if self.has_vector_lookup { 1 } else { 0 };
         (0..self.num_lookups())
             .flat_map(|lookup_index| {
                 let (lhs, rhs) = self.log_derivative_expr(cs, lookup_index, challenge_index);
@@ -214,7 +280,7 @@ impl<F: PrimeField> Arguments<F> {
         &self,
         circuit_data: &PlonkStructure<F>,
         witness: &[Vec<F>],
-        r: F,
+        challenges: &[F],
     ) -> Result<Vec<Vec<F>>, Error> {
         let data = LookupEvalDomain {
             num_lookup: circuit_data
@@ -222,7 +288,7 @@ impl<F: PrimeField> Arguments<F> {
                 .as_ref()
                 .map(|arg| arg.lookup_polys.len())
                 .unwrap_or(0),
-            challenges: vec![r],
+            challenges: challenges.to_vec(),
             selectors: &circuit_data.selectors,
             fixed: &circuit_data.fixed_columns,
             advice: witness,
@@ -248,7 +314,7 @@ impl<F: PrimeField> Arguments<F> {
         &self,
         circuit_data: &PlonkStructure<F>,
         witness: &[Vec<F>],
-        r: F,
+        challenges: &[F],
     ) -> Result<Vec<Vec<F>>, Error> {
         let data = LookupEvalDomain {
             num_lookup: circuit_data
@@ -256,7 +322,7 @@ impl<F: PrimeField> Arguments<F> {
                 .as_ref()
                 .map(|arg| arg.lookup_polys.len())
                 .unwrap_or(0),
-            challenges: vec![r],
+            challenges: challenges.to_vec(),
             selectors: &circuit_data.selectors,
             fixed: &circuit_data.fixed_columns,
             advice: witness,
@@ -274,7 +340,10 @@ impl<F: PrimeField> Arguments<F> {
             .collect()
     }
     /// calculate the coefficients {m_i} in the log derivative formula
-    /// m_i = sum_j \xi(w_j=t_i) assuming {t_i} have no duplicates
+    /// m_i = sum_j \xi(w_j=t_i) for the first occurrence of t_i and zero for its duplicates
+    ///
+    /// Dynamic tables usually contain duplicates (e.g. unused rows), so only the first
+    /// occurrence of each value accumulates the multiplicity
     fn evaluate_m(&self, l: &[F], t: &[F]) -> Vec<F> {
         debug!("evaluate_m: {} & {}", l.len(), t.len());
         let mut processed_t = HashSet::with_capacity(l.len().max(t.len()));
@@ -319,23 +388,35 @@ impl<F: PrimeField> Arguments<F> {
         (h, g)
     }
 
+    /// calculate `(l_i, t_i, m_i)`
+    ///
+    /// `challenges` are the challenges of the circuit (see [`crate::plonk::phases`]) followed by
+    /// r1, if there is any vector lookup
     #[instrument(name = "lookup_1", skip_all)]
     pub(crate) fn evaluate_coefficient_1(
         &self,
         circuit_data: &PlonkStructure<F>,
         witness: &[Vec<F>],
-        r: F,
+        challenges: &[F],
     ) -> Result<ArgumentCoefficient1<F>, Error> {
         debug!("start evaluate_coefficient_1");
-        let ls = self.evaluate_ls(circuit_data, witness, r)?;
+        let ls = self.evaluate_ls(circuit_data, witness, challenges)?;
         debug!("ls calculated: {}", ls.len());
-        let ts = self.evaluate_ts(circuit_data, witness, r)?;
+        let ts = self.evaluate_ts(circuit_data, witness, challenges)?;
         debug!("ts calculated: {}", ts.len());
 
         let mut ms = Vec::with_capacity(ls.len());
         ls.par_iter()
             .zip_eq(ts.par_iter())
-            .map(|(l, t)| self.evaluate_m(l, t))
+            .zip_eq(self.is_shuffle.par_iter())
+            .map(|((l, t), is_shuffle)| {
+                if *is_shuffle {
+                    // each element of a permutation is used exactly once
+                    vec![F::ONE; t.len()]
+                } else {
+                    self.evaluate_m(l, t)
+                }
+            })
             .collect_into_vec(&mut ms);
         debug!("ms calculated");
 
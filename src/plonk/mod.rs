@@ -9,13 +9,13 @@
 //!
 //! Additionally, it defines a method is_sat on PlonkStructure to determine if
 //! a given Plonk instance and witness satisfy the circuit constraints.
-use std::{iter, num::NonZeroUsize, time::Instant};
+use std::{borrow::Cow, iter, num::NonZeroUsize, time::Instant};
 
 use count_to_non_zero::*;
 use halo2_proofs::arithmetic::CurveAffine;
 use itertools::Itertools;
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use some_to_err::*;
 use tracing::{debug, error, info, info_span, instrument, warn};
 
@@ -28,6 +28,7 @@ use crate::{
     plonk::{
         self,
         eval::{Error as EvalError, GetDataForEval, PlonkEvalDomain},
+        phases::{AdvicePhases, AdviceSource},
     },
     polynomial::{
         expression::{HomogeneousExpression, QueryIndexContext},
@@ -44,6 +45,7 @@ use crate::{
 pub mod eval;
 pub mod lookup;
 pub mod permutation;
+pub mod phases;
 pub mod util;
 
 #[derive(Debug, thiserror::Error, PartialEq)]
@@ -64,17 +66,15 @@ pub enum Error {
 }
 
 /// This structure is a representation of a compressed set of custom gates & lookup
-#[derive(Clone, PartialEq, Serialize, Default)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Default)]
 pub(crate) struct CompressedGates<F: PrimeField> {
     /// The original custom gates & lookup expressions grouped using random linear combination
     compressed: Expression<F>,
     /// A homogeneous version of the `compressed` expression, achieved by adding another challenge
     /// if necessary
-    #[serde(skip_serializing)]
     homogeneous: HomogeneousExpression<F>,
     /// A degree-grouped version of the `homogeneous` expression, adds another expression, but
     /// implicitly
-    #[serde(skip_serializing)]
     grouped: GroupedPoly<F>,
 }
 
@@ -88,14 +88,15 @@ impl<F: PrimeField> CompressedGates<F> {
             "custom gates compressed in {} ns",
             timer.elapsed().as_nanos()
         );
-        ctx.num_challenges = compressed.num_challenges();
+        // challenges declared, but not used in gates, still take their indices
+        ctx.num_challenges = ctx.num_challenges.max(compressed.challenges_bound());
 
         let homogeneous = compressed.homogeneous(ctx);
         info!(
             "compressed made homogeneous in {} ns",
             timer.elapsed().as_nanos()
         );
-        ctx.num_challenges = homogeneous.num_challenges();
+        ctx.num_challenges = ctx.num_challenges.max(homogeneous.challenges_bound());
 
         let grouped = GroupedPoly::new(&homogeneous, ctx);
         info!(
@@ -122,10 +123,8 @@ impl<F: PrimeField> CompressedGates<F> {
     }
 }
 
-#[derive(Clone, PartialEq, Serialize, Default)]
-#[serde(bound(serialize = "
-    F: Serialize
-"))]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
 pub struct PlonkStructure<F: PrimeField> {
     /// k is a parameter such that 2^k is the total number of rows
     pub(crate) k: usize,
@@ -135,11 +134,15 @@ pub struct PlonkStructure<F: PrimeField> {
     pub(crate) fixed_columns: Vec<Vec<F>>,
 
     pub(crate) num_advice_columns: usize,
+    /// Location of the advice columns in the prover rounds, see [`phases`]
+    pub(crate) advice_phases: AdvicePhases,
 
     /// We follow the special soundness protocol(SPS), section 3.1 in [Protostar](https://eprint.iacr.org/2023/620)
     /// let k = num_challenges; when k > 0, we add extra verifier round, this is slightly different
     /// from protostar paper.
     /// see [`PlonkInstance::challenges`] for detail
+    ///
+    /// Includes the challenges of the multi-phase circuit, see [`phases`]
     pub(crate) num_challenges: usize,
     /// specify the witness size of each prover round
     pub(crate) round_sizes: Vec<usize>,
@@ -147,25 +150,48 @@ pub struct PlonkStructure<F: PrimeField> {
     pub(crate) custom_gates_lookup_compressed: CompressedGates<F>,
 
     /// TODO #262: after we switch from Sangaria IVC to IVC with cyclefold + protogalaxy
-    /// we will remove the field custom_gates_lookup_compressed and make gates field serializable
-    /// instead
+    /// we will remove the field custom_gates_lookup_compressed
     /// we use uncompressed gates instead of
     /// custom_gates_lookup_compressed in protogalaxy folding scheme
-    #[serde(skip_serializing)]
     pub(crate) gates: Vec<Expression<F>>,
 
     pub(crate) permutation_data: PermutationData,
     pub(crate) lookup_arguments: Option<lookup::Arguments<F>>,
 }
 
+/// The part of [`PlonkStructure`], which goes into the digest of public params, see
+/// [`PlonkStructure::serialize_for_digest`]
+///
+/// The derived forms of gates ([`CompressedGates::homogeneous`], [`CompressedGates::grouped`])
+/// & uncompressed [`PlonkStructure::gates`] are skipped, so the digest format is the same as
+/// before [`PlonkStructure`] became deserializable: the serialization of [`PlonkStructure`] is
+/// complete to make it round-trip, but it's not an input of the digest.
+#[derive(Serialize)]
+#[serde(bound(serialize = "F: Serialize"))]
+struct PlonkStructureDigestView<'l, F: PrimeField> {
+    k: usize,
+    num_io: &'l [usize],
+    selectors: &'l [Vec<bool>],
+    fixed_columns: &'l [Vec<F>],
+    num_advice_columns: usize,
+    advice_phases: &'l AdvicePhases,
+    num_challenges: usize,
+    round_sizes: &'l [usize],
+    custom_gates_lookup_compressed: &'l Expression<F>,
+    permutation_data: &'l PermutationData,
+    lookup_arguments: &'l Option<lookup::Arguments<F>>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PlonkInstance<C: CurveAffine> {
     /// `W_commitments = round_sizes.len()`, see [`PlonkStructure::round_sizes`]
     pub(crate) W_commitments: Vec<C>,
     pub(crate) instances: Vec<Vec<C::ScalarExt>>,
     /// challenges generated in special soundness protocol
-    /// we will have 0 ~ 3 challenges depending on different cases:
-    /// name them as r1, r2, r3.
+    ///
+    /// the first [`AdvicePhases::num_challenges`] of them are the challenges of the multi-phase
+    /// circuit (see [`phases`]), after them we will have 0 ~ 3 challenges depending on different
+    /// cases: name them as r1, r2, r3.
     /// r1: compress vector lookup, e.g. (a_1, a_2, a_3) -> a_1 + r1*a_2 + r1^2*a_3
     /// r2: challenge to calculate h and g in log-derivative relation
     /// r3: combine all custom gates (P_i) and lookup relations (L_i), e.g.:
@@ -276,13 +302,39 @@ impl<C: CurveAffine, RO: ROTrait<C::Base>> AbsorbInRO<C::Base, RO> for PlonkInst
 }
 
 impl<F: PrimeField> PlonkStructure<F> {
+    /// Serializes `S` as an input of the public params digest, for `#[serde(serialize_with)]`
+    ///
+    /// See [`PlonkStructureDigestView`] for the difference with [`Serialize`] of `S`
+    pub(crate) fn serialize_for_digest<Ser: serde::Serializer>(
+        S: &Self,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        F: Serialize,
+    {
+        PlonkStructureDigestView {
+            k: S.k,
+            num_io: &S.num_io,
+            selectors: &S.selectors,
+            fixed_columns: &S.fixed_columns,
+            num_advice_columns: S.num_advice_columns,
+            advice_phases: &S.advice_phases,
+            num_challenges: S.num_challenges,
+            round_sizes: &S.round_sizes,
+            custom_gates_lookup_compressed: S.custom_gates_lookup_compressed.compressed(),
+            permutation_data: &S.permutation_data,
+            lookup_arguments: &S.lookup_arguments,
+        }
+        .serialize(serializer)
+    }
+
     /// return the index offset of fixed variables(i.e. not folded)
     pub fn num_non_fold_vars(&self) -> usize {
         self.fixed_columns.len() + self.selectors.len()
     }
 
     /// return the number of variables to be folded
-    /// each lookup argument will add 5 variables (l,t,m,h,g)
+    /// each lookup (or shuffle) argument will add 5 variables (l,t,m,h,g)
     pub fn num_fold_vars(&self) -> usize {
         self.num_advice_columns + 5 * self.num_lookups()
     }
@@ -295,6 +347,13 @@ impl<F: PrimeField> PlonkStructure<F> {
         }
     }
 
+    pub fn num_shuffles(&self) -> usize {
+        self.lookup_arguments
+            .as_ref()
+            .map(|arg| arg.num_shuffles())
+            .unwrap_or(0)
+    }
+
     /// indicates whether the original constrain system contains vector lookup
     pub fn has_vector_lookup(&self) -> bool {
         self.lookup_arguments
@@ -316,7 +375,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         U.sps_verify(ro_nark)?;
 
         let data = PlonkEvalDomain {
-            num_advice: self.num_advice_columns,
+            advice_phases: &self.advice_phases,
             num_lookup: self.num_lookups(),
             challenges: &U.challenges,
             selectors: &self.selectors,
@@ -365,6 +424,9 @@ impl<F: PrimeField> PlonkStructure<F> {
     // permutation check for folding instance-witness pair
 
     /// check whether the log-derivative equation is satisfied
+    ///
+    /// Shuffle arguments are checked here too, since they are lookups with unit multiplicities,
+    /// see [`lookup`] module docs
     pub fn is_sat_log_derivative(&self, W: &[Vec<F>]) -> bool {
         let nrow = 1 << self.k;
         let check_is_zero = |hs: &[Vec<F>], gs: &[Vec<F>]| -> bool {
@@ -384,16 +446,18 @@ impl<F: PrimeField> PlonkStructure<F> {
                 .collect::<Vec<_>>()
         };
 
-        if self.has_vector_lookup() {
-            let hs = gather_vectors(&W[2], 0);
-            let gs = gather_vectors(&W[2], 1);
-            check_is_zero(&hs, &gs)
-        } else if self.num_lookups() > 0 {
-            let hs = gather_vectors(&W[1], 0);
-            let gs = gather_vectors(&W[1], 1);
-            check_is_zero(&hs, &gs)
-        } else {
-            true
+        if self.num_lookups() == 0 {
+            return true;
+        }
+
+        // (h_i, g_i) are always committed in the last prover round
+        match W.last() {
+            Some(W_hg) => {
+                let hs = gather_vectors(W_hg, 0);
+                let gs = gather_vectors(W_hg, 1);
+                check_is_zero(&hs, &gs)
+            }
+            None => false,
         }
     }
 
@@ -411,22 +475,98 @@ impl<F: PrimeField> PlonkStructure<F> {
     /// run special soundness protocol to generate witnesses and challenges
     /// depending on whether we have multiple gates, lookup arguments and whether
     /// we have vector lookup, we will call different sub-sps protocol
+    ///
+    /// For multi-phase circuits the advice columns of all phases except the last one are committed
+    /// before, one round per phase (see [`phases`]). After each of these rounds `advice` is
+    /// collected again with all challenges known so far, so it must be deterministic.
+    ///
+    /// Dynamic lookup tables (see [`lookup::Arguments`]) don't require an extra round: the table
+    /// column `t_i` is always committed together with `l_i` & `m_i`, which is never earlier than
+    /// the advice columns it depends on
     #[instrument(name = "sps", skip_all)]
     pub fn run_sps_protocol<C: CurveAffine<ScalarExt = F>, RO: ROTrait<C::Base>>(
         &self,
         ck: &CommitmentKey<C>,
         instances: &[Vec<F>],
-        advice: &[Vec<F>],
+        advice: &(impl AdviceSource<F> + ?Sized),
         ro_nark: &mut RO,
         num_challenges: usize,
     ) -> Result<PlonkTrace<C>, SpsError> {
-        match num_challenges {
-            0 => self.run_sps_protocol_0(instances, advice, ck),
-            1 => self.run_sps_protocol_1(instances, advice, ck, ro_nark),
-            2 => self.run_sps_protocol_2(instances, advice, ck, ro_nark),
-            3 => self.run_sps_protocol_3(instances, advice, ck, ro_nark),
-            challenges_count => Err(SpsError::UnsupportedChallengesCount { challenges_count }),
+        let (advice, phases) = self.run_sps_phases(ck, instances, advice, ro_nark)?;
+
+        match num_challenges.checked_sub(phases.challenges.len()) {
+            Some(0) => self.run_sps_protocol_0(instances, &advice, phases, ck),
+            Some(1) => self.run_sps_protocol_1(instances, &advice, phases, ck, ro_nark),
+            Some(2) => self.run_sps_protocol_2(instances, &advice, phases, ck, ro_nark),
+            Some(3) => self.run_sps_protocol_3(instances, &advice, phases, ck, ro_nark),
+            _ => Err(SpsError::UnsupportedChallengesCount {
+                challenges_count: num_challenges,
+            }),
+        }
+    }
+
+    /// commit advice columns of all phases except the last one, one round per challenge of the
+    /// circuit, see [`phases`]
+    /// notations: "[C]" absorb C; "]r[" squeeze r;
+    /// sequence of generating challenges:
+    /// [pi.instance] -> [C_0] -> ]c_0[ -> ... -> [C_{n-1}] -> ]c_{n-1}[
+    ///
+    /// returns advice columns of all phases, collected with all challenges `c_i`
+    #[instrument(name = "phases", skip_all)]
+    fn run_sps_phases<'a, C: CurveAffine<ScalarExt = F>, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        instances: &[Vec<F>],
+        advice: &'a (impl AdviceSource<F> + ?Sized),
+        ro_nark: &mut RO,
+    ) -> Result<(Cow<'a, [Vec<F>]>, PhasesTranscript<C>), SpsError> {
+        if self.advice_phases.has_unordered_challenges() {
+            return Err(SpsError::UnorderedChallenges);
+        }
+
+        let mut phases = PhasesTranscript {
+            W: vec![],
+            W_commitments: vec![],
+            challenges: vec![],
+        };
+
+        let num_phase_rounds = self.advice_phases.num_challenges();
+        if num_phase_rounds > 0 {
+            ro_nark.absorb_field_iter(
+                instances
+                    .iter()
+                    .flat_map(|inst| inst.iter())
+                    .map(|val| fe_to_fe(val).unwrap()),
+            );
         }
+
+        for phase in 0..num_phase_rounds {
+            let _s = info_span!("phase_commit", phase).entered();
+
+            // e.g. rounds of the second & next challenges of one phase commit nothing
+            let W = if self.advice_phases.phase_sizes()[phase] == 0 {
+                vec![]
+            } else {
+                self.advice_phases.concatenate(
+                    &advice.collect_advice(&phases.challenges)?,
+                    phase,
+                    1 << self.k,
+                )
+            };
+            let C_phase = ck.commit(&W).map_err(|err| SpsError::WrongCommitmentSize {
+                annotation: "W_phase",
+                err,
+            })?;
+            let challenge = ro_nark
+                .absorb_point(&C_phase)
+                .squeeze::<C>(NUM_CHALLENGE_BITS);
+
+            phases.W.push(W);
+            phases.W_commitments.push(C_phase);
+            phases.challenges.push(challenge);
+        }
+
+        Ok((advice.collect_advice(&phases.challenges)?, phases))
     }
 
     /// run 0-round special soundness protocol
@@ -436,11 +576,16 @@ impl<F: PrimeField> PlonkStructure<F> {
         &self,
         instances: &[Vec<F>],
         advice: &[Vec<F>],
+        phases: PhasesTranscript<C>,
         ck: &CommitmentKey<C>,
     ) -> Result<PlonkTrace<C>, SpsError> {
         let _span = info_span!("witness_commit").entered();
 
-        let W1 = concatenate_with_padding(advice, 1 << self.k);
+        let W1 = self.advice_phases.concatenate(
+            advice,
+            self.advice_phases.num_challenges(),
+            1 << self.k,
+        );
         let C1 = ck
             .commit(&W1)
             .map_err(|err| SpsError::WrongCommitmentSize {
@@ -448,14 +593,7 @@ impl<F: PrimeField> PlonkStructure<F> {
                 err,
             })?;
 
-        Ok(PlonkTrace {
-            u: PlonkInstance {
-                W_commitments: vec![C1],
-                instances: instances.to_vec(),
-                challenges: vec![],
-            },
-            w: PlonkWitness { W: vec![W1] },
-        })
+        Ok(phases.into_trace(instances, [W1], [C1], iter::empty()))
     }
 
     /// run 1-round special soundness protocol to generate witnesses and challenges
@@ -468,23 +606,31 @@ impl<F: PrimeField> PlonkStructure<F> {
         &self,
         instances: &[Vec<F>],
         advice: &[Vec<F>],
+        phases: PhasesTranscript<C>,
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
     ) -> Result<PlonkTrace<C>, SpsError> {
+        let is_instances_absorbed = phases.is_instances_absorbed();
         let PlonkTrace {
             u: mut plonk_instance,
             w: plonk_witness,
-        } = self.run_sps_protocol_0(instances, advice, ck)?;
+        } = self.run_sps_protocol_0(instances, advice, phases, ck)?;
 
         let _span = info_span!("instance_commit").entered();
-        ro_nark
-            .absorb_field_iter(
+        if !is_instances_absorbed {
+            ro_nark.absorb_field_iter(
                 instances
                     .iter()
                     .flat_map(|instance| instance.iter())
                     .map(|val| fe_to_fe(val).unwrap()),
-            )
-            .absorb_point_iter(plonk_instance.W_commitments.iter());
+            );
+        }
+        ro_nark.absorb_point_iter(
+            plonk_instance
+                .W_commitments
+                .iter()
+                .skip(self.advice_phases.num_challenges()),
+        );
 
         plonk_instance
             .challenges
@@ -506,6 +652,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         &self,
         instances: &[Vec<F>],
         advice: &[Vec<F>],
+        phases: PhasesTranscript<C>,
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
     ) -> Result<PlonkTrace<C>, SpsError> {
@@ -515,12 +662,16 @@ impl<F: PrimeField> PlonkStructure<F> {
         let lookup_coeff = self
             .lookup_arguments
             .as_ref()
-            .map(|la| la.evaluate_coefficient_1(self, advice, F::ZERO))
+            .map(|la| la.evaluate_coefficient_1(self, advice, &phases.challenges))
             .transpose()?
             .ok_or(SpsError::LackOfLookupArguments)?;
 
         let W1 = [
-            concatenate_with_padding(advice, k_power_of_2),
+            self.advice_phases.concatenate(
+                advice,
+                self.advice_phases.num_challenges(),
+                k_power_of_2,
+            ),
             concatenate_with_padding(
                 &concat_vec!(&lookup_coeff.ls, &lookup_coeff.ts, &lookup_coeff.ms),
                 k_power_of_2,
@@ -536,15 +687,15 @@ impl<F: PrimeField> PlonkStructure<F> {
             })
         }?;
 
-        let r1 = ro_nark
-            .absorb_field_iter(
+        if !phases.is_instances_absorbed() {
+            ro_nark.absorb_field_iter(
                 instances
                     .iter()
                     .flat_map(|inst| inst.iter())
                     .map(|val| fe_to_fe(val).unwrap()),
-            )
-            .absorb_point(&C1)
-            .squeeze::<C>(NUM_CHALLENGE_BITS);
+            );
+        }
+        let r1 = ro_nark.absorb_point(&C1).squeeze::<C>(NUM_CHALLENGE_BITS);
 
         // round 2
         let lookup_coeff = lookup_coeff.evaluate_coefficient_2(r1);
@@ -563,14 +714,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         }?;
         let r2 = ro_nark.absorb_point(&C2).squeeze::<C>(NUM_CHALLENGE_BITS);
 
-        Ok(PlonkTrace {
-            u: PlonkInstance {
-                W_commitments: vec![C1, C2],
-                instances: instances.to_vec(),
-                challenges: vec![r1, r2],
-            },
-            w: PlonkWitness { W: vec![W1, W2] },
-        })
+        Ok(phases.into_trace(instances, [W1, W2], [C1, C2], [r1, r2]))
     }
 
     /// run 3-round special soundness protocol to generate witnesses and challenges
@@ -582,20 +726,27 @@ impl<F: PrimeField> PlonkStructure<F> {
         &self,
         instances: &[Vec<F>],
         advice: &[Vec<F>],
+        phases: PhasesTranscript<C>,
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
     ) -> Result<PlonkTrace<C>, SpsError> {
-        ro_nark.absorb_field_iter(
-            instances
-                .iter()
-                .flat_map(|i| i.iter())
-                .map(|inst| fe_to_fe(inst).unwrap()),
-        );
+        if !phases.is_instances_absorbed() {
+            ro_nark.absorb_field_iter(
+                instances
+                    .iter()
+                    .flat_map(|i| i.iter())
+                    .map(|inst| fe_to_fe(inst).unwrap()),
+            );
+        }
 
         let k_power_of_2 = 1 << self.k;
 
         // round 1
-        let W1 = concatenate_with_padding(advice, k_power_of_2);
+        let W1 = self.advice_phases.concatenate(
+            advice,
+            self.advice_phases.num_challenges(),
+            k_power_of_2,
+        );
         let C1 = {
             let _s = info_span!("witness_commit").entered();
             ck.commit(&W1).map_err(|err| SpsError::WrongCommitmentSize {
@@ -609,7 +760,9 @@ impl<F: PrimeField> PlonkStructure<F> {
         let lookup_coeff = self
             .lookup_arguments
             .as_ref()
-            .map(|la| la.evaluate_coefficient_1(self, advice, r1))
+            .map(|la| {
+                la.evaluate_coefficient_1(self, advice, &concat_vec!(&phases.challenges, &[r1]))
+            })
             .transpose()?
             .ok_or(SpsError::LackOfLookupArguments)?;
 
@@ -643,16 +796,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         }?;
         let r3 = ro_nark.absorb_point(&C3).squeeze::<C>(NUM_CHALLENGE_BITS);
 
-        Ok(PlonkTrace {
-            u: PlonkInstance {
-                W_commitments: vec![C1, C2, C3],
-                instances: instances.to_vec(),
-                challenges: vec![r1, r2, r3],
-            },
-            w: PlonkWitness {
-                W: vec![W1, W2, W3],
-            },
-        })
+        Ok(phases.into_trace(instances, [W1, W2, W3], [C1, C2, C3], [r1, r2, r3]))
     }
 
     pub fn permutation_matrix(&self) -> SparseMatrix<F> {
@@ -674,6 +818,43 @@ impl<C: CurveAffine> PlonkInstance<C> {
     }
 }
 
+/// Witness, commitments & challenges of the advice phases committed before the rounds of the
+/// special soundness protocol, see [`phases`]
+struct PhasesTranscript<C: CurveAffine> {
+    W: Vec<Vec<C::ScalarExt>>,
+    W_commitments: Vec<C>,
+    challenges: Vec<C::ScalarExt>,
+}
+
+impl<C: CurveAffine> PhasesTranscript<C> {
+    /// instances are absorbed before the first phase, if there is any
+    fn is_instances_absorbed(&self) -> bool {
+        !self.W_commitments.is_empty()
+    }
+
+    /// append the rounds of the special soundness protocol
+    fn into_trace<const N: usize>(
+        mut self,
+        instances: &[Vec<C::ScalarExt>],
+        W: [Vec<C::ScalarExt>; N],
+        W_commitments: [C; N],
+        challenges: impl IntoIterator<Item = C::ScalarExt>,
+    ) -> PlonkTrace<C> {
+        self.W.extend(W);
+        self.W_commitments.extend(W_commitments);
+        self.challenges.extend(challenges);
+
+        PlonkTrace {
+            u: PlonkInstance {
+                W_commitments: self.W_commitments,
+                instances: instances.to_vec(),
+                challenges: self.challenges,
+            },
+            w: PlonkWitness { W: self.W },
+        }
+    }
+}
+
 // Evaluates the witness data for each gate in the PLONK structure.
 ///
 /// This function iterates through the gates of a provided [`PlonkStructure`],
@@ -701,7 +882,7 @@ pub(crate) fn iter_evaluate_witness<'link, F: PrimeField>(
 ) -> impl 'link + Send + Iterator<Item = Result<F, eval::Error>> {
     S.gates.iter().flat_map(|gate| {
         let eval_domain = PlonkEvalDomain {
-            num_advice: S.num_advice_columns,
+            advice_phases: &S.advice_phases,
             num_lookup: S.num_lookups(),
             selectors: &S.selectors,
             fixed: &S.fixed_columns,
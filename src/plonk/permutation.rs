@@ -5,7 +5,7 @@ use halo2_proofs::{
     halo2curves::ff::PrimeField,
     plonk::{permutation::Argument, Any, Column, Error},
 };
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use crate::polynomial::sparse::SparseMatrix;
@@ -114,16 +114,89 @@ impl Assembly {
     }
 }
 
-#[derive(Clone, PartialEq, Default)]
+/// Column of the permutation argument
+///
+/// Mirrors [`Column<Any>`], which has no public constructor and therefore can't be deserialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "ColumnEncoding", try_from = "ColumnEncoding")]
+pub(crate) enum PermutationColumn {
+    Instance(usize),
+    Fixed(usize),
+    Advice { index: usize, phase: u8 },
+}
+
+impl PermutationColumn {
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Instance(index) | Self::Fixed(index) | Self::Advice { index, .. } => *index,
+        }
+    }
+}
+
+impl From<&Column<Any>> for PermutationColumn {
+    fn from(column: &Column<Any>) -> Self {
+        match column.column_type() {
+            Any::Instance => Self::Instance(column.index()),
+            Any::Fixed => Self::Fixed(column.index()),
+            Any::Advice(advice) => Self::Advice {
+                index: column.index(),
+                phase: advice.phase(),
+            },
+        }
+    }
+}
+
+/// Binary encoding of [`PermutationColumn`]
+///
+/// [`PermutationData`] is a part of the public params digest, so the encoding is kept the same as
+/// of [`Column<Any>`] before [`PermutationColumn`] was introduced: `column_type` is `0` for
+/// instance, `1` for fixed & `2 + phase` for advice columns
+#[derive(Serialize, Deserialize)]
+struct ColumnEncoding {
+    index: usize,
+    column_type: u16,
+}
+
+impl From<PermutationColumn> for ColumnEncoding {
+    fn from(column: PermutationColumn) -> Self {
+        let (index, column_type) = match column {
+            PermutationColumn::Instance(index) => (index, 0),
+            PermutationColumn::Fixed(index) => (index, 1),
+            PermutationColumn::Advice { index, phase } => (index, 2 + phase as u16),
+        };
+
+        Self { index, column_type }
+    }
+}
+
+impl TryFrom<ColumnEncoding> for PermutationColumn {
+    type Error = String;
+
+    fn try_from(ColumnEncoding { index, column_type }: ColumnEncoding) -> Result<Self, String> {
+        match column_type {
+            0 => Ok(Self::Instance(index)),
+            1 => Ok(Self::Fixed(index)),
+            column_type => u8::try_from(column_type - 2)
+                .map(|phase| Self::Advice { index, phase })
+                .map_err(|_| format!("wrong type of permutation column: {column_type}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub(crate) struct PermutationData {
-    columns: Box<[Column<Any>]>,
+    columns: Box<[PermutationColumn]>,
     mapping: Box<[Vec<(usize, usize)>]>,
 }
 
 impl From<&Assembly> for PermutationData {
     fn from(assembly: &Assembly) -> Self {
         PermutationData {
-            columns: assembly.columns.clone().into_boxed_slice(),
+            columns: assembly
+                .columns
+                .iter()
+                .map(PermutationColumn::from)
+                .collect(),
             mapping: assembly.mapping.clone().into_boxed_slice(),
         }
     }
@@ -176,7 +249,7 @@ impl PermutationData {
             let instance_columns = self
                 .columns
                 .iter()
-                .filter(|column| column.column_type().eq(&Any::Instance))
+                .filter(|column| matches!(column, PermutationColumn::Instance(_)))
                 .map(|column| column.index())
                 .collect::<Box<[_]>>();
             debug!("instance_columns {instance_columns:?}");
@@ -189,7 +262,9 @@ impl PermutationData {
 
             columns_to_remove_set.sort();
 
-            move |column: &Column<Any>| columns_to_remove_set.binary_search(&column.index()).is_ok()
+            move |column: &PermutationColumn| {
+                columns_to_remove_set.binary_search(&column.index()).is_ok()
+            }
         };
 
         for (column_index, column) in self.columns.iter().enumerate() {
@@ -243,49 +318,6 @@ impl PermutationData {
     }
 }
 
-impl Serialize for PermutationData {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        use serde::ser::SerializeStruct;
-
-        let mut state = serializer.serialize_struct("Assembly", 4)?;
-
-        #[derive(Serialize)]
-        struct ColumnWrapper {
-            index: usize,
-            column_type: u16,
-        }
-
-        impl From<Column<Any>> for ColumnWrapper {
-            fn from(value: Column<Any>) -> Self {
-                Self {
-                    index: value.index(),
-                    column_type: match value.column_type() {
-                        Any::Instance => 0,
-                        Any::Fixed => 1,
-                        Any::Advice(advice) => 2 + advice.phase() as u16,
-                    },
-                }
-            }
-        }
-
-        state.serialize_field(
-            "columns",
-            &self
-                .columns
-                .iter()
-                .cloned()
-                .map(ColumnWrapper::from)
-                .collect::<Box<[_]>>(),
-        )?;
-        state.serialize_field("perm_assembly", &self.mapping)?;
-
-        state.end()
-    }
-}
-
 /// Constructs a sparse permutation matrix `P` of size `N * N` from copy constraints.
 ///
 /// The function accounts for the changes due to folding, which affects the values of advice/instance
@@ -311,7 +343,7 @@ impl Serialize for PermutationData {
 pub(crate) fn construct_permutation_matrix<F: PrimeField>(
     k_table_size: usize,
     num_io: &[usize],
-    perm_columns: &[Column<Any>],
+    perm_columns: &[PermutationColumn],
     num_advice: usize,
     permutation_mapping: &[Vec<(usize, usize)>],
 ) -> SparseMatrix<F> {
@@ -324,15 +356,17 @@ pub(crate) fn construct_permutation_matrix<F: PrimeField>(
         .chain(iter::repeat(num_rows).take(num_advice))
         .collect::<Box<[_]>>();
 
-    let to_flat_column_offset = |column: Column<Any>| -> usize {
-        match column.column_type() {
-            Any::Instance => column.index(),
-            Any::Advice(_) => num_io.len() + column.index(),
-            Any::Fixed => unreachable!("'fixed column' can't be a part of permutation"),
+    let to_flat_column_offset = |column: PermutationColumn| -> usize {
+        match column {
+            PermutationColumn::Instance(index) => index,
+            PermutationColumn::Advice { index, .. } => num_io.len() + index,
+            PermutationColumn::Fixed(_) => {
+                unreachable!("'fixed column' can't be a part of permutation")
+            }
         }
     };
 
-    let to_flat_index = |column: Column<Any>, row: usize| -> usize {
+    let to_flat_index = |column: PermutationColumn, row: usize| -> usize {
         rows_len
             .iter()
             .take(to_flat_column_offset(column))
@@ -348,8 +382,8 @@ pub(crate) fn construct_permutation_matrix<F: PrimeField>(
         let left_col = perm_columns[left_col];
         columns_not_in_perm.remove(&to_flat_column_offset(left_col));
 
-        let instance_rows_count = match left_col.column_type() {
-            Any::Instance => num_io.get(left_col.index()),
+        let instance_rows_count = match left_col {
+            PermutationColumn::Instance(index) => num_io.get(index),
             _ => None,
         };
 
@@ -0,0 +1,181 @@
+//! # Multi-phase Advice Columns
+//!
+//! halo2 allows to allocate advice columns in later phases (see
+//! [`ConstraintSystem::advice_column_in`]) and to query [`halo2_proofs::plonk::Challenge`]s,
+//! which are squeezed only after all advice columns of the earlier phases are committed. This is
+//! needed for e.g. random-linear-combination or permutation-style gadgets.
+//!
+//! In the special soundness protocol each challenge of the circuit takes a separate prover round:
+//!
+//! `[pi.instance] -> [C_0] -> ]c_0[ -> [C_1] -> ]c_1[ -> ... -> [C_n || ...] -> ...`
+//!
+//! where `C_i` is the commitment to the advice columns, which must be known before the challenge
+//! `c_i` is squeezed & weren't committed in earlier rounds. If several challenges are usable after
+//! the same phase, the rounds of all of them except the first one commit nothing (`C_i` is the
+//! point at infinity). The advice columns of phases after the last challenge are committed
+//! together with the first round of the original protocol (see
+//! [`PlonkStructure::run_sps_protocol`]), so single-phase circuits are not affected at all.
+//!
+//! The challenges of the circuit take indices `0..num_challenges` in [`PlonkInstance::challenges`],
+//! the challenges of the lookup argument & gates compression are placed after them.
+//!
+//! ## Limitations
+//!
+//! Challenges must be declared in order of their phases, i.e. a challenge can't be usable after
+//! an earlier phase than the challenge declared before it. Otherwise
+//! [`PlonkStructure::run_sps_protocol`] returns [`SpsError::UnorderedChallenges`].
+//!
+//! [`PlonkStructure::run_sps_protocol`]: super::PlonkStructure::run_sps_protocol
+//! [`PlonkInstance::challenges`]: super::PlonkInstance::challenges
+
+use std::borrow::Cow;
+
+use halo2_proofs::plonk::ConstraintSystem;
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ff::PrimeField, sps::Error as SpsError};
+
+/// Location of the advice columns in the prover rounds
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AdvicePhases {
+    /// `(round, position inside of round)` for each advice column
+    locations: Vec<(usize, usize)>,
+    /// Number of advice columns in each round
+    phase_sizes: Vec<usize>,
+    /// Whether some challenge is declared after a challenge of a later phase, see [`self`]
+    has_unordered_challenges: bool,
+}
+
+impl AdvicePhases {
+    pub fn new<F: PrimeField>(cs: &ConstraintSystem<F>) -> Self {
+        let num_challenges = cs.num_challenges();
+        let challenge_phases = cs.challenge_phase();
+
+        let has_unordered_challenges = challenge_phases
+            .iter()
+            .tuple_windows()
+            .any(|(prev, next)| prev > next);
+
+        let mut phase_sizes = vec![0; num_challenges + 1];
+        let locations = cs
+            .advice_column_phase()
+            .iter()
+            .map(|phase| {
+                // the advice column is committed right before the first challenge usable after
+                // its phase, columns of phases after all challenges don't depend on them and are
+                // committed together with the first round of the original protocol
+                let round = challenge_phases
+                    .iter()
+                    .position(|challenge_phase| challenge_phase >= phase)
+                    .unwrap_or(num_challenges);
+                let position = phase_sizes[round];
+                phase_sizes[round] += 1;
+                (round, position)
+            })
+            .collect();
+
+        Self {
+            locations,
+            phase_sizes,
+            has_unordered_challenges,
+        }
+    }
+
+    pub fn has_unordered_challenges(&self) -> bool {
+        self.has_unordered_challenges
+    }
+
+    /// Appends `num_columns` advice columns to the last phase, see
+    /// [`crate::polynomial::degree_reduction`]
+    pub(crate) fn extend_last_phase(&mut self, num_columns: usize) {
+        let last_phase = self.phase_sizes.len() - 1;
+        let position = self.phase_sizes[last_phase];
+        self.locations
+            .extend((position..position + num_columns).map(|position| (last_phase, position)));
+        self.phase_sizes[last_phase] += num_columns;
+    }
+
+    pub fn num_advice(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Number of challenges provided to circuit, which is one less than number of rounds
+    pub fn num_challenges(&self) -> usize {
+        self.phase_sizes.len().saturating_sub(1)
+    }
+
+    /// Number of advice columns in each round
+    pub fn phase_sizes(&self) -> &[usize] {
+        &self.phase_sizes
+    }
+
+    /// Number of advice columns committed together with the first round of the original protocol
+    pub fn last_phase_size(&self) -> usize {
+        self.phase_sizes.last().copied().unwrap_or_default()
+    }
+
+    /// `(round, position inside of round)` of the advice column
+    pub fn location(&self, column_index: usize) -> Option<(usize, usize)> {
+        self.locations.get(column_index).copied()
+    }
+
+    /// Concatenates advice columns of one `phase` with padding of each of them to `pad_size`
+    pub(crate) fn concatenate<F: PrimeField>(
+        &self,
+        advice: &[Vec<F>],
+        phase: usize,
+        pad_size: usize,
+    ) -> Vec<F> {
+        advice
+            .par_iter()
+            .zip_eq(self.locations.par_iter())
+            .filter(|(_, (column_phase, _))| *column_phase == phase)
+            .flat_map_iter(|(column, _)| column.iter().copied().pad_using(pad_size, |_| F::ZERO))
+            .collect()
+    }
+
+    /// Iterates over all advice values in order of columns, regardless of the prover round in
+    /// which they were committed
+    ///
+    /// `W` is the witness of prover rounds, see [`super::PlonkWitness::W`]
+    pub(crate) fn iter_advice<'link, F: PrimeField>(
+        &'link self,
+        W: &'link [Vec<F>],
+        nrow: usize,
+    ) -> impl 'link + Iterator<Item = &'link F> {
+        self.locations
+            .iter()
+            .flat_map(move |(phase, position)| &W[*phase][position * nrow..(position + 1) * nrow])
+    }
+}
+
+/// Source of the advice columns values for [`super::PlonkStructure::run_sps_protocol`]
+///
+/// For single-phase circuits it is enough to have witness collected in advance, but advice
+/// columns of later phases depend on challenges, so they must be (re)synthesized in each round.
+pub trait AdviceSource<F: PrimeField> {
+    /// Collects values of all advice columns, given challenges of all already committed phases.
+    ///
+    /// Advice columns of phases not committed yet may be left unassigned (zero)
+    fn collect_advice(&self, challenges: &[F]) -> Result<Cow<'_, [Vec<F>]>, SpsError>;
+}
+
+impl<F: PrimeField> AdviceSource<F> for [Vec<F>] {
+    fn collect_advice(&self, challenges: &[F]) -> Result<Cow<'_, [Vec<F>]>, SpsError> {
+        if challenges.is_empty() {
+            Ok(Cow::Borrowed(self))
+        } else {
+            Err(SpsError::WitnessDependsOnChallenges {
+                phase: challenges.len(),
+            })
+        }
+    }
+}
+
+impl<F: PrimeField> AdviceSource<F> for Vec<Vec<F>> {
+    fn collect_advice(&self, challenges: &[F]) -> Result<Cow<'_, [Vec<F>]>, SpsError> {
+        self.as_slice().collect_advice(challenges)
+    }
+}
@@ -0,0 +1,456 @@
+//! # Degree Reduction
+//!
+//! The degree of the folded relation is the maximum degree of custom gates (see
+//! [`crate::plonk::PlonkStructure::get_degree_for_folding`]), which determines the number of cross
+//! terms in [`crate::nifs::vanilla`] & the domain sizes in [`crate::nifs::protogalaxy`].
+//!
+//! This opt-in pass rewrites gates so that their degree is at most `max_degree`. High-degree
+//! products of witness factors are replaced with queries to auxiliary advice columns, each of them
+//! constrained by an extra gate:
+//!
+//! ```math
+//! q_5 \cdot s^5 \rightarrow q_5 \cdot s \cdot s \cdot x, \quad x - s \cdot s \cdot s = 0
+//! ```
+//!
+//! Auxiliary columns are appended after the advice columns of the circuit & belong to the last
+//! advice phase (see [`crate::plonk::phases`]), their values are computed from the witness by
+//! [`AuxColumns::evaluate`].
+//!
+//! ## Limitations
+//!
+//! Only factors depending on advice columns & challenges of the circuit itself can be extracted.
+//! Selectors & fixed columns are not part of the witness, so factors like `(s + q)` stay as is,
+//! as well as factors with lookup variables. For such gates the degree is reduced as much as
+//! possible.
+//!
+//! Each auxiliary column adds one more gate, and gates are compressed with powers of a challenge
+//! (see [`crate::plonk::util::compress_expression`]), which increases the degree of the compressed
+//! relation. So the pass pays off for gates of degree noticeably higher than `max_degree`.
+
+use halo2_proofs::poly::Rotation;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::*;
+
+use super::expression::{Expression, Query, QueryIndexContext};
+use crate::ff::PrimeField;
+
+/// Definitions of auxiliary advice columns introduced by [`reduce_degree`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct AuxColumns<F: PrimeField> {
+    /// Query index of the first advice column, i.e. number of selectors & fixed columns
+    advice_offset: usize,
+    /// Number of advice columns of the circuit, auxiliary ones go right after them
+    num_advice: usize,
+    /// `aux_i = definitions[i]`, may depend on previous auxiliary columns
+    definitions: Vec<Expression<F>>,
+}
+
+impl<F: PrimeField> AuxColumns<F> {
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    pub fn definitions(&self) -> &[Expression<F>] {
+        &self.definitions
+    }
+
+    /// Computes values of auxiliary columns by the advice columns of the circuit
+    ///
+    /// Challenges not provided yet are treated as zero, like the advice columns of the later
+    /// phases, see [`crate::plonk::phases::AdviceSource`]
+    pub fn evaluate(&self, advice: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>> {
+        assert_eq!(advice.len(), self.num_advice);
+
+        let nrow = advice.first().map(Vec::len).unwrap_or_default();
+        let mut aux: Vec<Vec<F>> = Vec::with_capacity(self.definitions.len());
+
+        for definition in self.definitions.iter() {
+            let column = (0..nrow)
+                .into_par_iter()
+                .map(|row| {
+                    definition.evaluate(
+                        &|constant| constant,
+                        &|Query { index, rotation }| {
+                            let column = index - self.advice_offset;
+                            let row = (row as i32 + rotation.0).rem_euclid(nrow as i32) as usize;
+                            advice
+                                .get(column)
+                                .unwrap_or_else(|| &aux[column - self.num_advice])[row]
+                        },
+                        &|index| challenges.get(index).copied().unwrap_or_default(),
+                        &|a| -a,
+                        &|a, b| a + b,
+                        &|a, b| a * b,
+                        &|a, k| a * k,
+                    )
+                })
+                .collect();
+
+            aux.push(column);
+        }
+
+        aux
+    }
+}
+
+/// Rewrites `gates` to have degree at most `max_degree`
+///
+/// Returns the rewritten gates followed by the constraints of auxiliary columns. Query indices of
+/// lookup variables are shifted by the number of auxiliary columns.
+///
+/// `num_circuit_challenges` - number of challenges of the circuit itself, which are known at the
+/// moment of the witness collection, see [`crate::plonk::phases`]
+pub fn reduce_degree<F: PrimeField>(
+    gates: &[Expression<F>],
+    ctx: &QueryIndexContext,
+    num_circuit_challenges: usize,
+    max_degree: usize,
+) -> (Vec<Expression<F>>, AuxColumns<F>) {
+    assert!(max_degree >= 2, "degree can't be reduced below 2");
+
+    let mut reducer = Reducer {
+        advice_offset: ctx.num_selectors + ctx.num_fixed,
+        num_advice: ctx.num_advice,
+        lookup_end: ctx.num_selectors + ctx.num_fixed + ctx.num_advice + 5 * ctx.num_lookups,
+        num_circuit_challenges,
+        max_degree,
+        definitions: vec![],
+    };
+
+    let reduced = gates
+        .iter()
+        .map(|gate| {
+            let reduced = reducer.reduce(gate);
+            if reducer.degree(&reduced) > max_degree {
+                warn!("gate {gate} can't be reduced to degree {max_degree}");
+            }
+            reduced
+        })
+        .collect::<Vec<_>>();
+
+    let aux_constraints = reducer
+        .definitions
+        .iter()
+        .enumerate()
+        .map(|(index, definition)| reducer.aux_query(index) - definition.clone())
+        .collect::<Vec<_>>();
+
+    debug!(
+        "{} auxiliary columns introduced to reduce degree to {max_degree}",
+        reducer.definitions.len()
+    );
+
+    let gates = reduced
+        .iter()
+        .chain(aux_constraints.iter())
+        .map(|expr| reducer.remap(expr))
+        .collect();
+
+    let aux = AuxColumns {
+        advice_offset: reducer.advice_offset,
+        num_advice: reducer.num_advice,
+        definitions: reducer
+            .definitions
+            .iter()
+            .map(|expr| reducer.remap(expr))
+            .collect(),
+    };
+
+    (gates, aux)
+}
+
+/// While reducing, auxiliary columns take query indices after the lookup variables, so that
+/// the original indices stay valid. See [`Reducer::remap`] for the final layout.
+struct Reducer<F: PrimeField> {
+    advice_offset: usize,
+    num_advice: usize,
+    lookup_end: usize,
+    num_circuit_challenges: usize,
+    max_degree: usize,
+    definitions: Vec<Expression<F>>,
+}
+
+impl<F: PrimeField> Reducer<F> {
+    fn degree(&self, expr: &Expression<F>) -> usize {
+        expr.evaluate(
+            &|_| 0,
+            &|query| usize::from(query.index >= self.advice_offset),
+            &|_| 1,
+            &|a| a,
+            &|a, b| a.max(b),
+            &|a, b| a + b,
+            &|a, _| a,
+        )
+    }
+
+    /// Whether the value of `expr` can be computed by the advice columns & circuit challenges
+    fn is_extractable(&self, expr: &Expression<F>) -> bool {
+        let advice_end = self.advice_offset + self.num_advice;
+        expr.evaluate(
+            &|_| true,
+            &|query| {
+                (self.advice_offset..advice_end).contains(&query.index)
+                    || query.index >= self.lookup_end
+            },
+            &|index| index < self.num_circuit_challenges,
+            &|a| a,
+            &|a, b| a && b,
+            &|a, b| a && b,
+            &|a, _| a,
+        )
+    }
+
+    fn aux_query(&self, index: usize) -> Expression<F> {
+        Expression::Polynomial(Query {
+            index: self.lookup_end + index,
+            rotation: Rotation::cur(),
+        })
+    }
+
+    /// Returns the query of the auxiliary column, reusing the column with the same definition
+    fn extract(&mut self, definition: Expression<F>) -> Expression<F> {
+        let index = self
+            .definitions
+            .iter()
+            .position(|existing| *existing == definition)
+            .unwrap_or_else(|| {
+                self.definitions.push(definition);
+                self.definitions.len() - 1
+            });
+
+        self.aux_query(index)
+    }
+
+    fn reduce(&mut self, expr: &Expression<F>) -> Expression<F> {
+        if self.degree(expr) <= self.max_degree {
+            return expr.clone();
+        }
+
+        match expr {
+            Expression::Negated(a) => -self.reduce(a),
+            Expression::Scaled(a, k) => self.reduce(a) * *k,
+            Expression::Sum(a, b) => self.reduce(a) + self.reduce(b),
+            Expression::Product(_, _) => self.reduce_product(expr),
+            Expression::Constant(_) | Expression::Polynomial(_) | Expression::Challenge(_) => {
+                unreachable!("degree of leaf is at most 1")
+            }
+        }
+    }
+
+    fn reduce_product(&mut self, expr: &Expression<F>) -> Expression<F> {
+        let mut factors = vec![];
+        flatten_product(expr, &mut factors);
+
+        let mut constant_factors = vec![];
+        let mut fixed_factors = vec![];
+        let mut items = vec![];
+        for factor in factors {
+            let factor = self.reduce(factor);
+            if self.degree(&factor) == 0 {
+                constant_factors.push(factor);
+            } else if self.is_extractable(&factor) {
+                items.push(factor);
+            } else {
+                fixed_factors.push(factor);
+            }
+        }
+
+        let budget = self
+            .max_degree
+            .saturating_sub(fixed_factors.iter().map(|factor| self.degree(factor)).sum());
+
+        while items.iter().map(|item| self.degree(item)).sum::<usize>() > budget {
+            items.sort_by_key(|item| self.degree(item));
+
+            // the longest prefix of lowest-degree items fitting into one auxiliary column
+            let mut prefix_degree = 0;
+            let prefix_len = items
+                .iter()
+                .take_while(|item| {
+                    prefix_degree += self.degree(item);
+                    prefix_degree <= self.max_degree
+                })
+                .count();
+
+            let group = if prefix_len > 1 {
+                items.drain(..prefix_len).collect::<Vec<_>>()
+            } else if self.degree(items.last().unwrap()) > 1 {
+                vec![items.pop().unwrap()]
+            } else {
+                // only one item of degree 1 left, the rest of degree is taken by `fixed_factors`
+                break;
+            };
+
+            let aux = self.extract(product(group));
+            items.push(aux);
+        }
+
+        product(
+            constant_factors
+                .into_iter()
+                .chain(fixed_factors)
+                .chain(items)
+                .collect(),
+        )
+    }
+
+    /// Moves auxiliary columns right after advice columns & shifts lookup variables
+    fn remap(&self, expr: &Expression<F>) -> Expression<F> {
+        let advice_end = self.advice_offset + self.num_advice;
+        let num_aux = self.definitions.len();
+
+        expr.evaluate(
+            &Expression::Constant,
+            &|Query { index, rotation }| {
+                let index = if index < advice_end {
+                    index
+                } else if index < self.lookup_end {
+                    index + num_aux
+                } else {
+                    index - self.lookup_end + advice_end
+                };
+                Expression::Polynomial(Query { index, rotation })
+            },
+            &Expression::Challenge,
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, k| a * k,
+        )
+    }
+}
+
+fn flatten_product<'e, F: PrimeField>(
+    expr: &'e Expression<F>,
+    factors: &mut Vec<&'e Expression<F>>,
+) {
+    match expr {
+        Expression::Product(a, b) => {
+            flatten_product(a, factors);
+            flatten_product(b, factors);
+        }
+        other => factors.push(other),
+    }
+}
+
+fn product<F: PrimeField>(factors: Vec<Expression<F>>) -> Expression<F> {
+    factors
+        .into_iter()
+        .reduce(|acc, factor| acc * factor)
+        .unwrap_or(Expression::Constant(F::ONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{ff::Field, halo2curves::pasta::Fp};
+
+    /// 1 selector, 1 fixed, 2 advice, 1 lookup
+    const CTX: QueryIndexContext = QueryIndexContext {
+        num_selectors: 1,
+        num_fixed: 1,
+        num_advice: 2,
+        num_challenges: 0,
+        num_lookups: 1,
+    };
+
+    fn query(index: usize) -> Expression<Fp> {
+        Expression::Polynomial(Query {
+            index,
+            rotation: Rotation::cur(),
+        })
+    }
+
+    fn pow(expr: Expression<Fp>, exp: usize) -> Expression<Fp> {
+        product(vec![expr; exp])
+    }
+
+    fn degree(expr: &Expression<Fp>, num_aux: usize) -> usize {
+        expr.degree(&QueryIndexContext {
+            num_advice: CTX.num_advice + num_aux,
+            ..CTX
+        })
+    }
+
+    #[traced_test]
+    #[test]
+    fn quintic() {
+        let [s, q, a, _b] = array::from_fn(query);
+        let gate = s * q * pow(a, 5);
+
+        let (gates, aux) = reduce_degree(&[gate.clone()], &CTX, 0, 3);
+        assert_eq!(aux.len(), 1);
+        assert_eq!(gates.len(), 2);
+        assert_eq!(gates[0].to_string(), "Z_0 * Z_1 * Z_2 * Z_2 * Z_4");
+        assert_eq!(gates[1].to_string(), "Z_4 - Z_2 * Z_2 * Z_2");
+        assert!(gates.iter().all(|gate| degree(gate, aux.len()) <= 3));
+
+        // already low degree gates are untouched
+        let (gates, aux) = reduce_degree(&[gate.clone()], &CTX, 0, 5);
+        assert!(aux.is_empty());
+        assert_eq!(gates, vec![gate]);
+    }
+
+    #[traced_test]
+    #[test]
+    fn shared_and_nested() {
+        let [_s, q, a, b] = array::from_fn(query);
+        let gates = [
+            pow(a.clone(), 6) + q.clone() * b.clone(),
+            pow(a.clone(), 4) * pow(b.clone() + a.clone(), 4),
+            // lookup variable is not extractable
+            pow(a.clone(), 2) * query(4) - Expression::Constant(Fp::ONE),
+        ];
+
+        let (reduced, aux) = reduce_degree(&gates, &CTX, 0, 2);
+        assert!(reduced.iter().all(|gate| degree(gate, aux.len()) <= 2));
+        assert_eq!(reduced.len(), gates.len() + aux.len());
+        // lookup variable is shifted after auxiliary columns
+        assert!(reduced[2]
+            .to_string()
+            .contains(&format!("Z_{}", 4 + aux.len())));
+
+        let nrow = 8;
+        let advice = vec![
+            (0..nrow)
+                .map(|row| Fp::from(row as u64 + 2))
+                .collect::<Vec<_>>(),
+            (0..nrow)
+                .map(|row| Fp::from(row as u64 * 3))
+                .collect::<Vec<_>>(),
+        ];
+        let aux_values = aux.evaluate(&advice, &[]);
+        let columns = advice.iter().chain(aux_values.iter()).collect::<Vec<_>>();
+
+        for row in 0..nrow {
+            let eval = |expr: &Expression<Fp>| {
+                expr.evaluate(
+                    &|constant| constant,
+                    &|query| columns[query.index - 2][row],
+                    &|_| unreachable!(),
+                    &|a| -a,
+                    &|a, b| a + b,
+                    &|a, b| a * b,
+                    &|a, k| a * k,
+                )
+            };
+
+            for constraint in &reduced[gates.len()..] {
+                assert_eq!(eval(constraint), Fp::ZERO);
+            }
+            assert_eq!(
+                eval(&reduced[1]),
+                eval(&(pow(a.clone(), 4) * pow(b.clone() + a.clone(), 4)))
+            );
+        }
+    }
+}
@@ -7,7 +7,7 @@ use std::{
 };
 
 use halo2_proofs::{plonk::Expression as PE, poly::Rotation};
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::{ff::PrimeField, plonk::PlonkStructure, util::trim_leading_zeros};
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
@@ -69,10 +69,13 @@ impl QueryIndexContext {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Query {
     pub index: usize,
-    #[serde(serialize_with = "serialize_rotation")]
+    #[serde(
+        serialize_with = "serialize_rotation",
+        deserialize_with = "deserialize_rotation"
+    )]
     pub rotation: Rotation,
 }
 
@@ -108,7 +111,11 @@ fn serialize_rotation<S: serde::ser::Serializer>(
     v.0.serialize(serializer)
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+fn deserialize_rotation<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rotation, D::Error> {
+    i32::deserialize(deserializer).map(Rotation)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Expression<F> {
     Constant(F),
     Polynomial(Query),
@@ -166,6 +173,20 @@ impl<F: PrimeField> Expression<F> {
         set.len()
     }
 
+    /// Maximum index of challenge in this expression plus one, unlike [`Self::num_challenges`]
+    /// it counts the challenges not used in the expression, but preceding the used ones
+    pub fn challenges_bound(&self) -> usize {
+        let mut set = HashSet::new();
+        self.collect_challenges(&mut set);
+        set.into_iter()
+            .filter_map(|index| match index {
+                ColumnIndex::Challenge { column_index } => Some(column_index + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
     fn collect_challenges(&self, set: &mut HashSet<ColumnIndex>) {
         match self {
             Expression::Constant(_) => (),
@@ -313,6 +334,8 @@ impl<F: PrimeField> Expression<F> {
                 index: num_selector + num_fixed + query.column_index(),
                 rotation: query.rotation(),
             }),
+            // challenges of the circuit take first indices, see [`crate::plonk::phases`]
+            PE::Challenge(challenge) => Expression::Challenge(challenge.index()),
             PE::Negated(a) => {
                 let a = Self::from_halo2_expr(a, num_selector, num_fixed);
                 -a
@@ -447,7 +470,7 @@ impl<F: PrimeField> Expression<F> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct HomogeneousExpression<F: PrimeField> {
     pub expr: Expression<F>,
     pub degree: usize,
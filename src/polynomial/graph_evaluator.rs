@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use halo2_proofs::poly::Rotation;
 use tracing::*;
 
@@ -53,7 +55,7 @@ fn get_rotation_idx(idx: usize, rot: i32, num_row: usize) -> usize {
 }
 
 /// Value used in a calculation
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
 enum ValueSource {
     /// This is a constant value
     Constant(usize),
@@ -68,7 +70,7 @@ enum ValueSource {
 }
 
 /// Calculation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 enum Calculation {
     /// This is an addition
     Add(ValueSource, ValueSource),
@@ -166,9 +168,9 @@ struct EvaluationData<F: PrimeField> {
 pub struct GraphEvaluator<F: PrimeField> {
     /// Constant values used during the calculation. They are accessed by index during execution.
     /// To avoid storing the same `Scalar` values in multiple nodes.
-    ///
-    /// TODO #159 Consider better ways of storage (sorted for example)
     constants: Vec<F>,
+    /// Index in `constants` by the representation of the constant
+    constants_index: HashMap<Vec<u8>, usize>,
     rotations: Vec<i32>,
     num_intermediates: usize,
     /// All calculations to be performed within the graph
@@ -177,15 +179,25 @@ pub struct GraphEvaluator<F: PrimeField> {
     /// will be at a lower index. This allows the nodes of calculations to be arranged linearly and
     /// is provided by recursion.
     calculations: Vec<CalculationInfo>,
+    /// Target of the calculation by the calculation itself, to reuse intermediate values
+    calculations_index: HashMap<Calculation, usize>,
 }
 
 impl<F: PrimeField> Default for GraphEvaluator<F> {
     fn default() -> Self {
+        // The most used constants are added here, for simplicity's sake
+        let constants = vec![F::ZERO, F::ONE, F::from(2u64)];
+
         Self {
-            // The most used constants are added here, for simplicity's sake
-            constants: vec![F::ZERO, F::ONE, F::from(2u64)],
+            constants_index: constants
+                .iter()
+                .enumerate()
+                .map(|(index, constant)| (constant.to_repr().as_ref().to_vec(), index))
+                .collect(),
+            constants,
             rotations: Default::default(),
             calculations: Default::default(),
+            calculations_index: Default::default(),
             num_intermediates: Default::default(),
         }
     }
@@ -220,7 +232,8 @@ impl<F: PrimeField> GraphEvaluator<F> {
 
     /// Adds a constant
     fn add_constant(&mut self, constant: &F) -> ValueSource {
-        ValueSource::Constant(match self.constants.iter().position(|&c| c == *constant) {
+        let repr = constant.to_repr().as_ref().to_vec();
+        ValueSource::Constant(match self.constants_index.get(&repr).copied() {
             Some(index) => {
                 debug!("constant {constant:?} already have index: {index}, will use it");
                 index
@@ -228,6 +241,7 @@ impl<F: PrimeField> GraphEvaluator<F> {
             None => {
                 self.constants.push(*constant);
                 let index = self.constants.len() - 1;
+                self.constants_index.insert(repr, index);
                 debug!("constant {constant:?} have't index, add it with index: {index}");
                 index
             }
@@ -239,14 +253,11 @@ impl<F: PrimeField> GraphEvaluator<F> {
     /// resulting value so the result can be reused  when that calculation
     /// is done multiple times.
     fn add_calculation(&mut self, calculation: Calculation) -> ValueSource {
-        let existing_calculation = self
-            .calculations
-            .iter()
-            .find(|c| c.calculation == calculation);
-        match existing_calculation {
-            Some(existing_calculation) => ValueSource::Intermediate(existing_calculation.target),
+        match self.calculations_index.get(&calculation) {
+            Some(target) => ValueSource::Intermediate(*target),
             None => {
                 let target = self.num_intermediates;
+                self.calculations_index.insert(calculation.clone(), target);
                 self.calculations.push(CalculationInfo {
                     calculation,
                     target,
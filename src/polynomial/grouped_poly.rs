@@ -6,7 +6,7 @@ use std::{
 };
 
 use itertools::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use super::{expression::QueryIndexContext, Expression};
@@ -21,7 +21,7 @@ use crate::{
 /// coefficients are represented as [`GroupedPoly::term`] values
 ///
 /// `x^0 * a + x^1 * b + x^3 * c -> { 0 => a, 1 => b, 3 => c }`
-#[derive(Clone, PartialEq, Serialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct GroupedPoly<F: PrimeField> {
     // TODO #159 depend on `evaluate` algo, can be changed to `BTreeMap`
     terms: Vec<Option<Expression<F>>>,
@@ -1,8 +1,12 @@
+pub mod degree_reduction;
 pub mod expression;
 pub mod graph_evaluator;
 pub mod grouped_poly;
 pub mod lagrange;
+pub mod multilinear;
+pub mod optimizer;
 pub mod sparse;
+pub mod sumcheck;
 pub mod univariate;
 
 pub use expression::{ColumnIndex, Expression, Query, QueryType};
@@ -0,0 +1,320 @@
+//! # Multilinear Polynomials
+//!
+//! A multilinear polynomial in `n` variables is represented by its evaluations over the boolean
+//! hypercube `{0, 1}^n` (the multilinear extension of this table is unique).
+//!
+//! The evaluation at index `i` corresponds to the point `(x_0, ..., x_{n-1})`, where `x_j` is
+//! the `j`-th bit of `i`, i.e. `x_0` is the least significant one. All operations that bind
+//! variables ([`MultilinearPoly::fix_variables`]) bind them starting from `x_0`, as the
+//! [`super::sumcheck`] protocol does.
+
+use std::iter;
+
+use rayon::prelude::*;
+
+use crate::ff::{Field, PrimeField};
+
+/// Dense multilinear polynomial, see [module-level documentation](self)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultilinearPoly<F: PrimeField> {
+    num_vars: usize,
+    evaluations: Box<[F]>,
+}
+
+impl<F: PrimeField> MultilinearPoly<F> {
+    /// Creates the polynomial from its evaluations over the boolean hypercube
+    ///
+    /// # Panics
+    ///
+    /// If `evaluations.len() != 2^num_vars`
+    pub fn new(num_vars: usize, evaluations: impl Into<Box<[F]>>) -> Self {
+        let evaluations = evaluations.into();
+        assert_eq!(
+            evaluations.len(),
+            1 << num_vars,
+            "multilinear polynomial in {num_vars} variables requires 2^{num_vars} evaluations"
+        );
+
+        Self {
+            num_vars,
+            evaluations,
+        }
+    }
+
+    /// Creates the polynomial from evaluations, padding them with zeros up to the nearest power
+    /// of two
+    pub fn from_evaluations_padded(evaluations: impl IntoIterator<Item = F>) -> Self {
+        let mut evaluations = evaluations.into_iter().collect::<Vec<_>>();
+        let num_vars = evaluations.len().next_power_of_two().trailing_zeros() as usize;
+        evaluations.resize(1 << num_vars, F::ZERO);
+
+        Self::new(num_vars, evaluations)
+    }
+
+    pub fn zero(num_vars: usize) -> Self {
+        Self::new(num_vars, vec![F::ZERO; 1 << num_vars])
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Evaluations over the boolean hypercube
+    pub fn evaluations(&self) -> &[F] {
+        &self.evaluations
+    }
+
+    /// Evaluates the polynomial at `point`
+    ///
+    /// # Panics
+    ///
+    /// If `point.len() != self.num_vars()`
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "wrong number of variables");
+        self.fix_variables(point).evaluations[0]
+    }
+
+    /// Partial evaluation: binds first `point.len()` variables to `point`
+    ///
+    /// The result is a polynomial in the remaining `self.num_vars() - point.len()` variables
+    pub fn fix_variables(&self, point: &[F]) -> Self {
+        assert!(point.len() <= self.num_vars, "too many variables to fix");
+
+        point
+            .iter()
+            .fold(self.clone(), |poly, r| poly.fix_first_variable(*r))
+    }
+
+    /// Binds `x_0` to `r`: `p'(x_1, ..., x_{n-1}) = p(r, x_1, ..., x_{n-1})`
+    pub fn fix_first_variable(&self, r: F) -> Self {
+        assert_ne!(self.num_vars, 0, "no variables to fix");
+
+        Self {
+            num_vars: self.num_vars - 1,
+            evaluations: self
+                .evaluations
+                .par_chunks_exact(2)
+                .map(|pair| pair[0] + r * (pair[1] - pair[0]))
+                .collect(),
+        }
+    }
+
+    /// The `eq` polynomial in `point.len()` variables:
+    ///
+    /// `eq(x, point) = prod_i (x_i * point_i + (1 - x_i) * (1 - point_i))`
+    ///
+    /// It equals one on `x = point` & zero on any other point of the boolean hypercube if `point`
+    /// is boolean, so it is used to select a row
+    pub fn eq(point: &[F]) -> Self {
+        let mut evaluations = Vec::with_capacity(1 << point.len());
+        evaluations.push(F::ONE);
+        // After processing `point[..j]`, `evaluations[i] = eq(bits(i), point[..j])`, so the
+        // next variable becomes the most significant bit
+        for r in point {
+            let hi = evaluations
+                .iter()
+                .map(|value| *value * r)
+                .collect::<Vec<_>>();
+            evaluations
+                .iter_mut()
+                .zip(hi.iter())
+                .for_each(|(lo, hi)| *lo -= hi);
+            evaluations.extend(hi);
+        }
+
+        Self::new(point.len(), evaluations)
+    }
+}
+
+/// Evaluates `eq(x, y)` directly, see [`MultilinearPoly::eq`]
+pub fn eq_eval<F: PrimeField>(x: &[F], y: &[F]) -> F {
+    assert_eq!(x.len(), y.len(), "points of different dimensions");
+
+    x.iter()
+        .zip(y.iter())
+        .map(|(x, y)| *x * y + (F::ONE - x) * (F::ONE - y))
+        .product()
+}
+
+/// Sparse multilinear polynomial: only non-zero evaluations over the boolean hypercube are kept
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMultilinearPoly<F: PrimeField> {
+    num_vars: usize,
+    /// `(index, value)` sorted by `index`
+    evaluations: Vec<(usize, F)>,
+}
+
+impl<F: PrimeField> SparseMultilinearPoly<F> {
+    /// Creates the polynomial from `(index, value)` pairs, repeated indices are summed
+    pub fn new(num_vars: usize, evaluations: impl IntoIterator<Item = (usize, F)>) -> Self {
+        let mut evaluations = evaluations.into_iter().collect::<Vec<_>>();
+        assert!(
+            evaluations.iter().all(|(index, _)| *index < 1 << num_vars),
+            "index out of the boolean hypercube"
+        );
+        evaluations.sort_by_key(|(index, _)| *index);
+
+        let mut merged: Vec<(usize, F)> = Vec::with_capacity(evaluations.len());
+        for (index, value) in evaluations {
+            match merged.last_mut() {
+                Some((last, acc)) if *last == index => *acc += value,
+                _ => merged.push((index, value)),
+            }
+        }
+        merged.retain(|(_, value)| !bool::from(value.is_zero()));
+
+        Self {
+            num_vars,
+            evaluations: merged,
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Non-zero evaluations as `(index, value)` pairs
+    pub fn evaluations(&self) -> &[(usize, F)] {
+        &self.evaluations
+    }
+
+    /// Evaluates the polynomial at `point` in `O(2^n + nnz)`
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "wrong number of variables");
+
+        let eq = MultilinearPoly::eq(point);
+        self.evaluations
+            .iter()
+            .map(|(index, value)| eq.evaluations[*index] * value)
+            .sum()
+    }
+
+    /// Partial evaluation: binds first `point.len()` variables to `point`
+    pub fn fix_variables(&self, point: &[F]) -> Self {
+        assert!(point.len() <= self.num_vars, "too many variables to fix");
+
+        let eq = MultilinearPoly::eq(point);
+        let mask = (1 << point.len()) - 1;
+        Self::new(
+            self.num_vars - point.len(),
+            self.evaluations
+                .iter()
+                .map(|(index, value)| (index >> point.len(), eq.evaluations[index & mask] * value)),
+        )
+    }
+
+    pub fn to_dense(&self) -> MultilinearPoly<F> {
+        let mut evaluations = vec![F::ZERO; 1 << self.num_vars];
+        for (index, value) in self.evaluations.iter() {
+            evaluations[*index] = *value;
+        }
+        MultilinearPoly::new(self.num_vars, evaluations)
+    }
+}
+
+impl<F: PrimeField> From<&MultilinearPoly<F>> for SparseMultilinearPoly<F> {
+    fn from(poly: &MultilinearPoly<F>) -> Self {
+        Self::new(
+            poly.num_vars,
+            poly.evaluations
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, value)| !bool::from(value.is_zero())),
+        )
+    }
+}
+
+/// Bits of `index` as a point of the boolean hypercube, see [module-level documentation](self)
+pub fn index_to_point<F: PrimeField>(index: usize, num_vars: usize) -> Vec<F> {
+    iter::successors(Some(index), |index| Some(index >> 1))
+        .take(num_vars)
+        .map(|index| if index & 1 == 1 { F::ONE } else { F::ZERO })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::halo2curves::bn256::Fr;
+
+    fn poly(values: &[u64]) -> MultilinearPoly<Fr> {
+        MultilinearPoly::from_evaluations_padded(values.iter().copied().map(Fr::from))
+    }
+
+    #[test]
+    fn evaluate_on_hypercube() {
+        let p = poly(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(p.num_vars(), 3);
+
+        for (index, value) in p.evaluations().iter().enumerate() {
+            assert_eq!(p.evaluate(&index_to_point(index, 3)), *value);
+        }
+    }
+
+    #[test]
+    fn evaluate_outside_hypercube() {
+        // p(x_0, x_1) = 1 + x_0 + 2 * x_1 + 3 * x_0 * x_1
+        let p = poly(&[1, 2, 3, 7]);
+        let (x0, x1) = (Fr::from(5), Fr::from(11));
+
+        assert_eq!(
+            p.evaluate(&[x0, x1]),
+            Fr::ONE + x0 + Fr::from(2) * x1 + Fr::from(3) * x0 * x1
+        );
+    }
+
+    #[test]
+    fn partial_evaluation() {
+        let p = poly(&[3, 1, 4, 1, 5, 9, 2, 6]);
+        let point = [Fr::from(7), Fr::from(13), Fr::from(17)];
+
+        let partial = p.fix_variables(&point[..1]);
+        assert_eq!(partial.num_vars(), 2);
+        assert_eq!(partial.evaluate(&point[1..]), p.evaluate(&point));
+
+        let sparse = SparseMultilinearPoly::from(&p);
+        assert_eq!(
+            sparse.fix_variables(&point[..2]).to_dense(),
+            p.fix_variables(&point[..2])
+        );
+    }
+
+    #[test]
+    fn eq_polynomial() {
+        let point = [Fr::from(2), Fr::from(3), Fr::from(5)];
+        let eq = MultilinearPoly::eq(&point);
+
+        for index in 0..8 {
+            let x = index_to_point::<Fr>(index, 3);
+            assert_eq!(eq.evaluations()[index], eq_eval(&x, &point));
+        }
+
+        let selector = MultilinearPoly::eq(&index_to_point::<Fr>(5, 3));
+        assert_eq!(
+            SparseMultilinearPoly::from(&selector).evaluations(),
+            [(5, Fr::ONE)]
+        );
+
+        let other = [Fr::from(7), Fr::from(11), Fr::from(13)];
+        assert_eq!(eq.evaluate(&other), eq_eval(&point, &other));
+    }
+
+    #[test]
+    fn sparse_matches_dense() {
+        let sparse = SparseMultilinearPoly::new(
+            4,
+            [
+                (3, Fr::from(2)),
+                (9, Fr::from(5)),
+                (3, Fr::from(1)),
+                (15, Fr::ZERO),
+            ],
+        );
+        assert_eq!(sparse.evaluations(), [(3, Fr::from(3)), (9, Fr::from(5))]);
+
+        let point = [Fr::from(2), Fr::from(3), Fr::from(5), Fr::from(7)];
+        assert_eq!(sparse.evaluate(&point), sparse.to_dense().evaluate(&point));
+        assert_eq!(SparseMultilinearPoly::from(&sparse.to_dense()), sparse);
+    }
+}
@@ -0,0 +1,438 @@
+//! # Expression Optimizer
+//!
+//! Gates converted by [`Expression::from_halo2_expr`] keep the shape of halo2 expressions, which
+//! is very redundant: multiplications by constant selectors, `x * 1`, `x + 0`, repeated terms,
+//! the same selector in every term of a sum, etc. [`Expression::optimize`] rewrites an expression
+//! into an equivalent one in a normal form:
+//!
+//! 1. **Constant folding**: constants, [`Expression::Negated`] & [`Expression::Scaled`] are
+//!    pulled out of sums & products into one coefficient per term, zero terms are dropped.
+//! 2. **Common subexpressions**: equal terms of a sum are merged by summing coefficients. Terms of
+//!    sums & factors of products are sorted, so equal subexpressions have the same shape
+//!    regardless of the order they were written in, and are reused by
+//!    [`super::graph_evaluator::GraphEvaluator`].
+//! 3. **Selector factoring**: selectors shared by several terms of a sum are factored out,
+//!    `s * a + s * b -> s * (a + b)`.
+//!
+//! The value of the expression on any input stays the same, as well as its degree, unless some
+//! terms cancel out.
+
+use std::cmp::Ordering;
+
+use super::expression::Expression;
+use crate::ff::PrimeField;
+
+impl<F: PrimeField> Expression<F> {
+    /// Equivalent expression in the normal form, see [`self`](super::optimizer)
+    ///
+    /// `num_selectors` - number of selectors, i.e. queries with lower indices are selectors
+    pub fn optimize(&self, num_selectors: usize) -> Self {
+        Optimizer { num_selectors }.normalize(self)
+    }
+}
+
+struct Optimizer {
+    num_selectors: usize,
+}
+
+impl Optimizer {
+    fn normalize<F: PrimeField>(&self, expr: &Expression<F>) -> Expression<F> {
+        match expr {
+            Expression::Constant(_) | Expression::Polynomial(_) | Expression::Challenge(_) => {
+                expr.clone()
+            }
+            Expression::Sum(_, _) | Expression::Negated(_) | Expression::Scaled(_, _) => {
+                let mut terms = vec![];
+                let mut constant = F::ZERO;
+                self.collect_terms(expr, F::ONE, &mut terms, &mut constant);
+                self.build_sum(terms, constant)
+            }
+            Expression::Product(_, _) => {
+                let mut factors = vec![];
+                let mut scalar = F::ONE;
+                self.collect_factors(expr, &mut factors, &mut scalar);
+                scale(product(factors), scalar)
+            }
+        }
+    }
+
+    /// Flattens linear part of `expr` into `sum coeff * term + constant`
+    fn collect_terms<F: PrimeField>(
+        &self,
+        expr: &Expression<F>,
+        coeff: F,
+        terms: &mut Vec<(F, Expression<F>)>,
+        constant: &mut F,
+    ) {
+        match expr {
+            Expression::Sum(a, b) => {
+                self.collect_terms(a, coeff, terms, constant);
+                self.collect_terms(b, coeff, terms, constant);
+            }
+            Expression::Negated(a) => self.collect_terms(a, -coeff, terms, constant),
+            Expression::Scaled(a, k) => self.collect_terms(a, coeff * k, terms, constant),
+            Expression::Constant(c) => *constant += coeff * c,
+            Expression::Polynomial(_) | Expression::Challenge(_) => {
+                add_term(terms, coeff, expr.clone())
+            }
+            Expression::Product(_, _) => match self.normalize(expr) {
+                term @ (Expression::Product(_, _)
+                | Expression::Polynomial(_)
+                | Expression::Challenge(_)) => add_term(terms, coeff, term),
+                // the product with a scalar or of one sum
+                linear => self.collect_terms(&linear, coeff, terms, constant),
+            },
+        }
+    }
+
+    /// Flattens `expr` into `scalar * prod factors`
+    fn collect_factors<F: PrimeField>(
+        &self,
+        expr: &Expression<F>,
+        factors: &mut Vec<Expression<F>>,
+        scalar: &mut F,
+    ) {
+        match expr {
+            Expression::Product(a, b) => {
+                self.collect_factors(a, factors, scalar);
+                self.collect_factors(b, factors, scalar);
+            }
+            other => {
+                let (k, factor) = match self.normalize(other) {
+                    Expression::Constant(c) => {
+                        *scalar *= c;
+                        return;
+                    }
+                    Expression::Scaled(factor, k) => (k, *factor),
+                    Expression::Negated(factor) => (-F::ONE, *factor),
+                    factor => (F::ONE, factor),
+                };
+                *scalar *= k;
+                factors.extend(self::factors(&factor).into_iter().cloned());
+            }
+        }
+    }
+
+    fn build_sum<F: PrimeField>(
+        &self,
+        terms: Vec<(F, Expression<F>)>,
+        constant: F,
+    ) -> Expression<F> {
+        let mut terms = terms
+            .into_iter()
+            .filter(|(coeff, _)| !bool::from(coeff.is_zero()))
+            .collect::<Vec<_>>();
+
+        // the selector shared by the most terms is factored out first
+        while let Some(selector) = self.shared_selector(&terms) {
+            let (with_selector, mut rest): (Vec<_>, Vec<_>) = terms
+                .into_iter()
+                .partition(|(_, term)| factors(term).contains(&&selector));
+
+            let mut inner = vec![];
+            let mut inner_constant = F::ZERO;
+            for (coeff, term) in with_selector {
+                match without_factor(&term, &selector) {
+                    Expression::Constant(c) => inner_constant += coeff * c,
+                    term => add_term(&mut inner, coeff, term),
+                }
+            }
+
+            let mut factored = vec![];
+            let mut scalar = F::ONE;
+            self.collect_factors(
+                &(selector * self.build_sum(inner, inner_constant)),
+                &mut factored,
+                &mut scalar,
+            );
+            if !bool::from(scalar.is_zero()) {
+                add_term(&mut rest, scalar, product(factored));
+            }
+
+            terms = rest;
+        }
+
+        terms.sort_by(|(_, lhs), (_, rhs)| cmp(lhs, rhs));
+
+        terms
+            .into_iter()
+            .map(|(coeff, term)| scale(term, coeff))
+            .chain((!bool::from(constant.is_zero())).then_some(Expression::Constant(constant)))
+            .reduce(|acc, term| acc + term)
+            .unwrap_or(Expression::Constant(F::ZERO))
+    }
+
+    /// The selector, which is a factor of the most `terms`, if there are at least two of them
+    ///
+    /// Factors of normalized products are sorted, so equal selectors are adjacent
+    fn shared_selector<F: PrimeField>(
+        &self,
+        terms: &[(F, Expression<F>)],
+    ) -> Option<Expression<F>> {
+        let mut selectors: Vec<(usize, &Expression<F>)> = vec![];
+        for (_, term) in terms {
+            let mut term_selectors = factors(term)
+                .into_iter()
+                .filter(|factor| {
+                    matches!(
+                        factor,
+                        Expression::Polynomial(query) if query.index < self.num_selectors
+                    )
+                })
+                .collect::<Vec<_>>();
+            term_selectors.dedup();
+
+            for factor in term_selectors {
+                match selectors
+                    .iter_mut()
+                    .find(|(_, selector)| *selector == factor)
+                {
+                    Some((count, _)) => *count += 1,
+                    None => selectors.push((1, factor)),
+                }
+            }
+        }
+
+        selectors
+            .into_iter()
+            .filter(|(count, _)| *count > 1)
+            .max_by(|(lhs_count, lhs), (rhs_count, rhs)| {
+                lhs_count.cmp(rhs_count).then(cmp(rhs, lhs))
+            })
+            .map(|(_, selector)| selector.clone())
+    }
+}
+
+/// Adds `coeff * term` to `terms`, merging it with the equal one
+fn add_term<F: PrimeField>(terms: &mut Vec<(F, Expression<F>)>, coeff: F, term: Expression<F>) {
+    match terms.iter_mut().find(|(_, existing)| *existing == term) {
+        Some((existing_coeff, _)) => *existing_coeff += coeff,
+        None => terms.push((coeff, term)),
+    }
+}
+
+fn factors<F: PrimeField>(expr: &Expression<F>) -> Vec<&Expression<F>> {
+    match expr {
+        Expression::Product(a, b) => factors(a).into_iter().chain(factors(b)).collect(),
+        other => vec![other],
+    }
+}
+
+/// Removes one occurrence of `factor` from the product `expr`
+fn without_factor<F: PrimeField>(expr: &Expression<F>, factor: &Expression<F>) -> Expression<F> {
+    let mut rest = factors(expr);
+    if let Some(position) = rest.iter().position(|existing| *existing == factor) {
+        rest.remove(position);
+    }
+    product(rest.into_iter().cloned().collect())
+}
+
+/// Product of factors in the canonical order
+fn product<F: PrimeField>(mut factors: Vec<Expression<F>>) -> Expression<F> {
+    factors.sort_by(cmp);
+    factors
+        .into_iter()
+        .reduce(|acc, factor| acc * factor)
+        .unwrap_or(Expression::Constant(F::ONE))
+}
+
+fn scale<F: PrimeField>(expr: Expression<F>, scalar: F) -> Expression<F> {
+    if scalar.is_zero().into() {
+        Expression::Constant(F::ZERO)
+    } else if scalar == F::ONE {
+        expr
+    } else if scalar == -F::ONE {
+        -expr
+    } else if let Expression::Constant(c) = expr {
+        Expression::Constant(c * scalar)
+    } else {
+        expr * scalar
+    }
+}
+
+/// Canonical order of expressions, used to sort terms & factors
+fn cmp<F: PrimeField>(lhs: &Expression<F>, rhs: &Expression<F>) -> Ordering {
+    fn rank<F>(expr: &Expression<F>) -> usize {
+        match expr {
+            Expression::Constant(_) => 0,
+            Expression::Challenge(_) => 1,
+            Expression::Polynomial(_) => 2,
+            Expression::Negated(_) => 3,
+            Expression::Scaled(_, _) => 4,
+            Expression::Sum(_, _) => 5,
+            Expression::Product(_, _) => 6,
+        }
+    }
+
+    let cmp_scalar = |lhs: &F, rhs: &F| lhs.to_repr().as_ref().cmp(rhs.to_repr().as_ref());
+
+    match (lhs, rhs) {
+        (Expression::Constant(lhs), Expression::Constant(rhs)) => cmp_scalar(lhs, rhs),
+        (Expression::Challenge(lhs), Expression::Challenge(rhs)) => lhs.cmp(rhs),
+        (Expression::Polynomial(lhs), Expression::Polynomial(rhs)) => lhs
+            .index
+            .cmp(&rhs.index)
+            .then(lhs.rotation.0.cmp(&rhs.rotation.0)),
+        (Expression::Negated(lhs), Expression::Negated(rhs)) => cmp(lhs, rhs),
+        (Expression::Scaled(lhs, lhs_k), Expression::Scaled(rhs, rhs_k)) => {
+            cmp(lhs, rhs).then_with(|| cmp_scalar(lhs_k, rhs_k))
+        }
+        (Expression::Sum(lhs_a, lhs_b), Expression::Sum(rhs_a, rhs_b))
+        | (Expression::Product(lhs_a, lhs_b), Expression::Product(rhs_a, rhs_b)) => {
+            cmp(lhs_a, rhs_a).then_with(|| cmp(lhs_b, rhs_b))
+        }
+        (lhs, rhs) => rank(lhs).cmp(&rank(rhs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use halo2_proofs::{plonk::ConstraintSystem, poly::Rotation};
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{
+        ff::Field,
+        halo2curves::bn256::Fr,
+        main_gate::{MainGate, MainGateConfig},
+        polynomial::{expression::QueryIndexContext, Query},
+    };
+
+    /// Queries `0..2` are selectors
+    const NUM_SELECTORS: usize = 2;
+
+    fn query(index: usize, rotation: i32) -> Expression<Fr> {
+        Expression::Polynomial(Query {
+            index,
+            rotation: Rotation(rotation),
+        })
+    }
+
+    fn constant(value: u64) -> Expression<Fr> {
+        Expression::Constant(Fr::from(value))
+    }
+
+    /// Evaluates `expr` with pseudo-random values of queries & challenges
+    fn eval(expr: &Expression<Fr>, seed: u64) -> Fr {
+        expr.evaluate(
+            &|c| c,
+            &|query| {
+                Fr::from(seed * 1000 + query.index as u64 * 10) + Fr::from(query.rotation.0 as u64)
+            },
+            &|index| Fr::from(seed * 7 + index as u64 + 100).invert().unwrap(),
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, k| a * k,
+        )
+    }
+
+    fn assert_equivalent(expr: &Expression<Fr>) -> Expression<Fr> {
+        let optimized = expr.optimize(NUM_SELECTORS);
+        for seed in 0..5 {
+            assert_eq!(
+                eval(expr, seed),
+                eval(&optimized, seed),
+                "{expr} != {optimized}"
+            );
+        }
+        optimized
+    }
+
+    #[traced_test]
+    #[test]
+    fn constant_folding() {
+        let [s, _, a, b] = array::from_fn(|index| query(index, 0));
+
+        assert_eq!(
+            assert_equivalent(&(a.clone() * constant(0) + constant(2) * constant(3))),
+            constant(6)
+        );
+        assert_eq!(
+            assert_equivalent(&((a.clone() * constant(1) + constant(0)) * Fr::ONE)),
+            a
+        );
+        assert_eq!(assert_equivalent(&(-(-b.clone()))), b);
+        assert_eq!(
+            assert_equivalent(&(s.clone() * (a.clone() - a.clone()))),
+            constant(0)
+        );
+        assert_eq!(
+            assert_equivalent(&((a.clone() * Fr::from(2)) * Fr::from(3) - a.clone() * Fr::from(6))),
+            constant(0)
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn common_subexpressions() {
+        let [_, _, a, b, c] = array::from_fn(|index| query(index, 0));
+
+        // `a * b` & `b * a` are the same term
+        let optimized =
+            assert_equivalent(&(a.clone() * b.clone() + c.clone() + b.clone() * a.clone()));
+        assert_eq!(optimized, c.clone() + (a.clone() * b.clone()) * Fr::from(2));
+
+        let optimized = assert_equivalent(&(b.clone() * a.clone() - a.clone() * b.clone()));
+        assert_eq!(optimized, constant(0));
+
+        // rotations are different queries
+        let a_next = query(2, 1);
+        let optimized = assert_equivalent(&(a.clone() + a_next.clone() - a.clone()));
+        assert_eq!(optimized, a_next);
+    }
+
+    #[traced_test]
+    #[test]
+    fn selector_factoring() {
+        let [s0, s1, a, b, c] = array::from_fn(|index| query(index, 0));
+
+        let expr = s0.clone() * a.clone() + s0.clone() * b.clone() * Fr::from(3)
+            - s0.clone() * constant(5)
+            + s1.clone() * c.clone();
+        let optimized = assert_equivalent(&expr);
+
+        let ctx = QueryIndexContext {
+            num_selectors: NUM_SELECTORS,
+            num_advice: 3,
+            ..Default::default()
+        };
+        assert_eq!(optimized.degree(&ctx), expr.degree(&ctx));
+        assert_eq!(
+            optimized,
+            s0 * (a + b * Fr::from(3) + Expression::Constant(-Fr::from(5))) + s1 * c
+        );
+        assert_eq!(optimized.optimize(NUM_SELECTORS), optimized);
+    }
+
+    #[traced_test]
+    #[test]
+    fn nested() {
+        let [s0, s1, a, b, c] = array::from_fn(|index| query(index, 0));
+        let r = Expression::<Fr>::Challenge(0);
+
+        let expr = (s0.clone() * (a.clone() + constant(0)) * (b.clone() - c.clone() * constant(1))
+            + s0.clone() * s1.clone() * r.clone())
+            * (a.clone() * a.clone() * a.clone() - s1.clone() * (b.clone() + b.clone()))
+            + -(r.clone() * constant(2)) * -(c.clone() + s1.clone() * a.clone());
+
+        assert_equivalent(&expr);
+    }
+
+    #[traced_test]
+    #[test]
+    fn main_gate() {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        let _: MainGateConfig<4> = MainGate::configure(&mut cs);
+
+        for poly in cs.gates().iter().flat_map(|gate| gate.polynomials()) {
+            let expr = Expression::from_halo2_expr(poly, cs.num_selectors, cs.num_fixed_columns());
+            let optimized = expr.optimize(cs.num_selectors);
+            for seed in 0..5 {
+                assert_eq!(eval(&expr, seed), eval(&optimized, seed));
+            }
+        }
+    }
+}
@@ -0,0 +1,443 @@
+//! # Sumcheck Protocol
+//!
+//! Proves the claim `sum_{x in {0, 1}^n} f(x) = s`, where `f` is a [`VirtualPoly`], i.e. a sum
+//! of products of [`MultilinearPoly`]s (the representation is borrowed from
+//! [HyperPlonk](https://eprint.iacr.org/2022/1355), section 3.2).
+//!
+//! In round `i` the prover sends the univariate polynomial
+//!
+//! `p_i(X) = sum_{x in {0, 1}^{n-i-1}} f(r_0, ..., r_{i-1}, X, x)`
+//!
+//! as its evaluations at `0, 1, ..., degree(f)`, and the verifier checks
+//! `p_i(0) + p_i(1) = p_{i-1}(r_{i-1})` (`= s` for the first round). The challenge `r_i` is
+//! produced by the random oracle after absorbing `p_i`.
+//!
+//! After `n` rounds the verifier is left with the [`SubClaim`] `f(r_0, ..., r_{n-1}) = e`,
+//! checking it requires the evaluations of the multilinear polynomials, which the caller must
+//! obtain by other means (e.g. opening of commitments or another folding step).
+
+use std::{iter, sync::Arc};
+
+use rayon::prelude::*;
+use tracing::*;
+
+use super::multilinear::MultilinearPoly;
+use crate::{
+    constants::NUM_CHALLENGE_BITS,
+    ff::{Field, PrimeField},
+    halo2curves::CurveAffine,
+    poseidon::ROTrait,
+    util,
+};
+
+/// Sum of products of multilinear polynomials: `sum_i c_i * prod_j f_{i, j}(x)`
+///
+/// Polynomials are stored once & referenced from products by index, so a polynomial used in
+/// several products is bound only once per sumcheck round
+#[derive(Clone, Debug)]
+pub struct VirtualPoly<F: PrimeField> {
+    num_vars: usize,
+    /// `c_i` & indices of `f_{i, j}` in [`Self::mles`]
+    products: Vec<(F, Vec<usize>)>,
+    mles: Vec<Arc<MultilinearPoly<F>>>,
+}
+
+impl<F: PrimeField> VirtualPoly<F> {
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            products: vec![],
+            mles: vec![],
+        }
+    }
+
+    /// Adds `coefficient * prod(product)`
+    ///
+    /// Polynomials are deduplicated by pointer, so pass clones of the same [`Arc`] to reuse them
+    pub fn add_product(
+        &mut self,
+        coefficient: F,
+        product: impl IntoIterator<Item = Arc<MultilinearPoly<F>>>,
+    ) -> &mut Self {
+        let indices = product
+            .into_iter()
+            .map(|mle| {
+                assert_eq!(mle.num_vars(), self.num_vars, "wrong number of variables");
+
+                match self.mles.iter().position(|known| Arc::ptr_eq(known, &mle)) {
+                    Some(index) => index,
+                    None => {
+                        self.mles.push(mle);
+                        self.mles.len() - 1
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        assert!(!indices.is_empty(), "empty product");
+
+        self.products.push((coefficient, indices));
+        self
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Max number of multiplied polynomials, at least one
+    pub fn degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, indices)| indices.len())
+            .max()
+            .unwrap_or_default()
+            .max(1)
+    }
+
+    /// Deduplicated multilinear polynomials, in order of first appearance
+    pub fn mles(&self) -> &[Arc<MultilinearPoly<F>>] {
+        &self.mles
+    }
+
+    /// Combines the evaluations of [`Self::mles`] at some point into the evaluation of `self`
+    pub fn evaluate_with(&self, mle_evaluations: &[F]) -> F {
+        assert_eq!(mle_evaluations.len(), self.mles.len());
+
+        self.products
+            .iter()
+            .map(|(coefficient, indices)| {
+                indices
+                    .iter()
+                    .fold(*coefficient, |acc, index| acc * mle_evaluations[*index])
+            })
+            .sum()
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        self.evaluate_with(
+            &self
+                .mles
+                .iter()
+                .map(|mle| mle.evaluate(point))
+                .collect::<Box<[_]>>(),
+        )
+    }
+
+    /// `sum_{x in {0, 1}^n} f(x)`
+    pub fn sum_over_hypercube(&self) -> F {
+        (0..1 << self.num_vars)
+            .into_par_iter()
+            .map(|index| {
+                self.evaluate_with(
+                    &self
+                        .mles
+                        .iter()
+                        .map(|mle| mle.evaluations()[index])
+                        .collect::<Box<[_]>>(),
+                )
+            })
+            .sum()
+    }
+}
+
+/// Round polynomials of the sumcheck, see [module-level documentation](self)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumcheckProof<F: PrimeField> {
+    /// Evaluations of `p_i` at `0, 1, ..., degree`
+    round_evaluations: Vec<Box<[F]>>,
+}
+
+/// Claim left after the sumcheck: `f(point) = expected_evaluation`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub expected_evaluation: F,
+}
+
+/// Result of the sumcheck prover
+#[derive(Clone, Debug)]
+pub struct ProverOutput<F: PrimeField> {
+    pub proof: SumcheckProof<F>,
+    /// The point `(r_0, ..., r_{n-1})` of [`SubClaim`]
+    pub point: Vec<F>,
+    /// Evaluations of [`VirtualPoly::mles`] at `point`
+    pub mle_evaluations: Vec<F>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("wrong number of rounds: expected {expected}, actual {actual}")]
+    WrongNumberOfRounds { expected: usize, actual: usize },
+    #[error("round {round}: expected {expected} evaluations, actual {actual}")]
+    WrongNumberOfEvaluations {
+        round: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("round {round}: p(0) + p(1) doesn't match the claim")]
+    ClaimMismatch { round: usize },
+}
+
+impl<F: PrimeField> SumcheckProof<F> {
+    pub fn round_evaluations(&self) -> &[Box<[F]>] {
+        &self.round_evaluations
+    }
+
+    #[instrument(name = "sumcheck_prove", skip_all)]
+    pub fn prove<C: CurveAffine<ScalarExt = F>>(
+        ro: &mut impl ROTrait<C::Base>,
+        poly: &VirtualPoly<F>,
+    ) -> ProverOutput<F> {
+        let degree = poly.degree();
+
+        let mut mles = poly
+            .mles
+            .iter()
+            .map(|mle| mle.as_ref().clone())
+            .collect::<Vec<_>>();
+        let mut point = Vec::with_capacity(poly.num_vars);
+        let mut round_evaluations = Vec::with_capacity(poly.num_vars);
+
+        for round in 0..poly.num_vars {
+            let half = 1 << (poly.num_vars - round - 1);
+
+            let evaluations = (0..half)
+                .into_par_iter()
+                .map(|index| {
+                    // `f_j(r_0, ..., r_{round - 1}, t, index)` for `t = 0..=degree`
+                    let mle_values = mles
+                        .iter()
+                        .map(|mle| {
+                            let lo = mle.evaluations()[2 * index];
+                            let step = mle.evaluations()[2 * index + 1] - lo;
+                            iter::successors(Some(lo), |value| Some(*value + step))
+                                .take(degree + 1)
+                                .collect::<Box<[_]>>()
+                        })
+                        .collect::<Box<[_]>>();
+
+                    (0..=degree)
+                        .map(|t| {
+                            poly.products
+                                .iter()
+                                .map(|(coefficient, indices)| {
+                                    indices.iter().fold(*coefficient, |acc, index| {
+                                        acc * mle_values[*index][t]
+                                    })
+                                })
+                                .sum::<F>()
+                        })
+                        .collect::<Box<[_]>>()
+                })
+                .reduce(
+                    || vec![F::ZERO; degree + 1].into_boxed_slice(),
+                    |mut lhs, rhs| {
+                        lhs.iter_mut().zip(rhs.iter()).for_each(|(l, r)| *l += r);
+                        lhs
+                    },
+                );
+
+            let r = ro
+                .absorb_field_iter(evaluations.iter().map(|v| util::fe_to_fe(v).unwrap()))
+                .squeeze::<C>(NUM_CHALLENGE_BITS);
+
+            mles = mles
+                .par_iter()
+                .map(|mle| mle.fix_first_variable(r))
+                .collect();
+            point.push(r);
+            round_evaluations.push(evaluations);
+        }
+
+        ProverOutput {
+            proof: Self { round_evaluations },
+            point,
+            mle_evaluations: mles.iter().map(|mle| mle.evaluations()[0]).collect(),
+        }
+    }
+
+    /// Checks the rounds of the protocol & reduces `claimed_sum` to the [`SubClaim`]
+    #[instrument(name = "sumcheck_verify", skip_all)]
+    pub fn verify<C: CurveAffine<ScalarExt = F>>(
+        &self,
+        ro: &mut impl ROTrait<C::Base>,
+        num_vars: usize,
+        degree: usize,
+        claimed_sum: F,
+    ) -> Result<SubClaim<F>, Error> {
+        if self.round_evaluations.len() != num_vars {
+            return Err(Error::WrongNumberOfRounds {
+                expected: num_vars,
+                actual: self.round_evaluations.len(),
+            });
+        }
+
+        let mut expected = claimed_sum;
+        let mut point = Vec::with_capacity(num_vars);
+        for (round, evaluations) in self.round_evaluations.iter().enumerate() {
+            if evaluations.len() != degree.max(1) + 1 {
+                return Err(Error::WrongNumberOfEvaluations {
+                    round,
+                    expected: degree.max(1) + 1,
+                    actual: evaluations.len(),
+                });
+            }
+
+            if evaluations[0] + evaluations[1] != expected {
+                return Err(Error::ClaimMismatch { round });
+            }
+
+            let r = ro
+                .absorb_field_iter(evaluations.iter().map(|v| util::fe_to_fe(v).unwrap()))
+                .squeeze::<C>(NUM_CHALLENGE_BITS);
+
+            expected = interpolate(evaluations, r);
+            point.push(r);
+        }
+
+        Ok(SubClaim {
+            point,
+            expected_evaluation: expected,
+        })
+    }
+}
+
+/// Evaluates at `x` the polynomial given by its evaluations at `0, 1, ..., evaluations.len() - 1`
+pub(crate) fn interpolate<F: PrimeField>(evaluations: &[F], x: F) -> F {
+    let points = (0..evaluations.len() as u64)
+        .map(F::from)
+        .collect::<Box<[_]>>();
+
+    evaluations
+        .iter()
+        .zip(points.iter())
+        .enumerate()
+        .map(|(i, (y, x_i))| {
+            let (numerator, denominator) = points.iter().enumerate().filter(|(j, _)| *j != i).fold(
+                (F::ONE, F::ONE),
+                |(numerator, denominator), (_, x_j)| {
+                    (numerator * (x - x_j), denominator * (*x_i - x_j))
+                },
+            );
+
+            *y * numerator * denominator.invert().unwrap()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{
+        halo2curves::bn256::{Fq, Fr, G1Affine},
+        polynomial::multilinear::eq_eval,
+        util::create_ro,
+    };
+
+    const T: usize = 3;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    fn mle(values: impl IntoIterator<Item = u64>) -> Arc<MultilinearPoly<Fr>> {
+        Arc::new(MultilinearPoly::from_evaluations_padded(
+            values.into_iter().map(Fr::from),
+        ))
+    }
+
+    /// `a * b * c + 3 * a - eq(x, r) * b`
+    fn virtual_poly() -> VirtualPoly<Fr> {
+        let a = mle(1..=16);
+        let b = mle((0..16).map(|i| i * i));
+        let c = mle((0..16).rev());
+        let eq = Arc::new(MultilinearPoly::eq(&[2u64, 3, 5, 7].map(Fr::from)));
+
+        let mut poly = VirtualPoly::new(4);
+        poly.add_product(Fr::ONE, [a.clone(), b.clone(), c])
+            .add_product(Fr::from(3), [a])
+            .add_product(-Fr::ONE, [eq, b]);
+        poly
+    }
+
+    #[test]
+    fn interpolation() {
+        // p(x) = 2 + 3x + 5x^2
+        let p = |x: u64| Fr::from(2 + 3 * x + 5 * x * x);
+        assert_eq!(interpolate(&[p(0), p(1), p(2)], Fr::from(10)), p(10));
+    }
+
+    #[traced_test]
+    #[test]
+    fn prove_verify() {
+        let poly = virtual_poly();
+        assert_eq!(poly.mles().len(), 4);
+        assert_eq!(poly.degree(), 3);
+
+        let ProverOutput {
+            proof,
+            point,
+            mle_evaluations,
+        } = SumcheckProof::prove::<G1Affine>(&mut create_ro::<Fq, T, RATE, R_F, R_P>(), &poly);
+
+        let sub_claim = proof
+            .verify::<G1Affine>(
+                &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+                poly.num_vars(),
+                poly.degree(),
+                poly.sum_over_hypercube(),
+            )
+            .unwrap();
+
+        assert_eq!(sub_claim.point, point);
+        assert_eq!(sub_claim.expected_evaluation, poly.evaluate(&point));
+        assert_eq!(
+            sub_claim.expected_evaluation,
+            poly.evaluate_with(&mle_evaluations)
+        );
+        assert_eq!(
+            mle_evaluations[3],
+            eq_eval(&point, &[2u64, 3, 5, 7].map(Fr::from))
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn wrong_claim() {
+        let poly = virtual_poly();
+        let ProverOutput { mut proof, .. } =
+            SumcheckProof::prove::<G1Affine>(&mut create_ro::<Fq, T, RATE, R_F, R_P>(), &poly);
+
+        let verify = |proof: &SumcheckProof<Fr>, claimed_sum| {
+            proof.verify::<G1Affine>(
+                &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+                poly.num_vars(),
+                poly.degree(),
+                claimed_sum,
+            )
+        };
+
+        assert_eq!(
+            verify(&proof, poly.sum_over_hypercube() + Fr::ONE),
+            Err(Error::ClaimMismatch { round: 0 })
+        );
+
+        // the last round is consistent with the previous one, but not with the polynomial
+        proof.round_evaluations[3][0] += Fr::ONE;
+        proof.round_evaluations[3][1] -= Fr::ONE;
+        let sub_claim = verify(&proof, poly.sum_over_hypercube()).unwrap();
+        assert_ne!(
+            sub_claim.expected_evaluation,
+            poly.evaluate(&sub_claim.point)
+        );
+
+        proof.round_evaluations.pop();
+        assert_eq!(
+            verify(&proof, poly.sum_over_hypercube()),
+            Err(Error::WrongNumberOfRounds {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+}
@@ -149,6 +149,10 @@ where
     fn squeeze<C: CurveAffine<Base = F>>(&mut self, num_bits: NonZeroUsize) -> C::Scalar {
         self.output::<C::Scalar>(num_bits)
     }
+
+    fn squeeze_native(&mut self) -> F {
+        self.output::<F>(NonZeroUsize::new(F::NUM_BITS as usize).expect("field can't be empty"))
+    }
 }
 
 #[derive(Clone, Debug)]
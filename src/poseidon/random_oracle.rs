@@ -4,7 +4,7 @@ use halo2_proofs::{arithmetic::CurveAffine, plonk::Error};
 
 use crate::{
     ff::{FromUniformBytes, PrimeField, PrimeFieldBits},
-    main_gate::{AssignedBit, RegionCtx, WrapValue},
+    main_gate::{AssignedBit, AssignedValue, RegionCtx, WrapValue},
 };
 
 /// A helper trait to obsorb different objects into RO
@@ -76,6 +76,12 @@ pub trait ROTrait<F: PrimeField> {
 
     /// Returns a challenge by hashing the internal state
     fn squeeze<C: CurveAffine<Base = F>>(&mut self, num_bits: NonZeroUsize) -> C::Scalar;
+
+    /// Returns the full-width hash of the internal state as an element of `F`
+    ///
+    /// Unlike [`ROTrait::squeeze`] the output is not moved into the scalar field of some curve,
+    /// so this allows to use RO as a plain hash function over `F`
+    fn squeeze_native(&mut self) -> F;
 }
 
 /// A helper trait that defines the behavior of a hash function used as a Random Oracle (RO)
@@ -119,6 +125,11 @@ pub trait ROCircuitTrait<F: PrimeFieldBits + FromUniformBytes<64>> {
         ctx: &mut RegionCtx<'_, F>,
         num_bits: NonZeroUsize,
     ) -> Result<Vec<AssignedBit<F>>, Error>;
+
+    /// Returns the full-width hash of the internal state as an assigned element of `F`
+    ///
+    /// On-circuit version of [`ROTrait::squeeze_native`]
+    fn squeeze_native(&mut self, ctx: &mut RegionCtx<'_, F>) -> Result<AssignedValue<F>, Error>;
 }
 
 /// Random Oracle is represented as a pair of on-circuit & off-circuit types,
@@ -25,6 +25,14 @@ pub enum Error {
         annotation: &'static str,
         err: commitment::Error,
     },
+    #[error(
+        "Advice columns of phase {phase} depend on challenges and can't be collected in advance"
+    )]
+    WitnessDependsOnChallenges { phase: usize },
+    #[error("Error while collect witness of phase {phase}: {err}")]
+    WitnessCollection { phase: usize, err: String },
+    #[error("Challenges must be declared in order of their phases")]
+    UnorderedChallenges,
 }
 
 /// This trait verifies whether the instance is faithly generated by a Special soundness protocol (sps)
@@ -1,10 +1,14 @@
+use std::borrow::Cow;
+
 use halo2_proofs::plonk::{Circuit, ConstraintSystem, Error, FloorPlanner};
 use tracing::*;
 
 use super::{circuit_data::CircuitData, ConstraintSystemMetainfo, WitnessCollector};
 use crate::{
     ff::PrimeField,
-    plonk::{self, permutation::PermutationData, PlonkStructure},
+    plonk::{self, permutation::PermutationData, phases::AdviceSource, PlonkStructure},
+    polynomial::degree_reduction::AuxColumns,
+    sps::Error as SpsError,
     util::batch_invert_assigned,
 };
 
@@ -17,6 +21,11 @@ pub struct CircuitRunner<F: PrimeField, CT: Circuit<F>> {
     pub(crate) cs: ConstraintSystem<F>,
     pub(crate) config: CT::Config,
     pub(crate) instances: Vec<Vec<F>>,
+    /// Opt-in degree bound of gates, see [`CircuitRunner::with_max_degree`]
+    pub(crate) max_degree: Option<usize>,
+    /// Opt-in simplification of gates, see [`CircuitRunner::with_optimized_gates`]
+    pub(crate) optimize_gates: bool,
+    pub(crate) aux_columns: AuxColumns<F>,
 }
 
 impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
@@ -29,19 +38,57 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
             circuit,
             cs,
             instances,
+            max_degree: None,
+            optimize_gates: false,
+            aux_columns: AuxColumns::default(),
         }
     }
 
+    /// Rewrites gates to have degree at most `max_degree`, reducing the cost of folding
+    ///
+    /// Auxiliary advice columns are appended to [`PlonkStructure`] & their values are appended to
+    /// the collected witness, see [`crate::polynomial::degree_reduction`]
+    pub fn with_max_degree(mut self, max_degree: usize) -> Self {
+        self.aux_columns = ConstraintSystemMetainfo::build_with(
+            self.k as usize,
+            &self.cs,
+            Some(max_degree),
+            self.optimize_gates,
+        )
+        .aux_columns;
+        self.max_degree = Some(max_degree);
+        self
+    }
+
+    /// Rewrites gates into the equivalent normal form, reducing the cost of their evaluation, see
+    /// [`crate::polynomial::optimizer`]
+    ///
+    /// Gates of [`PlonkStructure`] change & so does its digest, so all parties must agree on it
+    pub fn with_optimized_gates(mut self) -> Self {
+        self.optimize_gates = true;
+        if let Some(max_degree) = self.max_degree {
+            return self.with_max_degree(max_degree);
+        }
+        self
+    }
+
     #[instrument(name = "circuit_collect_plonk_struct", skip_all)]
     pub fn try_collect_plonk_structure(&self) -> Result<PlonkStructure<F>, Error> {
         debug!("start build metainfo");
         let ConstraintSystemMetainfo {
             num_challenges,
             round_sizes,
+            advice_phases,
             gates,
             custom_gates_lookup_compressed,
+            aux_columns,
             ..
-        } = ConstraintSystemMetainfo::build(self.k as usize, &self.cs);
+        } = ConstraintSystemMetainfo::build_with(
+            self.k as usize,
+            &self.cs,
+            self.max_degree,
+            self.optimize_gates,
+        );
         debug!("meta info is ready");
 
         debug!("start preprocessing");
@@ -57,7 +104,8 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
             num_io: self.instances.iter().map(|l| l.len()).collect(),
             selectors,
             fixed_columns,
-            num_advice_columns: self.cs.num_advice_columns(),
+            num_advice_columns: self.cs.num_advice_columns() + aux_columns.len(),
+            advice_phases,
             num_challenges,
             round_sizes,
             custom_gates_lookup_compressed,
@@ -69,14 +117,40 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
 
     #[instrument(name = "circuit_collect_witness", skip_all)]
     pub fn try_collect_witness(&self) -> Result<Witness<F>, Error> {
+        self.try_collect_witness_with_challenges(&[])
+    }
+
+    /// Collect witness of multi-phase circuit, given challenges of already committed phases
+    ///
+    /// Advice columns of phases, which depend on not yet provided challenges, are left zero.
+    /// See [`plonk::phases`] for details
+    ///
+    /// Values of auxiliary columns of [`CircuitRunner::with_max_degree`] go after advice columns
+    #[instrument(name = "circuit_collect_witness", skip_all)]
+    pub fn try_collect_witness_with_challenges(
+        &self,
+        challenges: &[F],
+    ) -> Result<Witness<F>, Error> {
         let mut witness = WitnessCollector {
             instances: self.instances.clone(),
             advice: vec![vec![F::ZERO.into(); 1 << self.k]; self.cs.num_advice_columns()],
+            challenges: challenges.to_vec(),
+            last_ready_phase: self
+                .cs
+                .challenge_phase()
+                .get(challenges.len()..)
+                .and_then(|unknown| unknown.iter().min().copied()),
         };
 
         CT::FloorPlanner::synthesize(&mut witness, &self.circuit, self.config.clone(), vec![])?;
 
-        Ok(batch_invert_assigned(&witness.advice))
+        let mut advice = batch_invert_assigned(&witness.advice);
+        if !self.aux_columns.is_empty() {
+            let aux = self.aux_columns.evaluate(&advice, challenges);
+            advice.extend(aux);
+        }
+
+        Ok(advice)
     }
 
     fn try_collect_preprocessing(&self) -> Result<PreprocessingData<F>, Error> {
@@ -105,6 +179,17 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
     }
 }
 
+impl<F: PrimeField, CT: Circuit<F>> AdviceSource<F> for CircuitRunner<F, CT> {
+    fn collect_advice(&self, challenges: &[F]) -> Result<Cow<'_, [Vec<F>]>, SpsError> {
+        self.try_collect_witness_with_challenges(challenges)
+            .map(Cow::Owned)
+            .map_err(|err| SpsError::WitnessCollection {
+                phase: challenges.len(),
+                err: err.to_string(),
+            })
+    }
+}
+
 struct PreprocessingData<F: PrimeField> {
     pub(crate) permutation_data: PermutationData,
     pub(crate) fixed_columns: Vec<Vec<F>>,
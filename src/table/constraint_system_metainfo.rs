@@ -3,16 +3,23 @@ use tracing::*;
 
 use crate::{
     ff::PrimeField,
-    plonk::{lookup, CompressedGates},
-    polynomial::{expression::QueryIndexContext, Expression},
+    plonk::{lookup, phases::AdvicePhases, CompressedGates},
+    polynomial::{
+        degree_reduction::{self, AuxColumns},
+        expression::QueryIndexContext,
+        Expression,
+    },
 };
 
 pub(crate) struct ConstraintSystemMetainfo<F: PrimeField> {
     pub num_challenges: usize,
     pub round_sizes: Vec<usize>,
+    pub advice_phases: AdvicePhases,
     pub folding_degree: usize,
     pub gates: Vec<Expression<F>>,
     pub custom_gates_lookup_compressed: CompressedGates<F>,
+    /// Advice columns introduced by the degree reduction, see [`Self::build_with`]
+    pub aux_columns: AuxColumns<F>,
 }
 
 impl<F: PrimeField> ConstraintSystemMetainfo<F> {
@@ -21,6 +28,21 @@ impl<F: PrimeField> ConstraintSystemMetainfo<F> {
     pub(crate) fn build(
         k_table_size: usize,
         cs: &ConstraintSystem<F>,
+    ) -> ConstraintSystemMetainfo<F> {
+        Self::build_with(k_table_size, cs, None, false)
+    }
+
+    /// Same as [`Self::build`], but
+    /// - if `optimize_gates` is set, gates are rewritten into the equivalent normal form, see
+    ///   [`crate::polynomial::optimizer`]. It changes the gates of [`PlonkStructure`] & so its
+    ///   digest
+    /// - if `max_degree` is provided, gates are rewritten to have at most this degree with
+    ///   auxiliary advice columns, see [`degree_reduction`]
+    pub(crate) fn build_with(
+        k_table_size: usize,
+        cs: &ConstraintSystem<F>,
+        max_degree: Option<usize>,
+        optimize_gates: bool,
     ) -> ConstraintSystemMetainfo<F> {
         let num_gates: usize = cs.gates().iter().map(|gate| gate.polynomials().len()).sum();
         info!("start build constraint system metainfo with {num_gates} custom gates");
@@ -51,17 +73,51 @@ impl<F: PrimeField> ConstraintSystemMetainfo<F> {
             .flat_map(|gate| gate.polynomials().iter())
             .map(|expr| Expression::from_halo2_expr(expr, cs.num_selectors, cs.num_fixed_columns()))
             .chain(lookup_exprs)
+            .map(|expr| {
+                if optimize_gates {
+                    expr.optimize(cs.num_selectors)
+                } else {
+                    expr
+                }
+            })
             .collect::<Vec<_>>();
 
-        // we have at most 3 prover rounds
+        let mut advice_phases = AdvicePhases::new(cs);
+
+        let (gates, aux_columns) = match max_degree {
+            Some(max_degree) => degree_reduction::reduce_degree(
+                &gates,
+                &QueryIndexContext {
+                    num_selectors: cs.num_selectors,
+                    num_fixed: cs.num_fixed_columns(),
+                    num_advice: cs.num_advice_columns(),
+                    num_lookups,
+                    num_challenges: cs.num_challenges(),
+                },
+                advice_phases.num_challenges(),
+                max_degree,
+            ),
+            None => (gates, AuxColumns::default()),
+        };
+        // auxiliary columns depend on all advice columns, so they are committed in the last phase
+        advice_phases.extend_last_phase(aux_columns.len());
+
+        let last_phase_size = advice_phases.last_phase_size();
+        debug!("advice phases: {:?}", advice_phases.phase_sizes());
+
+        // we have at most 3 prover rounds after the advice phases
         let nrow = 1 << k_table_size;
 
-        let mut round_sizes = Vec::new();
+        // advice columns of all phases except the last one, see [`AdvicePhases`]
+        let mut round_sizes = advice_phases.phase_sizes()[..advice_phases.num_challenges()]
+            .iter()
+            .map(|phase_size| phase_size * nrow)
+            .collect::<Vec<_>>();
 
         if has_vector_lookup {
             round_sizes.extend([
                 // advice columns
-                cs.num_advice_columns() * nrow,
+                last_phase_size * nrow,
                 // (l_i, t_i, m_i), see [`lookup.rs::Arguments::log_derivative_expr`]
                 3 * num_lookups * nrow,
                 // (h_i, g_i), see [`lookup.rs::Arguments::log_derivative_expr`]
@@ -70,41 +126,49 @@ impl<F: PrimeField> ConstraintSystemMetainfo<F> {
         } else if num_lookups > 0 {
             round_sizes.extend([
                 // advice columns || (l_i, t_i, m_i)
-                (cs.num_advice_columns() + 3 * num_lookups) * nrow,
+                (last_phase_size + 3 * num_lookups) * nrow,
                 // (h_i, g_i)
                 2 * num_lookups * nrow,
             ]);
         } else {
             // advice columns
-            round_sizes.push(cs.num_advice_columns() * nrow);
+            round_sizes.push(last_phase_size * nrow);
         };
 
         // we use r3 to combine all custom gates and lookup expressions
-        // find the challenge index of r3
+        // find the challenge index of r3, which goes after challenges of the circuit itself
         let mut ctx = QueryIndexContext {
             num_selectors: cs.num_selectors,
             num_fixed: cs.num_fixed_columns(),
-            num_advice: cs.num_advice_columns(),
+            num_advice: cs.num_advice_columns() + aux_columns.len(),
             num_lookups,
-            num_challenges: if has_vector_lookup {
-                2
-            } else if num_lookups > 0 {
-                1
-            } else {
-                0
-            },
+            num_challenges: advice_phases.num_challenges()
+                + if has_vector_lookup {
+                    2
+                } else if num_lookups > 0 {
+                    1
+                } else {
+                    0
+                },
         };
 
+        // challenges of the circuit & lookup argument take indices even if they are not used
+        let num_challenges = ctx.num_challenges;
         let custom_gates_lookup_compressed = CompressedGates::new(&gates, &mut ctx);
 
         let folding_degree = custom_gates_lookup_compressed.grouped().len();
 
         ConstraintSystemMetainfo {
-            num_challenges: custom_gates_lookup_compressed.compressed().num_challenges(),
+            num_challenges: custom_gates_lookup_compressed
+                .compressed()
+                .challenges_bound()
+                .max(num_challenges),
             round_sizes,
+            advice_phases,
             folding_degree,
             gates,
             custom_gates_lookup_compressed,
+            aux_columns,
         }
     }
 }
@@ -7,7 +7,7 @@
 //! - Implementation of special soundness protocols (`run_sps_protocol_*` functions), essential for
 //!   generating instance/witnesses/challenges securely
 //! - Construction of permutation matrices, ensuring copy constraints consistency in the constraint system.
-//! - Construction of lookup Arguments when the circuits contains lookup argument
+//! - Construction of lookup Arguments when the circuits contains lookup or shuffle argument
 //!
 //! The module is the intermediate data representation of plonkish constrain system defined by the
 //! circuits
@@ -19,7 +19,7 @@ mod witness_data;
 
 pub use circuit_runner::{CircuitRunner, Witness};
 pub(crate) use constraint_system_metainfo::ConstraintSystemMetainfo;
-pub(crate) use witness_data::WitnessCollector;
+pub(crate) use witness_data::{SparseWitnessCollector, WitnessCollector};
 
 #[cfg(test)]
 mod tests;
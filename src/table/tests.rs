@@ -100,3 +100,229 @@ fn test_assembly() -> Result<(), Error> {
     // table.printstd();
     Ok(())
 }
+
+#[traced_test]
+#[test]
+fn sparse_witness_matches_dense() -> Result<(), Error> {
+    use halo2_proofs::plonk::FloorPlanner;
+
+    use crate::halo2curves::pasta::Fp;
+
+    const K: u32 = 4;
+    let inputs = (1..10).map(Fp::from).collect::<Vec<_>>();
+    let output = Fp::from_str_vartime("45").unwrap();
+
+    let td = CircuitRunner::<Fp, _>::new(K, TestCircuit::new(inputs, Fp::ONE), vec![vec![output]]);
+    let dense = td.try_collect_witness()?;
+    let fixed = td.try_collect_plonk_structure()?.fixed_columns;
+
+    let TestCircuitConfig { pconfig, .. } = td.config.clone();
+    let mut sparse = (0..1 << K).fold(
+        SparseWitnessCollector::new(K, td.instances.clone()),
+        |sparse, row| sparse.track(pconfig.out, row).track(pconfig.q_o, row),
+    );
+    SimpleFloorPlanner::synthesize(&mut sparse, &td.circuit, td.config.clone(), vec![])?;
+
+    assert_eq!(sparse.tracked_cells.len(), 2 << K);
+    for row in 0..1 << K {
+        let out = sparse.value(pconfig.out, row).unwrap_or(Fp::ZERO);
+        assert_eq!(out, dense[pconfig.out.index()][row]);
+
+        let q_o = sparse.value(pconfig.q_o, row).unwrap_or(Fp::ZERO);
+        assert_eq!(q_o, fixed[pconfig.q_o.index()][row]);
+    }
+    assert_eq!(sparse.value(pconfig.state[0], 0), None);
+
+    Ok(())
+}
+
+#[traced_test]
+#[test]
+fn degree_reduction() -> Result<(), Error> {
+    use crate::{
+        commitment::CommitmentKey,
+        halo2curves::bn256::{Fq, Fr, G1Affine},
+        polynomial::expression::QueryIndexContext,
+        util::create_ro,
+    };
+
+    const K: u32 = 4;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    let inputs = (1..10).map(Fr::from).collect::<Vec<_>>();
+    let instances = vec![vec![Fr::from(45)]];
+
+    let S = CircuitRunner::<Fr, _>::new(
+        K,
+        TestCircuit::new(inputs.clone(), Fr::ONE),
+        instances.clone(),
+    )
+    .try_collect_plonk_structure()?;
+
+    let runner =
+        CircuitRunner::<Fr, _>::new(K, TestCircuit::new(inputs, Fr::ONE), instances.clone())
+            .with_max_degree(3);
+    let S_reduced = runner.try_collect_plonk_structure()?;
+
+    // `q_5[i] * s[i]^5` of each state column requires one auxiliary column `s[i]^3`
+    assert_eq!(runner.aux_columns.len(), T);
+    assert_eq!(S_reduced.num_advice_columns, S.num_advice_columns + T);
+    assert_eq!(S_reduced.gates.len(), S.gates.len() + T);
+
+    let ctx = QueryIndexContext::from(&S_reduced);
+    assert!(S.gates.iter().any(|gate| gate.degree(&ctx) > 3));
+    assert!(S_reduced.gates.iter().all(|gate| gate.degree(&ctx) <= 3));
+
+    let witness = runner.try_collect_witness()?;
+    assert_eq!(witness.len(), S_reduced.num_advice_columns);
+
+    let ck = CommitmentKey::<G1Affine>::setup(8, b"degree_reduction");
+    let trace = S_reduced
+        .run_sps_protocol(
+            &ck,
+            &instances,
+            &witness,
+            &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+            S_reduced.num_challenges,
+        )
+        .unwrap();
+
+    S_reduced
+        .is_sat(
+            &ck,
+            &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+            &trace.u,
+            &trace.w,
+        )
+        .unwrap();
+
+    Ok(())
+}
+
+#[traced_test]
+#[test]
+fn optimized_gates() -> Result<(), Error> {
+    use crate::{
+        commitment::CommitmentKey,
+        halo2curves::bn256::{Fq, Fr, G1Affine},
+        polynomial::Expression,
+        util::create_ro,
+    };
+
+    const K: u32 = 4;
+    const RATE: usize = 2;
+    const R_F: usize = 4;
+    const R_P: usize = 3;
+
+    /// Evaluates `expr` with pseudo-random values of queries & challenges
+    fn eval(expr: &Expression<Fr>, seed: u64) -> Fr {
+        expr.evaluate(
+            &|c| c,
+            &|query| {
+                Fr::from(seed * 1000 + query.index as u64 * 10) + Fr::from(query.rotation.0 as u64)
+            },
+            &|index| Fr::from(seed * 7 + index as u64 + 100).invert().unwrap(),
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, k| a * k,
+        )
+    }
+
+    let inputs = (1..10).map(Fr::from).collect::<Vec<_>>();
+    let instances = vec![vec![Fr::from(45)]];
+
+    let S = CircuitRunner::<Fr, _>::new(
+        K,
+        TestCircuit::new(inputs.clone(), Fr::ONE),
+        instances.clone(),
+    )
+    .try_collect_plonk_structure()?;
+
+    let runner =
+        CircuitRunner::<Fr, _>::new(K, TestCircuit::new(inputs, Fr::ONE), instances.clone())
+            .with_optimized_gates();
+    let S_optimized = runner.try_collect_plonk_structure()?;
+
+    // gates are rewritten only on demand, since they are a part of the digest
+    assert_ne!(S.gates, S_optimized.gates);
+    assert_eq!(S.gates.len(), S_optimized.gates.len());
+    for (gate, optimized) in S.gates.iter().zip(S_optimized.gates.iter()) {
+        for seed in 0..5 {
+            assert_eq!(
+                eval(gate, seed),
+                eval(optimized, seed),
+                "{gate} != {optimized}"
+            );
+        }
+    }
+
+    let ck = CommitmentKey::<G1Affine>::setup(8, b"optimized_gates");
+    let trace = S_optimized
+        .run_sps_protocol(
+            &ck,
+            &instances,
+            &runner.try_collect_witness()?,
+            &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+            S_optimized.num_challenges,
+        )
+        .unwrap();
+
+    S_optimized
+        .is_sat(
+            &ck,
+            &mut create_ro::<Fq, T, RATE, R_F, R_P>(),
+            &trace.u,
+            &trace.w,
+        )
+        .unwrap();
+
+    Ok(())
+}
+
+#[traced_test]
+#[test]
+fn plonk_structure_serde() -> Result<(), Error> {
+    use bincode::Options;
+
+    use crate::{halo2curves::bn256::Fr, plonk::PlonkStructure};
+
+    const K: u32 = 4;
+
+    let S = CircuitRunner::<Fr, _>::new(
+        K,
+        TestCircuit::new((1..10).map(Fr::from).collect(), Fr::ONE),
+        vec![vec![Fr::from(45)]],
+    )
+    .try_collect_plonk_structure()?;
+
+    let options = bincode::DefaultOptions::new()
+        .with_little_endian()
+        .with_fixint_encoding();
+
+    let bytes = options.serialize(&S).unwrap();
+    let decoded: PlonkStructure<Fr> = options.deserialize(&bytes).unwrap();
+
+    assert!(!decoded.gates.is_empty());
+    assert!(decoded == S);
+    assert_eq!(options.serialize(&decoded).unwrap(), bytes);
+
+    #[derive(serde::Serialize)]
+    struct ForDigest(
+        #[serde(serialize_with = "PlonkStructure::serialize_for_digest")] PlonkStructure<Fr>,
+    );
+
+    // uncompressed gates are not a part of the public params digest
+    let mut without_gates = S.clone();
+    without_gates.gates.clear();
+    let digest_bytes = options.serialize(&ForDigest(S)).unwrap();
+    assert_eq!(
+        digest_bytes,
+        options.serialize(&ForDigest(without_gates)).unwrap()
+    );
+    assert!(digest_bytes.len() < bytes.len());
+
+    Ok(())
+}
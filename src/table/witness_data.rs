@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use halo2_proofs::{
     circuit::Value,
     plonk::{
@@ -11,6 +13,11 @@ use crate::ff::PrimeField;
 pub struct WitnessCollector<F: PrimeField> {
     pub(crate) instances: Vec<Vec<F>>,
     pub(crate) advice: Vec<Vec<Assigned<F>>>,
+    /// Challenges of already committed phases, see [`crate::plonk::phases`]
+    pub(crate) challenges: Vec<F>,
+    /// The last phase, advice columns of which don't depend on unknown challenges, `None` if
+    /// all challenges are known
+    pub(crate) last_ready_phase: Option<u8>,
 }
 
 impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
@@ -68,7 +75,13 @@ impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        // TODO: support phases
+        let value = to().into_field();
+        // advice columns of phase `i` depend on challenges usable after phases `0..i`, so they
+        // can't be assigned until these challenges are known
+        let is_phase_ready = self.last_ready_phase.map_or(true, |last_ready_phase| {
+            column.column_type().phase() <= last_ready_phase
+        });
+
         *self
             .advice
             .get_mut(column.index())
@@ -79,7 +92,11 @@ impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
                     annotation().into()
                 );
                 Error::BoundsFailure
-            })? = to().into_field().assign()?;
+            })? = if is_phase_ready {
+            value.assign()?
+        } else {
+            value.assign().unwrap_or(Assigned::Zero)
+        };
 
         Ok(())
     }
@@ -113,8 +130,11 @@ impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
         Ok(())
     }
 
-    fn get_challenge(&self, _: Challenge) -> Value<F> {
-        Value::unknown()
+    fn get_challenge(&self, challenge: Challenge) -> Value<F> {
+        self.challenges
+            .get(challenge.index())
+            .map(|challenge| Value::known(*challenge))
+            .unwrap_or_else(Value::unknown)
     }
 
     fn push_namespace<NR, N>(&mut self, _: N)
@@ -137,3 +157,201 @@ impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
         todo!()
     }
 }
+
+/// Witness collector which stores only values of the tracked cells
+///
+/// Unlike [`WitnessCollector`], it doesn't allocate `2^k` rows for each advice column & drops
+/// values of the cells, which weren't tracked by [`SparseWitnessCollector::track`], so memory
+/// usage depends only on the number of tracked cells. It is used for the off-circuit evaluation
+/// of the circuit, e.g. [`crate::ivc::StepCircuit::process_step`], where the output values are
+/// read from the returned [`halo2_proofs::circuit::AssignedCell`], so no cells are tracked.
+///
+/// Challenges are unknown, so advice columns of later phases are not assigned
+/// (see [`crate::plonk::phases`]).
+pub struct SparseWitnessCollector<F: PrimeField> {
+    pub(crate) instances: Vec<Vec<F>>,
+    /// Number of rows, used only for bounds checking
+    pub(crate) num_rows: usize,
+    /// Values of the tracked cells by `(column, row)`, `None` until the cell is assigned
+    pub(crate) tracked_cells: HashMap<(Column<Any>, usize), Option<Assigned<F>>>,
+}
+
+impl<F: PrimeField> SparseWitnessCollector<F> {
+    pub fn new(k_table_size: u32, instances: Vec<Vec<F>>) -> Self {
+        Self {
+            instances,
+            num_rows: 1 << k_table_size,
+            tracked_cells: HashMap::new(),
+        }
+    }
+
+    /// Store the value of the cell, so it can be read by [`SparseWitnessCollector::value`] after
+    /// the synthesis or queried by the circuit itself during it
+    pub fn track(mut self, column: impl Into<Column<Any>>, row: usize) -> Self {
+        self.tracked_cells.insert((column.into(), row), None);
+        self
+    }
+
+    /// Value of the tracked cell, `None` if the cell is not tracked or was not assigned
+    pub fn value(&self, column: impl Into<Column<Any>>, row: usize) -> Option<F> {
+        self.tracked_cells
+            .get(&(column.into(), row))
+            .copied()
+            .flatten()
+            .map(|value| value.evaluate())
+    }
+
+    fn query(&self, column: Column<Any>, row: usize) -> Result<F, Error> {
+        if row >= self.num_rows {
+            return Err(Error::BoundsFailure);
+        }
+
+        self.value(column, row).ok_or_else(|| {
+            error!(
+                "cell of {column:?} at row {row} is not tracked or not assigned, can't query it"
+            );
+            Error::Synthesis
+        })
+    }
+}
+
+impl<F: PrimeField> Assignment<F> for SparseWitnessCollector<F> {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about regions in this context.
+    }
+
+    fn exit_region(&mut self) {
+        // Do nothing; we don't care about regions in this context.
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _: A,
+        _selector: &Selector,
+        _row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn annotate_column<A, AR>(&mut self, _annotation: A, _column: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // Do nothing
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        self.instances
+            .get(column.index())
+            .and_then(|col| col.get(row))
+            .map(|v| Value::known(*v))
+            .ok_or(Error::BoundsFailure)
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        if row >= self.num_rows {
+            error!(
+                "Error while assign advice {} in column {column:?} & row {row}",
+                annotation().into()
+            );
+            return Err(Error::BoundsFailure);
+        }
+
+        let value = to().into_field();
+        let value = match value.assign() {
+            Ok(value) => value,
+            // advice columns of later phases depend on unknown challenges
+            Err(_) if column.column_type().phase() != 0 => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        if let Some(cell) = self.tracked_cells.get_mut(&(column.into(), row)) {
+            *cell = Some(value);
+        }
+
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        if let Some(cell) = self.tracked_cells.get_mut(&(column.into(), row)) {
+            *cell = Some(to().into_field().assign()?);
+        }
+
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_challenge(&self, _: Challenge) -> Value<F> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    /// Only tracked cells can be queried, see [`SparseWitnessCollector::track`]
+    fn query_advice(&self, column: Column<Advice>, row: usize) -> Result<F, Error> {
+        self.query(column.into(), row)
+    }
+
+    /// Only tracked cells can be queried, see [`SparseWitnessCollector::track`]
+    ///
+    /// Fixed columns are not stored by default, since they can hold whole tables (e.g. of range
+    /// checks) with `2^k` rows, which is exactly the memory this collector avoids
+    fn query_fixed(&self, column: Column<Fixed>, row: usize) -> Result<F, Error> {
+        self.query(column.into(), row)
+    }
+}